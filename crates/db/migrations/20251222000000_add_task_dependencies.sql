@@ -0,0 +1,23 @@
+-- Blocked-by graph between tasks: task_id cannot start until depends_on_task_id is done
+CREATE TABLE task_dependencies (
+    task_id            BLOB NOT NULL,
+    depends_on_task_id BLOB NOT NULL,
+    created_at         TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    PRIMARY KEY (task_id, depends_on_task_id),
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE,
+    FOREIGN KEY (depends_on_task_id) REFERENCES tasks(id) ON DELETE CASCADE,
+    CHECK (task_id != depends_on_task_id)
+);
+
+CREATE INDEX idx_task_dependencies_depends_on_task_id
+ON task_dependencies(depends_on_task_id);
+
+-- Optional launch configuration used to start a task once its last blocker is done
+CREATE TABLE task_auto_starts (
+    task_id             BLOB PRIMARY KEY,
+    executor_profile_id TEXT NOT NULL,
+    repos               TEXT NOT NULL DEFAULT '[]',
+    created_at          TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at          TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE
+);
@@ -0,0 +1,2 @@
+-- Why an execution process was stopped by the runaway guard (NULL for ordinary exits)
+ALTER TABLE execution_processes ADD COLUMN failure_reason TEXT;
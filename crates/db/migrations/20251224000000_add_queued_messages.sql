@@ -0,0 +1,13 @@
+-- Ordered follow-up messages waiting to be sent to a session once its current run finishes
+CREATE TABLE queued_messages (
+    id          BLOB PRIMARY KEY,
+    session_id  BLOB NOT NULL,
+    position    INTEGER NOT NULL,
+    data        TEXT NOT NULL,
+    queued_at   TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (session_id) REFERENCES sessions(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_queued_messages_session_id_position
+ON queued_messages(session_id, position);
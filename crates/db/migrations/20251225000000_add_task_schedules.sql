@@ -0,0 +1,35 @@
+-- Cron-triggered task templates: each firing creates a task and starts a workspace for it
+CREATE TABLE task_schedules (
+    id                  BLOB PRIMARY KEY,
+    project_id          BLOB NOT NULL,
+    title               TEXT NOT NULL,
+    description         TEXT,
+    cron_expression     TEXT NOT NULL,
+    executor_profile_id TEXT NOT NULL,
+    repos               TEXT NOT NULL DEFAULT '[]',
+    enabled             BOOLEAN NOT NULL DEFAULT 1,
+    next_run_at         TEXT,
+    last_run_at         TEXT,
+    created_at          TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at          TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_task_schedules_project_id ON task_schedules(project_id);
+CREATE INDEX idx_task_schedules_next_run_at ON task_schedules(enabled, next_run_at);
+
+-- One row per firing; task/workspace are kept as history even after they are deleted
+CREATE TABLE task_schedule_runs (
+    id            BLOB PRIMARY KEY,
+    schedule_id   BLOB NOT NULL,
+    task_id       BLOB,
+    workspace_id  BLOB,
+    error         TEXT,
+    scheduled_for TEXT NOT NULL,
+    created_at    TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (schedule_id) REFERENCES task_schedules(id) ON DELETE CASCADE,
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE SET NULL,
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE SET NULL
+);
+
+CREATE INDEX idx_task_schedule_runs_schedule_id ON task_schedule_runs(schedule_id, created_at);
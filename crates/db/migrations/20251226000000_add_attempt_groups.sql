@@ -0,0 +1,25 @@
+-- Best-of-N runs: one task fanned out to several workspaces with different executor profiles
+CREATE TABLE attempt_groups (
+    id                  BLOB PRIMARY KEY,
+    task_id             BLOB NOT NULL,
+    winner_workspace_id BLOB,
+    created_at          TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at          TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE,
+    FOREIGN KEY (winner_workspace_id) REFERENCES workspaces(id) ON DELETE SET NULL
+);
+
+CREATE INDEX idx_attempt_groups_task_id ON attempt_groups(task_id);
+
+CREATE TABLE attempt_group_members (
+    group_id            BLOB NOT NULL,
+    workspace_id        BLOB NOT NULL,
+    executor_profile_id TEXT NOT NULL,
+    archived_at         TEXT,
+    created_at          TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    PRIMARY KEY (group_id, workspace_id),
+    FOREIGN KEY (group_id) REFERENCES attempt_groups(id) ON DELETE CASCADE,
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_attempt_group_members_workspace_id ON attempt_group_members(workspace_id);
@@ -0,0 +1,13 @@
+-- Compacted logs of completed execution processes: all JSONL rows of one process collapsed
+-- into a single zstd blob, stored inline or spilled to a file under the asset directory
+CREATE TABLE execution_process_log_archives (
+    execution_id    BLOB PRIMARY KEY,
+    data            BLOB,              -- zstd-compressed JSONL; NULL when spilled to a file
+    file_name       TEXT,              -- relative to the logs directory when spilled
+    line_count      INTEGER NOT NULL,
+    byte_size       INTEGER NOT NULL,  -- uncompressed
+    compressed_size INTEGER NOT NULL,
+    created_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (execution_id) REFERENCES execution_processes(id) ON DELETE CASCADE
+);
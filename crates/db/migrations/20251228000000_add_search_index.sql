@@ -0,0 +1,125 @@
+-- Full-text search over tasks, coding agent turns and agent conversations.
+-- search_documents holds one row per searchable item with links back to its task,
+-- workspace and execution process; search_documents_fts indexes its title and body.
+CREATE TABLE search_documents (
+    id                   INTEGER PRIMARY KEY,
+    kind                 TEXT NOT NULL CHECK (kind IN ('task', 'agent_turn', 'conversation')),
+    source_id            BLOB NOT NULL,     -- task id, coding agent turn id or execution process id
+    project_id           BLOB NOT NULL,
+    task_id              BLOB NOT NULL,
+    workspace_id         BLOB,
+    execution_process_id BLOB,
+    title                TEXT NOT NULL DEFAULT '',
+    body                 TEXT NOT NULL DEFAULT '',
+    updated_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    UNIQUE (kind, source_id)
+);
+
+CREATE INDEX idx_search_documents_task_id ON search_documents(task_id);
+CREATE INDEX idx_search_documents_workspace_id ON search_documents(workspace_id);
+CREATE INDEX idx_search_documents_execution_process_id ON search_documents(execution_process_id);
+
+CREATE VIRTUAL TABLE search_documents_fts USING fts5(
+    title,
+    body,
+    content = 'search_documents',
+    content_rowid = 'id',
+    tokenize = 'porter unicode61'
+);
+
+CREATE TRIGGER search_documents_ai AFTER INSERT ON search_documents BEGIN
+    INSERT INTO search_documents_fts (rowid, title, body) VALUES (new.id, new.title, new.body);
+END;
+
+CREATE TRIGGER search_documents_ad AFTER DELETE ON search_documents BEGIN
+    INSERT INTO search_documents_fts (search_documents_fts, rowid, title, body)
+    VALUES ('delete', old.id, old.title, old.body);
+END;
+
+CREATE TRIGGER search_documents_au AFTER UPDATE ON search_documents BEGIN
+    INSERT INTO search_documents_fts (search_documents_fts, rowid, title, body)
+    VALUES ('delete', old.id, old.title, old.body);
+    INSERT INTO search_documents_fts (rowid, title, body) VALUES (new.id, new.title, new.body);
+END;
+
+-- Tasks
+CREATE TRIGGER search_tasks_ai AFTER INSERT ON tasks BEGIN
+    INSERT INTO search_documents (kind, source_id, project_id, task_id, title, body)
+    VALUES ('task', new.id, new.project_id, new.id, new.title, COALESCE(new.description, ''));
+END;
+
+CREATE TRIGGER search_tasks_au AFTER UPDATE OF title, description ON tasks BEGIN
+    UPDATE search_documents
+    SET title = new.title,
+        body = COALESCE(new.description, ''),
+        updated_at = datetime('now', 'subsec')
+    WHERE kind = 'task' AND source_id = new.id;
+END;
+
+CREATE TRIGGER search_tasks_ad AFTER DELETE ON tasks BEGIN
+    DELETE FROM search_documents WHERE task_id = old.id;
+END;
+
+-- Coding agent turns: the prompt is the title, the final summary the body
+CREATE TRIGGER search_coding_agent_turns_ai AFTER INSERT ON coding_agent_turns BEGIN
+    INSERT INTO search_documents
+        (kind, source_id, project_id, task_id, workspace_id, execution_process_id, title, body)
+    SELECT 'agent_turn', new.id, t.project_id, t.id, w.id, new.execution_process_id,
+           COALESCE(new.prompt, ''), COALESCE(new.summary, '')
+    FROM execution_processes ep
+    JOIN sessions s ON s.id = ep.session_id
+    JOIN workspaces w ON w.id = s.workspace_id
+    JOIN tasks t ON t.id = w.task_id
+    WHERE ep.id = new.execution_process_id;
+END;
+
+CREATE TRIGGER search_coding_agent_turns_au AFTER UPDATE OF prompt, summary ON coding_agent_turns BEGIN
+    UPDATE search_documents
+    SET title = COALESCE(new.prompt, ''),
+        body = COALESCE(new.summary, ''),
+        updated_at = datetime('now', 'subsec')
+    WHERE kind = 'agent_turn' AND source_id = new.id;
+END;
+
+CREATE TRIGGER search_coding_agent_turns_ad AFTER DELETE ON coding_agent_turns BEGIN
+    DELETE FROM search_documents WHERE kind = 'agent_turn' AND source_id = old.id;
+END;
+
+-- Conversations are indexed by the application once a process completes; drop them
+-- together with their process or workspace
+CREATE TRIGGER search_execution_processes_ad AFTER DELETE ON execution_processes BEGIN
+    DELETE FROM search_documents WHERE execution_process_id = old.id;
+END;
+
+CREATE TRIGGER search_workspaces_ad AFTER DELETE ON workspaces BEGIN
+    DELETE FROM search_documents WHERE workspace_id = old.id;
+END;
+
+-- Backfill
+INSERT INTO search_documents (kind, source_id, project_id, task_id, title, body, updated_at)
+SELECT 'task', id, project_id, id, title, COALESCE(description, ''), updated_at
+FROM tasks;
+
+INSERT INTO search_documents
+    (kind, source_id, project_id, task_id, workspace_id, execution_process_id, title, body, updated_at)
+SELECT 'agent_turn', cat.id, t.project_id, t.id, w.id, cat.execution_process_id,
+       COALESCE(cat.prompt, ''), COALESCE(cat.summary, ''), cat.updated_at
+FROM coding_agent_turns cat
+JOIN execution_processes ep ON ep.id = cat.execution_process_id
+JOIN sessions s ON s.id = ep.session_id
+JOIN workspaces w ON w.id = s.workspace_id
+JOIN tasks t ON t.id = w.task_id;
+
+-- Only the final assistant message of earlier processes survives outside their raw logs,
+-- so it stands in for their conversation
+INSERT INTO search_documents
+    (kind, source_id, project_id, task_id, workspace_id, execution_process_id, body, updated_at)
+SELECT 'conversation', ep.id, t.project_id, t.id, w.id, ep.id, cat.summary, cat.updated_at
+FROM coding_agent_turns cat
+JOIN execution_processes ep ON ep.id = cat.execution_process_id
+JOIN sessions s ON s.id = ep.session_id
+JOIN workspaces w ON w.id = s.workspace_id
+JOIN tasks t ON t.id = w.task_id
+WHERE ep.status = 'completed'
+  AND TRIM(COALESCE(cat.summary, '')) != ''
+ON CONFLICT (kind, source_id) DO NOTHING;
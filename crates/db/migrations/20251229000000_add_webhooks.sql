@@ -0,0 +1,83 @@
+-- Outbound webhooks: lifecycle events are POSTed as signed JSON to each subscribed URL
+CREATE TABLE webhooks (
+    id         BLOB PRIMARY KEY,
+    name       TEXT NOT NULL,
+    url        TEXT NOT NULL,
+    secret     TEXT NOT NULL,
+    -- JSON array of event types; empty subscribes to every event
+    events     TEXT NOT NULL DEFAULT '[]',
+    enabled    BOOLEAN NOT NULL DEFAULT 1,
+    created_at TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
+
+-- Outbox of raised events, drained by the webhook service which fans each event out
+-- into one delivery per subscribed webhook. `subject_id` is the task, execution process
+-- or workspace the event is about; `payload` carries event-specific fields.
+CREATE TABLE webhook_events (
+    id         INTEGER PRIMARY KEY AUTOINCREMENT,
+    event_type TEXT NOT NULL,
+    subject_id BLOB NOT NULL,
+    payload    TEXT NOT NULL DEFAULT '{}',
+    created_at TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
+
+-- Delivery log; `payload` is the exact body that is signed and sent on every attempt
+CREATE TABLE webhook_deliveries (
+    id              BLOB PRIMARY KEY,
+    webhook_id      BLOB NOT NULL,
+    event_type      TEXT NOT NULL,
+    payload         TEXT NOT NULL,
+    status          TEXT NOT NULL DEFAULT 'pending'
+                       CHECK (status IN ('pending','delivered','failed')),
+    attempts        INTEGER NOT NULL DEFAULT 0,
+    next_attempt_at TEXT,
+    response_status INTEGER,
+    last_error      TEXT,
+    delivered_at    TEXT,
+    created_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (webhook_id) REFERENCES webhooks(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_webhook_deliveries_webhook_id ON webhook_deliveries(webhook_id, created_at);
+CREATE INDEX idx_webhook_deliveries_due ON webhook_deliveries(status, next_attempt_at);
+
+-- Events are only recorded while at least one webhook is enabled, so the outbox does not
+-- grow for installs that never configure webhooks
+CREATE TRIGGER webhook_events_task_status
+AFTER UPDATE OF status ON tasks
+WHEN OLD.status IS NOT NEW.status
+ AND EXISTS (SELECT 1 FROM webhooks WHERE enabled = 1)
+BEGIN
+    INSERT INTO webhook_events (event_type, subject_id, payload)
+    VALUES ('task.status_changed', NEW.id,
+            json_object('previous_status', OLD.status, 'status', NEW.status));
+END;
+
+CREATE TRIGGER webhook_events_execution_finished
+AFTER UPDATE OF status ON execution_processes
+WHEN OLD.status = 'running'
+ AND NEW.status IN ('completed', 'failed')
+ AND EXISTS (SELECT 1 FROM webhooks WHERE enabled = 1)
+BEGIN
+    INSERT INTO webhook_events (event_type, subject_id, payload)
+    VALUES (CASE NEW.status WHEN 'completed' THEN 'execution.completed' ELSE 'execution.failed' END,
+            NEW.id,
+            json_object('run_reason', NEW.run_reason, 'status', NEW.status,
+                        'exit_code', NEW.exit_code));
+END;
+
+CREATE TRIGGER webhook_events_pr_merged
+AFTER UPDATE OF pr_status ON merges
+WHEN NEW.merge_type = 'pr'
+ AND NEW.pr_status = 'merged'
+ AND OLD.pr_status IS NOT 'merged'
+ AND EXISTS (SELECT 1 FROM webhooks WHERE enabled = 1)
+BEGIN
+    INSERT INTO webhook_events (event_type, subject_id, payload)
+    VALUES ('pr.merged', NEW.workspace_id,
+            json_object('pr_number', NEW.pr_number, 'pr_url', NEW.pr_url,
+                        'target_branch_name', NEW.target_branch_name,
+                        'merge_commit_sha', NEW.pr_merge_commit_sha));
+END;
@@ -0,0 +1,21 @@
+-- Rules evaluated before a tool approval request is shown to the user. A matching deny
+-- rule rejects the tool call; otherwise a matching allow rule approves it.
+CREATE TABLE approval_rules (
+    id            BLOB PRIMARY KEY,
+    -- NULL applies the rule to every project
+    project_id    BLOB REFERENCES projects(id) ON DELETE CASCADE,
+    -- Executor profile the rule is limited to; NULL matches any executor or variant
+    executor      TEXT,
+    variant       TEXT,
+    action        TEXT NOT NULL CHECK (action IN ('allow', 'deny')),
+    -- Glob over the tool name, e.g. 'Bash' or '*'
+    tool_pattern  TEXT NOT NULL DEFAULT '*',
+    -- Glob over the string values in the tool input; NULL matches any input
+    input_pattern TEXT,
+    description   TEXT,
+    enabled       BOOLEAN NOT NULL DEFAULT 1,
+    created_at    TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at    TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
+
+CREATE INDEX idx_approval_rules_project_id ON approval_rules(project_id);
@@ -0,0 +1,21 @@
+-- Audit trail of tool approval requests and their decisions. `id` is the approval id
+-- handed to the frontend; `rule_id` is set when an approval rule decided the request.
+CREATE TABLE approvals (
+    id                   TEXT PRIMARY KEY,
+    execution_process_id BLOB NOT NULL REFERENCES execution_processes(id) ON DELETE CASCADE,
+    tool_name            TEXT NOT NULL,
+    tool_input           TEXT NOT NULL DEFAULT 'null',
+    tool_call_id         TEXT NOT NULL,
+    status               TEXT NOT NULL DEFAULT 'pending'
+                         CHECK (status IN ('pending', 'approved', 'denied', 'timed_out')),
+    denial_reason        TEXT,
+    rule_id              BLOB REFERENCES approval_rules(id) ON DELETE SET NULL,
+    -- Who decided the request: the user, an approval rule or the timeout. NULL while pending.
+    actor                TEXT CHECK (actor IN ('user', 'rule', 'timeout')),
+    created_at           TEXT NOT NULL,
+    timeout_at           TEXT NOT NULL,
+    responded_at         TEXT
+);
+
+CREATE INDEX idx_approvals_execution_process_id ON approvals(execution_process_id);
+CREATE INDEX idx_approvals_pending ON approvals(status) WHERE status = 'pending';
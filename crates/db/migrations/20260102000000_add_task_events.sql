@@ -0,0 +1,14 @@
+-- Status history of tasks. `old_status` is NULL for the event recorded when the task was
+-- created; `actor` says what made the change.
+CREATE TABLE task_events (
+    id         BLOB PRIMARY KEY,
+    task_id    BLOB NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+    old_status TEXT CHECK (old_status IN ('todo', 'inprogress', 'inreview', 'done', 'cancelled')),
+    new_status TEXT NOT NULL
+               CHECK (new_status IN ('todo', 'inprogress', 'inreview', 'done', 'cancelled')),
+    actor      TEXT NOT NULL
+               CHECK (actor IN ('user', 'agent', 'pr_monitor', 'shared_task_sync', 'scheduler')),
+    created_at TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
+
+CREATE INDEX idx_task_events_task_id_created_at ON task_events(task_id, created_at);
@@ -0,0 +1,54 @@
+-- Per-repo quality gate that must pass before a workspace can be merged or opened as a PR
+ALTER TABLE project_repos ADD COLUMN gate_script TEXT;
+ALTER TABLE project_repos ADD COLUMN gate_follow_up_on_failure INTEGER NOT NULL DEFAULT 0;
+
+-- Widen the run_reason CHECK to allow 'qualitygate'
+-- 1. The webhook trigger reads run_reason, which blocks dropping the column
+DROP TRIGGER IF EXISTS webhook_events_execution_finished;
+
+-- 2. Add the replacement column with the wider CHECK
+ALTER TABLE execution_processes
+  ADD COLUMN run_reason_new TEXT NOT NULL DEFAULT 'setupscript'
+    CHECK (run_reason_new IN ('setupscript',
+                              'codingagent',
+                              'devserver',
+                              'cleanupscript',
+                              'qualitygate'));
+
+-- 3. Copy existing values across
+UPDATE execution_processes
+  SET run_reason_new = run_reason;
+
+-- 4. Drop any indexes that mention the old column
+DROP INDEX IF EXISTS idx_execution_processes_run_reason;
+DROP INDEX IF EXISTS idx_execution_processes_session_status_run_reason;
+DROP INDEX IF EXISTS idx_execution_processes_session_run_reason_created;
+
+-- 5. Remove the old column
+ALTER TABLE execution_processes DROP COLUMN run_reason;
+
+-- 6. Rename the new column back to the canonical name
+ALTER TABLE execution_processes
+  RENAME COLUMN run_reason_new TO run_reason;
+
+-- 7. Re-create the indexes and the trigger
+CREATE INDEX idx_execution_processes_run_reason ON execution_processes(run_reason);
+
+CREATE INDEX idx_execution_processes_session_status_run_reason
+ON execution_processes (session_id, status, run_reason);
+
+CREATE INDEX idx_execution_processes_session_run_reason_created
+ON execution_processes (session_id, run_reason, created_at DESC);
+
+CREATE TRIGGER webhook_events_execution_finished
+AFTER UPDATE OF status ON execution_processes
+WHEN OLD.status = 'running'
+ AND NEW.status IN ('completed', 'failed')
+ AND EXISTS (SELECT 1 FROM webhooks WHERE enabled = 1)
+BEGIN
+    INSERT INTO webhook_events (event_type, subject_id, payload)
+    VALUES (CASE NEW.status WHEN 'completed' THEN 'execution.completed' ELSE 'execution.failed' END,
+            NEW.id,
+            json_object('run_reason', NEW.run_reason, 'status', NEW.status,
+                        'exit_code', NEW.exit_code));
+END;
@@ -0,0 +1,32 @@
+-- Tokens for the authenticated headless server mode. Only a SHA-256 hash of each token is
+-- stored; `token_prefix` keeps the first characters so tokens can be told apart.
+CREATE TABLE api_tokens (
+    id           BLOB PRIMARY KEY,
+    name         TEXT NOT NULL,
+    token_hash   TEXT NOT NULL UNIQUE,
+    token_prefix TEXT NOT NULL,
+    -- Admin tokens can create and revoke tokens
+    is_admin     BOOLEAN NOT NULL DEFAULT 0,
+    -- `mcp` tokens only authenticate the MCP Streamable HTTP endpoint, `api` tokens only the
+    -- rest of the API
+    scope        TEXT NOT NULL DEFAULT 'api' CHECK (scope IN ('api', 'mcp')),
+    -- Set on non-admin tokens handed to a coding agent run, so the MCP server it starts can
+    -- call the API of a headless server. They are revoked when the run ends.
+    execution_process_id BLOB REFERENCES execution_processes(id) ON DELETE CASCADE,
+    last_used_at TEXT,
+    revoked_at   TEXT,
+    created_at   TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
+
+-- One row per authenticated request
+CREATE TABLE api_token_audit_log (
+    id         INTEGER PRIMARY KEY AUTOINCREMENT,
+    token_id   BLOB NOT NULL,
+    method     TEXT NOT NULL,
+    path       TEXT NOT NULL,
+    status     INTEGER NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (token_id) REFERENCES api_tokens(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_api_token_audit_log_token_id ON api_token_audit_log(token_id, created_at);
@@ -0,0 +1,4 @@
+-- Workspaces created on top of another workspace's branch, so they can be restacked when it moves
+ALTER TABLE workspaces ADD COLUMN stack_parent_id BLOB REFERENCES workspaces(id) ON DELETE SET NULL;
+
+CREATE INDEX idx_workspaces_stack_parent_id ON workspaces(stack_parent_id);
@@ -0,0 +1,13 @@
+-- Coding agent runs resolving the conflicts of a stopped git operation, so the operation is
+-- continued or aborted when the run ends, and aborted on startup if the server stopped first
+CREATE TABLE conflict_resolutions (
+    execution_process_id BLOB PRIMARY KEY
+        REFERENCES execution_processes(id) ON DELETE CASCADE,
+    workspace_id BLOB NOT NULL REFERENCES workspaces(id) ON DELETE CASCADE,
+    repo_name TEXT NOT NULL,
+    worktree_path TEXT NOT NULL,
+    op TEXT NOT NULL,
+    conflicted_files TEXT NOT NULL,
+    round INTEGER NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
@@ -0,0 +1,188 @@
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::{
+    execution_process::{ExecutionProcessRunReason, ExecutionProcessStatus, ExecutorActionField},
+    merge::{MergeStatus, MergeType},
+    task::TaskStatus,
+    task_event::{TaskEvent, TaskEventActor},
+};
+
+/// Which tasks analytics are computed over. Tasks are selected by creation time and are
+/// reported with all of their attempts, whenever those ran.
+#[derive(Debug, Clone, Default, Deserialize, TS)]
+pub struct AnalyticsFilter {
+    pub project_id: Option<Uuid>,
+    /// Only include tasks created at or after this time
+    pub since: Option<DateTime<Utc>>,
+    /// Only include tasks created before this time
+    pub until: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct AnalyticsTask {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub project_name: String,
+    pub status: TaskStatus,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct AnalyticsAttempt {
+    pub id: Uuid,
+    pub task_id: Uuid,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct AnalyticsProcess {
+    pub workspace_id: Uuid,
+    pub run_reason: ExecutionProcessRunReason,
+    pub executor_action: sqlx::types::Json<ExecutorActionField>,
+    pub status: ExecutionProcessStatus,
+    pub started_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct AnalyticsMerge {
+    pub workspace_id: Uuid,
+    pub merge_type: MergeType,
+    pub pr_status: Option<MergeStatus>,
+    pub pr_merged_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Raw rows analytics are computed from
+#[derive(Debug, Clone, Default)]
+pub struct AnalyticsData {
+    pub tasks: Vec<AnalyticsTask>,
+    pub attempts: Vec<AnalyticsAttempt>,
+    /// Oldest first
+    pub processes: Vec<AnalyticsProcess>,
+    pub merges: Vec<AnalyticsMerge>,
+    /// Status changes, oldest first. Tasks created before status history was recorded
+    /// have none or only their later changes.
+    pub events: Vec<TaskEvent>,
+}
+
+impl AnalyticsData {
+    pub async fn load(pool: &SqlitePool, filter: &AnalyticsFilter) -> Result<Self, sqlx::Error> {
+        let tasks = sqlx::query_as!(
+            AnalyticsTask,
+            r#"SELECT t.id as "id!: Uuid",
+                      p.id as "project_id!: Uuid",
+                      p.name as project_name,
+                      t.status as "status!: TaskStatus",
+                      t.created_at as "created_at!: DateTime<Utc>",
+                      t.updated_at as "updated_at!: DateTime<Utc>"
+               FROM tasks t
+               JOIN projects p ON p.id = t.project_id
+               WHERE ($1 IS NULL OR p.id = $1)
+                 AND ($2 IS NULL OR t.created_at >= $2)
+                 AND ($3 IS NULL OR t.created_at < $3)
+               ORDER BY t.created_at ASC"#,
+            filter.project_id,
+            filter.since,
+            filter.until
+        )
+        .fetch_all(pool)
+        .await?;
+
+        let attempts = sqlx::query_as!(
+            AnalyticsAttempt,
+            r#"SELECT w.id as "id!: Uuid",
+                      t.id as "task_id!: Uuid",
+                      w.created_at as "created_at!: DateTime<Utc>"
+               FROM workspaces w
+               JOIN tasks t ON t.id = w.task_id
+               WHERE ($1 IS NULL OR t.project_id = $1)
+                 AND ($2 IS NULL OR t.created_at >= $2)
+                 AND ($3 IS NULL OR t.created_at < $3)
+               ORDER BY w.created_at ASC"#,
+            filter.project_id,
+            filter.since,
+            filter.until
+        )
+        .fetch_all(pool)
+        .await?;
+
+        let processes = sqlx::query_as!(
+            AnalyticsProcess,
+            r#"SELECT w.id as "workspace_id!: Uuid",
+                      ep.run_reason as "run_reason!: ExecutionProcessRunReason",
+                      ep.executor_action as "executor_action!: sqlx::types::Json<ExecutorActionField>",
+                      ep.status as "status!: ExecutionProcessStatus",
+                      ep.started_at as "started_at!: DateTime<Utc>",
+                      ep.completed_at as "completed_at?: DateTime<Utc>"
+               FROM execution_processes ep
+               JOIN sessions s ON s.id = ep.session_id
+               JOIN workspaces w ON w.id = s.workspace_id
+               JOIN tasks t ON t.id = w.task_id
+               WHERE ($1 IS NULL OR t.project_id = $1)
+                 AND ($2 IS NULL OR t.created_at >= $2)
+                 AND ($3 IS NULL OR t.created_at < $3)
+               ORDER BY ep.started_at ASC"#,
+            filter.project_id,
+            filter.since,
+            filter.until
+        )
+        .fetch_all(pool)
+        .await?;
+
+        let merges = sqlx::query_as!(
+            AnalyticsMerge,
+            r#"SELECT w.id as "workspace_id!: Uuid",
+                      m.merge_type as "merge_type!: MergeType",
+                      m.pr_status as "pr_status?: MergeStatus",
+                      m.pr_merged_at as "pr_merged_at?: DateTime<Utc>",
+                      m.created_at as "created_at!: DateTime<Utc>"
+               FROM merges m
+               JOIN workspaces w ON w.id = m.workspace_id
+               JOIN tasks t ON t.id = w.task_id
+               WHERE ($1 IS NULL OR t.project_id = $1)
+                 AND ($2 IS NULL OR t.created_at >= $2)
+                 AND ($3 IS NULL OR t.created_at < $3)
+               ORDER BY m.created_at ASC"#,
+            filter.project_id,
+            filter.since,
+            filter.until
+        )
+        .fetch_all(pool)
+        .await?;
+
+        let events = sqlx::query_as!(
+            TaskEvent,
+            r#"SELECT e.id as "id!: Uuid",
+                      e.task_id as "task_id!: Uuid",
+                      e.old_status as "old_status?: TaskStatus",
+                      e.new_status as "new_status!: TaskStatus",
+                      e.actor as "actor!: TaskEventActor",
+                      e.created_at as "created_at!: DateTime<Utc>"
+               FROM task_events e
+               JOIN tasks t ON t.id = e.task_id
+               WHERE ($1 IS NULL OR t.project_id = $1)
+                 AND ($2 IS NULL OR t.created_at >= $2)
+                 AND ($3 IS NULL OR t.created_at < $3)
+               ORDER BY e.created_at ASC, e.rowid ASC"#,
+            filter.project_id,
+            filter.since,
+            filter.until
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(Self {
+            tasks,
+            attempts,
+            processes,
+            merges,
+            events,
+        })
+    }
+}
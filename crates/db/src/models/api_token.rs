@@ -0,0 +1,349 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::{FromRow, SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+const TOKEN_PREFIX_LEN: usize = 10;
+
+/// What a token can authenticate
+#[derive(Debug, Clone, Copy, Default, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "api_token_scope", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ApiTokenScope {
+    /// The API of the headless server mode
+    #[default]
+    Api,
+    /// The MCP Streamable HTTP endpoint
+    Mcp,
+}
+
+/// A token for the authenticated headless server mode. The token itself is only shown
+/// once, when it is created.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ApiToken {
+    pub id: Uuid,
+    pub name: String,
+    /// First characters of the token, to tell tokens apart
+    pub token_prefix: String,
+    /// Admin tokens can create and revoke tokens
+    pub is_admin: bool,
+    pub scope: ApiTokenScope,
+    /// The coding agent run the token was handed to; it is revoked when the run ends
+    pub execution_process_id: Option<Uuid>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct CreateApiToken {
+    pub name: String,
+    #[serde(default)]
+    pub is_admin: bool,
+    #[serde(default)]
+    pub scope: ApiTokenScope,
+}
+
+/// One authenticated request made with a token
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ApiTokenAuditEntry {
+    pub id: i64,
+    pub token_id: Uuid,
+    pub method: String,
+    pub path: String,
+    pub status: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+impl ApiToken {
+    pub async fn find_all(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ApiToken,
+            r#"SELECT id as "id!: Uuid",
+                      name,
+                      token_prefix,
+                      is_admin as "is_admin!: bool",
+                      scope as "scope!: ApiTokenScope",
+                      execution_process_id as "execution_process_id?: Uuid",
+                      last_used_at as "last_used_at?: DateTime<Utc>",
+                      revoked_at as "revoked_at?: DateTime<Utc>",
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM api_tokens
+               ORDER BY created_at ASC"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ApiToken,
+            r#"SELECT id as "id!: Uuid",
+                      name,
+                      token_prefix,
+                      is_admin as "is_admin!: bool",
+                      scope as "scope!: ApiTokenScope",
+                      execution_process_id as "execution_process_id?: Uuid",
+                      last_used_at as "last_used_at?: DateTime<Utc>",
+                      revoked_at as "revoked_at?: DateTime<Utc>",
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM api_tokens
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// The unrevoked token with this hash and scope
+    pub async fn find_active_by_hash(
+        pool: &SqlitePool,
+        token_hash: &str,
+        scope: ApiTokenScope,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ApiToken,
+            r#"SELECT id as "id!: Uuid",
+                      name,
+                      token_prefix,
+                      is_admin as "is_admin!: bool",
+                      scope as "scope!: ApiTokenScope",
+                      execution_process_id as "execution_process_id?: Uuid",
+                      last_used_at as "last_used_at?: DateTime<Utc>",
+                      revoked_at as "revoked_at?: DateTime<Utc>",
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM api_tokens
+               WHERE token_hash = $1 AND scope = $2 AND revoked_at IS NULL"#,
+            token_hash,
+            scope
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        data: &CreateApiToken,
+        token_hash: &str,
+        token_prefix: &str,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            ApiToken,
+            r#"INSERT INTO api_tokens (id, name, token_hash, token_prefix, is_admin, scope)
+               VALUES ($1, $2, $3, $4, $5, $6)
+               RETURNING id as "id!: Uuid",
+                         name,
+                         token_prefix,
+                         is_admin as "is_admin!: bool",
+                         scope as "scope!: ApiTokenScope",
+                         execution_process_id as "execution_process_id?: Uuid",
+                         last_used_at as "last_used_at?: DateTime<Utc>",
+                         revoked_at as "revoked_at?: DateTime<Utc>",
+                         created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            data.name,
+            token_hash,
+            token_prefix,
+            data.is_admin,
+            data.scope
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// A non-admin API token for a coding agent run, so the MCP server it starts can call the
+    /// API of a headless server. Returns the token along with its row.
+    pub async fn create_for_execution(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+    ) -> Result<(Self, String), sqlx::Error> {
+        let id = Uuid::new_v4();
+        let token = generate_token();
+        let token_hash = hash_token(&token);
+        let token_prefix = token_prefix(&token);
+        let name = format!("Coding agent {execution_process_id}");
+        let api_token = sqlx::query_as!(
+            ApiToken,
+            r#"INSERT INTO api_tokens
+                   (id, name, token_hash, token_prefix, is_admin, scope, execution_process_id)
+               VALUES ($1, $2, $3, $4, 0, 'api', $5)
+               RETURNING id as "id!: Uuid",
+                         name,
+                         token_prefix,
+                         is_admin as "is_admin!: bool",
+                         scope as "scope!: ApiTokenScope",
+                         execution_process_id as "execution_process_id?: Uuid",
+                         last_used_at as "last_used_at?: DateTime<Utc>",
+                         revoked_at as "revoked_at?: DateTime<Utc>",
+                         created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            name,
+            token_hash,
+            token_prefix,
+            execution_process_id
+        )
+        .fetch_one(pool)
+        .await?;
+        Ok((api_token, token))
+    }
+
+    /// Returns the number of tokens revoked, 0 if it was already revoked or does not exist
+    pub async fn revoke(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"UPDATE api_tokens
+               SET revoked_at = datetime('now', 'subsec')
+               WHERE id = $1 AND revoked_at IS NULL"#,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Revoke every active token with the scope, returning how many were revoked
+    pub async fn revoke_scope(pool: &SqlitePool, scope: ApiTokenScope) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"UPDATE api_tokens
+               SET revoked_at = datetime('now', 'subsec')
+               WHERE scope = $1 AND revoked_at IS NULL"#,
+            scope
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Revoke the token handed to a coding agent run, once the run has ended
+    pub async fn revoke_for_execution(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"UPDATE api_tokens
+               SET revoked_at = datetime('now', 'subsec')
+               WHERE execution_process_id = $1 AND revoked_at IS NULL"#,
+            execution_process_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Revoke the tokens of every coding agent run; no run survives a restart
+    pub async fn revoke_all_for_executions(pool: &SqlitePool) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"UPDATE api_tokens
+               SET revoked_at = datetime('now', 'subsec')
+               WHERE execution_process_id IS NOT NULL AND revoked_at IS NULL"#
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    pub async fn has_active(pool: &SqlitePool, scope: ApiTokenScope) -> Result<bool, sqlx::Error> {
+        let count = sqlx::query_scalar!(
+            r#"SELECT COUNT(*) as "count!: i64"
+               FROM api_tokens
+               WHERE scope = $1 AND revoked_at IS NULL"#,
+            scope
+        )
+        .fetch_one(pool)
+        .await?;
+        Ok(count > 0)
+    }
+
+    pub async fn touch(pool: &SqlitePool, id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE api_tokens SET last_used_at = datetime('now', 'subsec') WHERE id = $1",
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
+
+/// A new random token; only its hash is stored
+pub fn generate_token() -> String {
+    format!("vk_{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple())
+}
+
+pub fn hash_token(token: &str) -> String {
+    let mut output = String::with_capacity(64);
+    let digest = Sha256::digest(token.as_bytes());
+    for byte in digest {
+        use std::fmt::Write;
+        let _ = write!(output, "{:02x}", byte);
+    }
+    output
+}
+
+pub fn token_prefix(token: &str) -> String {
+    token.chars().take(TOKEN_PREFIX_LEN).collect()
+}
+
+impl ApiTokenAuditEntry {
+    pub async fn create(
+        pool: &SqlitePool,
+        token_id: Uuid,
+        method: &str,
+        path: &str,
+        status: i64,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"INSERT INTO api_token_audit_log (token_id, method, path, status)
+               VALUES ($1, $2, $3, $4)"#,
+            token_id,
+            method,
+            path,
+            status
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Most recent first
+    pub async fn find_by_token_id(
+        pool: &SqlitePool,
+        token_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ApiTokenAuditEntry,
+            r#"SELECT id as "id!: i64",
+                      token_id as "token_id!: Uuid",
+                      method,
+                      path,
+                      status as "status!: i64",
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM api_token_audit_log
+               WHERE token_id = $1
+               ORDER BY id DESC
+               LIMIT $2"#,
+            token_id,
+            limit
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hashes_and_prefixes_tokens() {
+        let token = generate_token();
+        assert!(token.starts_with("vk_"));
+        assert_eq!(token.len(), 67);
+        assert_eq!(token_prefix(&token).len(), TOKEN_PREFIX_LEN);
+        assert_eq!(hash_token(&token), hash_token(&token));
+        assert_ne!(hash_token(&token), hash_token(&generate_token()));
+    }
+}
@@ -0,0 +1,203 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::{FromRow, SqlitePool, Type};
+use ts_rs::TS;
+use utils::approvals::{ApprovalRequest, ApprovalStatus};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "approval_status", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ApprovalDecision {
+    Pending,
+    Approved,
+    Denied,
+    TimedOut,
+}
+
+/// What decided an approval request
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "approval_actor", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ApprovalActor {
+    /// The UI or another API client
+    User,
+    Rule,
+    Timeout,
+}
+
+impl ApprovalDecision {
+    fn from_status(status: &ApprovalStatus) -> (Self, Option<&str>) {
+        match status {
+            ApprovalStatus::Pending => (Self::Pending, None),
+            ApprovalStatus::Approved => (Self::Approved, None),
+            ApprovalStatus::Denied { reason } => (Self::Denied, reason.as_deref()),
+            ApprovalStatus::TimedOut => (Self::TimedOut, None),
+        }
+    }
+}
+
+/// A tool approval request and how it was decided
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct Approval {
+    pub id: String,
+    pub execution_process_id: Uuid,
+    pub tool_name: String,
+    #[ts(type = "JsonValue")]
+    pub tool_input: sqlx::types::Json<Value>,
+    pub tool_call_id: String,
+    pub status: ApprovalDecision,
+    pub denial_reason: Option<String>,
+    /// The approval rule that decided the request instead of the user
+    pub rule_id: Option<Uuid>,
+    /// `None` while the request is pending
+    pub actor: Option<ApprovalActor>,
+    pub created_at: DateTime<Utc>,
+    pub timeout_at: DateTime<Utc>,
+    pub responded_at: Option<DateTime<Utc>>,
+}
+
+impl Approval {
+    /// Record a request, either pending or already decided by an approval rule
+    pub async fn create(
+        pool: &SqlitePool,
+        request: &ApprovalRequest,
+        status: &ApprovalStatus,
+        rule_id: Option<Uuid>,
+    ) -> Result<(), sqlx::Error> {
+        let (decision, denial_reason) = ApprovalDecision::from_status(status);
+        let tool_input = sqlx::types::Json(&request.tool_input);
+        let decided = decision != ApprovalDecision::Pending;
+        let responded_at = decided.then(Utc::now);
+        let actor = decided.then(|| {
+            if rule_id.is_some() {
+                ApprovalActor::Rule
+            } else {
+                ApprovalActor::User
+            }
+        });
+        sqlx::query!(
+            r#"INSERT INTO approvals
+                   (id, execution_process_id, tool_name, tool_input, tool_call_id, status,
+                    denial_reason, rule_id, actor, created_at, timeout_at, responded_at)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)"#,
+            request.id,
+            request.execution_process_id,
+            request.tool_name,
+            tool_input,
+            request.tool_call_id,
+            decision,
+            denial_reason,
+            rule_id,
+            actor,
+            request.created_at,
+            request.timeout_at,
+            responded_at
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Record the decision of a pending request. Returns false if it was already decided.
+    pub async fn update_status(
+        pool: &SqlitePool,
+        id: &str,
+        status: &ApprovalStatus,
+        actor: ApprovalActor,
+    ) -> Result<bool, sqlx::Error> {
+        let (decision, denial_reason) = ApprovalDecision::from_status(status);
+        let now = Utc::now();
+        let result = sqlx::query!(
+            r#"UPDATE approvals
+               SET status = $2, denial_reason = $3, actor = $4, responded_at = $5
+               WHERE id = $1 AND status = 'pending'"#,
+            id,
+            decision,
+            denial_reason,
+            actor,
+            now
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: &str) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Approval,
+            r#"SELECT id as "id!",
+                      execution_process_id as "execution_process_id!: Uuid",
+                      tool_name,
+                      tool_input as "tool_input!: sqlx::types::Json<Value>",
+                      tool_call_id,
+                      status as "status!: ApprovalDecision",
+                      denial_reason,
+                      rule_id as "rule_id: Uuid",
+                      actor as "actor: ApprovalActor",
+                      created_at as "created_at!: DateTime<Utc>",
+                      timeout_at as "timeout_at!: DateTime<Utc>",
+                      responded_at as "responded_at: DateTime<Utc>"
+               FROM approvals
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn find_pending(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Approval,
+            r#"SELECT id as "id!",
+                      execution_process_id as "execution_process_id!: Uuid",
+                      tool_name,
+                      tool_input as "tool_input!: sqlx::types::Json<Value>",
+                      tool_call_id,
+                      status as "status!: ApprovalDecision",
+                      denial_reason,
+                      rule_id as "rule_id: Uuid",
+                      actor as "actor: ApprovalActor",
+                      created_at as "created_at!: DateTime<Utc>",
+                      timeout_at as "timeout_at!: DateTime<Utc>",
+                      responded_at as "responded_at: DateTime<Utc>"
+               FROM approvals
+               WHERE status = 'pending'
+               ORDER BY created_at ASC"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Every approval requested by the task's coding agents, oldest first
+    pub async fn find_by_task_id(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Approval,
+            r#"SELECT a.id as "id!",
+                      a.execution_process_id as "execution_process_id!: Uuid",
+                      a.tool_name,
+                      a.tool_input as "tool_input!: sqlx::types::Json<Value>",
+                      a.tool_call_id,
+                      a.status as "status!: ApprovalDecision",
+                      a.denial_reason,
+                      a.rule_id as "rule_id: Uuid",
+                      a.actor as "actor: ApprovalActor",
+                      a.created_at as "created_at!: DateTime<Utc>",
+                      a.timeout_at as "timeout_at!: DateTime<Utc>",
+                      a.responded_at as "responded_at: DateTime<Utc>"
+               FROM approvals a
+               JOIN execution_processes ep ON ep.id = a.execution_process_id
+               JOIN sessions s ON s.id = ep.session_id
+               JOIN workspaces w ON w.id = s.workspace_id
+               WHERE w.task_id = $1
+               ORDER BY a.created_at ASC"#,
+            task_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
@@ -0,0 +1,318 @@
+use chrono::{DateTime, Utc};
+use executors::profile::ExecutorProfileId;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use thiserror::Error;
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+pub enum AttemptGroupError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error("Workspace {0} is not part of this attempt group")]
+    NotAMember(Uuid),
+    #[error("A different winner has already been selected for this attempt group")]
+    WinnerAlreadySelected,
+}
+
+/// A best-of-N run: one task started in several workspaces, each with its own executor
+/// profile, so the results can be compared and one kept
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct AttemptGroup {
+    pub id: Uuid,
+    pub task_id: Uuid,
+    pub winner_workspace_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct AttemptGroupMember {
+    pub group_id: Uuid,
+    pub workspace_id: Uuid,
+    #[ts(type = "ExecutorProfileId")]
+    pub executor_profile_id: sqlx::types::Json<ExecutorProfileId>,
+    /// Set once another member was picked as the winner and this workspace was cleaned up
+    pub archived_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl AttemptGroup {
+    pub async fn create(pool: &SqlitePool, task_id: Uuid) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            AttemptGroup,
+            r#"INSERT INTO attempt_groups (id, task_id)
+               VALUES ($1, $2)
+               RETURNING id as "id!: Uuid",
+                         task_id as "task_id!: Uuid",
+                         winner_workspace_id as "winner_workspace_id: Uuid",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            task_id
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            AttemptGroup,
+            r#"SELECT id as "id!: Uuid",
+                      task_id as "task_id!: Uuid",
+                      winner_workspace_id as "winner_workspace_id: Uuid",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM attempt_groups
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Most recent groups first
+    pub async fn find_by_task_id(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            AttemptGroup,
+            r#"SELECT id as "id!: Uuid",
+                      task_id as "task_id!: Uuid",
+                      winner_workspace_id as "winner_workspace_id: Uuid",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM attempt_groups
+               WHERE task_id = $1
+               ORDER BY created_at DESC"#,
+            task_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Record `workspace_id` as the winner. A group's winner can only be picked once, since
+    /// the losing workspaces are cleaned up afterwards; picking the same winner again is
+    /// accepted so that cleanup can be retried.
+    pub async fn set_winner(
+        pool: &SqlitePool,
+        id: Uuid,
+        workspace_id: Uuid,
+    ) -> Result<Self, AttemptGroupError> {
+        let members = AttemptGroupMember::find_by_group_id(pool, id).await?;
+        if !members.iter().any(|m| m.workspace_id == workspace_id) {
+            return Err(AttemptGroupError::NotAMember(workspace_id));
+        }
+
+        sqlx::query_as!(
+            AttemptGroup,
+            r#"UPDATE attempt_groups
+               SET winner_workspace_id = $2, updated_at = datetime('now', 'subsec')
+               WHERE id = $1 AND (winner_workspace_id IS NULL OR winner_workspace_id = $2)
+               RETURNING id as "id!: Uuid",
+                         task_id as "task_id!: Uuid",
+                         winner_workspace_id as "winner_workspace_id: Uuid",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            workspace_id
+        )
+        .fetch_optional(pool)
+        .await?
+        .ok_or(AttemptGroupError::WinnerAlreadySelected)
+    }
+}
+
+impl AttemptGroupMember {
+    pub async fn create(
+        pool: &SqlitePool,
+        group_id: Uuid,
+        workspace_id: Uuid,
+        executor_profile_id: &ExecutorProfileId,
+    ) -> Result<Self, sqlx::Error> {
+        let executor_profile_id = sqlx::types::Json(executor_profile_id);
+        sqlx::query_as!(
+            AttemptGroupMember,
+            r#"INSERT INTO attempt_group_members (group_id, workspace_id, executor_profile_id)
+               VALUES ($1, $2, $3)
+               RETURNING group_id as "group_id!: Uuid",
+                         workspace_id as "workspace_id!: Uuid",
+                         executor_profile_id as "executor_profile_id!: sqlx::types::Json<ExecutorProfileId>",
+                         archived_at as "archived_at: DateTime<Utc>",
+                         created_at as "created_at!: DateTime<Utc>""#,
+            group_id,
+            workspace_id,
+            executor_profile_id
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Members in the order they were started
+    pub async fn find_by_group_id(
+        pool: &SqlitePool,
+        group_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            AttemptGroupMember,
+            r#"SELECT group_id as "group_id!: Uuid",
+                      workspace_id as "workspace_id!: Uuid",
+                      executor_profile_id as "executor_profile_id!: sqlx::types::Json<ExecutorProfileId>",
+                      archived_at as "archived_at: DateTime<Utc>",
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM attempt_group_members
+               WHERE group_id = $1
+               ORDER BY rowid ASC"#,
+            group_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn mark_archived(
+        pool: &SqlitePool,
+        group_id: Uuid,
+        workspace_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE attempt_group_members
+               SET archived_at = datetime('now', 'subsec')
+               WHERE group_id = $1 AND workspace_id = $2 AND archived_at IS NULL"#,
+            group_id,
+            workspace_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use executors::executors::BaseCodingAgent;
+
+    use super::*;
+    use crate::models::{
+        project::{CreateProject, Project},
+        task::{CreateTask, Task},
+        task_event::TaskEventActor,
+        workspace::{CreateWorkspace, Workspace},
+    };
+
+    #[sqlx::test]
+    async fn winner_is_picked_once_and_losers_are_archived(pool: SqlitePool) {
+        let project = Project::create(
+            &pool,
+            &CreateProject {
+                name: "best of n".to_string(),
+                repositories: vec![],
+            },
+            Uuid::new_v4(),
+        )
+        .await
+        .unwrap();
+        let data = CreateTask::from_title_description(project.id, "task".to_string(), None);
+        let task = Task::create(&pool, &data, Uuid::new_v4(), TaskEventActor::User)
+            .await
+            .unwrap();
+        let group = AttemptGroup::create(&pool, task.id).await.unwrap();
+
+        let mut workspace_ids = Vec::new();
+        for (i, agent) in [
+            BaseCodingAgent::ClaudeCode,
+            BaseCodingAgent::Codex,
+            BaseCodingAgent::Gemini,
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let workspace = Workspace::create(
+                &pool,
+                &CreateWorkspace {
+                    branch: format!("vk/attempt-{i}"),
+                    agent_working_dir: None,
+                },
+                Uuid::new_v4(),
+                task.id,
+            )
+            .await
+            .unwrap();
+            AttemptGroupMember::create(
+                &pool,
+                group.id,
+                workspace.id,
+                &ExecutorProfileId::new(agent),
+            )
+            .await
+            .unwrap();
+            workspace_ids.push(workspace.id);
+        }
+        let (winner, losers) = (workspace_ids[1], [workspace_ids[0], workspace_ids[2]]);
+
+        // A workspace of the same task outside the group cannot win
+        let outsider = Workspace::create(
+            &pool,
+            &CreateWorkspace {
+                branch: "vk/outsider".to_string(),
+                agent_working_dir: None,
+            },
+            Uuid::new_v4(),
+            task.id,
+        )
+        .await
+        .unwrap();
+        assert!(matches!(
+            AttemptGroup::set_winner(&pool, group.id, outsider.id).await,
+            Err(AttemptGroupError::NotAMember(id)) if id == outsider.id
+        ));
+
+        let picked = AttemptGroup::set_winner(&pool, group.id, winner)
+            .await
+            .unwrap();
+        assert_eq!(picked.winner_workspace_id, Some(winner));
+
+        assert!(matches!(
+            AttemptGroup::set_winner(&pool, group.id, losers[0]).await,
+            Err(AttemptGroupError::WinnerAlreadySelected)
+        ));
+        // Picking the same winner again is accepted so cleanup can be retried
+        let repicked = AttemptGroup::set_winner(&pool, group.id, winner)
+            .await
+            .unwrap();
+        assert_eq!(repicked.winner_workspace_id, Some(winner));
+        let stored = AttemptGroup::find_by_id(&pool, group.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored.winner_workspace_id, Some(winner));
+
+        AttemptGroupMember::mark_archived(&pool, group.id, losers[0])
+            .await
+            .unwrap();
+        let first_archived_at = AttemptGroupMember::find_by_group_id(&pool, group.id)
+            .await
+            .unwrap()[0]
+            .archived_at;
+        assert!(first_archived_at.is_some());
+        // A retry archives the remaining loser and keeps the first timestamp
+        for loser in losers {
+            AttemptGroupMember::mark_archived(&pool, group.id, loser)
+                .await
+                .unwrap();
+        }
+
+        let members = AttemptGroupMember::find_by_group_id(&pool, group.id)
+            .await
+            .unwrap();
+        assert_eq!(
+            members.iter().map(|m| m.workspace_id).collect::<Vec<_>>(),
+            workspace_ids
+        );
+        assert_eq!(members[0].archived_at, first_archived_at);
+        assert!(members[1].archived_at.is_none());
+        assert!(members[2].archived_at.is_some());
+    }
+}
@@ -2,6 +2,7 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::{FromRow, SqlitePool};
 use ts_rs::TS;
+use utils::log_msg::TokenUsage;
 use uuid::Uuid;
 
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
@@ -11,6 +12,11 @@ pub struct CodingAgentTurn {
     pub agent_session_id: Option<String>, // Session ID from Claude/Amp coding agent
     pub prompt: Option<String>,           // The prompt sent to the executor
     pub summary: Option<String>,          // Final assistant message/summary
+    pub model: Option<String>,            // Model that reported the token usage
+    pub input_tokens: Option<i64>,
+    pub output_tokens: Option<i64>,
+    pub cache_read_tokens: Option<i64>,
+    pub cache_write_tokens: Option<i64>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -35,6 +41,11 @@ impl CodingAgentTurn {
                 agent_session_id,
                 prompt,
                 summary,
+                model,
+                input_tokens,
+                output_tokens,
+                cache_read_tokens,
+                cache_write_tokens,
                 created_at as "created_at!: DateTime<Utc>",
                 updated_at as "updated_at!: DateTime<Utc>"
                FROM coding_agent_turns
@@ -57,6 +68,11 @@ impl CodingAgentTurn {
                 agent_session_id,
                 prompt,
                 summary,
+                model,
+                input_tokens,
+                output_tokens,
+                cache_read_tokens,
+                cache_write_tokens,
                 created_at as "created_at!: DateTime<Utc>",
                 updated_at as "updated_at!: DateTime<Utc>"
                FROM coding_agent_turns
@@ -96,6 +112,11 @@ impl CodingAgentTurn {
                 agent_session_id,
                 prompt,
                 summary,
+                model,
+                input_tokens,
+                output_tokens,
+                cache_read_tokens,
+                cache_write_tokens,
                 created_at as "created_at!: DateTime<Utc>",
                 updated_at as "updated_at!: DateTime<Utc>""#,
             id,
@@ -151,4 +172,38 @@ impl CodingAgentTurn {
 
         Ok(())
     }
+
+    /// Record the latest usage totals reported by the coding agent
+    pub async fn update_token_usage(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        usage: &TokenUsage,
+    ) -> Result<(), sqlx::Error> {
+        let now = Utc::now();
+        let input_tokens = usage.input_tokens as i64;
+        let output_tokens = usage.output_tokens as i64;
+        let cache_read_tokens = usage.cache_read_tokens as i64;
+        let cache_write_tokens = usage.cache_write_tokens as i64;
+        sqlx::query!(
+            r#"UPDATE coding_agent_turns
+               SET model = COALESCE($1, model),
+                   input_tokens = $2,
+                   output_tokens = $3,
+                   cache_read_tokens = $4,
+                   cache_write_tokens = $5,
+                   updated_at = $6
+               WHERE execution_process_id = $7"#,
+            usage.model,
+            input_tokens,
+            output_tokens,
+            cache_read_tokens,
+            cache_write_tokens,
+            now,
+            execution_process_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
 }
@@ -0,0 +1,97 @@
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, SqlitePool, types::Json};
+use uuid::Uuid;
+
+/// A coding agent run resolving the conflicts of a stopped git operation in one repository of
+/// a workspace
+#[derive(Debug, Clone, FromRow)]
+pub struct ConflictResolution {
+    pub execution_process_id: Uuid,
+    pub workspace_id: Uuid,
+    pub repo_name: String,
+    pub worktree_path: String,
+    /// Git subcommand of the stopped operation, e.g. `rebase`
+    pub op: String,
+    pub conflicted_files: Json<Vec<String>>,
+    /// 1 for the first agent run on this operation
+    pub round: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CreateConflictResolution {
+    pub workspace_id: Uuid,
+    pub repo_name: String,
+    pub worktree_path: String,
+    pub op: String,
+    pub conflicted_files: Vec<String>,
+    pub round: i64,
+}
+
+impl ConflictResolution {
+    pub async fn create(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        data: &CreateConflictResolution,
+    ) -> Result<(), sqlx::Error> {
+        let conflicted_files = Json(&data.conflicted_files);
+        sqlx::query!(
+            r#"INSERT INTO conflict_resolutions
+                   (execution_process_id, workspace_id, repo_name, worktree_path, op,
+                    conflicted_files, round)
+               VALUES ($1, $2, $3, $4, $5, $6, $7)"#,
+            execution_process_id,
+            data.workspace_id,
+            data.repo_name,
+            data.worktree_path,
+            data.op,
+            conflicted_files,
+            data.round
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Remove and return the resolution an execution process was working on, so only one
+    /// caller acts on it
+    pub async fn take(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ConflictResolution,
+            r#"DELETE FROM conflict_resolutions
+               WHERE execution_process_id = $1
+               RETURNING execution_process_id as "execution_process_id!: Uuid",
+                         workspace_id as "workspace_id!: Uuid",
+                         repo_name,
+                         worktree_path,
+                         op,
+                         conflicted_files as "conflicted_files!: Json<Vec<String>>",
+                         round,
+                         created_at as "created_at!: DateTime<Utc>""#,
+            execution_process_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Remove and return every resolution, e.g. on startup when none of the runs are alive
+    pub async fn take_all(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ConflictResolution,
+            r#"DELETE FROM conflict_resolutions
+               RETURNING execution_process_id as "execution_process_id!: Uuid",
+                         workspace_id as "workspace_id!: Uuid",
+                         repo_name,
+                         worktree_path,
+                         op,
+                         conflicted_files as "conflicted_files!: Json<Vec<String>>",
+                         round,
+                         created_at as "created_at!: DateTime<Utc>""#
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
@@ -5,7 +5,7 @@ use executors::{
 };
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use sqlx::{FromRow, SqlitePool, Type};
+use sqlx::{Executor, FromRow, Sqlite, SqlitePool, Type};
 use thiserror::Error;
 use ts_rs::TS;
 use uuid::Uuid;
@@ -55,6 +55,17 @@ pub enum ExecutionProcessRunReason {
     CleanupScript,
     CodingAgent,
     DevServer,
+    QualityGate,
+}
+
+/// Why the container stopped a process that exceeded its profile's `ExecutionLimits`
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, TS)]
+#[sqlx(type_name = "execution_failure_reason", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum ExecutionFailureReason {
+    Timeout,
+    ToolCallLimit,
+    LogLimit,
 }
 
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
@@ -66,6 +77,7 @@ pub struct ExecutionProcess {
     pub executor_action: sqlx::types::Json<ExecutorActionField>,
     pub status: ExecutionProcessStatus,
     pub exit_code: Option<i64>,
+    pub failure_reason: Option<ExecutionFailureReason>,
     /// dropped: true if this process is excluded from the current
     /// history view (due to restore/trimming). Hidden from logs/timeline;
     /// still listed in the Processes tab.
@@ -83,6 +95,20 @@ pub struct CreateExecutionProcess {
     pub run_reason: ExecutionProcessRunReason,
 }
 
+/// A finished execution process recreated from an exported project, keeping its outcome and
+/// timestamps
+#[derive(Debug)]
+pub struct CreateImportedExecutionProcess {
+    pub session_id: Uuid,
+    pub executor_action: Value,
+    pub run_reason: ExecutionProcessRunReason,
+    pub status: ExecutionProcessStatus,
+    pub exit_code: Option<i64>,
+    pub dropped: bool,
+    pub started_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
 #[derive(Debug, Deserialize, TS)]
 #[allow(dead_code)]
 pub struct UpdateExecutionProcess {
@@ -131,6 +157,7 @@ impl ExecutionProcess {
                     ep.executor_action as "executor_action!: sqlx::types::Json<ExecutorActionField>",
                     ep.status as "status!: ExecutionProcessStatus",
                     ep.exit_code,
+                    ep.failure_reason as "failure_reason: ExecutionFailureReason",
                     ep.dropped as "dropped!: bool",
                     ep.started_at as "started_at!: DateTime<Utc>",
                     ep.completed_at as "completed_at?: DateTime<Utc>",
@@ -205,6 +232,7 @@ impl ExecutionProcess {
                     ep.executor_action as "executor_action!: sqlx::types::Json<ExecutorActionField>",
                     ep.status as "status!: ExecutionProcessStatus",
                     ep.exit_code,
+                    ep.failure_reason as "failure_reason: ExecutionFailureReason",
                     ep.dropped as "dropped!: bool",
                     ep.started_at as "started_at!: DateTime<Utc>",
                     ep.completed_at as "completed_at?: DateTime<Utc>",
@@ -232,6 +260,7 @@ impl ExecutionProcess {
                       ep.executor_action as "executor_action!: sqlx::types::Json<ExecutorActionField>",
                       ep.status          as "status!: ExecutionProcessStatus",
                       ep.exit_code,
+                      ep.failure_reason as "failure_reason: ExecutionFailureReason",
                       ep.dropped as "dropped!: bool",
                       ep.started_at      as "started_at!: DateTime<Utc>",
                       ep.completed_at    as "completed_at?: DateTime<Utc>",
@@ -259,6 +288,7 @@ impl ExecutionProcess {
                     ep.executor_action as "executor_action!: sqlx::types::Json<ExecutorActionField>",
                     ep.status as "status!: ExecutionProcessStatus",
                     ep.exit_code,
+                    ep.failure_reason as "failure_reason: ExecutionFailureReason",
                     ep.dropped as "dropped!: bool",
                     ep.started_at as "started_at!: DateTime<Utc>",
                     ep.completed_at as "completed_at?: DateTime<Utc>",
@@ -278,7 +308,7 @@ impl ExecutionProcess {
         sqlx::query_as!(
             ExecutionProcess,
             r#"SELECT ep.id as "id!: Uuid", ep.session_id as "session_id!: Uuid", ep.run_reason as "run_reason!: ExecutionProcessRunReason", ep.executor_action as "executor_action!: sqlx::types::Json<ExecutorActionField>",
-                      ep.status as "status!: ExecutionProcessStatus", ep.exit_code,
+                      ep.status as "status!: ExecutionProcessStatus", ep.exit_code, ep.failure_reason as "failure_reason: ExecutionFailureReason",
                       ep.dropped as "dropped!: bool", ep.started_at as "started_at!: DateTime<Utc>", ep.completed_at as "completed_at?: DateTime<Utc>", ep.created_at as "created_at!: DateTime<Utc>", ep.updated_at as "updated_at!: DateTime<Utc>"
                FROM execution_processes ep
                JOIN sessions s ON ep.session_id = s.id
@@ -326,6 +356,7 @@ impl ExecutionProcess {
             ep.executor_action as "executor_action!: sqlx::types::Json<ExecutorActionField>",
             ep.status as "status!: ExecutionProcessStatus",
             ep.exit_code,
+            ep.failure_reason as "failure_reason: ExecutionFailureReason",
             ep.dropped as "dropped!: bool",
             ep.started_at as "started_at!: DateTime<Utc>",
             ep.completed_at as "completed_at?: DateTime<Utc>",
@@ -388,6 +419,7 @@ impl ExecutionProcess {
                     ep.executor_action as "executor_action!: sqlx::types::Json<ExecutorActionField>",
                     ep.status as "status!: ExecutionProcessStatus",
                     ep.exit_code,
+                    ep.failure_reason as "failure_reason: ExecutionFailureReason",
                     ep.dropped as "dropped!: bool",
                     ep.started_at as "started_at!: DateTime<Utc>",
                     ep.completed_at as "completed_at?: DateTime<Utc>",
@@ -418,6 +450,7 @@ impl ExecutionProcess {
                     ep.executor_action as "executor_action!: sqlx::types::Json<ExecutorActionField>",
                     ep.status as "status!: ExecutionProcessStatus",
                     ep.exit_code,
+                    ep.failure_reason as "failure_reason: ExecutionFailureReason",
                     ep.dropped as "dropped!: bool",
                     ep.started_at as "started_at!: DateTime<Utc>",
                     ep.completed_at as "completed_at?: DateTime<Utc>",
@@ -434,6 +467,38 @@ impl ExecutionProcess {
         .await
     }
 
+    /// Find execution processes by workspace and run reason (across all sessions), newest first
+    pub async fn find_by_workspace_and_run_reason(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        run_reason: &ExecutionProcessRunReason,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ExecutionProcess,
+            r#"SELECT
+                    ep.id as "id!: Uuid",
+                    ep.session_id as "session_id!: Uuid",
+                    ep.run_reason as "run_reason!: ExecutionProcessRunReason",
+                    ep.executor_action as "executor_action!: sqlx::types::Json<ExecutorActionField>",
+                    ep.status as "status!: ExecutionProcessStatus",
+                    ep.exit_code,
+                    ep.failure_reason as "failure_reason: ExecutionFailureReason",
+                    ep.dropped as "dropped!: bool",
+                    ep.started_at as "started_at!: DateTime<Utc>",
+                    ep.completed_at as "completed_at?: DateTime<Utc>",
+                    ep.created_at as "created_at!: DateTime<Utc>",
+                    ep.updated_at as "updated_at!: DateTime<Utc>"
+               FROM execution_processes ep
+               JOIN sessions s ON ep.session_id = s.id
+               WHERE s.workspace_id = ? AND ep.run_reason = ? AND ep.dropped = FALSE
+               ORDER BY ep.created_at DESC"#,
+            workspace_id,
+            run_reason
+        )
+        .fetch_all(pool)
+        .await
+    }
+
     /// Create a new execution process
     ///
     /// Note: We intentionally avoid using a transaction here. SQLite update
@@ -476,11 +541,43 @@ impl ExecutionProcess {
             .ok_or(sqlx::Error::RowNotFound)
     }
 
+    /// Insert a process that is not run again, e.g. imported history
+    pub async fn create_imported(
+        executor: impl Executor<'_, Database = Sqlite>,
+        data: &CreateImportedExecutionProcess,
+        process_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        let executor_action_json = sqlx::types::Json(&data.executor_action);
+        let completed_at = data.completed_at.unwrap_or(data.started_at);
+
+        sqlx::query!(
+            r#"INSERT INTO execution_processes (
+                    id, session_id, run_reason, executor_action, status, exit_code, dropped,
+                    started_at, completed_at, created_at, updated_at
+                ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"#,
+            process_id,
+            data.session_id,
+            data.run_reason,
+            executor_action_json,
+            data.status,
+            data.exit_code,
+            data.dropped,
+            data.started_at,
+            completed_at,
+            data.started_at,
+            completed_at
+        )
+        .execute(executor)
+        .await?;
+        Ok(())
+    }
+
     pub async fn was_stopped(pool: &SqlitePool, id: Uuid) -> bool {
         if let Ok(exp_process) = Self::find_by_id(pool, id).await
             && exp_process.is_some_and(|ep| {
                 ep.status == ExecutionProcessStatus::Killed
                     || ep.status == ExecutionProcessStatus::Completed
+                    || ep.failure_reason.is_some()
             })
         {
             return true;
@@ -516,6 +613,25 @@ impl ExecutionProcess {
         Ok(())
     }
 
+    /// Record that the runaway guard stopped this process
+    pub async fn set_failure_reason(
+        pool: &SqlitePool,
+        id: Uuid,
+        reason: ExecutionFailureReason,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE execution_processes
+               SET failure_reason = $1
+               WHERE id = $2"#,
+            reason,
+            id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
     pub fn executor_action(&self) -> Result<&ExecutorAction, anyhow::Error> {
         match &self.executor_action.0 {
             ExecutorActionField::ExecutorAction(action) => Ok(action),
@@ -644,6 +760,7 @@ impl ExecutionProcess {
                     ep.executor_action as "executor_action!: sqlx::types::Json<ExecutorActionField>",
                     ep.status as "status!: ExecutionProcessStatus",
                     ep.exit_code,
+                    ep.failure_reason as "failure_reason: ExecutionFailureReason",
                     ep.dropped as "dropped!: bool",
                     ep.started_at as "started_at!: DateTime<Utc>",
                     ep.completed_at as "completed_at?: DateTime<Utc>",
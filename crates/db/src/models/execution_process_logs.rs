@@ -1,10 +1,27 @@
+use std::path::{Path, PathBuf};
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, SqlitePool};
+use sqlx::{Executor, FromRow, Sqlite, SqlitePool};
+use thiserror::Error;
 use ts_rs::TS;
-use utils::log_msg::LogMsg;
+use utils::{assets::asset_dir, log_msg::LogMsg};
 use uuid::Uuid;
 
+const ARCHIVE_COMPRESSION_LEVEL: i32 = 3;
+const ARCHIVE_FILE_EXTENSION: &str = "jsonl.zst";
+/// Compressed size from which archives are spilled to files when that is enabled. Smaller
+/// archives stay in the database, where they cost less than a file each.
+pub const SPILL_THRESHOLD_BYTES: usize = 64 * 1024;
+
+#[derive(Debug, Error)]
+pub enum ExecutionProcessLogsError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
 pub struct ExecutionProcessLogs {
     pub execution_id: Uuid,
@@ -13,26 +30,55 @@ pub struct ExecutionProcessLogs {
     pub inserted_at: DateTime<Utc>,
 }
 
+/// All log rows of a completed execution process, compressed into one zstd blob
+#[derive(Debug, Clone, FromRow)]
+pub struct ExecutionProcessLogArchive {
+    pub execution_id: Uuid,
+    pub data: Option<Vec<u8>>,     // None when spilled to `file_name`
+    pub file_name: Option<String>, // Relative to `ExecutionProcessLogArchive::dir()`
+    pub line_count: i64,
+    pub byte_size: i64,
+    pub compressed_size: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
 impl ExecutionProcessLogs {
-    /// Find logs by execution process ID
+    /// Find logs by execution process ID. Compacted logs are decompressed and returned as a
+    /// single leading record, followed by any rows appended since.
     pub async fn find_by_execution_id(
         pool: &SqlitePool,
         execution_id: Uuid,
-    ) -> Result<Vec<Self>, sqlx::Error> {
-        sqlx::query_as!(
-            ExecutionProcessLogs,
-            r#"SELECT 
-                execution_id as "execution_id!: Uuid",
-                logs,
-                byte_size,
-                inserted_at as "inserted_at!: DateTime<Utc>"
-               FROM execution_process_logs 
-               WHERE execution_id = $1
-               ORDER BY inserted_at ASC"#,
-            execution_id
-        )
-        .fetch_all(pool)
-        .await
+    ) -> Result<Vec<Self>, ExecutionProcessLogsError> {
+        let mut records = Vec::new();
+        if let Some(archive) =
+            ExecutionProcessLogArchive::find_by_execution_id(pool, execution_id).await?
+        {
+            records.push(Self {
+                execution_id,
+                logs: archive.decompress().await?,
+                byte_size: archive.byte_size,
+                inserted_at: archive.updated_at,
+            });
+        }
+
+        records.extend(
+            sqlx::query_as!(
+                ExecutionProcessLogs,
+                r#"SELECT
+                    execution_id as "execution_id!: Uuid",
+                    logs,
+                    byte_size,
+                    inserted_at as "inserted_at!: DateTime<Utc>"
+                   FROM execution_process_logs
+                   WHERE execution_id = $1
+                   ORDER BY inserted_at ASC"#,
+                execution_id
+            )
+            .fetch_all(pool)
+            .await?,
+        );
+        Ok(records)
     }
 
     /// Parse JSONL logs back into Vec<LogMsg>
@@ -49,7 +95,7 @@ impl ExecutionProcessLogs {
 
     /// Append a JSONL line to the logs for an execution process
     pub async fn append_log_line(
-        pool: &SqlitePool,
+        executor: impl Executor<'_, Database = Sqlite>,
         execution_id: Uuid,
         jsonl_line: &str,
     ) -> Result<(), sqlx::Error> {
@@ -61,9 +107,687 @@ impl ExecutionProcessLogs {
             jsonl_line,
             byte_size
         )
-        .execute(pool)
+        .execute(executor)
         .await?;
 
         Ok(())
     }
+
+    /// Completed execution processes that still have uncompacted log rows, oldest first
+    pub async fn find_compactable(
+        pool: &SqlitePool,
+        completed_before: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<Uuid>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT ep.id as "id!: Uuid"
+               FROM execution_processes ep
+               WHERE ep.completed_at IS NOT NULL
+                 AND datetime(ep.completed_at) < datetime($1)
+                 AND EXISTS (SELECT 1 FROM execution_process_logs l WHERE l.execution_id = ep.id)
+               ORDER BY ep.completed_at ASC
+               LIMIT $2"#,
+            completed_before,
+            limit
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Collapse the log rows of `execution_id` into its archive, merging with an existing
+    /// archive if the process was compacted before. With `spill_to_file`, archives of at
+    /// least `SPILL_THRESHOLD_BYTES` compressed are written under
+    /// `ExecutionProcessLogArchive::dir()` instead of the database.
+    pub async fn compact(
+        pool: &SqlitePool,
+        execution_id: Uuid,
+        spill_to_file: bool,
+    ) -> Result<Option<ExecutionProcessLogArchive>, ExecutionProcessLogsError> {
+        let spill_threshold = spill_to_file.then_some(SPILL_THRESHOLD_BYTES);
+        Self::compact_in(
+            pool,
+            execution_id,
+            spill_threshold,
+            &ExecutionProcessLogArchive::dir(),
+        )
+        .await
+    }
+
+    async fn compact_in(
+        pool: &SqlitePool,
+        execution_id: Uuid,
+        spill_threshold: Option<usize>,
+        dir: &Path,
+    ) -> Result<Option<ExecutionProcessLogArchive>, ExecutionProcessLogsError> {
+        let mut tx = pool.begin().await?;
+
+        let rows = sqlx::query!(
+            r#"SELECT rowid as "rowid!: i64", logs
+               FROM execution_process_logs
+               WHERE execution_id = $1
+               ORDER BY inserted_at ASC, rowid ASC"#,
+            execution_id
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+        let Some(max_rowid) = rows.iter().map(|row| row.rowid).max() else {
+            return Ok(None);
+        };
+
+        let existing =
+            ExecutionProcessLogArchive::find_by_execution_id(&mut *tx, execution_id).await?;
+        let mut jsonl = match &existing {
+            Some(archive) => archive.decompress_in(dir).await?,
+            None => String::new(),
+        };
+        for row in &rows {
+            jsonl.push_str(&row.logs);
+        }
+
+        let line_count = jsonl.lines().filter(|line| !line.trim().is_empty()).count() as i64;
+        let byte_size = jsonl.len() as i64;
+        let compressed = tokio::task::spawn_blocking(move || {
+            zstd::stream::encode_all(jsonl.as_bytes(), ARCHIVE_COMPRESSION_LEVEL)
+        })
+        .await
+        .map_err(std::io::Error::other)??;
+        let compressed_size = compressed.len() as i64;
+
+        // Spilled blobs are written to a temporary file and moved into place right before the
+        // transaction commits. A previous archive file is kept aside until then, so a failed
+        // compaction puts it back instead of leaving a file the database does not describe.
+        let file_name = ExecutionProcessLogArchive::file_name(execution_id);
+        let path = dir.join(&file_name);
+        let spill = spill_threshold.is_some_and(|threshold| compressed.len() >= threshold);
+        let (data, stored_file_name, tmp_path) = if spill {
+            tokio::fs::create_dir_all(dir).await?;
+            let tmp_path = path.with_file_name(format!("{file_name}.tmp"));
+            tokio::fs::write(&tmp_path, &compressed).await?;
+            (None, Some(file_name.clone()), Some(tmp_path))
+        } else {
+            (Some(compressed), None, None)
+        };
+
+        let archive = sqlx::query_as!(
+            ExecutionProcessLogArchive,
+            r#"INSERT INTO execution_process_log_archives
+                   (execution_id, data, file_name, line_count, byte_size, compressed_size)
+               VALUES ($1, $2, $3, $4, $5, $6)
+               ON CONFLICT(execution_id) DO UPDATE SET
+                   data = excluded.data,
+                   file_name = excluded.file_name,
+                   line_count = excluded.line_count,
+                   byte_size = excluded.byte_size,
+                   compressed_size = excluded.compressed_size,
+                   updated_at = datetime('now', 'subsec')
+               RETURNING execution_id as "execution_id!: Uuid",
+                         data,
+                         file_name,
+                         line_count,
+                         byte_size,
+                         compressed_size,
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            execution_id,
+            data,
+            stored_file_name,
+            line_count,
+            byte_size,
+            compressed_size
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            "DELETE FROM execution_process_logs WHERE execution_id = $1 AND rowid <= $2",
+            execution_id,
+            max_rowid
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        let backup_path = path.with_file_name(format!("{file_name}.bak"));
+        let had_file = existing.as_ref().is_some_and(|a| a.file_name.is_some());
+        let mut backed_up = false;
+        if let Some(tmp_path) = &tmp_path {
+            // Returning drops the transaction, which rolls it back
+            match Self::move_into_place(tmp_path, &path, &backup_path).await {
+                Ok(moved_previous) => backed_up = moved_previous,
+                Err(e) => {
+                    let _ = tokio::fs::remove_file(tmp_path).await;
+                    return Err(e.into());
+                }
+            }
+        }
+
+        if let Err(e) = tx.commit().await {
+            if tmp_path.is_some() {
+                let restored = if backed_up {
+                    tokio::fs::rename(&backup_path, &path).await
+                } else {
+                    tokio::fs::remove_file(&path).await
+                };
+                if let Err(e) = restored {
+                    tracing::error!(
+                        "Failed to restore log archive file {}: {}",
+                        path.display(),
+                        e
+                    );
+                }
+            }
+            return Err(e.into());
+        }
+
+        let stale_path = if tmp_path.is_some() {
+            backed_up.then_some(&backup_path)
+        } else {
+            had_file.then_some(&path)
+        };
+        if let Some(stale_path) = stale_path
+            && let Err(e) = tokio::fs::remove_file(stale_path).await
+        {
+            tracing::warn!(
+                "Failed to remove log archive file {}: {}",
+                stale_path.display(),
+                e
+            );
+        }
+
+        Ok(Some(archive))
+    }
+
+    /// Move a freshly written archive to `path`, first moving any previous archive there to
+    /// `backup_path`. Returns whether there was a previous archive.
+    async fn move_into_place(
+        tmp_path: &Path,
+        path: &Path,
+        backup_path: &Path,
+    ) -> std::io::Result<bool> {
+        let backed_up = match tokio::fs::rename(path, backup_path).await {
+            Ok(()) => true,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => false,
+            Err(e) => return Err(e),
+        };
+        if let Err(e) = tokio::fs::rename(tmp_path, path).await {
+            if backed_up {
+                let _ = tokio::fs::rename(backup_path, path).await;
+            }
+            return Err(e);
+        }
+        Ok(backed_up)
+    }
+
+    /// Delete raw and compacted logs of processes that completed before `completed_before`.
+    /// Coding agent turns (prompts and summaries) are kept. Returns the number of processes
+    /// whose archive was removed.
+    pub async fn delete_expired(
+        pool: &SqlitePool,
+        completed_before: DateTime<Utc>,
+    ) -> Result<u64, ExecutionProcessLogsError> {
+        Self::delete_expired_in(pool, completed_before, &ExecutionProcessLogArchive::dir()).await
+    }
+
+    async fn delete_expired_in(
+        pool: &SqlitePool,
+        completed_before: DateTime<Utc>,
+        dir: &Path,
+    ) -> Result<u64, ExecutionProcessLogsError> {
+        sqlx::query!(
+            r#"DELETE FROM execution_process_logs
+               WHERE execution_id IN (
+                   SELECT id FROM execution_processes
+                   WHERE completed_at IS NOT NULL AND datetime(completed_at) < datetime($1)
+               )"#,
+            completed_before
+        )
+        .execute(pool)
+        .await?;
+
+        let deleted = sqlx::query!(
+            r#"DELETE FROM execution_process_log_archives
+               WHERE execution_id IN (
+                   SELECT id FROM execution_processes
+                   WHERE completed_at IS NOT NULL AND datetime(completed_at) < datetime($1)
+               )
+               RETURNING file_name"#,
+            completed_before
+        )
+        .fetch_all(pool)
+        .await?;
+
+        for file_name in deleted.iter().filter_map(|row| row.file_name.as_ref()) {
+            let path = dir.join(file_name);
+            if let Err(e) = tokio::fs::remove_file(&path).await {
+                tracing::warn!(
+                    "Failed to remove log archive file {}: {}",
+                    path.display(),
+                    e
+                );
+            }
+        }
+
+        Ok(deleted.len() as u64)
+    }
+}
+
+impl ExecutionProcessLogArchive {
+    /// Directory that spilled archives are written to
+    pub fn dir() -> PathBuf {
+        asset_dir().join("logs")
+    }
+
+    fn file_name(execution_id: Uuid) -> String {
+        format!("{execution_id}.{ARCHIVE_FILE_EXTENSION}")
+    }
+
+    pub async fn find_by_execution_id<'e, E>(
+        executor: E,
+        execution_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error>
+    where
+        E: sqlx::Executor<'e, Database = sqlx::Sqlite>,
+    {
+        sqlx::query_as!(
+            ExecutionProcessLogArchive,
+            r#"SELECT execution_id as "execution_id!: Uuid",
+                      data,
+                      file_name,
+                      line_count,
+                      byte_size,
+                      compressed_size,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM execution_process_log_archives
+               WHERE execution_id = $1"#,
+            execution_id
+        )
+        .fetch_optional(executor)
+        .await
+    }
+
+    /// Decompress the archived JSONL, reading it from disk if it was spilled
+    pub async fn decompress(&self) -> Result<String, std::io::Error> {
+        self.decompress_in(&Self::dir()).await
+    }
+
+    async fn decompress_in(&self, dir: &Path) -> Result<String, std::io::Error> {
+        let compressed = match (&self.data, &self.file_name) {
+            (Some(data), _) => data.clone(),
+            (None, Some(file_name)) => tokio::fs::read(dir.join(file_name)).await?,
+            (None, None) => return Ok(String::new()),
+        };
+        let bytes =
+            tokio::task::spawn_blocking(move || zstd::stream::decode_all(compressed.as_slice()))
+                .await
+                .map_err(std::io::Error::other)??;
+        String::from_utf8(bytes)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+    }
+
+    /// Remove spilled files whose archive row is gone, e.g. because the task was deleted
+    pub async fn remove_orphaned_files(pool: &SqlitePool) -> Result<u64, std::io::Error> {
+        Self::remove_orphaned_files_in(pool, &Self::dir()).await
+    }
+
+    async fn remove_orphaned_files_in(
+        pool: &SqlitePool,
+        dir: &Path,
+    ) -> Result<u64, std::io::Error> {
+        let mut entries = match tokio::fs::read_dir(dir).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+            Err(e) => return Err(e),
+        };
+
+        let mut removed = 0;
+        while let Some(entry) = entries.next_entry().await? {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            let Some(execution_id) = file_name
+                .strip_suffix(&format!(".{ARCHIVE_FILE_EXTENSION}"))
+                .and_then(|id| Uuid::parse_str(id).ok())
+            else {
+                continue;
+            };
+
+            match Self::find_by_execution_id(pool, execution_id).await {
+                Ok(Some(archive)) if archive.file_name.is_some() => continue,
+                Ok(_) => {
+                    tokio::fs::remove_file(entry.path()).await?;
+                    removed += 1;
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        "Failed to look up log archive for execution {}: {}",
+                        execution_id,
+                        e
+                    );
+                }
+            }
+        }
+        Ok(removed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::models::{
+        coding_agent_turn::{CodingAgentTurn, CreateCodingAgentTurn},
+        execution_process::{
+            CreateImportedExecutionProcess, ExecutionProcess, ExecutionProcessRunReason,
+            ExecutionProcessStatus,
+        },
+        project::{CreateProject, Project},
+        session::{CreateSession, Session},
+        task::{CreateTask, Task},
+        task_event::TaskEventActor,
+        workspace::{CreateWorkspace, Workspace},
+    };
+
+    async fn create_process(pool: &SqlitePool, completed_at: DateTime<Utc>) -> Uuid {
+        let project = Project::create(
+            pool,
+            &CreateProject {
+                name: "logs".to_string(),
+                repositories: vec![],
+            },
+            Uuid::new_v4(),
+        )
+        .await
+        .unwrap();
+        let data = CreateTask::from_title_description(project.id, "task".to_string(), None);
+        let task = Task::create(pool, &data, Uuid::new_v4(), TaskEventActor::User)
+            .await
+            .unwrap();
+        let workspace = Workspace::create(
+            pool,
+            &CreateWorkspace {
+                branch: "vk/logs".to_string(),
+                agent_working_dir: None,
+            },
+            Uuid::new_v4(),
+            task.id,
+        )
+        .await
+        .unwrap();
+        let session = Session::create(
+            pool,
+            &CreateSession { executor: None },
+            Uuid::new_v4(),
+            workspace.id,
+        )
+        .await
+        .unwrap();
+
+        let process_id = Uuid::new_v4();
+        ExecutionProcess::create_imported(
+            pool,
+            &CreateImportedExecutionProcess {
+                session_id: session.id,
+                executor_action: serde_json::json!({}),
+                run_reason: ExecutionProcessRunReason::CodingAgent,
+                status: ExecutionProcessStatus::Completed,
+                exit_code: Some(0),
+                dropped: false,
+                started_at: completed_at,
+                completed_at: Some(completed_at),
+            },
+            process_id,
+        )
+        .await
+        .unwrap();
+        process_id
+    }
+
+    async fn append_lines(pool: &SqlitePool, execution_id: Uuid, lines: &[String]) {
+        for line in lines {
+            ExecutionProcessLogs::append_log_line(pool, execution_id, &format!("{line}\n"))
+                .await
+                .unwrap();
+        }
+    }
+
+    fn stdout_lines(range: std::ops::Range<usize>) -> Vec<String> {
+        range
+            .map(|i| serde_json::to_string(&LogMsg::Stdout(format!("line {i}"))).unwrap())
+            .collect()
+    }
+
+    async fn read_lines(pool: &SqlitePool, execution_id: Uuid, dir: &Path) -> Vec<String> {
+        let mut jsonl = match ExecutionProcessLogArchive::find_by_execution_id(pool, execution_id)
+            .await
+            .unwrap()
+        {
+            Some(archive) => archive.decompress_in(dir).await.unwrap(),
+            None => String::new(),
+        };
+        let rows: Vec<String> = sqlx::query_scalar(
+            "SELECT logs FROM execution_process_logs WHERE execution_id = $1 ORDER BY rowid",
+        )
+        .bind(execution_id)
+        .fetch_all(pool)
+        .await
+        .unwrap();
+        for row in rows {
+            jsonl.push_str(&row);
+        }
+        jsonl.lines().map(str::to_string).collect()
+    }
+
+    async fn raw_row_count(pool: &SqlitePool, execution_id: Uuid) -> i64 {
+        sqlx::query_scalar("SELECT COUNT(*) FROM execution_process_logs WHERE execution_id = $1")
+            .bind(execution_id)
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    #[sqlx::test]
+    async fn compact_round_trips_and_merges_into_the_existing_archive(pool: SqlitePool) {
+        let dir = TempDir::new().unwrap();
+        let execution_id = create_process(&pool, Utc::now()).await;
+        let first = stdout_lines(0..3);
+        append_lines(&pool, execution_id, &first).await;
+
+        let archive = ExecutionProcessLogs::compact_in(&pool, execution_id, None, dir.path())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(archive.line_count, 3);
+        assert!(archive.data.is_some());
+        assert!(archive.file_name.is_none());
+        assert_eq!(raw_row_count(&pool, execution_id).await, 0);
+        assert_eq!(
+            archive
+                .decompress_in(dir.path())
+                .await
+                .unwrap()
+                .lines()
+                .count(),
+            3
+        );
+        assert_eq!(read_lines(&pool, execution_id, dir.path()).await, first);
+        let records = ExecutionProcessLogs::find_by_execution_id(&pool, execution_id)
+            .await
+            .unwrap();
+        assert_eq!(ExecutionProcessLogs::parse_logs(&records).unwrap().len(), 3);
+
+        // Nothing new to compact
+        assert!(
+            ExecutionProcessLogs::compact_in(&pool, execution_id, None, dir.path())
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        let second = stdout_lines(3..5);
+        append_lines(&pool, execution_id, &second).await;
+        let archive = ExecutionProcessLogs::compact_in(&pool, execution_id, None, dir.path())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(archive.line_count, 5);
+        assert_eq!(raw_row_count(&pool, execution_id).await, 0);
+        assert_eq!(
+            read_lines(&pool, execution_id, dir.path()).await,
+            [first, second].concat()
+        );
+    }
+
+    #[sqlx::test]
+    async fn compact_spills_archives_above_the_threshold(pool: SqlitePool) {
+        let dir = TempDir::new().unwrap();
+        let small_id = create_process(&pool, Utc::now()).await;
+        let large_id = create_process(&pool, Utc::now()).await;
+        append_lines(&pool, small_id, &stdout_lines(0..2)).await;
+        append_lines(&pool, large_id, &stdout_lines(0..2000)).await;
+
+        let small = ExecutionProcessLogs::compact_in(&pool, small_id, Some(1024), dir.path())
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(small.data.is_some());
+        assert!(small.file_name.is_none());
+
+        let large = ExecutionProcessLogs::compact_in(&pool, large_id, Some(1024), dir.path())
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(large.compressed_size >= 1024);
+        assert!(large.data.is_none());
+        let file_name = large.file_name.clone().unwrap();
+        let path = dir.path().join(&file_name);
+        assert!(path.exists());
+        assert!(!path.with_file_name(format!("{file_name}.tmp")).exists());
+        assert_eq!(
+            read_lines(&pool, large_id, dir.path()).await,
+            stdout_lines(0..2000)
+        );
+
+        // Merging into a spilled archive replaces the file
+        append_lines(&pool, large_id, &stdout_lines(2000..2010)).await;
+        let merged = ExecutionProcessLogs::compact_in(&pool, large_id, Some(1024), dir.path())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(merged.line_count, 2010);
+        assert!(!path.with_file_name(format!("{file_name}.bak")).exists());
+        assert_eq!(
+            read_lines(&pool, large_id, dir.path()).await,
+            stdout_lines(0..2010)
+        );
+
+        // Once spilling is turned off the archive moves back into the database
+        append_lines(&pool, large_id, &stdout_lines(2010..2011)).await;
+        let stored = ExecutionProcessLogs::compact_in(&pool, large_id, None, dir.path())
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(stored.data.is_some());
+        assert!(stored.file_name.is_none());
+        assert!(!path.exists());
+        assert_eq!(
+            read_lines(&pool, large_id, dir.path()).await,
+            stdout_lines(0..2011)
+        );
+    }
+
+    #[sqlx::test]
+    async fn delete_expired_keeps_coding_agent_turns(pool: SqlitePool) {
+        let dir = TempDir::new().unwrap();
+        let old_id = create_process(&pool, Utc::now() - Duration::days(10)).await;
+        let recent_id = create_process(&pool, Utc::now()).await;
+        let turn = CodingAgentTurn::create(
+            &pool,
+            &CreateCodingAgentTurn {
+                execution_process_id: old_id,
+                prompt: Some("fix the bug".to_string()),
+            },
+            Uuid::new_v4(),
+        )
+        .await
+        .unwrap();
+
+        append_lines(&pool, old_id, &stdout_lines(0..2000)).await;
+        ExecutionProcessLogs::compact_in(&pool, old_id, Some(1024), dir.path())
+            .await
+            .unwrap();
+        append_lines(&pool, old_id, &stdout_lines(0..1)).await;
+        append_lines(&pool, recent_id, &stdout_lines(0..1)).await;
+        ExecutionProcessLogs::compact_in(&pool, recent_id, None, dir.path())
+            .await
+            .unwrap();
+        let spilled_path = dir
+            .path()
+            .join(ExecutionProcessLogArchive::file_name(old_id));
+        assert!(spilled_path.exists());
+
+        let deleted = ExecutionProcessLogs::delete_expired_in(
+            &pool,
+            Utc::now() - Duration::days(7),
+            dir.path(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(deleted, 1);
+        assert!(
+            ExecutionProcessLogArchive::find_by_execution_id(&pool, old_id)
+                .await
+                .unwrap()
+                .is_none()
+        );
+        assert_eq!(raw_row_count(&pool, old_id).await, 0);
+        assert!(!spilled_path.exists());
+
+        let kept = CodingAgentTurn::find_by_execution_process_id(&pool, old_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(kept.id, turn.id);
+        assert_eq!(kept.prompt.as_deref(), Some("fix the bug"));
+
+        assert_eq!(
+            read_lines(&pool, recent_id, dir.path()).await,
+            stdout_lines(0..1)
+        );
+    }
+
+    #[sqlx::test]
+    async fn remove_orphaned_files_keeps_referenced_archives(pool: SqlitePool) {
+        let dir = TempDir::new().unwrap();
+        let execution_id = create_process(&pool, Utc::now()).await;
+        append_lines(&pool, execution_id, &stdout_lines(0..2000)).await;
+        ExecutionProcessLogs::compact_in(&pool, execution_id, Some(1024), dir.path())
+            .await
+            .unwrap();
+        let kept = dir
+            .path()
+            .join(ExecutionProcessLogArchive::file_name(execution_id));
+
+        let orphan = dir
+            .path()
+            .join(ExecutionProcessLogArchive::file_name(Uuid::new_v4()));
+        let unrelated = dir.path().join("notes.txt");
+        std::fs::write(&orphan, b"stale").unwrap();
+        std::fs::write(&unrelated, b"keep").unwrap();
+
+        let removed = ExecutionProcessLogArchive::remove_orphaned_files_in(&pool, dir.path())
+            .await
+            .unwrap();
+        assert_eq!(removed, 1);
+        assert!(kept.exists());
+        assert!(!orphan.exists());
+        assert!(unrelated.exists());
+
+        let missing = dir.path().join("missing");
+        assert_eq!(
+            ExecutionProcessLogArchive::remove_orphaned_files_in(&pool, &missing)
+                .await
+                .unwrap(),
+            0
+        );
+    }
 }
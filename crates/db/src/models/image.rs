@@ -1,6 +1,6 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, SqlitePool};
+use sqlx::{Acquire, FromRow, Sqlite, SqlitePool};
 use ts_rs::TS;
 use uuid::Uuid;
 
@@ -179,10 +179,11 @@ impl Image {
 impl TaskImage {
     /// Associate multiple images with a task, skipping duplicates.
     pub async fn associate_many_dedup(
-        pool: &SqlitePool,
+        db: impl Acquire<'_, Database = Sqlite>,
         task_id: Uuid,
         image_ids: &[Uuid],
     ) -> Result<(), sqlx::Error> {
+        let mut conn = db.acquire().await?;
         for &image_id in image_ids {
             let id = Uuid::new_v4();
             sqlx::query!(
@@ -195,7 +196,7 @@ impl TaskImage {
                 task_id,
                 image_id
             )
-            .execute(pool)
+            .execute(&mut *conn)
             .await?;
         }
         Ok(())
@@ -1,4 +1,10 @@
+pub mod analytics;
+pub mod api_token;
+pub mod approval;
+pub mod approval_rule;
+pub mod attempt_group;
 pub mod coding_agent_turn;
+pub mod conflict_resolution;
 pub mod execution_process;
 pub mod execution_process_logs;
 pub mod execution_process_repo_state;
@@ -6,10 +12,18 @@ pub mod image;
 pub mod merge;
 pub mod project;
 pub mod project_repo;
+pub mod queued_message;
 pub mod repo;
 pub mod scratch;
+pub mod search;
 pub mod session;
 pub mod tag;
 pub mod task;
+pub mod task_auto_start;
+pub mod task_dependency;
+pub mod task_event;
+pub mod task_schedule;
+pub mod token_usage;
+pub mod webhook;
 pub mod workspace;
 pub mod workspace_repo;
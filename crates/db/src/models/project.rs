@@ -1,6 +1,6 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::{Executor, FromRow, Sqlite, SqlitePool};
+use sqlx::{Acquire, Executor, FromRow, Sqlite, SqlitePool};
 use thiserror::Error;
 use ts_rs::TS;
 use uuid::Uuid;
@@ -108,7 +108,10 @@ impl Project {
         .await
     }
 
-    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+    pub async fn find_by_id(
+        executor: impl Executor<'_, Database = Sqlite>,
+        id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
         sqlx::query_as!(
             Project,
             r#"SELECT id as "id!: Uuid",
@@ -123,7 +126,7 @@ impl Project {
                WHERE id = $1"#,
             id
         )
-        .fetch_optional(pool)
+        .fetch_optional(executor)
         .await
     }
 
@@ -198,11 +201,12 @@ impl Project {
     }
 
     pub async fn update(
-        pool: &SqlitePool,
+        db: impl Acquire<'_, Database = Sqlite>,
         id: Uuid,
         payload: &UpdateProject,
     ) -> Result<Self, sqlx::Error> {
-        let existing = Self::find_by_id(pool, id)
+        let mut conn = db.acquire().await?;
+        let existing = Self::find_by_id(&mut *conn, id)
             .await?
             .ok_or(sqlx::Error::RowNotFound)?;
 
@@ -230,7 +234,7 @@ impl Project {
             dev_script_working_dir,
             default_agent_working_dir,
         )
-        .fetch_one(pool)
+        .fetch_one(&mut *conn)
         .await
     }
 
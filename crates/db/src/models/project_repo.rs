@@ -2,7 +2,7 @@ use std::path::Path;
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, SqlitePool};
+use sqlx::{Acquire, Executor, FromRow, Sqlite, SqlitePool};
 use thiserror::Error;
 use ts_rs::TS;
 use uuid::Uuid;
@@ -28,6 +28,10 @@ pub struct ProjectRepo {
     pub cleanup_script: Option<String>,
     pub copy_files: Option<String>,
     pub parallel_setup_script: bool,
+    /// Runs in the worktree before merge or PR creation; a non-zero exit blocks both
+    pub gate_script: Option<String>,
+    /// Send a failed gate's output back to the coding agent as a follow-up
+    pub gate_follow_up_on_failure: bool,
 }
 
 /// ProjectRepo with the associated repo name (for script execution in worktrees)
@@ -41,6 +45,8 @@ pub struct ProjectRepoWithName {
     pub cleanup_script: Option<String>,
     pub copy_files: Option<String>,
     pub parallel_setup_script: bool,
+    pub gate_script: Option<String>,
+    pub gate_follow_up_on_failure: bool,
 }
 
 #[derive(Debug, Clone, Deserialize, TS)]
@@ -56,6 +62,8 @@ pub struct UpdateProjectRepo {
     pub cleanup_script: Option<String>,
     pub copy_files: Option<String>,
     pub parallel_setup_script: Option<bool>,
+    pub gate_script: Option<String>,
+    pub gate_follow_up_on_failure: Option<bool>,
 }
 
 impl ProjectRepo {
@@ -71,7 +79,9 @@ impl ProjectRepo {
                       setup_script,
                       cleanup_script,
                       copy_files,
-                      parallel_setup_script as "parallel_setup_script!: bool"
+                      parallel_setup_script as "parallel_setup_script!: bool",
+                      gate_script,
+                      gate_follow_up_on_failure as "gate_follow_up_on_failure!: bool"
                FROM project_repos
                WHERE project_id = $1"#,
             project_id
@@ -92,7 +102,9 @@ impl ProjectRepo {
                       setup_script,
                       cleanup_script,
                       copy_files,
-                      parallel_setup_script as "parallel_setup_script!: bool"
+                      parallel_setup_script as "parallel_setup_script!: bool",
+                      gate_script,
+                      gate_follow_up_on_failure as "gate_follow_up_on_failure!: bool"
                FROM project_repos
                WHERE repo_id = $1"#,
             repo_id
@@ -114,7 +126,9 @@ impl ProjectRepo {
                       pr.setup_script,
                       pr.cleanup_script,
                       pr.copy_files,
-                      pr.parallel_setup_script as "parallel_setup_script!: bool"
+                      pr.parallel_setup_script as "parallel_setup_script!: bool",
+                      pr.gate_script,
+                      pr.gate_follow_up_on_failure as "gate_follow_up_on_failure!: bool"
                FROM project_repos pr
                JOIN repos r ON r.id = pr.repo_id
                WHERE pr.project_id = $1
@@ -148,7 +162,7 @@ impl ProjectRepo {
     }
 
     pub async fn find_by_project_and_repo(
-        pool: &SqlitePool,
+        executor: impl Executor<'_, Database = Sqlite>,
         project_id: Uuid,
         repo_id: Uuid,
     ) -> Result<Option<Self>, sqlx::Error> {
@@ -160,13 +174,15 @@ impl ProjectRepo {
                       setup_script,
                       cleanup_script,
                       copy_files,
-                      parallel_setup_script as "parallel_setup_script!: bool"
+                      parallel_setup_script as "parallel_setup_script!: bool",
+                      gate_script,
+                      gate_follow_up_on_failure as "gate_follow_up_on_failure!: bool"
                FROM project_repos
                WHERE project_id = $1 AND repo_id = $2"#,
             project_id,
             repo_id
         )
-        .fetch_optional(pool)
+        .fetch_optional(executor)
         .await
     }
 
@@ -235,7 +251,9 @@ impl ProjectRepo {
                          setup_script,
                          cleanup_script,
                          copy_files,
-                         parallel_setup_script as "parallel_setup_script!: bool""#,
+                         parallel_setup_script as "parallel_setup_script!: bool",
+                         gate_script,
+                         gate_follow_up_on_failure as "gate_follow_up_on_failure!: bool""#,
             id,
             project_id,
             repo_id
@@ -245,12 +263,13 @@ impl ProjectRepo {
     }
 
     pub async fn update(
-        pool: &SqlitePool,
+        db: impl Acquire<'_, Database = Sqlite>,
         project_id: Uuid,
         repo_id: Uuid,
         payload: &UpdateProjectRepo,
     ) -> Result<Self, ProjectRepoError> {
-        let existing = Self::find_by_project_and_repo(pool, project_id, repo_id).await?;
+        let mut conn = db.acquire().await?;
+        let existing = Self::find_by_project_and_repo(&mut *conn, project_id, repo_id).await?;
         let existing = existing.ok_or(ProjectRepoError::NotFound)?;
 
         let setup_script = payload.setup_script.clone();
@@ -259,6 +278,10 @@ impl ProjectRepo {
         let parallel_setup_script = payload
             .parallel_setup_script
             .unwrap_or(existing.parallel_setup_script);
+        let gate_script = payload.gate_script.clone();
+        let gate_follow_up_on_failure = payload
+            .gate_follow_up_on_failure
+            .unwrap_or(existing.gate_follow_up_on_failure);
 
         sqlx::query_as!(
             ProjectRepo,
@@ -266,23 +289,29 @@ impl ProjectRepo {
                SET setup_script = $1,
                    cleanup_script = $2,
                    copy_files = $3,
-                   parallel_setup_script = $4
-               WHERE project_id = $5 AND repo_id = $6
+                   parallel_setup_script = $4,
+                   gate_script = $5,
+                   gate_follow_up_on_failure = $6
+               WHERE project_id = $7 AND repo_id = $8
                RETURNING id as "id!: Uuid",
                          project_id as "project_id!: Uuid",
                          repo_id as "repo_id!: Uuid",
                          setup_script,
                          cleanup_script,
                          copy_files,
-                         parallel_setup_script as "parallel_setup_script!: bool""#,
+                         parallel_setup_script as "parallel_setup_script!: bool",
+                         gate_script,
+                         gate_follow_up_on_failure as "gate_follow_up_on_failure!: bool""#,
             setup_script,
             cleanup_script,
             copy_files,
             parallel_setup_script,
+            gate_script,
+            gate_follow_up_on_failure,
             project_id,
             repo_id
         )
-        .fetch_one(pool)
+        .fetch_one(&mut *conn)
         .await
         .map_err(ProjectRepoError::from)
     }
@@ -0,0 +1,224 @@
+use std::collections::HashSet;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use thiserror::Error;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::scratch::DraftFollowUpData;
+
+#[derive(Debug, Error)]
+pub enum QueuedMessageError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error("Queued message not found")]
+    NotFound,
+    #[error("The new order must contain every queued message of the session exactly once")]
+    InvalidOrder,
+}
+
+/// A follow-up message waiting for the session's current execution to finish.
+/// Messages are sent one at a time in ascending `position` order.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct QueuedMessage {
+    pub id: Uuid,
+    pub session_id: Uuid,
+    pub position: i64,
+    /// The follow-up data (message + variant)
+    #[ts(type = "DraftFollowUpData")]
+    pub data: sqlx::types::Json<DraftFollowUpData>,
+    pub queued_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl QueuedMessage {
+    pub async fn find_by_session_id(
+        pool: &SqlitePool,
+        session_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            QueuedMessage,
+            r#"SELECT id as "id!: Uuid",
+                      session_id as "session_id!: Uuid",
+                      position as "position!: i64",
+                      data as "data!: sqlx::types::Json<DraftFollowUpData>",
+                      queued_at as "queued_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM queued_messages
+               WHERE session_id = $1
+               ORDER BY position ASC"#,
+            session_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// The message that will be sent next, if any
+    pub async fn find_next(
+        pool: &SqlitePool,
+        session_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            QueuedMessage,
+            r#"SELECT id as "id!: Uuid",
+                      session_id as "session_id!: Uuid",
+                      position as "position!: i64",
+                      data as "data!: sqlx::types::Json<DraftFollowUpData>",
+                      queued_at as "queued_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM queued_messages
+               WHERE session_id = $1
+               ORDER BY position ASC
+               LIMIT 1"#,
+            session_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Sessions with at least one queued message
+    pub async fn find_session_ids(pool: &SqlitePool) -> Result<Vec<Uuid>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT DISTINCT session_id as "session_id!: Uuid" FROM queued_messages"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn exists_for_session(
+        pool: &SqlitePool,
+        session_id: Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        let exists = sqlx::query_scalar!(
+            r#"SELECT EXISTS (SELECT 1 FROM queued_messages WHERE session_id = $1) AS "exists!: bool""#,
+            session_id
+        )
+        .fetch_one(pool)
+        .await?;
+        Ok(exists)
+    }
+
+    /// Append a message to the end of the session's queue
+    pub async fn create(
+        pool: &SqlitePool,
+        session_id: Uuid,
+        data: &DraftFollowUpData,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let data = sqlx::types::Json(data);
+        sqlx::query_as!(
+            QueuedMessage,
+            r#"INSERT INTO queued_messages (id, session_id, position, data)
+               VALUES (
+                   $1,
+                   $2,
+                   (SELECT COALESCE(MAX(position) + 1, 0) FROM queued_messages WHERE session_id = $2),
+                   $3
+               )
+               RETURNING id as "id!: Uuid",
+                         session_id as "session_id!: Uuid",
+                         position as "position!: i64",
+                         data as "data!: sqlx::types::Json<DraftFollowUpData>",
+                         queued_at as "queued_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            session_id,
+            data
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn update(
+        pool: &SqlitePool,
+        session_id: Uuid,
+        id: Uuid,
+        data: &DraftFollowUpData,
+    ) -> Result<Self, QueuedMessageError> {
+        let data = sqlx::types::Json(data);
+        sqlx::query_as!(
+            QueuedMessage,
+            r#"UPDATE queued_messages
+               SET data = $3, updated_at = datetime('now', 'subsec')
+               WHERE id = $1 AND session_id = $2
+               RETURNING id as "id!: Uuid",
+                         session_id as "session_id!: Uuid",
+                         position as "position!: i64",
+                         data as "data!: sqlx::types::Json<DraftFollowUpData>",
+                         queued_at as "queued_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            session_id,
+            data
+        )
+        .fetch_optional(pool)
+        .await?
+        .ok_or(QueuedMessageError::NotFound)
+    }
+
+    /// Rewrite positions so the queue follows `ordered_ids`, which must be a permutation
+    /// of the session's current queue
+    pub async fn reorder(
+        pool: &SqlitePool,
+        session_id: Uuid,
+        ordered_ids: &[Uuid],
+    ) -> Result<(), QueuedMessageError> {
+        let mut tx = pool.begin().await?;
+
+        let current: HashSet<Uuid> = sqlx::query_scalar!(
+            r#"SELECT id as "id!: Uuid" FROM queued_messages WHERE session_id = $1"#,
+            session_id
+        )
+        .fetch_all(&mut *tx)
+        .await?
+        .into_iter()
+        .collect();
+        let requested: HashSet<Uuid> = ordered_ids.iter().copied().collect();
+        if requested.len() != ordered_ids.len() || requested != current {
+            return Err(QueuedMessageError::InvalidOrder);
+        }
+
+        for (position, id) in ordered_ids.iter().enumerate() {
+            let position = position as i64;
+            sqlx::query!(
+                r#"UPDATE queued_messages
+                   SET position = $3, updated_at = datetime('now', 'subsec')
+                   WHERE id = $1 AND session_id = $2"#,
+                id,
+                session_id,
+                position
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    pub async fn delete(pool: &SqlitePool, session_id: Uuid, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM queued_messages WHERE id = $1 AND session_id = $2",
+            id,
+            session_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    pub async fn delete_by_session_id(
+        pool: &SqlitePool,
+        session_id: Uuid,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM queued_messages WHERE session_id = $1",
+            session_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,148 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+const DEFAULT_SEARCH_LIMIT: i64 = 20;
+const MAX_SEARCH_LIMIT: i64 = 100;
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, TS)]
+#[sqlx(type_name = "search_document_kind", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum SearchDocumentKind {
+    /// Task title and description
+    Task,
+    /// Coding agent prompt and final summary
+    AgentTurn,
+    /// Assistant messages of a completed coding agent process
+    Conversation,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct FullTextSearchQuery {
+    pub q: String,
+    pub project_id: Option<Uuid>,
+    /// Only return items created or updated at or after this time
+    pub since: Option<DateTime<Utc>>,
+    pub limit: Option<u32>,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct SearchHit {
+    pub kind: SearchDocumentKind,
+    pub project_id: Uuid,
+    pub task_id: Uuid,
+    pub task_title: String,
+    pub workspace_id: Option<Uuid>,
+    pub execution_process_id: Option<Uuid>,
+    /// Matching excerpt with matched terms wrapped in `<mark>` tags
+    pub snippet: String,
+    /// Relevance, higher is better
+    pub score: f64,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Turn free text into an FTS5 query: every word must match, the last one as a prefix
+/// so results update while typing. Words are quoted so FTS5 syntax characters in user
+/// input are matched literally rather than rejected.
+pub fn fts_query(input: &str) -> Option<String> {
+    let terms: Vec<String> = input
+        .split_whitespace()
+        .map(|term| format!("\"{}\"", term.replace('"', "\"\"")))
+        .collect();
+    (!terms.is_empty()).then(|| format!("{}*", terms.join(" ")))
+}
+
+impl SearchHit {
+    /// Ranked hits for `query`; an empty query returns no hits
+    pub async fn search(
+        pool: &SqlitePool,
+        query: &FullTextSearchQuery,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let Some(fts_query) = fts_query(&query.q) else {
+            return Ok(Vec::new());
+        };
+        let limit = query
+            .limit
+            .map_or(DEFAULT_SEARCH_LIMIT, i64::from)
+            .clamp(1, MAX_SEARCH_LIMIT);
+
+        // Title matches weigh more than body matches; bm25 is lower for better matches
+        sqlx::query_as!(
+            SearchHit,
+            r#"SELECT d.kind as "kind!: SearchDocumentKind",
+                      d.project_id as "project_id!: Uuid",
+                      d.task_id as "task_id!: Uuid",
+                      t.title as "task_title!",
+                      d.workspace_id as "workspace_id: Uuid",
+                      d.execution_process_id as "execution_process_id: Uuid",
+                      snippet(search_documents_fts, -1, '<mark>', '</mark>', '…', 24) as "snippet!: String",
+                      -bm25(search_documents_fts, 5.0, 1.0) as "score!: f64",
+                      d.updated_at as "updated_at!: DateTime<Utc>"
+               FROM search_documents_fts
+               JOIN search_documents d ON d.id = search_documents_fts.rowid
+               JOIN tasks t ON t.id = d.task_id
+               WHERE search_documents_fts MATCH $1
+                 AND ($2 IS NULL OR d.project_id = $2)
+                 AND ($3 IS NULL OR datetime(d.updated_at) >= datetime($3))
+               ORDER BY bm25(search_documents_fts, 5.0, 1.0)
+               LIMIT $4"#,
+            fts_query,
+            query.project_id,
+            query.since,
+            limit
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Index the assistant messages of a coding agent process, replacing any earlier
+    /// version of its conversation
+    pub async fn upsert_conversation(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        body: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"INSERT INTO search_documents
+                   (kind, source_id, project_id, task_id, workspace_id, execution_process_id, body)
+               SELECT 'conversation', ep.id, t.project_id, t.id, w.id, ep.id, $2
+               FROM execution_processes ep
+               JOIN sessions s ON s.id = ep.session_id
+               JOIN workspaces w ON w.id = s.workspace_id
+               JOIN tasks t ON t.id = w.task_id
+               WHERE ep.id = $1
+               ON CONFLICT(kind, source_id) DO UPDATE SET
+                   body = excluded.body,
+                   updated_at = datetime('now', 'subsec')"#,
+            execution_process_id,
+            body
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fts_query_quotes_terms_and_prefixes_the_last() {
+        assert_eq!(
+            fts_query("auth middleware").as_deref(),
+            Some(r#""auth" "middleware"*"#)
+        );
+        assert_eq!(fts_query("  ").as_deref(), None);
+    }
+
+    #[test]
+    fn fts_query_escapes_syntax_characters() {
+        assert_eq!(
+            fts_query(r#"say "hi" OR -x"#).as_deref(),
+            Some(r#""say" """hi""" "OR" "-x"*"#)
+        );
+    }
+}
@@ -1,6 +1,6 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, SqlitePool};
+use sqlx::{Executor, FromRow, Sqlite, SqlitePool};
 use thiserror::Error;
 use ts_rs::TS;
 use uuid::Uuid;
@@ -89,7 +89,7 @@ impl Session {
     }
 
     pub async fn create(
-        pool: &SqlitePool,
+        executor: impl Executor<'_, Database = Sqlite>,
         data: &CreateSession,
         id: Uuid,
         workspace_id: Uuid,
@@ -107,7 +107,7 @@ impl Session {
             workspace_id,
             data.executor
         )
-        .fetch_one(pool)
+        .fetch_one(executor)
         .await?)
     }
 }
@@ -1,11 +1,15 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::{Executor, FromRow, Sqlite, SqlitePool, Type};
+use sqlx::{Acquire, Executor, FromRow, Sqlite, SqlitePool, Type};
 use strum_macros::{Display, EnumString};
 use ts_rs::TS;
 use uuid::Uuid;
 
-use super::{project::Project, workspace::Workspace};
+use super::{
+    project::Project,
+    task_event::{TaskEvent, TaskEventActor},
+    workspace::Workspace,
+};
 
 #[derive(
     Debug, Clone, Type, Serialize, Deserialize, PartialEq, TS, EnumString, Display, Default,
@@ -42,6 +46,7 @@ pub struct TaskWithAttemptStatus {
     pub task: Task,
     pub has_in_progress_attempt: bool,
     pub last_attempt_failed: bool,
+    pub is_blocked: bool,
     pub executor: String,
 }
 
@@ -173,6 +178,14 @@ impl Task {
   ) IN ('failed','killed') THEN 1 ELSE 0 END
                                  AS "last_attempt_failed!: i64",
 
+  CASE WHEN EXISTS (
+    SELECT 1
+      FROM task_dependencies td
+      JOIN tasks blocker ON blocker.id = td.depends_on_task_id
+     WHERE td.task_id = t.id
+       AND blocker.status != 'done'
+  ) THEN 1 ELSE 0 END            AS "is_blocked!: i64",
+
   ( SELECT s.executor
       FROM workspaces w
       JOIN sessions s ON s.workspace_id = w.id
@@ -205,6 +218,7 @@ ORDER BY t.created_at DESC"#,
                 },
                 has_in_progress_attempt: rec.has_in_progress_attempt != 0,
                 last_attempt_failed: rec.last_attempt_failed != 0,
+                is_blocked: rec.is_blocked != 0,
                 executor: rec.executor,
             })
             .collect();
@@ -266,13 +280,16 @@ ORDER BY t.created_at DESC"#,
         .await
     }
 
+    /// Create a task and record its initial status as made by `actor`, in one transaction
     pub async fn create(
-        pool: &SqlitePool,
+        db: impl Acquire<'_, Database = Sqlite>,
         data: &CreateTask,
         task_id: Uuid,
+        actor: TaskEventActor,
     ) -> Result<Self, sqlx::Error> {
+        let mut tx = db.begin().await?;
         let status = data.status.clone().unwrap_or_default();
-        sqlx::query_as!(
+        let task = sqlx::query_as!(
             Task,
             r#"INSERT INTO tasks (id, project_id, title, description, status, parent_workspace_id, shared_task_id)
                VALUES ($1, $2, $3, $4, $5, $6, $7)
@@ -285,10 +302,16 @@ ORDER BY t.created_at DESC"#,
             data.parent_workspace_id,
             data.shared_task_id
         )
-        .fetch_one(pool)
-        .await
+        .fetch_one(&mut *tx)
+        .await?;
+        TaskEvent::create(&mut *tx, task.id, None, &task.status, actor).await?;
+        tx.commit().await?;
+        Ok(task)
     }
 
+    /// Update a task, recording a status change in its history in the same transaction.
+    /// Returns the updated task along with the status it had before.
+    #[allow(clippy::too_many_arguments)]
     pub async fn update(
         pool: &SqlitePool,
         id: Uuid,
@@ -297,8 +320,16 @@ ORDER BY t.created_at DESC"#,
         description: Option<String>,
         status: TaskStatus,
         parent_workspace_id: Option<Uuid>,
-    ) -> Result<Self, sqlx::Error> {
-        sqlx::query_as!(
+        actor: TaskEventActor,
+    ) -> Result<(Self, TaskStatus), sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        let old_status = sqlx::query_scalar!(
+            r#"SELECT status as "status!: TaskStatus" FROM tasks WHERE id = $1"#,
+            id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        let task = sqlx::query_as!(
             Task,
             r#"UPDATE tasks
                SET title = $3, description = $4, status = $5, parent_workspace_id = $6
@@ -311,22 +342,43 @@ ORDER BY t.created_at DESC"#,
             status,
             parent_workspace_id
         )
-        .fetch_one(pool)
-        .await
+        .fetch_one(&mut *tx)
+        .await?;
+        if old_status != task.status {
+            TaskEvent::create(&mut *tx, id, Some(&old_status), &task.status, actor).await?;
+        }
+        tx.commit().await?;
+        Ok((task, old_status))
     }
 
+    /// Set the status of a task, recording the change in its history if it is one. Both
+    /// writes happen in one transaction so the history never disagrees with the task
     pub async fn update_status(
         pool: &SqlitePool,
         id: Uuid,
         status: TaskStatus,
+        actor: TaskEventActor,
     ) -> Result<(), sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        let old_status = sqlx::query_scalar!(
+            r#"SELECT status as "status!: TaskStatus" FROM tasks WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
         sqlx::query!(
             "UPDATE tasks SET status = $2, updated_at = CURRENT_TIMESTAMP WHERE id = $1",
             id,
             status
         )
-        .execute(pool)
+        .execute(&mut *tx)
         .await?;
+        if let Some(old_status) = old_status
+            && old_status != status
+        {
+            TaskEvent::create(&mut *tx, id, Some(&old_status), &status, actor).await?;
+        }
+        tx.commit().await?;
         Ok(())
     }
 
@@ -490,3 +542,103 @@ ORDER BY t.created_at DESC"#,
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::project::CreateProject;
+
+    #[sqlx::test]
+    async fn update_records_status_changes_in_the_history(pool: SqlitePool) {
+        let project = Project::create(
+            &pool,
+            &CreateProject {
+                name: "history".to_string(),
+                repositories: vec![],
+            },
+            Uuid::new_v4(),
+        )
+        .await
+        .unwrap();
+        let data = CreateTask::from_title_description(project.id, "task".to_string(), None);
+        let task = Task::create(&pool, &data, Uuid::new_v4(), TaskEventActor::User)
+            .await
+            .unwrap();
+
+        let (updated, old_status) = Task::update(
+            &pool,
+            task.id,
+            project.id,
+            "renamed".to_string(),
+            None,
+            TaskStatus::InProgress,
+            None,
+            TaskEventActor::User,
+        )
+        .await
+        .unwrap();
+        assert_eq!(old_status, TaskStatus::Todo);
+        assert_eq!(updated.status, TaskStatus::InProgress);
+
+        // Editing other fields does not add an event
+        let (_, old_status) = Task::update(
+            &pool,
+            task.id,
+            project.id,
+            "renamed again".to_string(),
+            None,
+            TaskStatus::InProgress,
+            None,
+            TaskEventActor::User,
+        )
+        .await
+        .unwrap();
+        assert_eq!(old_status, TaskStatus::InProgress);
+
+        let events = TaskEvent::find_by_task_id(&pool, task.id).await.unwrap();
+        let transitions: Vec<_> = events
+            .iter()
+            .map(|event| (event.old_status.clone(), event.new_status.clone()))
+            .collect();
+        assert_eq!(
+            transitions,
+            vec![
+                (None, TaskStatus::Todo),
+                (Some(TaskStatus::Todo), TaskStatus::InProgress),
+            ]
+        );
+
+        // A task from another project is not updated and leaves no event behind
+        let other = Project::create(
+            &pool,
+            &CreateProject {
+                name: "other".to_string(),
+                repositories: vec![],
+            },
+            Uuid::new_v4(),
+        )
+        .await
+        .unwrap();
+        assert!(matches!(
+            Task::update(
+                &pool,
+                task.id,
+                other.id,
+                "moved".to_string(),
+                None,
+                TaskStatus::Done,
+                None,
+                TaskEventActor::User,
+            )
+            .await,
+            Err(sqlx::Error::RowNotFound)
+        ));
+        assert_eq!(
+            TaskEvent::find_by_task_id(&pool, task.id)
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
+    }
+}
@@ -0,0 +1,117 @@
+use chrono::{DateTime, Utc};
+use executors::profile::ExecutorProfileId;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::workspace_repo::CreateWorkspaceRepo;
+
+/// How to launch a task automatically once its last blocker reaches `TaskStatus::Done`
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct TaskAutoStart {
+    pub task_id: Uuid,
+    #[ts(type = "ExecutorProfileId")]
+    pub executor_profile_id: sqlx::types::Json<ExecutorProfileId>,
+    #[ts(type = "Array<CreateWorkspaceRepo>")]
+    pub repos: sqlx::types::Json<Vec<CreateWorkspaceRepo>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UpsertTaskAutoStart {
+    pub executor_profile_id: ExecutorProfileId,
+    pub repos: Vec<CreateWorkspaceRepo>,
+}
+
+impl TaskAutoStart {
+    pub async fn find_by_task_id(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskAutoStart,
+            r#"SELECT task_id as "task_id!: Uuid",
+                      executor_profile_id as "executor_profile_id!: sqlx::types::Json<ExecutorProfileId>",
+                      repos as "repos!: sqlx::types::Json<Vec<CreateWorkspaceRepo>>",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_auto_starts
+               WHERE task_id = $1"#,
+            task_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn upsert(
+        pool: &SqlitePool,
+        task_id: Uuid,
+        data: &UpsertTaskAutoStart,
+    ) -> Result<Self, sqlx::Error> {
+        let executor_profile_id = sqlx::types::Json(&data.executor_profile_id);
+        let repos = sqlx::types::Json(&data.repos);
+        sqlx::query_as!(
+            TaskAutoStart,
+            r#"INSERT INTO task_auto_starts (task_id, executor_profile_id, repos)
+               VALUES ($1, $2, $3)
+               ON CONFLICT (task_id) DO UPDATE SET
+                   executor_profile_id = excluded.executor_profile_id,
+                   repos = excluded.repos,
+                   updated_at = datetime('now', 'subsec')
+               RETURNING task_id as "task_id!: Uuid",
+                         executor_profile_id as "executor_profile_id!: sqlx::types::Json<ExecutorProfileId>",
+                         repos as "repos!: sqlx::types::Json<Vec<CreateWorkspaceRepo>>",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            task_id,
+            executor_profile_id,
+            repos
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Remove and return the configuration of `task_id`. Only one caller gets the row, so a
+    /// task unblocked by two completions at once is launched once.
+    pub async fn claim(pool: &SqlitePool, task_id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskAutoStart,
+            r#"DELETE FROM task_auto_starts
+               WHERE task_id = $1
+               RETURNING task_id as "task_id!: Uuid",
+                         executor_profile_id as "executor_profile_id!: sqlx::types::Json<ExecutorProfileId>",
+                         repos as "repos!: sqlx::types::Json<Vec<CreateWorkspaceRepo>>",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            task_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Put back a configuration taken by `claim`, unless a new one was saved meanwhile
+    pub async fn restore(pool: &SqlitePool, auto_start: &Self) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"INSERT INTO task_auto_starts (task_id, executor_profile_id, repos, created_at, updated_at)
+               VALUES ($1, $2, $3, $4, $5)
+               ON CONFLICT (task_id) DO NOTHING"#,
+            auto_start.task_id,
+            auto_start.executor_profile_id,
+            auto_start.repos,
+            auto_start.created_at,
+            auto_start.updated_at
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn delete(pool: &SqlitePool, task_id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM task_auto_starts WHERE task_id = $1", task_id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
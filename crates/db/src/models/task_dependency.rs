@@ -0,0 +1,304 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use thiserror::Error;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::{
+    task::{Task, TaskStatus},
+    task_auto_start::TaskAutoStart,
+};
+
+#[derive(Debug, Error)]
+pub enum TaskDependencyError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error("Task not found")]
+    TaskNotFound,
+    #[error("A task cannot depend on itself")]
+    SelfDependency,
+    #[error("Dependencies can only be created between tasks in the same project")]
+    ProjectMismatch,
+    #[error("Adding this dependency would create a cycle")]
+    Cycle,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct TaskDependency {
+    pub task_id: Uuid,
+    pub depends_on_task_id: Uuid,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct CreateTaskDependency {
+    pub depends_on_task_id: Uuid,
+}
+
+/// Both directions of the dependency graph around a single task
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct TaskDependencies {
+    pub blocked_by: Vec<Task>, // Tasks that must be done before this one can start
+    pub blocking: Vec<Task>,   // Tasks waiting on this one
+    pub auto_start: Option<TaskAutoStart>,
+}
+
+impl TaskDependency {
+    pub async fn find_blockers(pool: &SqlitePool, task_id: Uuid) -> Result<Vec<Task>, sqlx::Error> {
+        sqlx::query_as!(
+            Task,
+            r#"SELECT t.id as "id!: Uuid", t.project_id as "project_id!: Uuid", t.title, t.description, t.status as "status!: TaskStatus", t.parent_workspace_id as "parent_workspace_id: Uuid", t.shared_task_id as "shared_task_id: Uuid", t.created_at as "created_at!: DateTime<Utc>", t.updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_dependencies td
+               JOIN tasks t ON t.id = td.depends_on_task_id
+               WHERE td.task_id = $1
+               ORDER BY td.created_at ASC"#,
+            task_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_dependents(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Vec<Task>, sqlx::Error> {
+        sqlx::query_as!(
+            Task,
+            r#"SELECT t.id as "id!: Uuid", t.project_id as "project_id!: Uuid", t.title, t.description, t.status as "status!: TaskStatus", t.parent_workspace_id as "parent_workspace_id: Uuid", t.shared_task_id as "shared_task_id: Uuid", t.created_at as "created_at!: DateTime<Utc>", t.updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_dependencies td
+               JOIN tasks t ON t.id = td.task_id
+               WHERE td.depends_on_task_id = $1
+               ORDER BY td.created_at ASC"#,
+            task_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Dependents of `task_id` that have no remaining blocker outside `TaskStatus::Done`
+    pub async fn find_unblocked_dependents(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Vec<Task>, sqlx::Error> {
+        sqlx::query_as!(
+            Task,
+            r#"SELECT t.id as "id!: Uuid", t.project_id as "project_id!: Uuid", t.title, t.description, t.status as "status!: TaskStatus", t.parent_workspace_id as "parent_workspace_id: Uuid", t.shared_task_id as "shared_task_id: Uuid", t.created_at as "created_at!: DateTime<Utc>", t.updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_dependencies td
+               JOIN tasks t ON t.id = td.task_id
+               WHERE td.depends_on_task_id = $1
+                 AND NOT EXISTS (
+                     SELECT 1
+                       FROM task_dependencies other
+                       JOIN tasks blocker ON blocker.id = other.depends_on_task_id
+                      WHERE other.task_id = t.id
+                        AND blocker.status != 'done'
+                 )
+               ORDER BY td.created_at ASC"#,
+            task_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// True if `task_id` has at least one blocker that is not done yet
+    pub async fn is_blocked(pool: &SqlitePool, task_id: Uuid) -> Result<bool, sqlx::Error> {
+        let blocked = sqlx::query_scalar!(
+            r#"SELECT EXISTS (
+                   SELECT 1
+                     FROM task_dependencies td
+                     JOIN tasks blocker ON blocker.id = td.depends_on_task_id
+                    WHERE td.task_id = $1
+                      AND blocker.status != 'done'
+               ) AS "blocked!: bool""#,
+            task_id
+        )
+        .fetch_one(pool)
+        .await?;
+        Ok(blocked)
+    }
+
+    /// Adding the edge `task_id -> depends_on_task_id` closes a cycle iff
+    /// `depends_on_task_id` already (transitively) depends on `task_id`.
+    pub async fn would_create_cycle(
+        pool: &SqlitePool,
+        task_id: Uuid,
+        depends_on_task_id: Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        if task_id == depends_on_task_id {
+            return Ok(true);
+        }
+
+        let reachable = sqlx::query_scalar!(
+            r#"WITH RECURSIVE upstream(id) AS (
+                   SELECT depends_on_task_id FROM task_dependencies WHERE task_id = $1
+                   UNION
+                   SELECT td.depends_on_task_id
+                     FROM task_dependencies td
+                     JOIN upstream u ON td.task_id = u.id
+               )
+               SELECT EXISTS (SELECT 1 FROM upstream WHERE id = $2) AS "reachable!: bool""#,
+            depends_on_task_id,
+            task_id
+        )
+        .fetch_one(pool)
+        .await?;
+        Ok(reachable)
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        task_id: Uuid,
+        depends_on_task_id: Uuid,
+    ) -> Result<Self, TaskDependencyError> {
+        if task_id == depends_on_task_id {
+            return Err(TaskDependencyError::SelfDependency);
+        }
+
+        let task = Task::find_by_id(pool, task_id)
+            .await?
+            .ok_or(TaskDependencyError::TaskNotFound)?;
+        let blocker = Task::find_by_id(pool, depends_on_task_id)
+            .await?
+            .ok_or(TaskDependencyError::TaskNotFound)?;
+        if task.project_id != blocker.project_id {
+            return Err(TaskDependencyError::ProjectMismatch);
+        }
+
+        if Self::would_create_cycle(pool, task_id, depends_on_task_id).await? {
+            return Err(TaskDependencyError::Cycle);
+        }
+
+        sqlx::query!(
+            r#"INSERT INTO task_dependencies (task_id, depends_on_task_id)
+               VALUES ($1, $2)
+               ON CONFLICT (task_id, depends_on_task_id) DO NOTHING"#,
+            task_id,
+            depends_on_task_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(sqlx::query_as!(
+            TaskDependency,
+            r#"SELECT task_id as "task_id!: Uuid", depends_on_task_id as "depends_on_task_id!: Uuid", created_at as "created_at!: DateTime<Utc>"
+               FROM task_dependencies
+               WHERE task_id = $1 AND depends_on_task_id = $2"#,
+            task_id,
+            depends_on_task_id
+        )
+        .fetch_one(pool)
+        .await?)
+    }
+
+    pub async fn delete(
+        pool: &SqlitePool,
+        task_id: Uuid,
+        depends_on_task_id: Uuid,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM task_dependencies WHERE task_id = $1 AND depends_on_task_id = $2",
+            task_id,
+            depends_on_task_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    pub async fn find_for_task(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<TaskDependencies, sqlx::Error> {
+        Ok(TaskDependencies {
+            blocked_by: Self::find_blockers(pool, task_id).await?,
+            blocking: Self::find_dependents(pool, task_id).await?,
+            auto_start: TaskAutoStart::find_by_task_id(pool, task_id).await?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{
+        project::{CreateProject, Project},
+        task::CreateTask,
+        task_event::TaskEventActor,
+    };
+
+    async fn create_tasks(pool: &SqlitePool, count: usize) -> Vec<Uuid> {
+        let project = Project::create(
+            pool,
+            &CreateProject {
+                name: "deps".to_string(),
+                repositories: vec![],
+            },
+            Uuid::new_v4(),
+        )
+        .await
+        .unwrap();
+        let mut ids = Vec::new();
+        for i in 0..count {
+            let data = CreateTask::from_title_description(project.id, format!("task {i}"), None);
+            let task = Task::create(pool, &data, Uuid::new_v4(), TaskEventActor::User)
+                .await
+                .unwrap();
+            ids.push(task.id);
+        }
+        ids
+    }
+
+    #[sqlx::test]
+    async fn rejects_direct_and_transitive_cycles(pool: SqlitePool) {
+        let ids = create_tasks(&pool, 4).await;
+        let (a, b, c, d) = (ids[0], ids[1], ids[2], ids[3]);
+        // a -> b -> c
+        TaskDependency::create(&pool, a, b).await.unwrap();
+        TaskDependency::create(&pool, b, c).await.unwrap();
+
+        assert!(matches!(
+            TaskDependency::create(&pool, b, a).await,
+            Err(TaskDependencyError::Cycle)
+        ));
+        assert!(matches!(
+            TaskDependency::create(&pool, c, a).await,
+            Err(TaskDependencyError::Cycle)
+        ));
+        assert!(matches!(
+            TaskDependency::create(&pool, a, a).await,
+            Err(TaskDependencyError::SelfDependency)
+        ));
+
+        // Edges that keep the graph acyclic are accepted, including a shortcut a -> c
+        assert!(
+            !TaskDependency::would_create_cycle(&pool, a, c)
+                .await
+                .unwrap()
+        );
+        TaskDependency::create(&pool, a, c).await.unwrap();
+        TaskDependency::create(&pool, d, a).await.unwrap();
+        assert!(
+            TaskDependency::would_create_cycle(&pool, c, d)
+                .await
+                .unwrap()
+        );
+    }
+
+    #[sqlx::test]
+    async fn removing_an_edge_allows_the_reverse(pool: SqlitePool) {
+        let ids = create_tasks(&pool, 2).await;
+        TaskDependency::create(&pool, ids[0], ids[1]).await.unwrap();
+        assert!(
+            TaskDependency::would_create_cycle(&pool, ids[1], ids[0])
+                .await
+                .unwrap()
+        );
+
+        assert_eq!(
+            TaskDependency::delete(&pool, ids[0], ids[1]).await.unwrap(),
+            1
+        );
+        TaskDependency::create(&pool, ids[1], ids[0]).await.unwrap();
+    }
+}
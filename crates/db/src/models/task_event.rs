@@ -0,0 +1,105 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{Executor, FromRow, Sqlite, SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::task::TaskStatus;
+
+/// What changed a task's status
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "task_event_actor", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum TaskEventActor {
+    /// The UI, the MCP task server or another API client
+    User,
+    /// Coding agent runs starting, finishing or waiting for approval
+    Agent,
+    PrMonitor,
+    SharedTaskSync,
+    Scheduler,
+}
+
+/// One status change of a task
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct TaskEvent {
+    pub id: Uuid,
+    pub task_id: Uuid,
+    /// `None` for the event recorded when the task was created
+    pub old_status: Option<TaskStatus>,
+    pub new_status: TaskStatus,
+    pub actor: TaskEventActor,
+    pub created_at: DateTime<Utc>,
+}
+
+impl TaskEvent {
+    pub async fn create<'e, E>(
+        executor: E,
+        task_id: Uuid,
+        old_status: Option<&TaskStatus>,
+        new_status: &TaskStatus,
+        actor: TaskEventActor,
+    ) -> Result<Self, sqlx::Error>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            TaskEvent,
+            r#"INSERT INTO task_events (id, task_id, old_status, new_status, actor)
+               VALUES ($1, $2, $3, $4, $5)
+               RETURNING id as "id!: Uuid",
+                         task_id as "task_id!: Uuid",
+                         old_status as "old_status?: TaskStatus",
+                         new_status as "new_status!: TaskStatus",
+                         actor as "actor!: TaskEventActor",
+                         created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            task_id,
+            old_status,
+            new_status,
+            actor
+        )
+        .fetch_one(executor)
+        .await
+    }
+
+    /// Status history of a task, oldest first
+    pub async fn find_by_task_id(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskEvent,
+            r#"SELECT id as "id!: Uuid",
+                      task_id as "task_id!: Uuid",
+                      old_status as "old_status?: TaskStatus",
+                      new_status as "new_status!: TaskStatus",
+                      actor as "actor!: TaskEventActor",
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM task_events
+               WHERE task_id = $1
+               ORDER BY created_at ASC, rowid ASC"#,
+            task_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_rowid(pool: &SqlitePool, rowid: i64) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskEvent,
+            r#"SELECT id as "id!: Uuid",
+                      task_id as "task_id!: Uuid",
+                      old_status as "old_status?: TaskStatus",
+                      new_status as "new_status!: TaskStatus",
+                      actor as "actor!: TaskEventActor",
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM task_events
+               WHERE rowid = $1"#,
+            rowid
+        )
+        .fetch_optional(pool)
+        .await
+    }
+}
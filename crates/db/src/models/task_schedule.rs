@@ -0,0 +1,364 @@
+use chrono::{DateTime, Utc};
+use executors::profile::ExecutorProfileId;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use thiserror::Error;
+use ts_rs::TS;
+use utils::cron::{CronError, CronSchedule};
+use uuid::Uuid;
+
+use super::workspace_repo::CreateWorkspaceRepo;
+
+#[derive(Debug, Error)]
+pub enum TaskScheduleError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    InvalidCron(#[from] CronError),
+    #[error("Cron expression '{0}' never fires")]
+    NeverFires(String),
+    #[error("A schedule needs at least one repository")]
+    NoRepos,
+}
+
+/// A task template that is instantiated and started whenever its cron expression fires
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct TaskSchedule {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub title: String,
+    pub description: Option<String>,
+    /// Five-field cron expression, evaluated in UTC
+    pub cron_expression: String,
+    #[ts(type = "ExecutorProfileId")]
+    pub executor_profile_id: sqlx::types::Json<ExecutorProfileId>,
+    #[ts(type = "Array<CreateWorkspaceRepo>")]
+    pub repos: sqlx::types::Json<Vec<CreateWorkspaceRepo>>,
+    pub enabled: bool,
+    /// `None` while the schedule is disabled
+    pub next_run_at: Option<DateTime<Utc>>,
+    pub last_run_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct CreateTaskSchedule {
+    pub project_id: Uuid,
+    pub title: String,
+    pub description: Option<String>,
+    pub cron_expression: String,
+    pub executor_profile_id: ExecutorProfileId,
+    pub repos: Vec<CreateWorkspaceRepo>,
+    pub enabled: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UpdateTaskSchedule {
+    pub title: Option<String>,
+    /// An empty string removes the description
+    pub description: Option<String>,
+    pub cron_expression: Option<String>,
+    pub executor_profile_id: Option<ExecutorProfileId>,
+    pub repos: Option<Vec<CreateWorkspaceRepo>>,
+    pub enabled: Option<bool>,
+}
+
+/// One firing of a schedule. `task_id`/`workspace_id` are cleared if the spawned task is
+/// deleted; `error` is set when the task could not be created or started.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct TaskScheduleRun {
+    pub id: Uuid,
+    pub schedule_id: Uuid,
+    pub task_id: Option<Uuid>,
+    pub workspace_id: Option<Uuid>,
+    pub error: Option<String>,
+    pub scheduled_for: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Next firing after `now`, or `None` for a disabled schedule
+fn next_run_at(
+    cron_expression: &str,
+    enabled: bool,
+    now: DateTime<Utc>,
+) -> Result<Option<DateTime<Utc>>, TaskScheduleError> {
+    let cron = CronSchedule::parse(cron_expression)?;
+    let next = cron
+        .next_after(now)
+        .ok_or_else(|| TaskScheduleError::NeverFires(cron_expression.to_string()))?;
+    Ok(enabled.then_some(next))
+}
+
+impl TaskSchedule {
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskSchedule,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      title,
+                      description,
+                      cron_expression,
+                      executor_profile_id as "executor_profile_id!: sqlx::types::Json<ExecutorProfileId>",
+                      repos as "repos!: sqlx::types::Json<Vec<CreateWorkspaceRepo>>",
+                      enabled as "enabled!: bool",
+                      next_run_at as "next_run_at: DateTime<Utc>",
+                      last_run_at as "last_run_at: DateTime<Utc>",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_schedules
+               WHERE project_id = $1
+               ORDER BY created_at ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskSchedule,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      title,
+                      description,
+                      cron_expression,
+                      executor_profile_id as "executor_profile_id!: sqlx::types::Json<ExecutorProfileId>",
+                      repos as "repos!: sqlx::types::Json<Vec<CreateWorkspaceRepo>>",
+                      enabled as "enabled!: bool",
+                      next_run_at as "next_run_at: DateTime<Utc>",
+                      last_run_at as "last_run_at: DateTime<Utc>",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_schedules
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Enabled schedules whose next firing is at or before `now`
+    pub async fn find_due(pool: &SqlitePool, now: DateTime<Utc>) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskSchedule,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      title,
+                      description,
+                      cron_expression,
+                      executor_profile_id as "executor_profile_id!: sqlx::types::Json<ExecutorProfileId>",
+                      repos as "repos!: sqlx::types::Json<Vec<CreateWorkspaceRepo>>",
+                      enabled as "enabled!: bool",
+                      next_run_at as "next_run_at: DateTime<Utc>",
+                      last_run_at as "last_run_at: DateTime<Utc>",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_schedules
+               WHERE enabled = 1
+                 AND next_run_at IS NOT NULL
+                 AND next_run_at <= $1
+               ORDER BY next_run_at ASC"#,
+            now
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        data: &CreateTaskSchedule,
+    ) -> Result<Self, TaskScheduleError> {
+        if data.repos.is_empty() {
+            return Err(TaskScheduleError::NoRepos);
+        }
+        let enabled = data.enabled.unwrap_or(true);
+        let next_run_at = next_run_at(&data.cron_expression, enabled, Utc::now())?;
+
+        let id = Uuid::new_v4();
+        let cron_expression = data.cron_expression.trim();
+        let executor_profile_id = sqlx::types::Json(&data.executor_profile_id);
+        let repos = sqlx::types::Json(&data.repos);
+        Ok(sqlx::query_as!(
+            TaskSchedule,
+            r#"INSERT INTO task_schedules
+                   (id, project_id, title, description, cron_expression, executor_profile_id, repos, enabled, next_run_at)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+               RETURNING id as "id!: Uuid",
+                         project_id as "project_id!: Uuid",
+                         title,
+                         description,
+                         cron_expression,
+                         executor_profile_id as "executor_profile_id!: sqlx::types::Json<ExecutorProfileId>",
+                         repos as "repos!: sqlx::types::Json<Vec<CreateWorkspaceRepo>>",
+                         enabled as "enabled!: bool",
+                         next_run_at as "next_run_at: DateTime<Utc>",
+                         last_run_at as "last_run_at: DateTime<Utc>",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            data.project_id,
+            data.title,
+            data.description,
+            cron_expression,
+            executor_profile_id,
+            repos,
+            enabled,
+            next_run_at
+        )
+        .fetch_one(pool)
+        .await?)
+    }
+
+    /// Apply a partial update. The next firing is recomputed from now, so re-enabling a
+    /// schedule never replays the runs it missed while disabled.
+    pub async fn update(
+        pool: &SqlitePool,
+        id: Uuid,
+        data: &UpdateTaskSchedule,
+    ) -> Result<Self, TaskScheduleError> {
+        let existing = Self::find_by_id(pool, id)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)?;
+
+        let title = data.title.as_ref().unwrap_or(&existing.title);
+        let description = match &data.description {
+            Some(description) if description.trim().is_empty() => None,
+            Some(description) => Some(description.clone()),
+            None => existing.description,
+        };
+        let cron_expression = data
+            .cron_expression
+            .as_deref()
+            .map(str::trim)
+            .unwrap_or(&existing.cron_expression);
+        let executor_profile_id = sqlx::types::Json(
+            data.executor_profile_id
+                .as_ref()
+                .unwrap_or(&existing.executor_profile_id.0),
+        );
+        let repos = data.repos.as_ref().unwrap_or(&existing.repos.0);
+        if repos.is_empty() {
+            return Err(TaskScheduleError::NoRepos);
+        }
+        let repos = sqlx::types::Json(repos);
+        let enabled = data.enabled.unwrap_or(existing.enabled);
+        let next_run_at = next_run_at(cron_expression, enabled, Utc::now())?;
+
+        Ok(sqlx::query_as!(
+            TaskSchedule,
+            r#"UPDATE task_schedules
+               SET title = $2, description = $3, cron_expression = $4, executor_profile_id = $5,
+                   repos = $6, enabled = $7, next_run_at = $8, updated_at = datetime('now', 'subsec')
+               WHERE id = $1
+               RETURNING id as "id!: Uuid",
+                         project_id as "project_id!: Uuid",
+                         title,
+                         description,
+                         cron_expression,
+                         executor_profile_id as "executor_profile_id!: sqlx::types::Json<ExecutorProfileId>",
+                         repos as "repos!: sqlx::types::Json<Vec<CreateWorkspaceRepo>>",
+                         enabled as "enabled!: bool",
+                         next_run_at as "next_run_at: DateTime<Utc>",
+                         last_run_at as "last_run_at: DateTime<Utc>",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            title,
+            description,
+            cron_expression,
+            executor_profile_id,
+            repos,
+            enabled,
+            next_run_at
+        )
+        .fetch_one(pool)
+        .await?)
+    }
+
+    /// Record that the schedule fired at `fired_at` and move it to its next firing
+    pub async fn advance(
+        pool: &SqlitePool,
+        id: Uuid,
+        fired_at: DateTime<Utc>,
+        next_run_at: Option<DateTime<Utc>>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE task_schedules
+               SET last_run_at = $2, next_run_at = $3
+               WHERE id = $1"#,
+            id,
+            fired_at,
+            next_run_at
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM task_schedules WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+impl TaskScheduleRun {
+    pub async fn create(
+        pool: &SqlitePool,
+        schedule_id: Uuid,
+        scheduled_for: DateTime<Utc>,
+        task_id: Option<Uuid>,
+        workspace_id: Option<Uuid>,
+        error: Option<String>,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            TaskScheduleRun,
+            r#"INSERT INTO task_schedule_runs (id, schedule_id, task_id, workspace_id, error, scheduled_for)
+               VALUES ($1, $2, $3, $4, $5, $6)
+               RETURNING id as "id!: Uuid",
+                         schedule_id as "schedule_id!: Uuid",
+                         task_id as "task_id: Uuid",
+                         workspace_id as "workspace_id: Uuid",
+                         error,
+                         scheduled_for as "scheduled_for!: DateTime<Utc>",
+                         created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            schedule_id,
+            task_id,
+            workspace_id,
+            error,
+            scheduled_for
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Most recent firings first
+    pub async fn find_by_schedule_id(
+        pool: &SqlitePool,
+        schedule_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskScheduleRun,
+            r#"SELECT id as "id!: Uuid",
+                      schedule_id as "schedule_id!: Uuid",
+                      task_id as "task_id: Uuid",
+                      workspace_id as "workspace_id: Uuid",
+                      error,
+                      scheduled_for as "scheduled_for!: DateTime<Utc>",
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM task_schedule_runs
+               WHERE schedule_id = $1
+               ORDER BY created_at DESC"#,
+            schedule_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
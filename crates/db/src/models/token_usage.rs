@@ -0,0 +1,80 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Which coding agent turns to include in usage reports
+#[derive(Debug, Clone, Default, Deserialize, TS)]
+pub struct TokenUsageFilter {
+    pub project_id: Option<Uuid>,
+    pub task_id: Option<Uuid>,
+    pub workspace_id: Option<Uuid>,
+    /// Only include turns started at or after this time
+    pub since: Option<DateTime<Utc>>,
+    /// Only include turns started before this time
+    pub until: Option<DateTime<Utc>>,
+}
+
+/// Token usage of one coding agent turn, with the task, workspace and project it belongs to
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct TurnTokenUsage {
+    pub execution_process_id: Uuid,
+    pub task_id: Uuid,
+    pub task_title: String,
+    pub workspace_id: Uuid,
+    pub workspace_branch: String,
+    pub project_id: Uuid,
+    pub project_name: String,
+    pub model: Option<String>,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub cache_read_tokens: i64,
+    pub cache_write_tokens: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+impl TurnTokenUsage {
+    /// Turns that reported usage, oldest first
+    pub async fn find(
+        pool: &SqlitePool,
+        filter: &TokenUsageFilter,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TurnTokenUsage,
+            r#"SELECT ep.id as "execution_process_id!: Uuid",
+                      t.id as "task_id!: Uuid",
+                      t.title as task_title,
+                      w.id as "workspace_id!: Uuid",
+                      w.branch as workspace_branch,
+                      p.id as "project_id!: Uuid",
+                      p.name as project_name,
+                      cat.model,
+                      cat.input_tokens as "input_tokens!: i64",
+                      COALESCE(cat.output_tokens, 0) as "output_tokens!: i64",
+                      COALESCE(cat.cache_read_tokens, 0) as "cache_read_tokens!: i64",
+                      COALESCE(cat.cache_write_tokens, 0) as "cache_write_tokens!: i64",
+                      cat.created_at as "created_at!: DateTime<Utc>"
+               FROM coding_agent_turns cat
+               JOIN execution_processes ep ON ep.id = cat.execution_process_id
+               JOIN sessions s ON s.id = ep.session_id
+               JOIN workspaces w ON w.id = s.workspace_id
+               JOIN tasks t ON t.id = w.task_id
+               JOIN projects p ON p.id = t.project_id
+               WHERE cat.input_tokens IS NOT NULL
+                 AND ($1 IS NULL OR p.id = $1)
+                 AND ($2 IS NULL OR t.id = $2)
+                 AND ($3 IS NULL OR w.id = $3)
+                 AND ($4 IS NULL OR cat.created_at >= $4)
+                 AND ($5 IS NULL OR cat.created_at < $5)
+               ORDER BY cat.created_at ASC"#,
+            filter.project_id,
+            filter.task_id,
+            filter.workspace_id,
+            filter.since,
+            filter.until
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
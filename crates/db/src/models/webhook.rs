@@ -0,0 +1,521 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::{FromRow, SqlitePool, Type};
+use strum_macros::Display;
+use thiserror::Error;
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+pub enum WebhookError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error("Webhook URL must start with http:// or https://")]
+    InvalidUrl,
+}
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS, Display)]
+#[sqlx(type_name = "TEXT")]
+pub enum WebhookEventType {
+    #[sqlx(rename = "task.status_changed")]
+    #[serde(rename = "task.status_changed")]
+    #[strum(serialize = "task.status_changed")]
+    TaskStatusChanged,
+    #[sqlx(rename = "execution.completed")]
+    #[serde(rename = "execution.completed")]
+    #[strum(serialize = "execution.completed")]
+    ExecutionCompleted,
+    #[sqlx(rename = "execution.failed")]
+    #[serde(rename = "execution.failed")]
+    #[strum(serialize = "execution.failed")]
+    ExecutionFailed,
+    #[sqlx(rename = "approval.requested")]
+    #[serde(rename = "approval.requested")]
+    #[strum(serialize = "approval.requested")]
+    ApprovalRequested,
+    #[sqlx(rename = "pr.merged")]
+    #[serde(rename = "pr.merged")]
+    #[strum(serialize = "pr.merged")]
+    PrMerged,
+    /// Sent when testing a webhook; always delivered regardless of the event filter
+    #[sqlx(rename = "ping")]
+    #[serde(rename = "ping")]
+    #[strum(serialize = "ping")]
+    Ping,
+}
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "webhook_delivery_status", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookDeliveryStatus {
+    Pending,
+    Delivered,
+    /// Gave up after the last retry
+    Failed,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct Webhook {
+    pub id: Uuid,
+    pub name: String,
+    pub url: String,
+    /// Key for the `X-Vibe-Kanban-Signature` HMAC-SHA256 of each request body. Never sent
+    /// to clients after the webhook is created.
+    #[serde(skip_serializing)]
+    #[ts(skip)]
+    pub secret: String,
+    /// Subscribed event types; empty subscribes to every event
+    #[ts(type = "Array<WebhookEventType>")]
+    pub events: sqlx::types::Json<Vec<WebhookEventType>>,
+    pub enabled: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct CreateWebhook {
+    pub name: String,
+    pub url: String,
+    /// Generated when omitted
+    pub secret: Option<String>,
+    pub events: Vec<WebhookEventType>,
+    pub enabled: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UpdateWebhook {
+    pub name: Option<String>,
+    pub url: Option<String>,
+    pub secret: Option<String>,
+    pub events: Option<Vec<WebhookEventType>>,
+    pub enabled: Option<bool>,
+}
+
+/// A raised event waiting to be fanned out into deliveries
+#[derive(Debug, Clone, FromRow)]
+pub struct WebhookEvent {
+    pub id: i64,
+    pub event_type: WebhookEventType,
+    pub subject_id: Uuid,
+    pub payload: sqlx::types::Json<Value>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct WebhookDelivery {
+    pub id: Uuid,
+    pub webhook_id: Uuid,
+    pub event_type: WebhookEventType,
+    /// Request body, sent unchanged on every attempt
+    pub payload: String,
+    pub status: WebhookDeliveryStatus,
+    pub attempts: i64,
+    /// When the next attempt is due while the delivery is pending
+    pub next_attempt_at: Option<DateTime<Utc>>,
+    /// HTTP status of the most recent attempt, if a response was received
+    pub response_status: Option<i64>,
+    pub last_error: Option<String>,
+    pub delivered_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+fn validate_url(url: &str) -> Result<(), WebhookError> {
+    if url.starts_with("http://") || url.starts_with("https://") {
+        Ok(())
+    } else {
+        Err(WebhookError::InvalidUrl)
+    }
+}
+
+impl Webhook {
+    pub fn subscribes_to(&self, event_type: WebhookEventType) -> bool {
+        event_type == WebhookEventType::Ping
+            || self.events.is_empty()
+            || self.events.contains(&event_type)
+    }
+
+    pub async fn find_all(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Webhook,
+            r#"SELECT id as "id!: Uuid",
+                      name,
+                      url,
+                      secret,
+                      events as "events!: sqlx::types::Json<Vec<WebhookEventType>>",
+                      enabled as "enabled!: bool",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM webhooks
+               ORDER BY created_at ASC"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_enabled(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Webhook,
+            r#"SELECT id as "id!: Uuid",
+                      name,
+                      url,
+                      secret,
+                      events as "events!: sqlx::types::Json<Vec<WebhookEventType>>",
+                      enabled as "enabled!: bool",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM webhooks
+               WHERE enabled = 1
+               ORDER BY created_at ASC"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Webhook,
+            r#"SELECT id as "id!: Uuid",
+                      name,
+                      url,
+                      secret,
+                      events as "events!: sqlx::types::Json<Vec<WebhookEventType>>",
+                      enabled as "enabled!: bool",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM webhooks
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn create(pool: &SqlitePool, data: &CreateWebhook) -> Result<Self, WebhookError> {
+        let url = data.url.trim();
+        validate_url(url)?;
+
+        let id = Uuid::new_v4();
+        let secret = data
+            .secret
+            .clone()
+            .filter(|secret| !secret.is_empty())
+            .unwrap_or_else(|| format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple()));
+        let events = sqlx::types::Json(&data.events);
+        let enabled = data.enabled.unwrap_or(true);
+        Ok(sqlx::query_as!(
+            Webhook,
+            r#"INSERT INTO webhooks (id, name, url, secret, events, enabled)
+               VALUES ($1, $2, $3, $4, $5, $6)
+               RETURNING id as "id!: Uuid",
+                         name,
+                         url,
+                         secret,
+                         events as "events!: sqlx::types::Json<Vec<WebhookEventType>>",
+                         enabled as "enabled!: bool",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            data.name,
+            url,
+            secret,
+            events,
+            enabled
+        )
+        .fetch_one(pool)
+        .await?)
+    }
+
+    pub async fn update(
+        pool: &SqlitePool,
+        id: Uuid,
+        data: &UpdateWebhook,
+    ) -> Result<Self, WebhookError> {
+        let existing = Self::find_by_id(pool, id)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)?;
+
+        let name = data.name.as_ref().unwrap_or(&existing.name);
+        let url = data.url.as_deref().map(str::trim).unwrap_or(&existing.url);
+        validate_url(url)?;
+        let secret = data
+            .secret
+            .as_ref()
+            .filter(|secret| !secret.is_empty())
+            .unwrap_or(&existing.secret);
+        let events = sqlx::types::Json(data.events.as_ref().unwrap_or(&existing.events.0));
+        let enabled = data.enabled.unwrap_or(existing.enabled);
+
+        Ok(sqlx::query_as!(
+            Webhook,
+            r#"UPDATE webhooks
+               SET name = $2, url = $3, secret = $4, events = $5, enabled = $6,
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = $1
+               RETURNING id as "id!: Uuid",
+                         name,
+                         url,
+                         secret,
+                         events as "events!: sqlx::types::Json<Vec<WebhookEventType>>",
+                         enabled as "enabled!: bool",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            name,
+            url,
+            secret,
+            events,
+            enabled
+        )
+        .fetch_one(pool)
+        .await?)
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM webhooks WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+impl WebhookEvent {
+    /// Raise an event from application code. Events backed by a row change are recorded
+    /// by triggers instead; see the webhooks migration.
+    pub async fn create(
+        pool: &SqlitePool,
+        event_type: WebhookEventType,
+        subject_id: Uuid,
+        payload: &Value,
+    ) -> Result<(), sqlx::Error> {
+        let payload = sqlx::types::Json(payload);
+        sqlx::query!(
+            r#"INSERT INTO webhook_events (event_type, subject_id, payload)
+               SELECT $1, $2, $3
+               WHERE EXISTS (SELECT 1 FROM webhooks WHERE enabled = 1)"#,
+            event_type,
+            subject_id,
+            payload
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Oldest events first
+    pub async fn find_pending(pool: &SqlitePool, limit: i64) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            WebhookEvent,
+            r#"SELECT id as "id!: i64",
+                      event_type as "event_type!: WebhookEventType",
+                      subject_id as "subject_id!: Uuid",
+                      payload as "payload!: sqlx::types::Json<Value>",
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM webhook_events
+               ORDER BY id ASC
+               LIMIT $1"#,
+            limit
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: i64) -> Result<(), sqlx::Error> {
+        sqlx::query!("DELETE FROM webhook_events WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(())
+    }
+}
+
+impl WebhookDelivery {
+    /// Queue a delivery whose first attempt is due immediately
+    pub async fn create(
+        pool: &SqlitePool,
+        id: Uuid,
+        webhook_id: Uuid,
+        event_type: WebhookEventType,
+        payload: &str,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            WebhookDelivery,
+            r#"INSERT INTO webhook_deliveries (id, webhook_id, event_type, payload, next_attempt_at)
+               VALUES ($1, $2, $3, $4, datetime('now', 'subsec'))
+               RETURNING id as "id!: Uuid",
+                         webhook_id as "webhook_id!: Uuid",
+                         event_type as "event_type!: WebhookEventType",
+                         payload,
+                         status as "status!: WebhookDeliveryStatus",
+                         attempts as "attempts!: i64",
+                         next_attempt_at as "next_attempt_at: DateTime<Utc>",
+                         response_status,
+                         last_error,
+                         delivered_at as "delivered_at: DateTime<Utc>",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            webhook_id,
+            event_type,
+            payload
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Most recent deliveries first
+    pub async fn find_by_webhook_id(
+        pool: &SqlitePool,
+        webhook_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            WebhookDelivery,
+            r#"SELECT id as "id!: Uuid",
+                      webhook_id as "webhook_id!: Uuid",
+                      event_type as "event_type!: WebhookEventType",
+                      payload,
+                      status as "status!: WebhookDeliveryStatus",
+                      attempts as "attempts!: i64",
+                      next_attempt_at as "next_attempt_at: DateTime<Utc>",
+                      response_status,
+                      last_error,
+                      delivered_at as "delivered_at: DateTime<Utc>",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM webhook_deliveries
+               WHERE webhook_id = $1
+               ORDER BY created_at DESC
+               LIMIT $2"#,
+            webhook_id,
+            limit
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Pending deliveries whose next attempt is at or before `now`
+    pub async fn find_due(
+        pool: &SqlitePool,
+        now: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            WebhookDelivery,
+            r#"SELECT id as "id!: Uuid",
+                      webhook_id as "webhook_id!: Uuid",
+                      event_type as "event_type!: WebhookEventType",
+                      payload,
+                      status as "status!: WebhookDeliveryStatus",
+                      attempts as "attempts!: i64",
+                      next_attempt_at as "next_attempt_at: DateTime<Utc>",
+                      response_status,
+                      last_error,
+                      delivered_at as "delivered_at: DateTime<Utc>",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM webhook_deliveries
+               WHERE status = 'pending'
+                 AND datetime(next_attempt_at) <= datetime($1)
+               ORDER BY next_attempt_at ASC
+               LIMIT $2"#,
+            now,
+            limit
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn mark_delivered(
+        pool: &SqlitePool,
+        id: Uuid,
+        response_status: i64,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE webhook_deliveries
+               SET status = 'delivered', attempts = attempts + 1, next_attempt_at = NULL,
+                   response_status = $2, last_error = NULL,
+                   delivered_at = datetime('now', 'subsec'), updated_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            response_status
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Record a failed attempt. The delivery is retried at `retry_at`, or marked failed
+    /// when there is no retry left.
+    pub async fn record_failure(
+        pool: &SqlitePool,
+        id: Uuid,
+        response_status: Option<i64>,
+        error: &str,
+        retry_at: Option<DateTime<Utc>>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE webhook_deliveries
+               SET status = CASE WHEN $4 IS NULL THEN 'failed' ELSE 'pending' END,
+                   attempts = attempts + 1, next_attempt_at = $4,
+                   response_status = $2, last_error = $3, updated_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            response_status,
+            error,
+            retry_at
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Delete finished deliveries created before `before`
+    pub async fn delete_finished_before(
+        pool: &SqlitePool,
+        before: DateTime<Utc>,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"DELETE FROM webhook_deliveries
+               WHERE status != 'pending'
+                 AND datetime(created_at) < datetime($1)"#,
+            before
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn webhook(events: Vec<WebhookEventType>) -> Webhook {
+        Webhook {
+            id: Uuid::new_v4(),
+            name: "ci".to_string(),
+            url: "https://example.com/hook".to_string(),
+            secret: "secret".to_string(),
+            events: sqlx::types::Json(events),
+            enabled: true,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn empty_event_filter_subscribes_to_everything() {
+        let hook = webhook(vec![]);
+        assert!(hook.subscribes_to(WebhookEventType::TaskStatusChanged));
+        assert!(hook.subscribes_to(WebhookEventType::PrMerged));
+    }
+
+    #[test]
+    fn event_filter_limits_events_but_not_pings() {
+        let hook = webhook(vec![WebhookEventType::ExecutionFailed]);
+        assert!(hook.subscribes_to(WebhookEventType::ExecutionFailed));
+        assert!(!hook.subscribes_to(WebhookEventType::ExecutionCompleted));
+        assert!(hook.subscribes_to(WebhookEventType::Ping));
+    }
+}
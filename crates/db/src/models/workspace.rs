@@ -1,6 +1,6 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, SqlitePool, Type};
+use sqlx::{Executor, FromRow, Sqlite, SqlitePool, Type};
 use thiserror::Error;
 use ts_rs::TS;
 use uuid::Uuid;
@@ -51,6 +51,8 @@ pub struct Workspace {
     pub container_ref: Option<String>,
     pub branch: String,
     pub agent_working_dir: Option<String>,
+    /// Workspace whose branch this one is stacked on, set when it was created on top of it
+    pub stack_parent_id: Option<Uuid>,
     pub setup_completed_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
@@ -111,6 +113,7 @@ impl Workspace {
                               container_ref,
                               branch,
                               agent_working_dir,
+                              stack_parent_id AS "stack_parent_id: Uuid",
                               setup_completed_at AS "setup_completed_at: DateTime<Utc>",
                               created_at AS "created_at!: DateTime<Utc>",
                               updated_at AS "updated_at!: DateTime<Utc>"
@@ -129,6 +132,7 @@ impl Workspace {
                               container_ref,
                               branch,
                               agent_working_dir,
+                              stack_parent_id AS "stack_parent_id: Uuid",
                               setup_completed_at AS "setup_completed_at: DateTime<Utc>",
                               created_at AS "created_at!: DateTime<Utc>",
                               updated_at AS "updated_at!: DateTime<Utc>"
@@ -157,6 +161,7 @@ impl Workspace {
                        w.container_ref,
                        w.branch,
                        w.agent_working_dir,
+                       w.stack_parent_id AS "stack_parent_id: Uuid",
                        w.setup_completed_at AS "setup_completed_at: DateTime<Utc>",
                        w.created_at        AS "created_at!: DateTime<Utc>",
                        w.updated_at        AS "updated_at!: DateTime<Utc>"
@@ -231,6 +236,7 @@ impl Workspace {
                        container_ref,
                        branch,
                        agent_working_dir,
+                       stack_parent_id AS "stack_parent_id: Uuid",
                        setup_completed_at AS "setup_completed_at: DateTime<Utc>",
                        created_at        AS "created_at!: DateTime<Utc>",
                        updated_at        AS "updated_at!: DateTime<Utc>"
@@ -250,6 +256,7 @@ impl Workspace {
                        container_ref,
                        branch,
                        agent_working_dir,
+                       stack_parent_id AS "stack_parent_id: Uuid",
                        setup_completed_at AS "setup_completed_at: DateTime<Utc>",
                        created_at        AS "created_at!: DateTime<Utc>",
                        updated_at        AS "updated_at!: DateTime<Utc>"
@@ -288,6 +295,7 @@ impl Workspace {
                 w.container_ref,
                 w.branch as "branch!",
                 w.agent_working_dir,
+                w.stack_parent_id as "stack_parent_id: Uuid",
                 w.setup_completed_at as "setup_completed_at: DateTime<Utc>",
                 w.created_at as "created_at!: DateTime<Utc>",
                 w.updated_at as "updated_at!: DateTime<Utc>"
@@ -323,7 +331,7 @@ impl Workspace {
     }
 
     pub async fn create(
-        pool: &SqlitePool,
+        executor: impl Executor<'_, Database = Sqlite>,
         data: &CreateWorkspace,
         id: Uuid,
         task_id: Uuid,
@@ -332,7 +340,7 @@ impl Workspace {
             Workspace,
             r#"INSERT INTO workspaces (id, task_id, container_ref, branch, agent_working_dir, setup_completed_at)
                VALUES ($1, $2, $3, $4, $5, $6)
-               RETURNING id as "id!: Uuid", task_id as "task_id!: Uuid", container_ref, branch, agent_working_dir, setup_completed_at as "setup_completed_at: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+               RETURNING id as "id!: Uuid", task_id as "task_id!: Uuid", container_ref, branch, agent_working_dir, stack_parent_id as "stack_parent_id: Uuid", setup_completed_at as "setup_completed_at: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
             id,
             task_id,
             Option::<String>::None,
@@ -340,10 +348,58 @@ impl Workspace {
             data.agent_working_dir,
             Option::<DateTime<Utc>>::None
         )
-        .fetch_one(pool)
+        .fetch_one(executor)
         .await?)
     }
 
+    /// Delete the workspace with its repositories, sessions and execution processes
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM workspaces WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    pub async fn set_stack_parent(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        stack_parent_id: Option<Uuid>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE workspaces SET stack_parent_id = $1, updated_at = datetime('now') WHERE id = $2",
+            stack_parent_id,
+            workspace_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Workspaces stacked directly on `workspace_id`. Oldest first.
+    pub async fn find_stack_children(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Workspace,
+            r#"SELECT  id                AS "id!: Uuid",
+                       task_id           AS "task_id!: Uuid",
+                       container_ref,
+                       branch,
+                       agent_working_dir,
+                       stack_parent_id   AS "stack_parent_id: Uuid",
+                       setup_completed_at AS "setup_completed_at: DateTime<Utc>",
+                       created_at        AS "created_at!: DateTime<Utc>",
+                       updated_at        AS "updated_at!: DateTime<Utc>"
+               FROM    workspaces
+               WHERE   stack_parent_id = $1
+               ORDER BY created_at ASC"#,
+            workspace_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
     pub async fn update_branch_name(
         pool: &SqlitePool,
         workspace_id: Uuid,
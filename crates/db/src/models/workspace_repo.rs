@@ -2,7 +2,7 @@ use std::path::PathBuf;
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, SqlitePool};
+use sqlx::{Acquire, FromRow, Sqlite, SqlitePool};
 use ts_rs::TS;
 use uuid::Uuid;
 
@@ -20,7 +20,7 @@ pub struct WorkspaceRepo {
     pub updated_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Clone, Deserialize, TS)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
 pub struct CreateWorkspaceRepo {
     pub repo_id: Uuid,
     pub target_branch: String,
@@ -45,10 +45,11 @@ pub struct RepoWithCopyFiles {
 
 impl WorkspaceRepo {
     pub async fn create_many(
-        pool: &SqlitePool,
+        db: impl Acquire<'_, Database = Sqlite>,
         workspace_id: Uuid,
         repos: &[CreateWorkspaceRepo],
     ) -> Result<Vec<Self>, sqlx::Error> {
+        let mut conn = db.acquire().await?;
         let mut results = Vec::with_capacity(repos.len());
 
         for repo in repos {
@@ -68,7 +69,7 @@ impl WorkspaceRepo {
                 repo.repo_id,
                 repo.target_branch
             )
-            .fetch_one(pool)
+            .fetch_one(&mut *conn)
             .await?;
             results.push(workspace_repo);
         }
@@ -208,6 +209,7 @@ impl WorkspaceRepo {
                      SELECT w.id FROM workspaces w
                      JOIN tasks t ON w.task_id = t.id
                      WHERE t.parent_workspace_id = $3
+                        OR w.stack_parent_id = $3
                  )"#,
             new_branch,
             old_branch,
@@ -27,11 +27,14 @@ use services::services::{
     filesystem_watcher::FilesystemWatcherError,
     git::{GitService, GitServiceError},
     image::{ImageError, ImageService},
+    log_compactor::LogCompactorService,
     pr_monitor::PrMonitorService,
     project::ProjectService,
     queued_message::QueuedMessageService,
     repo::RepoService,
     share::SharePublisher,
+    task_scheduler::TaskSchedulerService,
+    webhooks::WebhookService,
     worktree_manager::WorktreeError,
 };
 use sqlx::Error as SqlxError;
@@ -133,7 +136,26 @@ pub trait Deployment: Clone + Send + Sync + 'static {
                 analytics_service: analytics_service.clone(),
             });
         let publisher = self.share_publisher().ok();
-        PrMonitorService::spawn(db, analytics, publisher).await
+        PrMonitorService::spawn(
+            db,
+            self.container().clone(),
+            self.config().clone(),
+            analytics,
+            publisher,
+        )
+        .await
+    }
+
+    async fn spawn_task_scheduler_service(&self) -> tokio::task::JoinHandle<()> {
+        TaskSchedulerService::spawn(self.db().clone(), self.container().clone()).await
+    }
+
+    async fn spawn_log_compactor_service(&self) -> tokio::task::JoinHandle<()> {
+        LogCompactorService::spawn(self.db().clone(), self.config().clone()).await
+    }
+
+    async fn spawn_webhook_service(&self) -> tokio::task::JoinHandle<()> {
+        WebhookService::spawn(self.db().clone()).await
     }
 
     async fn track_if_analytics_allowed(&self, event_name: &str, properties: Value) {
@@ -13,6 +13,7 @@ use crate::{
     approvals::ExecutorApprovalService,
     env::ExecutionEnv,
     executors::{BaseCodingAgent, ExecutorError, SpawnedChild},
+    profile::ExecutorProfileId,
 };
 pub mod coding_agent_follow_up;
 pub mod coding_agent_initial;
@@ -63,6 +64,17 @@ impl ExecutorAction {
             ExecutorActionType::ScriptRequest(_) => None,
         }
     }
+    pub fn executor_profile_id(&self) -> Option<ExecutorProfileId> {
+        match self.typ() {
+            ExecutorActionType::CodingAgentInitialRequest(request) => {
+                Some(request.executor_profile_id.clone())
+            }
+            ExecutorActionType::CodingAgentFollowUpRequest(request) => {
+                Some(request.get_executor_profile_id())
+            }
+            ExecutorActionType::ScriptRequest(_) => None,
+        }
+    }
 }
 
 #[async_trait]
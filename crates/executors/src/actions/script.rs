@@ -3,7 +3,6 @@ use std::{path::Path, sync::Arc};
 use async_trait::async_trait;
 use command_group::AsyncCommandGroup;
 use serde::{Deserialize, Serialize};
-use tokio::process::Command;
 use ts_rs::TS;
 use workspace_utils::shell::get_shell_command;
 
@@ -25,6 +24,7 @@ pub enum ScriptContext {
     CleanupScript,
     DevServer,
     ToolInstallScript,
+    QualityGate,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
@@ -52,19 +52,20 @@ impl Executable for ScriptRequest {
             None => current_dir.to_path_buf(),
         };
 
-        let (shell_cmd, shell_arg) = get_shell_command();
-        let mut command = Command::new(shell_cmd);
+        // The host login shell is unlikely to exist inside a workspace container
+        let (shell_cmd, shell_arg) = if env.container.is_some() {
+            ("sh".to_string(), "-c")
+        } else {
+            get_shell_command()
+        };
+        let mut command = env.command(shell_cmd, &effective_dir);
         command
             .kill_on_drop(true)
             .stdin(std::process::Stdio::null())
             .stdout(std::process::Stdio::piped())
             .stderr(std::process::Stdio::piped())
             .arg(shell_arg)
-            .arg(&self.script)
-            .current_dir(&effective_dir);
-
-        // Apply environment variables
-        env.apply_to_command(&mut command);
+            .arg(&self.script);
 
         let child = command.group_spawn()?;
 
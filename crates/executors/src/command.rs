@@ -6,7 +6,7 @@ use thiserror::Error;
 use ts_rs::TS;
 use workspace_utils::shell::resolve_executable_path;
 
-use crate::executors::ExecutorError;
+use crate::{env::ExecutionEnv, executors::ExecutorError};
 
 #[derive(Debug, Error)]
 pub enum CommandBuildError {
@@ -29,6 +29,10 @@ impl CommandParts {
         Self { program, args }
     }
 
+    pub fn program(&self) -> &str {
+        &self.program
+    }
+
     pub async fn into_resolved(self) -> Result<(PathBuf, Vec<String>), ExecutorError> {
         let CommandParts { program, args } = self;
         let executable = resolve_executable_path(&program)
@@ -36,6 +40,18 @@ impl CommandParts {
             .ok_or(ExecutorError::ExecutableNotFound { program })?;
         Ok((executable, args))
     }
+
+    /// Like [`Self::into_resolved`], but leaves the program name untouched when the env
+    /// routes processes into a container, where it is looked up on the container's PATH.
+    pub async fn into_resolved_in(
+        self,
+        env: &ExecutionEnv,
+    ) -> Result<(PathBuf, Vec<String>), ExecutorError> {
+        if env.container.is_some() {
+            return Ok((PathBuf::from(self.program), self.args));
+        }
+        self.into_resolved().await
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS, JsonSchema, Default)]
@@ -58,6 +74,40 @@ pub struct CmdOverrides {
     )]
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub env: Option<HashMap<String, String>>,
+    #[serde(flatten)]
+    pub limits: ExecutionLimits,
+}
+
+/// Runaway guard for a single execution process. Exceeding any limit interrupts the agent,
+/// then kills it if it does not exit in time.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS, JsonSchema, Default)]
+pub struct ExecutionLimits {
+    #[schemars(
+        title = "Max Runtime (seconds)",
+        description = "Stop the agent after it has been running for this many seconds"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_runtime_secs: Option<u64>,
+    #[schemars(
+        title = "Max Tool Calls",
+        description = "Stop the agent after it has made this many tool calls"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tool_calls: Option<u64>,
+    #[schemars(
+        title = "Max Log Size (bytes)",
+        description = "Stop the agent once its raw stdout/stderr output exceeds this many bytes"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_log_bytes: Option<u64>,
+}
+
+impl ExecutionLimits {
+    pub fn is_unbounded(&self) -> bool {
+        self.max_runtime_secs.is_none()
+            && self.max_tool_calls.is_none()
+            && self.max_log_bytes.is_none()
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS, JsonSchema)]
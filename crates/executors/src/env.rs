@@ -1,22 +1,41 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, ffi::OsStr, path::Path};
 
 use tokio::process::Command;
 
 use crate::command::CmdOverrides;
 
+/// Target for running executor processes inside an already running OCI container
+/// instead of directly on the host.
+#[derive(Debug, Clone)]
+pub struct ContainerExec {
+    /// Container engine CLI, e.g. `docker` or `podman`
+    pub engine: String,
+    /// Name of the running container; the workspace is bind-mounted at the same path
+    pub container: String,
+    /// Path inside the container where the wrapped process records its pid
+    pub pid_file: String,
+}
+
 /// Environment variables to inject into executor processes
 #[derive(Debug, Clone, Default)]
 pub struct ExecutionEnv {
     pub vars: HashMap<String, String>,
+    pub container: Option<ContainerExec>,
 }
 
 impl ExecutionEnv {
     pub fn new() -> Self {
         Self {
             vars: HashMap::new(),
+            container: None,
         }
     }
 
+    pub fn with_container(mut self, container: ContainerExec) -> Self {
+        self.container = Some(container);
+        self
+    }
+
     /// Insert an environment variable
     pub fn insert(&mut self, key: impl Into<String>, value: impl Into<String>) {
         self.vars.insert(key.into(), value.into());
@@ -53,6 +72,39 @@ impl ExecutionEnv {
     pub fn contains_key(&self, key: &str) -> bool {
         self.vars.contains_key(key)
     }
+
+    /// Build a `Command` that runs `program` in `current_dir` with these variables applied.
+    /// When a container is attached the command is routed through `<engine> exec`, forwarding
+    /// only the variables held by this env; arguments appended afterwards reach `program`.
+    pub fn command(&self, program: impl AsRef<OsStr>, current_dir: &Path) -> Command {
+        let Some(container) = &self.container else {
+            let mut command = Command::new(program);
+            command.current_dir(current_dir);
+            self.apply_to_command(&mut command);
+            return command;
+        };
+
+        let mut command = Command::new(&container.engine);
+        command
+            .current_dir(current_dir)
+            .arg("exec")
+            .arg("-i")
+            .arg("-w")
+            .arg(current_dir);
+        // `-e KEY` without a value makes the engine copy the value from its own environment
+        for key in self.vars.keys() {
+            command.arg("-e").arg(key);
+        }
+        command
+            .arg(&container.container)
+            .arg("sh")
+            .arg("-c")
+            .arg(r#"echo $$ > "$0" && exec "$@""#)
+            .arg(&container.pid_file)
+            .arg(program);
+        self.apply_to_command(&mut command);
+        command
+    }
 }
 
 #[cfg(test)]
@@ -75,4 +127,47 @@ mod tests {
         assert_eq!(merged.vars.get("FOO").unwrap(), "profile"); // overrides
         assert_eq!(merged.vars.get("BAR").unwrap(), "profile");
     }
+
+    #[test]
+    fn container_command_wraps_program_and_forwards_vars() {
+        let mut env = ExecutionEnv::new().with_container(ContainerExec {
+            engine: "podman".to_string(),
+            container: "vk-1234".to_string(),
+            pid_file: "/tmp/vk-exec-1.pid".to_string(),
+        });
+        env.insert("VK_TASK_ID", "task");
+
+        let mut command = env.command("claude", Path::new("/work/space"));
+        command.arg("--print");
+
+        let std_command = command.as_std();
+        assert_eq!(std_command.get_program(), "podman");
+        let args: Vec<_> = std_command
+            .get_args()
+            .map(|a| a.to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(
+            args,
+            vec![
+                "exec",
+                "-i",
+                "-w",
+                "/work/space",
+                "-e",
+                "VK_TASK_ID",
+                "vk-1234",
+                "sh",
+                "-c",
+                r#"echo $$ > "$0" && exec "$@""#,
+                "/tmp/vk-exec-1.pid",
+                "claude",
+                "--print",
+            ]
+        );
+        assert!(
+            std_command
+                .get_envs()
+                .any(|(k, v)| k == "VK_TASK_ID" && v == Some("task".as_ref()))
+        );
+    }
 }
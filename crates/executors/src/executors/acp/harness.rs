@@ -9,13 +9,16 @@ use agent_client_protocol as proto;
 use agent_client_protocol::Agent as _;
 use command_group::{AsyncCommandGroup, AsyncGroupChild};
 use futures::StreamExt;
-use tokio::{io::AsyncWriteExt, process::Command, sync::mpsc};
+use serde_json::Value;
+use tokio::{io::AsyncWriteExt, sync::mpsc};
 use tokio_util::{
     compat::{TokioAsyncReadCompatExt, TokioAsyncWriteCompatExt},
     io::ReaderStream,
 };
 use tracing::error;
-use workspace_utils::{approvals::ApprovalStatus, stream_lines::LinesStreamExt};
+use workspace_utils::{
+    approvals::ApprovalStatus, log_msg::TokenUsage, stream_lines::LinesStreamExt,
+};
 
 use super::{AcpClient, SessionManager};
 use crate::{
@@ -77,21 +80,17 @@ impl AcpAgentHarness {
         cmd_overrides: &CmdOverrides,
         approvals: Option<std::sync::Arc<dyn ExecutorApprovalService>>,
     ) -> Result<SpawnedChild, ExecutorError> {
-        let (program_path, args) = command_parts.into_resolved().await?;
-        let mut command = Command::new(program_path);
+        let (program_path, args) = command_parts.into_resolved_in(env).await?;
+        let env = env.clone().with_profile(cmd_overrides);
+        let mut command = env.command(program_path, current_dir);
         command
             .kill_on_drop(true)
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
-            .current_dir(current_dir)
             .args(&args)
             .env("NODE_NO_WARNINGS", "1");
 
-        env.clone()
-            .with_profile(cmd_overrides)
-            .apply_to_command(&mut command);
-
         let mut child = command.group_spawn()?;
 
         let (exit_tx, exit_rx) = tokio::sync::oneshot::channel::<ExecutorExitResult>();
@@ -126,21 +125,17 @@ impl AcpAgentHarness {
         cmd_overrides: &CmdOverrides,
         approvals: Option<std::sync::Arc<dyn ExecutorApprovalService>>,
     ) -> Result<SpawnedChild, ExecutorError> {
-        let (program_path, args) = command_parts.into_resolved().await?;
-        let mut command = Command::new(program_path);
+        let (program_path, args) = command_parts.into_resolved_in(env).await?;
+        let env = env.clone().with_profile(cmd_overrides);
+        let mut command = env.command(program_path, current_dir);
         command
             .kill_on_drop(true)
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
-            .current_dir(current_dir)
             .args(&args)
             .env("NODE_NO_WARNINGS", "1");
 
-        env.clone()
-            .with_profile(cmd_overrides)
-            .apply_to_command(&mut command);
-
         let mut child = command.group_spawn()?;
 
         let (exit_tx, exit_rx) = tokio::sync::oneshot::channel::<ExecutorExitResult>();
@@ -444,6 +439,11 @@ impl AcpAgentHarness {
                                     // Emit done with stop_reason
                                     let stop_reason = serde_json::to_string(&resp.stop_reason)
                                         .unwrap_or_default();
+                                    if let Some(usage) =
+                                        usage_from_prompt_response(&resp, model.as_deref())
+                                    {
+                                        let _ = log_tx.send(AcpEvent::Usage(usage).to_string());
+                                    }
                                     let _ = log_tx.send(AcpEvent::Done(stop_reason).to_string());
                                 }
                                 Err(e) => {
@@ -507,3 +507,39 @@ impl AcpAgentHarness {
         Ok(())
     }
 }
+
+/// ACP has no standard usage reporting yet. Agents that report it put it in the prompt
+/// response's `_meta`, e.g. `{"usage": {"inputTokens": 1200, "outputTokens": 300}}`.
+fn usage_from_prompt_response(
+    resp: &proto::PromptResponse,
+    model: Option<&str>,
+) -> Option<TokenUsage> {
+    let value = serde_json::to_value(resp).ok()?;
+    let meta = value.get("_meta")?;
+    let usage = meta
+        .get("usage")
+        .or_else(|| meta.get("tokenUsage"))
+        .unwrap_or(meta);
+    let count = |keys: &[&str]| {
+        keys.iter()
+            .find_map(|key| usage.get(*key).and_then(Value::as_u64))
+            .unwrap_or(0)
+    };
+
+    let usage = TokenUsage {
+        model: meta
+            .get("model")
+            .and_then(Value::as_str)
+            .or(model)
+            .map(str::to_string),
+        input_tokens: count(&["inputTokens", "input_tokens", "promptTokens"]),
+        output_tokens: count(&["outputTokens", "output_tokens", "completionTokens"]),
+        cache_read_tokens: count(&["cachedReadTokens", "cacheReadTokens", "cache_read_tokens"]),
+        cache_write_tokens: count(&[
+            "cachedWriteTokens",
+            "cacheWriteTokens",
+            "cache_write_tokens",
+        ]),
+    };
+    (!usage.is_empty()).then_some(usage)
+}
@@ -10,7 +10,7 @@ pub use harness::AcpAgentHarness;
 pub use normalize_logs::*;
 use serde::{Deserialize, Serialize};
 pub use session::SessionManager;
-use workspace_utils::approvals::ApprovalStatus;
+use workspace_utils::{approvals::ApprovalStatus, log_msg::TokenUsage};
 
 /// Parsed event types for internal processing
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -28,6 +28,8 @@ pub enum AcpEvent {
     ApprovalResponse(ApprovalResponse),
     Error(String),
     Done(String),
+    /// Tokens used by one prompt turn, when the agent reports them
+    Usage(TokenUsage),
     Other(agent_client_protocol::SessionNotification),
 }
 
@@ -8,7 +8,8 @@ use agent_client_protocol::{self as acp, SessionNotification};
 use futures::StreamExt;
 use regex::Regex;
 use serde::Deserialize;
-use workspace_utils::{approvals::ApprovalStatus, msg_store::MsgStore};
+use tokio::task::JoinHandle;
+use workspace_utils::{approvals::ApprovalStatus, log_msg::TokenUsage, msg_store::MsgStore};
 
 pub use super::AcpAgentHarness;
 use super::AcpEvent;
@@ -22,18 +23,19 @@ use crate::{
     },
 };
 
-pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
+pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) -> Vec<JoinHandle<()>> {
     // stderr normalization
     let entry_index = EntryIndexProvider::start_from(&msg_store);
-    normalize_stderr_logs(msg_store.clone(), entry_index.clone());
+    let stderr_handle = normalize_stderr_logs(msg_store.clone(), entry_index.clone());
 
     // stdout normalization (main loop)
     let worktree_path = worktree_path.to_path_buf();
     // Type aliases to simplify complex state types and appease clippy
-    tokio::spawn(async move {
+    let stdout_handle = tokio::spawn(async move {
         type ToolStates = std::collections::HashMap<String, PartialToolCallData>;
 
         let mut stored_session_id = false;
+        let mut token_usage = TokenUsage::default();
         let mut streaming: StreamingState = StreamingState::default();
         let mut tool_states: ToolStates = HashMap::new();
 
@@ -60,6 +62,11 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                         };
                         msg_store.push_patch(ConversationPatch::add_normalized_entry(idx, entry));
                     }
+                    AcpEvent::Usage(usage) => {
+                        // Usage is reported per prompt; keep the running total of the execution
+                        token_usage.add(&usage);
+                        msg_store.push_token_usage(token_usage.clone());
+                    }
                     AcpEvent::Done(_) => {
                         streaming.assistant_text = None;
                         streaming.thinking_text = None;
@@ -604,6 +611,8 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
             }
         }
     });
+
+    vec![stderr_handle, stdout_handle]
 }
 
 struct PartialToolCallData {
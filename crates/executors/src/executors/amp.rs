@@ -4,7 +4,7 @@ use async_trait::async_trait;
 use command_group::AsyncCommandGroup;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use tokio::{io::AsyncWriteExt, process::Command};
+use tokio::{io::AsyncWriteExt, task::JoinHandle};
 use ts_rs::TS;
 use workspace_utils::msg_store::MsgStore;
 
@@ -52,23 +52,19 @@ impl StandardCodingAgentExecutor for Amp {
         env: &ExecutionEnv,
     ) -> Result<SpawnedChild, ExecutorError> {
         let command_parts = self.build_command_builder().build_initial()?;
-        let (executable_path, args) = command_parts.into_resolved().await?;
+        let (executable_path, args) = command_parts.into_resolved_in(env).await?;
 
         let combined_prompt = self.append_prompt.combine_prompt(prompt);
 
-        let mut command = Command::new(executable_path);
+        let env = env.clone().with_profile(&self.cmd);
+        let mut command = env.command(executable_path, current_dir);
         command
             .kill_on_drop(true)
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
-            .current_dir(current_dir)
             .args(&args);
 
-        env.clone()
-            .with_profile(&self.cmd)
-            .apply_to_command(&mut command);
-
         let mut child = command.group_spawn()?;
 
         // Feed the prompt in, then close the pipe so amp sees EOF
@@ -94,12 +90,12 @@ impl StandardCodingAgentExecutor for Amp {
             "fork".to_string(),
             session_id.to_string(),
         ])?;
-        let (fork_program, fork_args) = fork_line.into_resolved().await?;
-        let fork_output = Command::new(fork_program)
+        let (fork_program, fork_args) = fork_line.into_resolved_in(env).await?;
+        let fork_output = env
+            .command(fork_program, current_dir)
             .kill_on_drop(true)
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
-            .current_dir(current_dir)
             .args(&fork_args)
             .output()
             .await?;
@@ -125,23 +121,19 @@ impl StandardCodingAgentExecutor for Amp {
             "continue".to_string(),
             new_thread_id.clone(),
         ])?;
-        let (continue_program, continue_args) = continue_line.into_resolved().await?;
+        let (continue_program, continue_args) = continue_line.into_resolved_in(env).await?;
 
         let combined_prompt = self.append_prompt.combine_prompt(prompt);
 
-        let mut command = Command::new(continue_program);
+        let env = env.clone().with_profile(&self.cmd);
+        let mut command = env.command(continue_program, current_dir);
         command
             .kill_on_drop(true)
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
-            .current_dir(current_dir)
             .args(&continue_args);
 
-        env.clone()
-            .with_profile(&self.cmd)
-            .apply_to_command(&mut command);
-
         let mut child = command.group_spawn()?;
 
         // Feed the prompt in, then close the pipe so amp sees EOF
@@ -153,11 +145,11 @@ impl StandardCodingAgentExecutor for Amp {
         Ok(child.into())
     }
 
-    fn normalize_logs(&self, msg_store: Arc<MsgStore>, current_dir: &Path) {
+    fn normalize_logs(&self, msg_store: Arc<MsgStore>, current_dir: &Path) -> Vec<JoinHandle<()>> {
         let entry_index_provider = EntryIndexProvider::start_from(&msg_store);
 
         // Process stdout logs (Amp's stream JSON output) using Claude's log processor
-        ClaudeLogProcessor::process_logs(
+        let stdout_handle = ClaudeLogProcessor::process_logs(
             msg_store.clone(),
             current_dir,
             entry_index_provider.clone(),
@@ -165,7 +157,9 @@ impl StandardCodingAgentExecutor for Amp {
         );
 
         // Process stderr logs using the standard stderr processor
-        normalize_stderr_logs(msg_store, entry_index_provider);
+        let stderr_handle = normalize_stderr_logs(msg_store, entry_index_provider);
+
+        vec![stdout_handle, stderr_handle]
     }
 
     // MCP configuration methods
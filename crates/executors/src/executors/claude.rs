@@ -10,10 +10,13 @@ use command_group::AsyncCommandGroup;
 use futures::StreamExt;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use tokio::process::Command;
+use tokio::task::JoinHandle;
 use ts_rs::TS;
 use workspace_utils::{
-    approvals::ApprovalStatus, diff::create_unified_diff, log_msg::LogMsg, msg_store::MsgStore,
+    approvals::ApprovalStatus,
+    diff::create_unified_diff,
+    log_msg::{LogMsg, TokenUsage},
+    msg_store::MsgStore,
     path::make_path_relative,
 };
 
@@ -192,11 +195,11 @@ impl StandardCodingAgentExecutor for ClaudeCode {
             .await
     }
 
-    fn normalize_logs(&self, msg_store: Arc<MsgStore>, current_dir: &Path) {
+    fn normalize_logs(&self, msg_store: Arc<MsgStore>, current_dir: &Path) -> Vec<JoinHandle<()>> {
         let entry_index_provider = EntryIndexProvider::start_from(&msg_store);
 
         // Process stdout logs (Claude's JSON output)
-        ClaudeLogProcessor::process_logs(
+        let stdout_handle = ClaudeLogProcessor::process_logs(
             msg_store.clone(),
             current_dir,
             entry_index_provider.clone(),
@@ -204,7 +207,9 @@ impl StandardCodingAgentExecutor for ClaudeCode {
         );
 
         // Process stderr logs using the standard stderr processor
-        normalize_stderr_logs(msg_store, entry_index_provider);
+        let stderr_handle = normalize_stderr_logs(msg_store, entry_index_provider);
+
+        vec![stdout_handle, stderr_handle]
     }
 
     // MCP configuration methods
@@ -238,22 +243,18 @@ impl ClaudeCode {
         command_parts: CommandParts,
         env: &ExecutionEnv,
     ) -> Result<SpawnedChild, ExecutorError> {
-        let (program_path, args) = command_parts.into_resolved().await?;
+        let (program_path, args) = command_parts.into_resolved_in(env).await?;
         let combined_prompt = self.append_prompt.combine_prompt(prompt);
 
-        let mut command = Command::new(program_path);
+        let env = env.clone().with_profile(&self.cmd);
+        let mut command = env.command(program_path, current_dir);
         command
             .kill_on_drop(true)
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
-            .current_dir(current_dir)
             .args(&args);
 
-        env.clone()
-            .with_profile(&self.cmd)
-            .apply_to_command(&mut command);
-
         // Remove ANTHROPIC_API_KEY if disable_api_key is enabled
         if self.disable_api_key.unwrap_or(false) {
             command.env_remove("ANTHROPIC_API_KEY");
@@ -332,6 +333,8 @@ pub struct ClaudeLogProcessor {
     strategy: HistoryStrategy,
     streaming_messages: HashMap<String, StreamingMessageState>,
     streaming_message_id: Option<String>,
+    // Map message id -> usage, since an assistant message is emitted once per content block
+    message_usage: HashMap<String, TokenUsage>,
 }
 
 impl ClaudeLogProcessor {
@@ -347,6 +350,7 @@ impl ClaudeLogProcessor {
             strategy,
             streaming_messages: HashMap::new(),
             streaming_message_id: None,
+            message_usage: HashMap::new(),
         }
     }
 
@@ -356,19 +360,23 @@ impl ClaudeLogProcessor {
         current_dir: &Path,
         entry_index_provider: EntryIndexProvider,
         strategy: HistoryStrategy,
-    ) {
+    ) -> JoinHandle<()> {
         let current_dir_clone = current_dir.to_owned();
         tokio::spawn(async move {
             let mut stream = msg_store.history_plus_stream();
             let mut buffer = String::new();
             let worktree_path = current_dir_clone.to_string_lossy().to_string();
             let mut session_id_extracted = false;
+            let mut model = None;
             let mut processor = Self::new_with_strategy(strategy);
 
             while let Some(Ok(msg)) = stream.next().await {
                 let chunk = match msg {
                     LogMsg::Stdout(x) => x,
-                    LogMsg::JsonPatch(_) | LogMsg::SessionId(_) | LogMsg::Stderr(_) => continue,
+                    LogMsg::JsonPatch(_)
+                    | LogMsg::SessionId(_)
+                    | LogMsg::TokenUsage(_)
+                    | LogMsg::Stderr(_) => continue,
                     LogMsg::Finished => break,
                 };
 
@@ -404,6 +412,18 @@ impl ClaudeLogProcessor {
                                 session_id_extracted = true;
                             }
 
+                            if let ClaudeJson::System {
+                                model: Some(init_model),
+                                ..
+                            } = &claude_json
+                            {
+                                model = Some(init_model.clone());
+                            }
+                            if let Some(usage) = processor.extract_token_usage(&claude_json, &model)
+                            {
+                                msg_store.push_token_usage(usage);
+                            }
+
                             let patches = processor.normalize_entries(
                                 &claude_json,
                                 &worktree_path,
@@ -449,7 +469,7 @@ impl ClaudeLogProcessor {
                 let patch = ConversationPatch::add_normalized_entry(patch_id, entry);
                 msg_store.push_patch(patch);
             }
-        });
+        })
     }
 
     /// Extract session ID from Claude JSON
@@ -467,6 +487,44 @@ impl ClaudeLogProcessor {
         }
     }
 
+    /// The running total of the run so far. Each assistant message reports its own usage, so
+    /// runs that are interrupted before the result message still have a total; the result
+    /// message reports the usage of the whole run and replaces the sum.
+    fn extract_token_usage(
+        &mut self,
+        claude_json: &ClaudeJson,
+        model: &Option<String>,
+    ) -> Option<TokenUsage> {
+        let to_token_usage = |usage: &ClaudeUsage, model: Option<String>| TokenUsage {
+            model,
+            input_tokens: usage.input_tokens.unwrap_or(0),
+            output_tokens: usage.output_tokens.unwrap_or(0),
+            cache_read_tokens: usage.cache_read_input_tokens.unwrap_or(0),
+            cache_write_tokens: usage.cache_creation_input_tokens.unwrap_or(0),
+        };
+        match claude_json {
+            ClaudeJson::Assistant { message, .. } => {
+                let (Some(id), Some(usage)) = (&message.id, &message.usage) else {
+                    return None;
+                };
+                self.message_usage
+                    .insert(id.clone(), to_token_usage(usage, None));
+                let mut total = TokenUsage {
+                    model: model.clone().or_else(|| message.model.clone()),
+                    ..Default::default()
+                };
+                for usage in self.message_usage.values() {
+                    total.add(usage);
+                }
+                Some(total)
+            }
+            ClaudeJson::Result {
+                usage: Some(usage), ..
+            } => Some(to_token_usage(usage, model.clone())),
+            _ => None,
+        }
+    }
+
     /// Generate warning entry if API key source is ANTHROPIC_API_KEY
     fn warn_if_unmanaged_key(src: &Option<String>) -> Option<NormalizedEntry> {
         match src.as_deref() {
@@ -1504,6 +1562,8 @@ pub enum ClaudeJson {
         num_turns: Option<u32>,
         #[serde(default, alias = "sessionId")]
         session_id: Option<String>,
+        #[serde(default)]
+        usage: Option<ClaudeUsage>,
     },
     #[serde(rename = "approval_response")]
     ApprovalResponse {
@@ -1528,6 +1588,8 @@ pub struct ClaudeMessage {
     pub model: Option<String>,
     pub content: Vec<ClaudeContentItem>,
     pub stop_reason: Option<String>,
+    #[serde(default)]
+    pub usage: Option<ClaudeUsage>,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
@@ -2018,6 +2080,7 @@ mod tests {
                 base_command_override: None,
                 additional_params: None,
                 env: None,
+                limits: Default::default(),
             },
             approvals_service: None,
             disable_api_key: None,
@@ -2050,6 +2113,57 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_token_usage_counts_each_message_once_until_the_result() {
+        let msg_store = Arc::new(MsgStore::new());
+        for line in [
+            r#"{"type":"system","subtype":"init","session_id":"s1","model":"claude-sonnet-4"}"#,
+            // One message per content block, all reporting the usage of message 1
+            r#"{"type":"assistant","message":{"id":"msg_1","role":"assistant","content":[{"type":"thinking","thinking":"Plan"}],"usage":{"input_tokens":10,"output_tokens":5,"cache_read_input_tokens":100,"cache_creation_input_tokens":20}}}"#,
+            r#"{"type":"assistant","message":{"id":"msg_1","role":"assistant","content":[{"type":"text","text":"Reading"}],"usage":{"input_tokens":10,"output_tokens":5,"cache_read_input_tokens":100,"cache_creation_input_tokens":20}}}"#,
+            r#"{"type":"assistant","message":{"id":"msg_2","role":"assistant","content":[{"type":"text","text":"Done"}],"usage":{"input_tokens":3,"output_tokens":7,"cache_read_input_tokens":120}}}"#,
+            r#"{"type":"result","subtype":"success","is_error":false,"usage":{"input_tokens":14,"output_tokens":12,"cache_read_input_tokens":220,"cache_creation_input_tokens":20}}"#,
+        ] {
+            msg_store.push_stdout(format!("{line}\n"));
+        }
+        msg_store.push_finished();
+
+        ClaudeLogProcessor::process_logs(
+            msg_store.clone(),
+            Path::new("/tmp/test-worktree"),
+            EntryIndexProvider::test_new(),
+            HistoryStrategy::Default,
+        )
+        .await
+        .unwrap();
+
+        let usage =
+            |input_tokens, output_tokens, cache_read_tokens, cache_write_tokens| TokenUsage {
+                model: Some("claude-sonnet-4".to_string()),
+                input_tokens,
+                output_tokens,
+                cache_read_tokens,
+                cache_write_tokens,
+            };
+        let pushed: Vec<TokenUsage> = msg_store
+            .get_history()
+            .into_iter()
+            .filter_map(|msg| match msg {
+                LogMsg::TokenUsage(usage) => Some(usage),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            pushed,
+            vec![
+                usage(10, 5, 100, 20),
+                usage(10, 5, 100, 20),
+                usage(13, 12, 220, 20),
+                usage(14, 12, 220, 20),
+            ]
+        );
+    }
+
     #[test]
     fn test_session_id_extraction() {
         let system_json = r#"{"type":"system","session_id":"test-session-123"}"#;
@@ -19,7 +19,7 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use strum_macros::AsRefStr;
-use tokio::process::Command;
+use tokio::task::JoinHandle;
 use ts_rs::TS;
 use workspace_utils::msg_store::MsgStore;
 
@@ -171,8 +171,12 @@ impl StandardCodingAgentExecutor for Codex {
             .await
     }
 
-    fn normalize_logs(&self, msg_store: Arc<MsgStore>, worktree_path: &Path) {
-        normalize_logs(msg_store, worktree_path);
+    fn normalize_logs(
+        &self,
+        msg_store: Arc<MsgStore>,
+        worktree_path: &Path,
+    ) -> Vec<JoinHandle<()>> {
+        normalize_logs(msg_store, worktree_path)
     }
 
     fn default_mcp_config_path(&self) -> Option<PathBuf> {
@@ -300,24 +304,20 @@ impl Codex {
         env: &ExecutionEnv,
     ) -> Result<SpawnedChild, ExecutorError> {
         let combined_prompt = self.append_prompt.combine_prompt(prompt);
-        let (program_path, args) = command_parts.into_resolved().await?;
+        let (program_path, args) = command_parts.into_resolved_in(env).await?;
 
-        let mut process = Command::new(program_path);
+        let env = env.clone().with_profile(&self.cmd);
+        let mut process = env.command(program_path, current_dir);
         process
             .kill_on_drop(true)
             .stdin(std::process::Stdio::piped())
             .stdout(std::process::Stdio::piped())
             .stderr(std::process::Stdio::piped())
-            .current_dir(current_dir)
             .args(&args)
             .env("NODE_NO_WARNINGS", "1")
             .env("NO_COLOR", "1")
             .env("RUST_LOG", "error");
 
-        env.clone()
-            .with_profile(&self.cmd)
-            .apply_to_command(&mut process);
-
         let mut child = process.group_spawn()?;
 
         let child_stdout = child.inner().stdout.take().ok_or_else(|| {
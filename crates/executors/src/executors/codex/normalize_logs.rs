@@ -25,9 +25,10 @@ use futures::StreamExt;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use tokio::task::JoinHandle;
 use workspace_utils::{
-    approvals::ApprovalStatus, diff::normalize_unified_diff, msg_store::MsgStore,
-    path::make_path_relative,
+    approvals::ApprovalStatus, diff::normalize_unified_diff, log_msg::TokenUsage,
+    msg_store::MsgStore, path::make_path_relative,
 };
 
 use crate::{
@@ -216,6 +217,7 @@ struct LogState {
     patches: HashMap<String, PatchState>,
     web_searches: HashMap<String, WebSearchState>,
     token_usage_info: Option<TokenUsageInfo>,
+    model: Option<String>,
 }
 
 enum StreamingTextKind {
@@ -234,6 +236,7 @@ impl LogState {
             patches: HashMap::new(),
             web_searches: HashMap::new(),
             token_usage_info: None,
+            model: None,
         }
     }
 
@@ -357,12 +360,12 @@ fn format_todo_status(status: &StepStatus) -> String {
     .to_string()
 }
 
-pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
+pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) -> Vec<JoinHandle<()>> {
     let entry_index = EntryIndexProvider::start_from(&msg_store);
-    normalize_stderr_logs(msg_store.clone(), entry_index.clone());
+    let stderr_handle = normalize_stderr_logs(msg_store.clone(), entry_index.clone());
 
     let worktree_path_str = worktree_path.to_string_lossy().to_string();
-    tokio::spawn(async move {
+    let stdout_handle = tokio::spawn(async move {
         let mut state = LogState::new(entry_index.clone());
         let mut stdout_lines = msg_store.stdout_lines_stream();
 
@@ -380,7 +383,9 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
             }
 
             if let Ok(response) = serde_json::from_str::<JSONRPCResponse>(&line) {
-                handle_jsonrpc_response(response, &msg_store, &entry_index);
+                if let Some(model) = handle_jsonrpc_response(response, &msg_store, &entry_index) {
+                    state.model = Some(model);
+                }
                 continue;
             }
 
@@ -389,6 +394,7 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                     server_notification
                 {
                     msg_store.push_session_id(session_configured.session_id.to_string());
+                    state.model = Some(session_configured.model.clone());
                     handle_model_params(
                         session_configured.model,
                         session_configured.reasoning_effort,
@@ -427,6 +433,7 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
             match event {
                 EventMsg::SessionConfigured(payload) => {
                     msg_store.push_session_id(payload.session_id.to_string());
+                    state.model = Some(payload.model.clone());
                     handle_model_params(
                         payload.model,
                         payload.reasoning_effort,
@@ -971,6 +978,17 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                 }
                 EventMsg::TokenCount(payload) => {
                     if let Some(info) = payload.info {
+                        // Codex reports running totals; cached tokens are part of the input count
+                        let total = &info.total_token_usage;
+                        let input_tokens = token_count(total.input_tokens);
+                        let cache_read_tokens = token_count(total.cached_input_tokens);
+                        msg_store.push_token_usage(TokenUsage {
+                            model: state.model.clone(),
+                            input_tokens: input_tokens.saturating_sub(cache_read_tokens),
+                            output_tokens: token_count(total.output_tokens),
+                            cache_read_tokens,
+                            cache_write_tokens: 0,
+                        });
                         state.token_usage_info = Some(info);
                     }
                 }
@@ -1015,16 +1033,19 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
             }
         }
     });
+
+    vec![stderr_handle, stdout_handle]
 }
 
+/// Returns the model of a new conversation
 fn handle_jsonrpc_response(
     response: JSONRPCResponse,
     msg_store: &Arc<MsgStore>,
     entry_index: &EntryIndexProvider,
-) {
+) -> Option<String> {
     let Ok(response) = serde_json::from_value::<NewConversationResponse>(response.result.clone())
     else {
-        return;
+        return None;
     };
 
     match SessionHandler::extract_session_id_from_rollout_path(response.rollout_path) {
@@ -1033,11 +1054,16 @@ fn handle_jsonrpc_response(
     }
 
     handle_model_params(
-        response.model,
+        response.model.clone(),
         response.reasoning_effort,
         msg_store,
         entry_index,
     );
+    Some(response.model)
+}
+
+fn token_count<T: TryInto<u64>>(count: T) -> u64 {
+    count.try_into().unwrap_or(0)
 }
 
 fn handle_model_params(
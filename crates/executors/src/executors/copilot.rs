@@ -14,7 +14,7 @@ use serde::{Deserialize, Serialize};
 use tokio::{
     fs,
     io::AsyncWriteExt,
-    process::Command,
+    task::JoinHandle,
     time::{interval, timeout},
 };
 use ts_rs::TS;
@@ -108,24 +108,20 @@ impl StandardCodingAgentExecutor for Copilot {
         let command_parts = self
             .build_command_builder(&log_dir.to_string_lossy())
             .build_initial()?;
-        let (program_path, args) = command_parts.into_resolved().await?;
+        let (program_path, args) = command_parts.into_resolved_in(env).await?;
 
         let combined_prompt = self.append_prompt.combine_prompt(prompt);
 
-        let mut command = Command::new(program_path);
+        let env = env.clone().with_profile(&self.cmd);
+        let mut command = env.command(program_path, current_dir);
         command
             .kill_on_drop(true)
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
-            .current_dir(current_dir)
             .args(&args)
             .env("NODE_NO_WARNINGS", "1");
 
-        env.clone()
-            .with_profile(&self.cmd)
-            .apply_to_command(&mut command);
-
         let mut child = command.group_spawn()?;
 
         // Write prompt to stdin
@@ -151,25 +147,21 @@ impl StandardCodingAgentExecutor for Copilot {
         let command_parts = self
             .build_command_builder(&log_dir.to_string_lossy())
             .build_follow_up(&["--resume".to_string(), session_id.to_string()])?;
-        let (program_path, args) = command_parts.into_resolved().await?;
+        let (program_path, args) = command_parts.into_resolved_in(env).await?;
 
         let combined_prompt = self.append_prompt.combine_prompt(prompt);
 
-        let mut command = Command::new(program_path);
+        let env = env.clone().with_profile(&self.cmd);
+        let mut command = env.command(program_path, current_dir);
 
         command
             .kill_on_drop(true)
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
-            .current_dir(current_dir)
             .args(&args)
             .env("NODE_NO_WARNINGS", "1");
 
-        env.clone()
-            .with_profile(&self.cmd)
-            .apply_to_command(&mut command);
-
         let mut child = command.group_spawn()?;
 
         // Write comprehensive prompt to stdin
@@ -187,12 +179,16 @@ impl StandardCodingAgentExecutor for Copilot {
     /// Parses both stderr and stdout logs for Copilot executor using PlainTextLogProcessor.
     ///
     /// Each entry is converted into an `AssistantMessage` or `ErrorMessage` and emitted as patches.
-    fn normalize_logs(&self, msg_store: Arc<MsgStore>, _worktree_path: &Path) {
+    fn normalize_logs(
+        &self,
+        msg_store: Arc<MsgStore>,
+        _worktree_path: &Path,
+    ) -> Vec<JoinHandle<()>> {
         let entry_index_counter = EntryIndexProvider::start_from(&msg_store);
-        normalize_stderr_logs(msg_store.clone(), entry_index_counter.clone());
+        let stderr_handle = normalize_stderr_logs(msg_store.clone(), entry_index_counter.clone());
 
         // Normalize Agent logs
-        tokio::spawn(async move {
+        let stdout_handle = tokio::spawn(async move {
             let mut stdout_lines = msg_store.stdout_lines_stream();
 
             let mut processor = Self::create_simple_stdout_normalizer(entry_index_counter);
@@ -208,6 +204,8 @@ impl StandardCodingAgentExecutor for Copilot {
                 }
             }
         });
+
+        vec![stderr_handle, stdout_handle]
     }
 
     // MCP configuration methods
@@ -0,0 +1,543 @@
+use std::{path::Path, process::Stdio, sync::Arc};
+
+use async_trait::async_trait;
+use command_group::AsyncCommandGroup;
+use derivative::Derivative;
+use futures::StreamExt;
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::{io::AsyncWriteExt, task::JoinHandle};
+use ts_rs::TS;
+use workspace_utils::msg_store::MsgStore;
+
+use crate::{
+    approvals::ExecutorApprovalService,
+    command::{CmdOverrides, CommandBuilder, CommandParts, apply_overrides},
+    env::ExecutionEnv,
+    executors::{
+        AppendPrompt, AvailabilityInfo, ExecutorError, SpawnedChild, StandardCodingAgentExecutor,
+        acp::AcpAgentHarness,
+    },
+    logs::{
+        ActionType, NormalizedEntry, NormalizedEntryError, NormalizedEntryType, ToolStatus,
+        plain_text_processor::PlainTextLogProcessor,
+        stderr_processor::normalize_stderr_logs,
+        utils::{EntryIndexProvider, patch::add_normalized_entry},
+    },
+};
+
+const PROMPT_PLACEHOLDER: &str = "{prompt}";
+const SESSION_ID_PLACEHOLDER: &str = "{session_id}";
+
+/// How the agent reports its progress on stdout
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, TS, JsonSchema, Default)]
+#[serde(rename_all = "snake_case")]
+#[ts(rename_all = "snake_case")]
+pub enum CustomOutputFormat {
+    #[default]
+    PlainText,
+    Jsonl,
+    Acp,
+}
+
+/// Where to find the parts of a normalized entry in each JSON line
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS, JsonSchema, Default)]
+pub struct JsonlFieldMappings {
+    #[schemars(
+        title = "Kind Pointer",
+        description = "JSON pointer to the field that identifies the kind of event, e.g. /type"
+    )]
+    #[serde(default)]
+    pub kind_pointer: String,
+    #[schemars(
+        title = "Content Pointer",
+        description = "JSON pointer to the text of an event, e.g. /message/text"
+    )]
+    #[serde(default)]
+    pub content_pointer: String,
+    #[schemars(
+        title = "Tool Name Pointer",
+        description = "JSON pointer to the tool name of tool events"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_name_pointer: Option<String>,
+    #[schemars(
+        title = "Tool Arguments Pointer",
+        description = "JSON pointer to the arguments of tool events"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_arguments_pointer: Option<String>,
+    #[schemars(title = "Assistant Message Kinds")]
+    #[serde(default)]
+    pub assistant_kinds: Vec<String>,
+    #[schemars(title = "Thinking Kinds")]
+    #[serde(default)]
+    pub thinking_kinds: Vec<String>,
+    #[schemars(title = "Tool Call Kinds")]
+    #[serde(default)]
+    pub tool_kinds: Vec<String>,
+    #[schemars(title = "Error Kinds")]
+    #[serde(default)]
+    pub error_kinds: Vec<String>,
+}
+
+impl JsonlFieldMappings {
+    /// Map one JSON line to an entry. Events of unmapped kinds are skipped.
+    fn to_entry(&self, event: &Value) -> Option<NormalizedEntry> {
+        let kind = event.pointer(&self.kind_pointer)?.as_str()?;
+        let content = match event.pointer(&self.content_pointer) {
+            Some(Value::String(text)) => text.clone(),
+            Some(Value::Null) | None => String::new(),
+            Some(other) => other.to_string(),
+        };
+        let is_kind = |kinds: &[String]| kinds.iter().any(|k| k == kind);
+
+        let entry_type = if is_kind(&self.assistant_kinds) {
+            NormalizedEntryType::AssistantMessage
+        } else if is_kind(&self.thinking_kinds) {
+            NormalizedEntryType::Thinking
+        } else if is_kind(&self.error_kinds) {
+            NormalizedEntryType::ErrorMessage {
+                error_type: NormalizedEntryError::Other,
+            }
+        } else if is_kind(&self.tool_kinds) {
+            let tool_name = self
+                .tool_name_pointer
+                .as_deref()
+                .and_then(|pointer| event.pointer(pointer))
+                .and_then(Value::as_str)
+                .unwrap_or(kind)
+                .to_string();
+            let arguments = self
+                .tool_arguments_pointer
+                .as_deref()
+                .and_then(|pointer| event.pointer(pointer))
+                .cloned();
+            NormalizedEntryType::ToolUse {
+                tool_name: tool_name.clone(),
+                action_type: ActionType::Tool {
+                    tool_name,
+                    arguments,
+                    result: None,
+                },
+                status: ToolStatus::Success,
+            }
+        } else {
+            return None;
+        };
+
+        Some(NormalizedEntry {
+            timestamp: None,
+            entry_type,
+            content,
+            metadata: Some(event.clone()),
+        })
+    }
+}
+
+/// An in-house CLI agent described entirely by its profile
+#[derive(Derivative, Clone, Serialize, Deserialize, TS, JsonSchema)]
+#[derivative(Debug, PartialEq)]
+pub struct CustomAgent {
+    #[serde(default)]
+    pub append_prompt: AppendPrompt,
+    #[schemars(
+        title = "Command",
+        description = "Command that starts a new session. {prompt} is replaced with the prompt; without it the prompt is written to stdin"
+    )]
+    #[serde(default)]
+    pub command: String,
+    #[schemars(
+        title = "Follow-up Command",
+        description = "Command that continues a session, with the same {prompt} handling and {session_id} replaced with the extracted session id"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub follow_up_command: Option<String>,
+    #[schemars(
+        title = "Output Format",
+        description = "plain_text shows output as assistant messages, jsonl maps JSON lines with the field mappings, acp talks the Agent Client Protocol"
+    )]
+    #[serde(default)]
+    pub output_format: CustomOutputFormat,
+    #[schemars(
+        title = "JSONL Field Mappings",
+        description = "How to read JSON lines when the output format is jsonl"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jsonl: Option<JsonlFieldMappings>,
+    #[schemars(
+        title = "Session ID Regex",
+        description = "Regex matched against each output line; its first capture group is the session id"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_id_regex: Option<String>,
+    #[schemars(
+        title = "Session ID JSON Pointer",
+        description = "JSON pointer to the session id in JSON output lines, e.g. /session_id"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_id_pointer: Option<String>,
+    #[serde(flatten)]
+    pub cmd: CmdOverrides,
+    #[serde(skip)]
+    #[ts(skip)]
+    #[derivative(Debug = "ignore", PartialEq = "ignore")]
+    pub approvals: Option<Arc<dyn ExecutorApprovalService>>,
+}
+
+impl CustomAgent {
+    fn build_command_builder(&self, template: &str) -> CommandBuilder {
+        apply_overrides(CommandBuilder::new(template), &self.cmd)
+    }
+
+    fn follow_up_template(&self) -> Result<&str, ExecutorError> {
+        self.follow_up_command
+            .as_deref()
+            .filter(|command| !command.trim().is_empty())
+            .ok_or_else(|| {
+                ExecutorError::FollowUpNotSupported(
+                    "custom agent has no follow_up_command".to_string(),
+                )
+            })
+    }
+
+    fn acp_harness(&self) -> AcpAgentHarness {
+        AcpAgentHarness::with_session_namespace("custom_agent_sessions")
+    }
+
+    /// Spawn a plain text or JSONL agent, substituting placeholders per argument so
+    /// prompts are never re-split or interpreted by a shell
+    async fn spawn_command(
+        &self,
+        current_dir: &Path,
+        command_parts: CommandParts,
+        prompt: &str,
+        session_id: Option<&str>,
+        env: &ExecutionEnv,
+    ) -> Result<SpawnedChild, ExecutorError> {
+        let (program_path, args) = command_parts.into_resolved_in(env).await?;
+
+        let prompt_in_args = args.iter().any(|arg| arg.contains(PROMPT_PLACEHOLDER));
+        let args: Vec<String> = args
+            .into_iter()
+            .map(|arg| {
+                let arg = arg.replace(PROMPT_PLACEHOLDER, prompt);
+                match session_id {
+                    Some(id) => arg.replace(SESSION_ID_PLACEHOLDER, id),
+                    None => arg,
+                }
+            })
+            .collect();
+
+        let env = env.clone().with_profile(&self.cmd);
+        let mut command = env.command(program_path, current_dir);
+        command
+            .kill_on_drop(true)
+            .stdin(if prompt_in_args {
+                Stdio::null()
+            } else {
+                Stdio::piped()
+            })
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .args(&args);
+
+        let mut child = command.group_spawn()?;
+
+        if !prompt_in_args && let Some(mut stdin) = child.inner().stdin.take() {
+            stdin.write_all(prompt.as_bytes()).await?;
+            stdin.shutdown().await?;
+        }
+
+        Ok(child.into())
+    }
+
+    fn session_id_extractor(&self) -> SessionIdExtractor {
+        let regex =
+            self.session_id_regex
+                .as_deref()
+                .and_then(|pattern| match Regex::new(pattern) {
+                    Ok(regex) => Some(regex),
+                    Err(e) => {
+                        tracing::error!("Invalid custom agent session_id_regex '{pattern}': {e}");
+                        None
+                    }
+                });
+        SessionIdExtractor {
+            regex,
+            pointer: self.session_id_pointer.clone(),
+        }
+    }
+
+    fn create_plain_text_normalizer(index_provider: EntryIndexProvider) -> PlainTextLogProcessor {
+        PlainTextLogProcessor::builder()
+            .normalized_entry_producer(Box::new(|content: String| NormalizedEntry {
+                timestamp: None,
+                entry_type: NormalizedEntryType::AssistantMessage,
+                content,
+                metadata: None,
+            }))
+            .transform_lines(Box::new(|lines| {
+                lines.iter_mut().for_each(|line| {
+                    *line = strip_ansi_escapes::strip_str(&line);
+                })
+            }))
+            .index_provider(index_provider)
+            .build()
+    }
+}
+
+struct SessionIdExtractor {
+    regex: Option<Regex>,
+    pointer: Option<String>,
+}
+
+impl SessionIdExtractor {
+    fn extract(&self, line: &str) -> Option<String> {
+        if let Some(caps) = self.regex.as_ref().and_then(|regex| regex.captures(line)) {
+            let id = caps.get(1).or_else(|| caps.get(0))?.as_str().trim();
+            return (!id.is_empty()).then(|| id.to_string());
+        }
+        let pointer = self.pointer.as_deref()?;
+        let value = serde_json::from_str::<Value>(line.trim()).ok()?;
+        match value.pointer(pointer)? {
+            Value::String(id) if !id.is_empty() => Some(id.clone()),
+            Value::Number(id) => Some(id.to_string()),
+            _ => None,
+        }
+    }
+}
+
+#[async_trait]
+impl StandardCodingAgentExecutor for CustomAgent {
+    fn use_approvals(&mut self, approvals: Arc<dyn ExecutorApprovalService>) {
+        self.approvals = Some(approvals);
+    }
+
+    async fn spawn(
+        &self,
+        current_dir: &Path,
+        prompt: &str,
+        env: &ExecutionEnv,
+    ) -> Result<SpawnedChild, ExecutorError> {
+        let command_parts = self.build_command_builder(&self.command).build_initial()?;
+        let combined_prompt = self.append_prompt.combine_prompt(prompt);
+
+        if self.output_format == CustomOutputFormat::Acp {
+            return self
+                .acp_harness()
+                .spawn_with_command(
+                    current_dir,
+                    combined_prompt,
+                    command_parts,
+                    env,
+                    &self.cmd,
+                    self.approvals.clone(),
+                )
+                .await;
+        }
+
+        self.spawn_command(current_dir, command_parts, &combined_prompt, None, env)
+            .await
+    }
+
+    async fn spawn_follow_up(
+        &self,
+        current_dir: &Path,
+        prompt: &str,
+        session_id: &str,
+        env: &ExecutionEnv,
+    ) -> Result<SpawnedChild, ExecutorError> {
+        let combined_prompt = self.append_prompt.combine_prompt(prompt);
+
+        if self.output_format == CustomOutputFormat::Acp {
+            // ACP resumes sessions itself, so the initial command is reused
+            let command_parts = self
+                .build_command_builder(&self.command)
+                .build_follow_up(&[])?;
+            return self
+                .acp_harness()
+                .spawn_follow_up_with_command(
+                    current_dir,
+                    combined_prompt,
+                    session_id,
+                    command_parts,
+                    env,
+                    &self.cmd,
+                    self.approvals.clone(),
+                )
+                .await;
+        }
+
+        let command_parts = self
+            .build_command_builder(self.follow_up_template()?)
+            .build_follow_up(&[])?;
+        self.spawn_command(
+            current_dir,
+            command_parts,
+            &combined_prompt,
+            Some(session_id),
+            env,
+        )
+        .await
+    }
+
+    fn normalize_logs(
+        &self,
+        msg_store: Arc<MsgStore>,
+        worktree_path: &Path,
+    ) -> Vec<JoinHandle<()>> {
+        if self.output_format == CustomOutputFormat::Acp {
+            return crate::executors::acp::normalize_logs(msg_store, worktree_path);
+        }
+
+        let entry_index_provider = EntryIndexProvider::start_from(&msg_store);
+        let stderr_handle = normalize_stderr_logs(msg_store.clone(), entry_index_provider.clone());
+
+        let session_ids = self.session_id_extractor();
+        let jsonl = (self.output_format == CustomOutputFormat::Jsonl)
+            .then(|| self.jsonl.clone().unwrap_or_default());
+
+        let stdout_handle = tokio::spawn(async move {
+            let mut stdout_lines = msg_store.stdout_lines_stream();
+            let mut plain_text = Self::create_plain_text_normalizer(entry_index_provider.clone());
+            let mut session_id_sent = false;
+
+            while let Some(Ok(line)) = stdout_lines.next().await {
+                if !session_id_sent && let Some(session_id) = session_ids.extract(&line) {
+                    msg_store.push_session_id(session_id);
+                    session_id_sent = true;
+                }
+
+                let Some(mappings) = &jsonl else {
+                    for patch in plain_text.process(line + "\n") {
+                        msg_store.push_patch(patch);
+                    }
+                    continue;
+                };
+
+                match serde_json::from_str::<Value>(line.trim()) {
+                    Ok(event) => {
+                        if let Some(entry) = mappings.to_entry(&event) {
+                            add_normalized_entry(&msg_store, &entry_index_provider, entry);
+                        }
+                    }
+                    // Keep stray non-JSON output visible
+                    Err(_) if !line.trim().is_empty() => {
+                        add_normalized_entry(
+                            &msg_store,
+                            &entry_index_provider,
+                            NormalizedEntry {
+                                timestamp: None,
+                                entry_type: NormalizedEntryType::SystemMessage,
+                                content: strip_ansi_escapes::strip_str(&line),
+                                metadata: None,
+                            },
+                        );
+                    }
+                    Err(_) => {}
+                }
+            }
+        });
+
+        vec![stderr_handle, stdout_handle]
+    }
+
+    // Custom agents manage their own MCP configuration, if any
+    fn default_mcp_config_path(&self) -> Option<std::path::PathBuf> {
+        None
+    }
+
+    fn get_availability_info(&self) -> AvailabilityInfo {
+        let program = self
+            .build_command_builder(&self.command)
+            .build_initial()
+            .ok()
+            .map(|parts| parts.program().to_string());
+        let found = program.is_some_and(|program| {
+            let path = Path::new(&program);
+            if path.components().count() > 1 {
+                return path.is_file();
+            }
+            std::env::var_os("PATH").is_some_and(|paths| {
+                std::env::split_paths(&paths).any(|dir| dir.join(&program).is_file())
+            })
+        });
+
+        if found {
+            AvailabilityInfo::InstallationFound
+        } else {
+            AvailabilityInfo::NotFound
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn maps_jsonl_events_by_kind() {
+        let mappings = JsonlFieldMappings {
+            kind_pointer: "/type".to_string(),
+            content_pointer: "/text".to_string(),
+            tool_name_pointer: Some("/tool/name".to_string()),
+            tool_arguments_pointer: Some("/tool/input".to_string()),
+            assistant_kinds: vec!["message".to_string()],
+            tool_kinds: vec!["tool_call".to_string()],
+            ..Default::default()
+        };
+
+        let entry = mappings
+            .to_entry(&json!({"type": "message", "text": "Done."}))
+            .unwrap();
+        assert!(matches!(
+            entry.entry_type,
+            NormalizedEntryType::AssistantMessage
+        ));
+        assert_eq!(entry.content, "Done.");
+
+        let entry = mappings
+            .to_entry(&json!({
+                "type": "tool_call",
+                "text": "Reading src/main.rs",
+                "tool": {"name": "read_file", "input": {"path": "src/main.rs"}}
+            }))
+            .unwrap();
+        let NormalizedEntryType::ToolUse { tool_name, .. } = entry.entry_type else {
+            panic!("expected a tool use");
+        };
+        assert_eq!(tool_name, "read_file");
+
+        assert!(mappings.to_entry(&json!({"type": "heartbeat"})).is_none());
+    }
+
+    #[test]
+    fn extracts_session_ids() {
+        let from_regex = SessionIdExtractor {
+            regex: Some(Regex::new(r"session: ([\w-]+)").unwrap()),
+            pointer: None,
+        };
+        assert_eq!(
+            from_regex.extract("started session: abc-123").as_deref(),
+            Some("abc-123")
+        );
+        assert_eq!(from_regex.extract("no id here"), None);
+
+        let from_json = SessionIdExtractor {
+            regex: None,
+            pointer: Some("/session/id".to_string()),
+        };
+        assert_eq!(
+            from_json
+                .extract(r#"{"session": {"id": "s-42"}}"#)
+                .as_deref(),
+            Some("s-42")
+        );
+        assert_eq!(from_json.extract("plain text"), None);
+    }
+}
@@ -5,7 +5,7 @@ use command_group::AsyncCommandGroup;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use strum_macros::AsRefStr;
-use tokio::{io::AsyncWriteExt, process::Command};
+use tokio::{io::AsyncWriteExt, task::JoinHandle};
 use ts_rs::TS;
 use workspace_utils::msg_store::MsgStore;
 
@@ -112,21 +112,17 @@ async fn spawn_droid(
     env: &ExecutionEnv,
     cmd_overrides: &crate::command::CmdOverrides,
 ) -> Result<SpawnedChild, ExecutorError> {
-    let (program_path, args) = command_parts.into_resolved().await?;
+    let (program_path, args) = command_parts.into_resolved_in(env).await?;
 
-    let mut command = Command::new(program_path);
+    let env = env.clone().with_profile(cmd_overrides);
+    let mut command = env.command(program_path, current_dir);
     command
         .kill_on_drop(true)
         .stdin(Stdio::piped())
         .stdout(Stdio::piped())
         .stderr(Stdio::piped())
-        .current_dir(current_dir)
         .args(args);
 
-    env.clone()
-        .with_profile(cmd_overrides)
-        .apply_to_command(&mut command);
-
     let mut child = command.group_spawn()?;
 
     if let Some(mut stdin) = child.inner().stdin.take() {
@@ -171,12 +167,12 @@ impl StandardCodingAgentExecutor for Droid {
         spawn_droid(continue_cmd, &combined_prompt, current_dir, env, &self.cmd).await
     }
 
-    fn normalize_logs(&self, msg_store: Arc<MsgStore>, current_dir: &Path) {
+    fn normalize_logs(&self, msg_store: Arc<MsgStore>, current_dir: &Path) -> Vec<JoinHandle<()>> {
         normalize_logs(
             msg_store.clone(),
             current_dir,
             EntryIndexProvider::start_from(&msg_store),
-        );
+        )
     }
 
     fn default_mcp_config_path(&self) -> Option<std::path::PathBuf> {
@@ -7,6 +7,7 @@ use std::{
 use futures::{StreamExt, future::ready};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use tokio::task::JoinHandle;
 use workspace_utils::{
     diff::normalize_unified_diff, msg_store::MsgStore, path::make_path_relative,
 };
@@ -25,11 +26,11 @@ pub fn normalize_logs(
     msg_store: Arc<MsgStore>,
     worktree_path: &Path,
     entry_index_provider: EntryIndexProvider,
-) {
-    normalize_stderr_logs(msg_store.clone(), entry_index_provider.clone());
+) -> Vec<JoinHandle<()>> {
+    let stderr_handle = normalize_stderr_logs(msg_store.clone(), entry_index_provider.clone());
 
     let worktree_path = worktree_path.to_path_buf();
-    tokio::spawn(async move {
+    let stdout_handle = tokio::spawn(async move {
         let mut state = ToolCallStates::new(entry_index_provider.clone());
         let mut session_id_extracted = false;
         let mut sent_completion = false;
@@ -666,9 +667,14 @@ pub fn normalize_logs(
             }
         }
     });
+
+    vec![stderr_handle, stdout_handle]
 }
 
-fn normalize_stderr_logs(msg_store: Arc<MsgStore>, entry_index_provider: EntryIndexProvider) {
+fn normalize_stderr_logs(
+    msg_store: Arc<MsgStore>,
+    entry_index_provider: EntryIndexProvider,
+) -> JoinHandle<()> {
     tokio::spawn(async move {
         let mut stderr = msg_store.stderr_chunked_stream();
 
@@ -699,7 +705,7 @@ fn normalize_stderr_logs(msg_store: Arc<MsgStore>, entry_index_provider: EntryIn
                 msg_store.push_patch(patch);
             }
         }
-    });
+    })
 }
 
 /// Extract path from ApplyPatch input format
@@ -9,17 +9,18 @@ use serde::{Deserialize, Serialize};
 use sqlx::Type;
 use strum_macros::{Display, EnumDiscriminants, EnumString, VariantNames};
 use thiserror::Error;
+use tokio::task::JoinHandle;
 use ts_rs::TS;
 use workspace_utils::msg_store::MsgStore;
 
 use crate::{
     actions::ExecutorAction,
     approvals::ExecutorApprovalService,
-    command::CommandBuildError,
+    command::{CommandBuildError, ExecutionLimits},
     env::ExecutionEnv,
     executors::{
         amp::Amp, claude::ClaudeCode, codex::Codex, copilot::Copilot, cursor::CursorAgent,
-        droid::Droid, gemini::Gemini, opencode::Opencode, qwen::QwenCode,
+        custom::CustomAgent, droid::Droid, gemini::Gemini, opencode::Opencode, qwen::QwenCode,
     },
     mcp_config::McpConfig,
 };
@@ -30,6 +31,7 @@ pub mod claude;
 pub mod codex;
 pub mod copilot;
 pub mod cursor;
+pub mod custom;
 pub mod droid;
 pub mod gemini;
 pub mod opencode;
@@ -100,6 +102,7 @@ pub enum CodingAgent {
     QwenCode,
     Copilot,
     Droid,
+    CustomAgent,
 }
 
 impl CodingAgent {
@@ -149,6 +152,21 @@ impl CodingAgent {
         }
     }
 
+    pub fn limits(&self) -> &ExecutionLimits {
+        match self {
+            Self::ClaudeCode(agent) => &agent.cmd.limits,
+            Self::Amp(agent) => &agent.cmd.limits,
+            Self::Gemini(agent) => &agent.cmd.limits,
+            Self::Codex(agent) => &agent.cmd.limits,
+            Self::Opencode(agent) => &agent.cmd.limits,
+            Self::CursorAgent(agent) => &agent.cmd.limits,
+            Self::QwenCode(agent) => &agent.cmd.limits,
+            Self::Copilot(agent) => &agent.cmd.limits,
+            Self::Droid(agent) => &agent.cmd.limits,
+            Self::CustomAgent(agent) => &agent.cmd.limits,
+        }
+    }
+
     pub fn supports_mcp(&self) -> bool {
         self.default_mcp_config_path().is_some()
     }
@@ -166,7 +184,7 @@ impl CodingAgent {
                 BaseAgentCapability::SetupHelper,
             ],
             Self::CursorAgent(_) => vec![BaseAgentCapability::SetupHelper],
-            Self::Copilot(_) => vec![],
+            Self::Copilot(_) | Self::CustomAgent(_) => vec![],
         }
     }
 }
@@ -207,7 +225,13 @@ pub trait StandardCodingAgentExecutor {
         session_id: &str,
         env: &ExecutionEnv,
     ) -> Result<SpawnedChild, ExecutorError>;
-    fn normalize_logs(&self, _raw_logs_event_store: Arc<MsgStore>, _worktree_path: &Path);
+    /// Spawn the tasks normalizing the raw output in the store. They finish once they have
+    /// read the store's `Finished` message.
+    fn normalize_logs(
+        &self,
+        _raw_logs_event_store: Arc<MsgStore>,
+        _worktree_path: &Path,
+    ) -> Vec<JoinHandle<()>>;
 
     // MCP configuration methods
     fn default_mcp_config_path(&self) -> Option<std::path::PathBuf>;
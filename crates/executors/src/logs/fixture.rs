@@ -0,0 +1,148 @@
+//! Raw executor output recorded from real runs, replayed through the log normalizers.
+//!
+//! A fixture is a JSONL file: a header line with the executor configuration and worktree
+//! path of the run, followed by the raw `LogMsg` lines in the same format as the stored
+//! execution process logs.
+
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use futures::{StreamExt, future};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use workspace_utils::{log_msg::LogMsg, msg_store::MsgStore};
+
+use crate::{
+    executors::{CodingAgent, StandardCodingAgentExecutor},
+    logs::{NormalizedEntry, utils::patch::fold_normalized_entries},
+};
+
+/// When set, the raw output of every coding agent run is recorded as a fixture in this directory
+pub const RECORD_DIR_ENV: &str = "VK_RECORD_LOG_FIXTURES";
+
+const REPLAY_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Error)]
+pub enum LogFixtureError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error("fixture has no header line")]
+    MissingHeader,
+    #[error("normalizer did not finish within {0:?}")]
+    Timeout(Duration),
+    #[error(transparent)]
+    Normalizer(#[from] tokio::task::JoinError),
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct LogFixtureHeader {
+    executor: CodingAgent,
+    worktree_path: PathBuf,
+}
+
+#[derive(Debug, Clone)]
+pub struct LogFixture {
+    pub executor: CodingAgent,
+    pub worktree_path: PathBuf,
+    /// Raw stdout/stderr messages, in the order they were produced
+    pub messages: Vec<LogMsg>,
+}
+
+impl LogFixture {
+    /// Keep the raw output of a run, dropping anything the normalizers produced from it
+    pub fn from_history(executor: CodingAgent, worktree_path: &Path, history: &[LogMsg]) -> Self {
+        Self {
+            executor,
+            worktree_path: worktree_path.to_path_buf(),
+            messages: history
+                .iter()
+                .filter(|msg| matches!(msg, LogMsg::Stdout(_) | LogMsg::Stderr(_)))
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// Wait for a running execution to finish and write its raw output to `path`
+    pub async fn record(
+        executor: CodingAgent,
+        worktree_path: PathBuf,
+        msg_store: Arc<MsgStore>,
+        path: PathBuf,
+    ) -> Result<(), LogFixtureError> {
+        let mut stream = msg_store.history_plus_stream();
+        let mut history = Vec::new();
+        while let Some(Ok(msg)) = stream.next().await {
+            if matches!(msg, LogMsg::Finished) {
+                break;
+            }
+            history.push(msg);
+        }
+
+        let fixture = Self::from_history(executor, &worktree_path, &history);
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        tokio::fs::write(&path, fixture.to_jsonl()?).await?;
+        tracing::info!("Recorded log fixture to {}", path.display());
+        Ok(())
+    }
+
+    pub fn parse(contents: &str) -> Result<Self, LogFixtureError> {
+        let mut lines = contents.lines().filter(|line| !line.trim().is_empty());
+        let header: LogFixtureHeader =
+            serde_json::from_str(lines.next().ok_or(LogFixtureError::MissingHeader)?)?;
+        let messages = lines
+            .map(serde_json::from_str)
+            .collect::<Result<Vec<LogMsg>, _>>()?;
+        Ok(Self {
+            executor: header.executor,
+            worktree_path: header.worktree_path,
+            messages,
+        })
+    }
+
+    pub fn load(path: &Path) -> Result<Self, LogFixtureError> {
+        Self::parse(&std::fs::read_to_string(path)?)
+    }
+
+    pub fn to_jsonl(&self) -> Result<String, LogFixtureError> {
+        let header = LogFixtureHeader {
+            executor: self.executor.clone(),
+            worktree_path: self.worktree_path.clone(),
+        };
+        let mut out = serde_json::to_string(&header)?;
+        out.push('\n');
+        for msg in &self.messages {
+            out.push_str(&serde_json::to_string(msg)?);
+            out.push('\n');
+        }
+        Ok(out)
+    }
+
+    /// Feed the recorded output through the executor's normalizer and return the
+    /// conversation it builds once the normalizer has read all of it
+    pub async fn replay(&self) -> Result<Vec<NormalizedEntry>, LogFixtureError> {
+        let msg_store = Arc::new(MsgStore::new());
+        for msg in &self.messages {
+            msg_store.push(msg.clone());
+        }
+        msg_store.push_finished();
+
+        let normalizers = self
+            .executor
+            .normalize_logs(msg_store.clone(), &self.worktree_path);
+        let results = tokio::time::timeout(REPLAY_TIMEOUT, future::join_all(normalizers))
+            .await
+            .map_err(|_| LogFixtureError::Timeout(REPLAY_TIMEOUT))?;
+        for result in results {
+            result?;
+        }
+
+        Ok(fold_normalized_entries(&msg_store.get_history()))
+    }
+}
@@ -1,8 +1,9 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
-use workspace_utils::approvals::ApprovalStatus;
+use workspace_utils::approvals::{ApprovalStatus, MatchedApprovalRule};
 
+pub mod fixture;
 pub mod plain_text_processor;
 pub mod stderr_processor;
 pub mod utils;
@@ -138,6 +139,14 @@ pub enum ToolStatus {
     Failed,
     Denied {
         reason: Option<String>,
+        /// Set when an approval rule denied the call instead of the user
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[ts(optional)]
+        rule: Option<MatchedApprovalRule>,
+    },
+    /// Approved by an approval rule without asking the user
+    AutoApproved {
+        rule: MatchedApprovalRule,
     },
     PendingApproval {
         approval_id: String,
@@ -153,6 +162,7 @@ impl ToolStatus {
             ApprovalStatus::Approved => Some(ToolStatus::Created),
             ApprovalStatus::Denied { reason } => Some(ToolStatus::Denied {
                 reason: reason.clone(),
+                rule: None,
             }),
             ApprovalStatus::TimedOut => Some(ToolStatus::TimedOut),
             ApprovalStatus::Pending => None, // this should not happen
@@ -2,9 +2,9 @@ use std::sync::Arc;
 
 use json_patch::Patch;
 use serde::{Deserialize, Serialize};
-use serde_json::{from_value, json, to_value};
+use serde_json::{Value, from_value, json, to_value};
 use ts_rs::TS;
-use workspace_utils::{diff::Diff, msg_store::MsgStore};
+use workspace_utils::{diff::Diff, log_msg::LogMsg, msg_store::MsgStore};
 
 use crate::logs::{NormalizedEntry, utils::EntryIndexProvider};
 
@@ -175,3 +175,157 @@ pub fn replace_normalized_entry(
 ) {
     upsert_normalized_entry(msg_store, index, normalized_entry, false);
 }
+
+/// Applies the `/entries/{index}` patches of a log stream one message at a time, the way the
+/// frontend does
+#[derive(Debug, Default)]
+pub struct NormalizedEntryFolder {
+    entries: Vec<Option<NormalizedEntry>>,
+}
+
+impl NormalizedEntryFolder {
+    pub fn apply(&mut self, msg: &LogMsg) {
+        let LogMsg::JsonPatch(patch) = msg else {
+            return;
+        };
+        let Ok(Value::Array(ops)) = to_value(patch) else {
+            return;
+        };
+        for op in ops {
+            let Some(index) = op
+                .get("path")
+                .and_then(Value::as_str)
+                .and_then(|path| path.strip_prefix("/entries/"))
+                .and_then(|index| index.parse::<usize>().ok())
+            else {
+                continue;
+            };
+            let entry = op
+                .get("value")
+                .filter(|value| {
+                    value.get("type").and_then(Value::as_str) == Some("NORMALIZED_ENTRY")
+                })
+                .and_then(|value| value.get("content"))
+                .and_then(|content| from_value(content.clone()).ok());
+            let entries = &mut self.entries;
+            match op.get("op").and_then(Value::as_str) {
+                Some("add") => entries.insert(index.min(entries.len()), entry),
+                Some("replace") if index < entries.len() => entries[index] = entry,
+                Some("remove") if index < entries.len() => {
+                    entries.remove(index);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Number of entry slots so far, including patches that were not normalized entries
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The normalized entry at `index`, if that slot holds one
+    pub fn get(&self, index: usize) -> Option<&NormalizedEntry> {
+        self.entries.get(index).and_then(Option::as_ref)
+    }
+
+    pub fn into_entries(self) -> Vec<NormalizedEntry> {
+        self.entries.into_iter().flatten().collect()
+    }
+}
+
+/// Apply the `/entries/{index}` patches of a log stream the way the frontend does
+pub fn fold_normalized_entries(messages: &[LogMsg]) -> Vec<NormalizedEntry> {
+    let mut folder = NormalizedEntryFolder::default();
+    for msg in messages {
+        folder.apply(msg);
+    }
+    folder.into_entries()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logs::NormalizedEntryType;
+
+    fn entry(content: &str) -> NormalizedEntry {
+        NormalizedEntry {
+            timestamp: None,
+            entry_type: NormalizedEntryType::AssistantMessage,
+            content: content.to_string(),
+            metadata: None,
+        }
+    }
+
+    /// The content of each slot, `-` for slots that do not hold a normalized entry
+    fn contents(folder: &NormalizedEntryFolder) -> Vec<String> {
+        (0..folder.len())
+            .map(|index| {
+                folder
+                    .get(index)
+                    .map_or("-".to_string(), |entry| entry.content.clone())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_folder_applies_patches_one_message_at_a_time() {
+        let messages = vec![
+            LogMsg::JsonPatch(ConversationPatch::add_normalized_entry(0, entry("Hel"))),
+            LogMsg::Stdout("raw output".to_string()),
+            LogMsg::JsonPatch(ConversationPatch::add_stdout(1, "stdout".to_string())),
+            LogMsg::JsonPatch(ConversationPatch::replace(0, entry("Hello"))),
+            LogMsg::JsonPatch(ConversationPatch::add_normalized_entry(2, entry("Bye"))),
+            LogMsg::JsonPatch(ConversationPatch::remove(1)),
+            LogMsg::Finished,
+        ];
+
+        let mut folder = NormalizedEntryFolder::default();
+        let mut states = Vec::new();
+        for msg in &messages {
+            folder.apply(msg);
+            states.push(contents(&folder));
+        }
+        assert_eq!(
+            states,
+            [
+                vec!["Hel"],
+                vec!["Hel"],
+                // Slots that are not normalized entries still take up an index
+                vec!["Hel", "-"],
+                vec!["Hello", "-"],
+                vec!["Hello", "-", "Bye"],
+                vec!["Hello", "Bye"],
+                vec!["Hello", "Bye"],
+            ]
+        );
+
+        let folded: Vec<_> = fold_normalized_entries(&messages)
+            .into_iter()
+            .map(|entry| entry.content)
+            .collect();
+        assert_eq!(folded, ["Hello", "Bye"]);
+    }
+
+    #[test]
+    fn test_folder_ignores_out_of_range_updates() {
+        let mut folder = NormalizedEntryFolder::default();
+        folder.apply(&LogMsg::JsonPatch(ConversationPatch::replace(
+            3,
+            entry("late"),
+        )));
+        folder.apply(&LogMsg::JsonPatch(ConversationPatch::remove(0)));
+        assert!(folder.is_empty());
+
+        // An add past the end is appended rather than dropped
+        folder.apply(&LogMsg::JsonPatch(ConversationPatch::add_normalized_entry(
+            5,
+            entry("first"),
+        )));
+        assert_eq!(contents(&folder), ["first"]);
+    }
+}
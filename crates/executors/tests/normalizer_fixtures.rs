@@ -0,0 +1,79 @@
+//! Replays recorded executor output through the log normalizers and compares the
+//! conversations they build against checked-in snapshots.
+//!
+//! To add a fixture, run vibe-kanban with `VK_RECORD_LOG_FIXTURES=<dir>`, start a task with
+//! the agent, and copy the recorded `.jsonl` file into `tests/fixtures/normalizers`. Run the
+//! tests with `UPDATE_SNAPSHOTS=1` to write its snapshot, or to accept changed output, and
+//! commit the `.snap.json` files with the fixtures.
+
+use std::path::{Path, PathBuf};
+
+use executors::logs::{NormalizedEntry, fixture::LogFixture};
+use serde_json::Value;
+
+fn fixtures_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/normalizers")
+}
+
+/// Entries as compared against snapshots. Timestamps are taken at normalization time, so
+/// they are left out.
+fn snapshot(entries: &[NormalizedEntry]) -> String {
+    let mut value = serde_json::to_value(entries).unwrap();
+    if let Value::Array(entries) = &mut value {
+        for entry in entries.iter_mut().filter_map(Value::as_object_mut) {
+            entry.remove("timestamp");
+        }
+    }
+    serde_json::to_string_pretty(&value).unwrap() + "\n"
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn normalizers_match_snapshots() {
+    let mut fixtures: Vec<PathBuf> = std::fs::read_dir(fixtures_dir())
+        .unwrap()
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "jsonl"))
+        .collect();
+    fixtures.sort();
+    assert!(!fixtures.is_empty(), "no normalizer fixtures found");
+
+    let update = std::env::var("UPDATE_SNAPSHOTS").is_ok_and(|value| value == "1");
+    for path in fixtures {
+        let name = path.file_stem().unwrap().to_string_lossy().to_string();
+        let fixture =
+            LogFixture::load(&path).unwrap_or_else(|e| panic!("failed to load {name}: {e}"));
+        let entries = fixture
+            .replay()
+            .await
+            .unwrap_or_else(|e| panic!("failed to replay {name}: {e}"));
+        assert!(!entries.is_empty(), "{name} produced no entries");
+
+        let actual = snapshot(&entries);
+        let snapshot_path = path.with_extension("snap.json");
+        if update {
+            std::fs::write(&snapshot_path, actual).unwrap();
+            eprintln!("wrote snapshot {}", snapshot_path.display());
+            continue;
+        }
+        let expected = std::fs::read_to_string(&snapshot_path).unwrap_or_else(|_| {
+            panic!("{name} has no snapshot, run the tests with UPDATE_SNAPSHOTS=1 to write it")
+        });
+        assert_eq!(
+            expected, actual,
+            "{name} no longer matches its snapshot, rerun with UPDATE_SNAPSHOTS=1 if the change is intended"
+        );
+    }
+}
+
+#[test]
+fn fixtures_round_trip() {
+    let path = fixtures_dir().join("droid_read_execute.jsonl");
+    let fixture = LogFixture::load(&path).unwrap();
+    assert_eq!(fixture.messages.len(), 8);
+
+    let jsonl = fixture.to_jsonl().unwrap();
+    let reparsed = LogFixture::parse(&jsonl).unwrap();
+    assert_eq!(reparsed.executor, fixture.executor);
+    assert_eq!(reparsed.worktree_path, fixture.worktree_path);
+    assert_eq!(reparsed.to_jsonl().unwrap(), jsonl);
+}
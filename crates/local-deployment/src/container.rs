@@ -1,5 +1,5 @@
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
     io,
     path::{Path, PathBuf},
     sync::Arc,
@@ -12,15 +12,21 @@ use command_group::AsyncGroupChild;
 use db::{
     DBService,
     models::{
+        api_token::ApiToken,
         coding_agent_turn::CodingAgentTurn,
+        conflict_resolution::ConflictResolution,
         execution_process::{
-            ExecutionContext, ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus,
+            ExecutionContext, ExecutionFailureReason, ExecutionProcess, ExecutionProcessRunReason,
+            ExecutionProcessStatus,
         },
         execution_process_repo_state::ExecutionProcessRepoState,
         project_repo::ProjectRepo,
         repo::Repo,
-        scratch::{DraftFollowUpData, Scratch, ScratchType},
+        scratch::DraftFollowUpData,
+        search::SearchHit,
+        session::Session,
         task::{Task, TaskStatus},
+        task_event::TaskEventActor,
         workspace::Workspace,
         workspace_repo::WorkspaceRepo,
     },
@@ -33,22 +39,25 @@ use executors::{
         coding_agent_initial::CodingAgentInitialRequest,
     },
     approvals::{ExecutorApprovalService, NoopExecutorApprovalService},
+    command::ExecutionLimits,
     env::ExecutionEnv,
     executors::{BaseCodingAgent, ExecutorExitResult, ExecutorExitSignal, InterruptSender},
     logs::{NormalizedEntryType, utils::patch::extract_normalized_entry_from_patch},
-    profile::ExecutorProfileId,
+    profile::{ExecutorConfigs, ExecutorProfileId},
 };
-use futures::{FutureExt, TryStreamExt, stream::select};
+use futures::{FutureExt, StreamExt, TryStreamExt, stream::select};
 use serde_json::json;
 use services::services::{
     analytics::AnalyticsContext,
     approvals::{Approvals, executor_approvals::ExecutorApprovalBridge},
     config::Config,
+    conflict_resolution::{self, ConflictDetails, PendingConflictResolution},
     container::{ContainerError, ContainerRef, ContainerService},
     diff_stream::{self, DiffStreamHandle},
-    git::{Commit, GitCli, GitService},
+    git::{Commit, GitCli, GitService, GitServiceError},
     image::ImageService,
     notification::NotificationService,
+    quality_gate,
     queued_message::QueuedMessageService,
     share::SharePublisher,
     workspace_manager::{RepoWorkspaceInput, WorkspaceManager},
@@ -62,7 +71,7 @@ use utils::{
 };
 use uuid::Uuid;
 
-use crate::{command, copy};
+use crate::{command, copy, guard::ExecutionGuard, oci::OciBackend};
 
 #[derive(Clone)]
 pub struct LocalContainerService {
@@ -78,6 +87,7 @@ pub struct LocalContainerService {
     queued_message_service: QueuedMessageService,
     publisher: Result<SharePublisher, RemoteClientNotConfigured>,
     notification_service: NotificationService,
+    oci: Option<OciBackend>,
 }
 
 impl LocalContainerService {
@@ -92,6 +102,7 @@ impl LocalContainerService {
         approvals: Approvals,
         queued_message_service: QueuedMessageService,
         publisher: Result<SharePublisher, RemoteClientNotConfigured>,
+        oci: Option<OciBackend>,
     ) -> Self {
         let child_store = Arc::new(RwLock::new(HashMap::new()));
         let interrupt_senders = Arc::new(RwLock::new(HashMap::new()));
@@ -110,6 +121,7 @@ impl LocalContainerService {
             queued_message_service,
             publisher,
             notification_service,
+            oci,
         };
 
         container.spawn_workspace_cleanup().await;
@@ -142,7 +154,43 @@ impl LocalContainerService {
         map.remove(id)
     }
 
-    pub async fn cleanup_workspace(db: &DBService, workspace: &Workspace) {
+    /// Start (or restart) the workspace's OCI container when that backend is enabled.
+    /// Repositories are mounted alongside the worktree so its `.git` pointer resolves.
+    async fn ensure_oci_container(
+        &self,
+        workspace: &Workspace,
+        workspace_dir: &Path,
+    ) -> Result<(), ContainerError> {
+        let Some(oci) = &self.oci else {
+            return Ok(());
+        };
+
+        let repositories =
+            WorkspaceRepo::find_repos_for_workspace(&self.db.pool, workspace.id).await?;
+        let mut mounts = vec![workspace_dir.to_path_buf()];
+        mounts.extend(repositories.into_iter().map(|repo| repo.path));
+
+        oci.ensure_running(&workspace.id, &mounts)
+            .await
+            .map_err(|e| ContainerError::Other(anyhow!(e)))?;
+        Ok(())
+    }
+
+    pub async fn cleanup_workspace(
+        db: &DBService,
+        oci: Option<&OciBackend>,
+        workspace: &Workspace,
+    ) {
+        if let Some(oci) = oci
+            && let Err(e) = oci.remove(&workspace.id).await
+        {
+            tracing::warn!(
+                "Failed to remove container for workspace {}: {}",
+                workspace.id,
+                e
+            );
+        }
+
         let Some(container_ref) = &workspace.container_ref else {
             return;
         };
@@ -178,7 +226,10 @@ impl LocalContainerService {
         let _ = Workspace::clear_container_ref(&db.pool, workspace.id).await;
     }
 
-    pub async fn cleanup_expired_workspaces(db: &DBService) -> Result<(), DeploymentError> {
+    pub async fn cleanup_expired_workspaces(
+        db: &DBService,
+        oci: Option<&OciBackend>,
+    ) -> Result<(), DeploymentError> {
         let expired_workspaces = Workspace::find_expired_for_cleanup(&db.pool).await?;
         if expired_workspaces.is_empty() {
             tracing::debug!("No expired workspaces found");
@@ -189,20 +240,21 @@ impl LocalContainerService {
             expired_workspaces.len()
         );
         for workspace in &expired_workspaces {
-            Self::cleanup_workspace(db, workspace).await;
+            Self::cleanup_workspace(db, oci, workspace).await;
         }
         Ok(())
     }
 
     pub async fn spawn_workspace_cleanup(&self) {
         let db = self.db.clone();
+        let oci = self.oci.clone();
         let mut cleanup_interval = tokio::time::interval(tokio::time::Duration::from_secs(1800)); // 30 minutes
         WorkspaceManager::cleanup_orphan_workspaces(&self.db.pool).await;
         tokio::spawn(async move {
             loop {
                 cleanup_interval.tick().await;
                 tracing::info!("Starting periodic workspace cleanup...");
-                Self::cleanup_expired_workspaces(&db)
+                Self::cleanup_expired_workspaces(&db, oci.as_ref())
                     .await
                     .unwrap_or_else(|e| {
                         tracing::error!("Failed to clean up expired workspaces: {}", e)
@@ -338,6 +390,73 @@ impl LocalContainerService {
         any_committed
     }
 
+    /// Spawn a background task that stops the execution once it exceeds its wall-clock,
+    /// tool-call or output limit. The process is interrupted first, then killed.
+    fn spawn_execution_guard(&self, exec_id: Uuid, limits: ExecutionLimits) -> JoinHandle<()> {
+        let container = self.clone();
+
+        tokio::spawn(async move {
+            let Some(store) = container.get_msg_store_by_id(&exec_id).await else {
+                return;
+            };
+            let max_runtime = limits.max_runtime_secs.map(Duration::from_secs);
+            let mut guard = ExecutionGuard::new(limits);
+            let mut stream = store.history_plus_stream();
+
+            let mut deadline = match max_runtime {
+                Some(duration) => tokio::time::sleep(duration).boxed(),
+                None => std::future::pending().boxed(),
+            };
+
+            let reason = loop {
+                tokio::select! {
+                    _ = &mut deadline => break ExecutionFailureReason::Timeout,
+                    msg = stream.next() => match msg {
+                        Some(Ok(LogMsg::Finished)) | None => return,
+                        Some(Ok(msg)) => {
+                            if let Some(reason) = guard.observe(&msg) {
+                                break reason;
+                            }
+                        }
+                        Some(Err(_)) => continue,
+                    },
+                }
+            };
+
+            let process = match ExecutionProcess::find_by_id(&container.db.pool, exec_id).await {
+                Ok(Some(process)) if process.status == ExecutionProcessStatus::Running => process,
+                _ => return,
+            };
+
+            tracing::warn!(
+                "Stopping execution process {} after exceeding its limit: {:?}",
+                exec_id,
+                reason
+            );
+            store.push_stderr(format!(
+                "Execution stopped by vibe-kanban: {}",
+                match reason {
+                    ExecutionFailureReason::Timeout => "maximum runtime exceeded",
+                    ExecutionFailureReason::ToolCallLimit =>
+                        "maximum number of tool calls exceeded",
+                    ExecutionFailureReason::LogLimit => "maximum log output exceeded",
+                }
+            ));
+
+            if let Err(e) =
+                ExecutionProcess::set_failure_reason(&container.db.pool, exec_id, reason).await
+            {
+                tracing::error!("Failed to record failure reason for {}: {}", exec_id, e);
+            }
+            if let Err(e) = container
+                .stop_execution(&process, ExecutionProcessStatus::Failed)
+                .await
+            {
+                tracing::error!("Failed to stop execution process {}: {}", exec_id, e);
+            }
+        })
+    }
+
     /// Spawn a background task that polls the child process for completion and
     /// cleans up the execution entry when it exits.
     pub fn spawn_exit_monitor(
@@ -410,12 +529,40 @@ impl LocalContainerService {
                 tracing::error!("Failed to update execution process completion: {}", e);
             }
 
+            if let Err(e) = ApiToken::revoke_for_execution(&db.pool, exec_id).await {
+                tracing::error!("Failed to revoke API token of execution {}: {}", exec_id, e);
+            }
+
             if let Ok(ctx) = ExecutionProcess::load_context(&db.pool, exec_id).await {
                 // Update executor session summary if available
                 if let Err(e) = container.update_executor_session_summary(&exec_id).await {
                     tracing::warn!("Failed to update executor session summary: {}", e);
                 }
 
+                if matches!(
+                    ctx.execution_process.run_reason,
+                    ExecutionProcessRunReason::CodingAgent
+                ) && let Err(e) = container.index_conversation(&exec_id).await
+                {
+                    tracing::warn!("Failed to index conversation for search: {}", e);
+                }
+
+                // Continue or abort a conflicted operation before changes are committed. While
+                // another resolution round runs, this process neither commits nor finalizes.
+                let conflict_resolution = match ConflictResolution::take(&db.pool, exec_id).await {
+                    Ok(record) => record.and_then(PendingConflictResolution::from_record),
+                    Err(e) => {
+                        tracing::error!("Failed to load conflict resolution of {}: {}", exec_id, e);
+                        None
+                    }
+                };
+                let resolving_conflicts = match conflict_resolution {
+                    Some(resolution) => {
+                        container.finish_conflict_resolution(&ctx, resolution).await
+                    }
+                    None => false,
+                };
+
                 let success = matches!(
                     ctx.execution_process.status,
                     ExecutionProcessStatus::Completed
@@ -429,7 +576,7 @@ impl LocalContainerService {
                     ExecutionProcessStatus::Running
                 );
 
-                if success || cleanup_done {
+                if (success || cleanup_done) && !resolving_conflicts {
                     // Commit changes (if any) and get feedback about whether changes were made
                     let changes_committed = match container.try_commit_changes(&ctx).await {
                         Ok(committed) => committed,
@@ -465,60 +612,38 @@ impl LocalContainerService {
                     }
                 }
 
-                if container.should_finalize(&ctx) {
-                    // Only execute queued messages if the execution succeeded
-                    // If it failed or was killed, just clear the queue and finalize
+                if !resolving_conflicts && container.should_finalize(&ctx) {
+                    // Only drain the queue if the execution succeeded. If it failed or was
+                    // killed, keep the queue for the next successful run and just finalize.
                     let should_execute_queued = !matches!(
                         ctx.execution_process.status,
                         ExecutionProcessStatus::Failed | ExecutionProcessStatus::Killed
                     );
 
-                    if let Some(queued_msg) =
-                        container.queued_message_service.take_queued(ctx.session.id)
-                    {
-                        if should_execute_queued {
-                            tracing::info!(
-                                "Found queued message for session {}, starting follow-up execution",
-                                ctx.session.id
-                            );
-
-                            // Delete the scratch since we're consuming the queued message
-                            if let Err(e) = Scratch::delete(
-                                &db.pool,
-                                ctx.session.id,
-                                &ScratchType::DraftFollowUp,
-                            )
-                            .await
-                            {
-                                tracing::warn!(
-                                    "Failed to delete scratch after consuming queued message: {}",
-                                    e
-                                );
-                            }
-
-                            // Execute the queued follow-up
-                            if let Err(e) = container
-                                .start_queued_follow_up(&ctx, &queued_msg.data)
-                                .await
-                            {
-                                tracing::error!("Failed to start queued follow-up: {}", e);
-                                // Fall back to finalization if follow-up fails
-                                container.finalize_task(publisher.as_ref().ok(), &ctx).await;
-                            }
-                        } else {
-                            // Execution failed or was killed - discard the queued message and finalize
-                            tracing::info!(
-                                "Discarding queued message for session {} due to execution status {:?}",
-                                ctx.session.id,
-                                ctx.execution_process.status
-                            );
-                            container.finalize_task(publisher.as_ref().ok(), &ctx).await;
-                        }
+                    let started_queued = if should_execute_queued {
+                        container.start_next_queued(&ctx).await.unwrap_or_else(|e| {
+                            tracing::error!("Failed to start queued follow-up: {}", e);
+                            None
+                        })
                     } else {
+                        None
+                    };
+
+                    // Finalize unless a queued follow-up took over the session
+                    if started_queued.is_none() {
                         container.finalize_task(publisher.as_ref().ok(), &ctx).await;
                     }
                 }
 
+                if matches!(
+                    ctx.execution_process.run_reason,
+                    ExecutionProcessRunReason::QualityGate
+                ) && matches!(ctx.execution_process.status, ExecutionProcessStatus::Failed)
+                    && let Err(e) = container.start_gate_follow_up(&ctx).await
+                {
+                    tracing::error!("Failed to send quality gate failure to coding agent: {}", e);
+                }
+
                 // Fire analytics event when CodingAgent execution has finished
                 if config.read().await.analytics_enabled
                     && matches!(
@@ -697,6 +822,43 @@ impl LocalContainerService {
         Ok(())
     }
 
+    /// Join all assistant messages from the MsgStore history, in conversation order
+    async fn extract_assistant_messages(&self, exec_id: &Uuid) -> Option<String> {
+        let history = self.msg_stores.read().await.get(exec_id)?.get_history();
+
+        // Entries are re-emitted while they stream in, so keep the latest content per index
+        let mut messages = BTreeMap::new();
+        for msg in history.iter() {
+            if let LogMsg::JsonPatch(patch) = msg
+                && let Some((index, entry)) = extract_normalized_entry_from_patch(patch)
+                && matches!(entry.entry_type, NormalizedEntryType::AssistantMessage)
+            {
+                messages.insert(index, entry.content);
+            }
+        }
+
+        let conversation = messages
+            .values()
+            .map(|content| content.trim())
+            .filter(|content| !content.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        if conversation.is_empty() {
+            return None;
+        }
+
+        const MAX_CONVERSATION_LENGTH: usize = 64 * 1024;
+        Some(truncate_to_char_boundary(&conversation, MAX_CONVERSATION_LENGTH).to_string())
+    }
+
+    /// Index the assistant messages of a finished coding agent process for full-text search
+    async fn index_conversation(&self, exec_id: &Uuid) -> Result<(), anyhow::Error> {
+        if let Some(conversation) = self.extract_assistant_messages(exec_id).await {
+            SearchHit::upsert_conversation(&self.db.pool, *exec_id, &conversation).await?;
+        }
+        Ok(())
+    }
+
     /// Copy project files and images to the workspace.
     /// Skips files/images that already exist (fast no-op if all exist).
     async fn copy_files_and_images(
@@ -790,6 +952,23 @@ impl LocalContainerService {
         Ok(())
     }
 
+    /// Start the session's next queued follow-up, if any, and drop it from the queue once
+    /// its execution has started
+    async fn start_next_queued(
+        &self,
+        ctx: &ExecutionContext,
+    ) -> Result<Option<ExecutionProcess>, ContainerError> {
+        self.queued_message_service
+            .start_next(ctx.session.id, |queued_data| async move {
+                tracing::info!(
+                    "Found queued message for session {}, starting follow-up execution",
+                    ctx.session.id
+                );
+                self.start_queued_follow_up(ctx, &queued_data).await
+            })
+            .await
+    }
+
     /// Start a follow-up execution from a queued message
     async fn start_queued_follow_up(
         &self,
@@ -809,19 +988,102 @@ impl LocalContainerService {
             variant: queued_data.variant.clone(),
         };
 
-        // Get latest agent session ID for session continuity (from coding agent turns)
-        let latest_agent_session_id = ExecutionProcess::find_latest_coding_agent_turn_session_id(
+        self.start_follow_up(ctx, &queued_data.message, executor_profile_id)
+            .await
+    }
+
+    /// Send a failed quality gate's output to the coding agent, if the gate's repo opts in
+    /// and no coding agent is already running in the session
+    async fn start_gate_follow_up(
+        &self,
+        ctx: &ExecutionContext,
+    ) -> Result<Option<ExecutionProcess>, ContainerError> {
+        let Some(repo_name) = quality_gate::gated_repo_name(&ctx.execution_process) else {
+            return Ok(None);
+        };
+        let project_repos =
+            ProjectRepo::find_by_project_id_with_names(&self.db.pool, ctx.project.id).await?;
+        let Some(project_repo) = project_repos
+            .iter()
+            .find(|repo| repo.repo_name == repo_name && repo.gate_follow_up_on_failure)
+        else {
+            return Ok(None);
+        };
+
+        let latest_coding_agent = ExecutionProcess::find_latest_by_session_and_run_reason(
             &self.db.pool,
             ctx.session.id,
+            &ExecutionProcessRunReason::CodingAgent,
         )
         .await?;
+        if latest_coding_agent
+            .is_some_and(|process| process.status == ExecutionProcessStatus::Running)
+        {
+            tracing::info!(
+                "Not sending quality gate failure to the coding agent for session {} - agent is running",
+                ctx.session.id
+            );
+            return Ok(None);
+        }
+
+        let history = match self.msg_stores.read().await.get(&ctx.execution_process.id) {
+            Some(store) => store.get_history(),
+            None => Vec::new(),
+        };
+        let prompt = quality_gate::failure_follow_up_prompt(
+            repo_name,
+            project_repo.gate_script.as_deref().unwrap_or_default(),
+            &history,
+        );
+
+        let executor_profile_id =
+            ExecutionProcess::latest_executor_profile_for_session(&self.db.pool, ctx.session.id)
+                .await
+                .map_err(|e| {
+                    ContainerError::Other(anyhow!("Failed to get executor profile: {e}"))
+                })?;
+
+        self.start_follow_up(ctx, &prompt, executor_profile_id)
+            .await
+            .map(Some)
+    }
 
+    /// Continue the session's coding agent conversation with `prompt`, followed by cleanup scripts
+    async fn start_follow_up(
+        &self,
+        ctx: &ExecutionContext,
+        prompt: &str,
+        executor_profile_id: ExecutorProfileId,
+    ) -> Result<ExecutionProcess, ContainerError> {
         let project_repos =
             ProjectRepo::find_by_project_id_with_names(&self.db.pool, ctx.project.id).await?;
         let cleanup_action = self.cleanup_actions_for_repos(&project_repos);
 
-        let working_dir = ctx
-            .workspace
+        self.start_coding_agent_turn(
+            &ctx.workspace,
+            &ctx.session,
+            prompt,
+            executor_profile_id,
+            cleanup_action,
+        )
+        .await
+    }
+
+    /// Continue the session's coding agent conversation with `prompt`, then run `next_action`
+    async fn start_coding_agent_turn(
+        &self,
+        workspace: &Workspace,
+        session: &Session,
+        prompt: &str,
+        executor_profile_id: ExecutorProfileId,
+        next_action: Option<ExecutorAction>,
+    ) -> Result<ExecutionProcess, ContainerError> {
+        // Get latest agent session ID for session continuity (from coding agent turns)
+        let latest_agent_session_id =
+            ExecutionProcess::find_latest_coding_agent_turn_session_id(&self.db.pool, session.id)
+                .await?;
+
+        let working_dir = workspace
             .agent_working_dir
             .as_ref()
             .filter(|dir| !dir.is_empty())
@@ -829,29 +1091,188 @@ impl LocalContainerService {
 
         let action_type = if let Some(agent_session_id) = latest_agent_session_id {
             ExecutorActionType::CodingAgentFollowUpRequest(CodingAgentFollowUpRequest {
-                prompt: queued_data.message.clone(),
+                prompt: prompt.to_string(),
                 session_id: agent_session_id,
                 executor_profile_id: executor_profile_id.clone(),
                 working_dir: working_dir.clone(),
             })
         } else {
             ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
-                prompt: queued_data.message.clone(),
-                executor_profile_id: executor_profile_id.clone(),
+                prompt: prompt.to_string(),
+                executor_profile_id,
                 working_dir,
             })
         };
 
-        let action = ExecutorAction::new(action_type, cleanup_action.map(Box::new));
+        let action = ExecutorAction::new(action_type, next_action.map(Box::new));
 
         self.start_execution(
-            &ctx.workspace,
-            &ctx.session,
+            workspace,
+            session,
             &action,
             &ExecutionProcessRunReason::CodingAgent,
         )
         .await
     }
+
+    /// Ask the coding agent to resolve the conflicts currently stopping `worktree_path`. Cleanup
+    /// scripts are not chained, so the operation can be continued as soon as the agent stops.
+    async fn start_conflict_resolution_round(
+        &self,
+        workspace: &Workspace,
+        repo_name: &str,
+        worktree_path: PathBuf,
+        round: u32,
+    ) -> Result<ExecutionProcess, ContainerError> {
+        let op = self
+            .git
+            .detect_conflict_op(&worktree_path)?
+            .ok_or_else(|| {
+                ContainerError::Other(anyhow!("No conflicted git operation in '{repo_name}'"))
+            })?;
+        let conflicted_files = self.git.get_conflicted_files(&worktree_path)?;
+        if conflicted_files.is_empty() {
+            return Err(ContainerError::Other(anyhow!(
+                "No conflicted files in '{repo_name}'"
+            )));
+        }
+        let (head_subjects, incoming_subjects) = self
+            .git
+            .conflict_commit_subjects(&worktree_path, &op, &conflicted_files)
+            .unwrap_or_else(|e| {
+                tracing::warn!(
+                    "Failed to list commits for conflicts in {}: {}",
+                    repo_name,
+                    e
+                );
+                Default::default()
+            });
+        let prompt = conflict_resolution::resolution_prompt(&ConflictDetails {
+            repo_name,
+            op: &op,
+            conflicted_files: &conflicted_files,
+            head_subjects: &head_subjects,
+            incoming_subjects: &incoming_subjects,
+        });
+
+        let session = Session::find_latest_by_workspace_id(&self.db.pool, workspace.id)
+            .await?
+            .ok_or_else(|| {
+                ContainerError::Other(anyhow!("Workspace has no session to continue"))
+            })?;
+        let latest_coding_agent = ExecutionProcess::find_latest_by_session_and_run_reason(
+            &self.db.pool,
+            session.id,
+            &ExecutionProcessRunReason::CodingAgent,
+        )
+        .await?;
+        if latest_coding_agent
+            .is_some_and(|process| process.status == ExecutionProcessStatus::Running)
+        {
+            return Err(ContainerError::Other(anyhow!(
+                "A coding agent is already running in this workspace"
+            )));
+        }
+        let executor_profile_id =
+            ExecutionProcess::latest_executor_profile_for_session(&self.db.pool, session.id)
+                .await
+                .map_err(|e| {
+                    ContainerError::Other(anyhow!("Failed to get executor profile: {e}"))
+                })?;
+
+        let process = self
+            .start_coding_agent_turn(workspace, &session, &prompt, executor_profile_id, None)
+            .await?;
+        let resolution = PendingConflictResolution {
+            workspace_id: workspace.id,
+            repo_name: repo_name.to_string(),
+            worktree_path,
+            op,
+            conflicted_files,
+            round,
+        };
+        ConflictResolution::create(&self.db.pool, process.id, &resolution.to_record()).await?;
+        Ok(process)
+    }
+
+    /// Continue the operation a conflict-resolution run was working on if the agent succeeded
+    /// and left no conflict markers, starting another round if it stops at the next conflict.
+    /// Aborts the operation otherwise. Returns true if another round was started.
+    async fn finish_conflict_resolution(
+        &self,
+        ctx: &ExecutionContext,
+        resolution: PendingConflictResolution,
+    ) -> bool {
+        let worktree_path = resolution.worktree_path.clone();
+        let agent_succeeded = ctx.execution_process.status == ExecutionProcessStatus::Completed
+            && ctx.execution_process.exit_code == Some(0);
+
+        let outcome = if !agent_succeeded {
+            Err("the coding agent did not finish successfully".to_string())
+        } else {
+            let unresolved = conflict_resolution::files_with_conflict_markers(
+                &worktree_path,
+                &resolution.conflicted_files,
+            );
+            if unresolved.is_empty() {
+                Ok(())
+            } else {
+                Err(format!(
+                    "conflict markers remain in {}",
+                    unresolved.join(", ")
+                ))
+            }
+        };
+
+        let outcome: Result<(), String> = match outcome {
+            Ok(()) => match self.git.continue_conflicts(&worktree_path, &resolution.op) {
+                Ok(()) => {
+                    tracing::info!(
+                        "Resolved conflicts in {} for workspace {}",
+                        resolution.repo_name,
+                        resolution.workspace_id
+                    );
+                    return false;
+                }
+                Err(GitServiceError::MergeConflicts(_))
+                    if resolution.round < conflict_resolution::MAX_RESOLUTION_ROUNDS =>
+                {
+                    match self
+                        .start_conflict_resolution_round(
+                            &ctx.workspace,
+                            &resolution.repo_name,
+                            worktree_path.clone(),
+                            resolution.round + 1,
+                        )
+                        .await
+                    {
+                        Ok(_) => return true,
+                        Err(e) => Err(format!("failed to start the next round: {e}")),
+                    }
+                }
+                Err(e) => Err(e.to_string()),
+            },
+            Err(reason) => Err(reason),
+        };
+
+        if let Err(reason) = outcome {
+            tracing::warn!(
+                "Aborting git {} in {} for workspace {}: {}",
+                resolution.op.subcommand(),
+                resolution.repo_name,
+                resolution.workspace_id,
+                reason
+            );
+            if let Err(e) = self.git.abort_conflicts(&worktree_path) {
+                tracing::error!(
+                    "Failed to abort conflicts in {}: {}",
+                    worktree_path.display(),
+                    e
+                );
+            }
+        }
+        false
+    }
 }
 
 fn failure_exit_status() -> std::process::ExitStatus {
@@ -945,6 +1366,9 @@ impl ContainerService for LocalContainerService {
         Self::create_workspace_config_files(&created_workspace.workspace_dir, &repositories)
             .await?;
 
+        self.ensure_oci_container(workspace, &created_workspace.workspace_dir)
+            .await?;
+
         Workspace::update_container_ref(
             &self.db.pool,
             workspace.id,
@@ -960,7 +1384,7 @@ impl ContainerService for LocalContainerService {
 
     async fn delete(&self, workspace: &Workspace) -> Result<(), ContainerError> {
         self.try_stop(workspace, true).await;
-        Self::cleanup_workspace(&self.db, workspace).await;
+        Self::cleanup_workspace(&self.db, self.oci.as_ref(), workspace).await;
         Ok(())
     }
 
@@ -1007,6 +1431,8 @@ impl ContainerService for LocalContainerService {
 
         Self::create_workspace_config_files(&workspace_dir, &repositories).await?;
 
+        self.ensure_oci_container(workspace, &workspace_dir).await?;
+
         Ok(workspace_dir.to_string_lossy().to_string())
     }
 
@@ -1086,6 +1512,19 @@ impl ContainerService for LocalContainerService {
         env.insert("VK_WORKSPACE_ID", workspace.id.to_string());
         env.insert("VK_WORKSPACE_BRANCH", &workspace.branch);
 
+        // The agent's vibe-kanban MCP server calls the API, which needs a token when headless.
+        // It gets one that cannot administer tokens and only lives as long as this run.
+        if utils::is_headless() && executor_action.base_executor().is_some() {
+            let (_, token) =
+                ApiToken::create_for_execution(&self.db.pool, execution_process.id).await?;
+            env.insert("VK_API_TOKEN", token);
+        }
+
+        if let Some(oci) = &self.oci {
+            self.ensure_oci_container(workspace, &current_dir).await?;
+            env = env.with_container(oci.exec_target(&workspace.id, &execution_process.id));
+        }
+
         // Create the child and stream, add to execution tracker with timeout
         let mut spawned = tokio::time::timeout(
             Duration::from_secs(30),
@@ -1113,6 +1552,14 @@ impl ContainerService for LocalContainerService {
         // Spawn unified exit monitor: watches OS exit and optional executor signal
         let _hn = self.spawn_exit_monitor(&execution_process.id, spawned.exit_signal);
 
+        // Enforce the profile's runaway limits, if any
+        if let Some(profile_id) = executor_action.executor_profile_id()
+            && let Some(agent) = ExecutorConfigs::get_cached().get_coding_agent(&profile_id)
+            && !agent.limits().is_unbounded()
+        {
+            let _hn = self.spawn_execution_guard(execution_process.id, agent.limits().clone());
+        }
+
         Ok(())
     }
 
@@ -1166,6 +1613,20 @@ impl ContainerService for LocalContainerService {
             }
         }
 
+        // The local exec client dying does not stop the process inside the container
+        if let Some(oci) = &self.oci
+            && let Ok(Some(session)) = execution_process.parent_session(&self.db.pool).await
+            && let Err(e) = oci
+                .kill_execution(&session.workspace_id, &execution_process.id)
+                .await
+        {
+            tracing::warn!(
+                "Failed to kill containerized process for execution {}: {}",
+                execution_process.id,
+                e
+            );
+        }
+
         // Kill the child process and remove from the store
         {
             let mut child_guard = child.write().await;
@@ -1192,7 +1653,14 @@ impl ContainerService for LocalContainerService {
                 ExecutionProcessRunReason::DevServer
             )
         {
-            match Task::update_status(&self.db.pool, ctx.task.id, TaskStatus::InReview).await {
+            match Task::update_status(
+                &self.db.pool,
+                ctx.task.id,
+                TaskStatus::InReview,
+                TaskEventActor::Agent,
+            )
+            .await
+            {
                 Ok(_) => {
                     if let Some(publisher) = self.share_publisher()
                         && let Err(err) = publisher.update_shared_task_by_id(ctx.task.id).await
@@ -1315,6 +1783,20 @@ impl ContainerService for LocalContainerService {
         Ok(self.commit_repos(repos_with_changes, &message))
     }
 
+    async fn start_conflict_resolution(
+        &self,
+        workspace: &Workspace,
+        repo_name: &str,
+    ) -> Result<ExecutionProcess, ContainerError> {
+        let container_ref = workspace
+            .container_ref
+            .as_ref()
+            .ok_or_else(|| ContainerError::Other(anyhow!("Container reference not found")))?;
+        let worktree_path = PathBuf::from(container_ref).join(repo_name);
+        self.start_conflict_resolution_round(workspace, repo_name, worktree_path, 1)
+            .await
+    }
+
     /// Copy files from the original project directory to the worktree.
     /// Skips files that already exist at target with same size.
     async fn copy_project_files(
@@ -1338,6 +1820,19 @@ impl ContainerService for LocalContainerService {
         .map_err(|e| ContainerError::Other(anyhow!("Copy files task failed: {e}")))?
     }
 
+    async fn drain_idle_queues(&self) -> Result<(), ContainerError> {
+        for ctx in self.queued_message_service.find_idle_sessions().await? {
+            if let Err(e) = self.start_next_queued(&ctx).await {
+                tracing::error!(
+                    "Failed to start queued follow-up for session {}: {}",
+                    ctx.session.id,
+                    e
+                );
+            }
+        }
+        Ok(())
+    }
+
     async fn kill_all_running_processes(&self) -> Result<(), ContainerError> {
         tracing::info!("Killing all running processes");
         let running_processes = ExecutionProcess::find_running(&self.db.pool).await?;
@@ -0,0 +1,117 @@
+use std::collections::HashSet;
+
+use db::models::execution_process::ExecutionFailureReason;
+use executors::{
+    command::ExecutionLimits,
+    logs::{NormalizedEntryType, utils::patch::extract_normalized_entry_from_patch},
+};
+use utils::log_msg::LogMsg;
+
+/// Tracks tool calls and output volume of a running execution against its profile limits.
+/// The wall-clock limit is enforced separately by the caller.
+#[derive(Debug)]
+pub struct ExecutionGuard {
+    limits: ExecutionLimits,
+    tool_call_indices: HashSet<usize>,
+    log_bytes: u64,
+}
+
+impl ExecutionGuard {
+    pub fn new(limits: ExecutionLimits) -> Self {
+        Self {
+            limits,
+            tool_call_indices: HashSet::new(),
+            log_bytes: 0,
+        }
+    }
+
+    /// Feed one message from the execution's `MsgStore`; returns the limit it breaks, if any.
+    /// Tool calls are counted per normalized entry index, since status updates re-emit the
+    /// same `ToolUse` entry.
+    pub fn observe(&mut self, msg: &LogMsg) -> Option<ExecutionFailureReason> {
+        match msg {
+            LogMsg::Stdout(chunk) | LogMsg::Stderr(chunk) => {
+                self.log_bytes += chunk.len() as u64;
+                if self
+                    .limits
+                    .max_log_bytes
+                    .is_some_and(|max| self.log_bytes > max)
+                {
+                    return Some(ExecutionFailureReason::LogLimit);
+                }
+            }
+            LogMsg::JsonPatch(patch) => {
+                if let Some((index, entry)) = extract_normalized_entry_from_patch(patch)
+                    && matches!(entry.entry_type, NormalizedEntryType::ToolUse { .. })
+                {
+                    self.tool_call_indices.insert(index);
+                    if self
+                        .limits
+                        .max_tool_calls
+                        .is_some_and(|max| self.tool_call_indices.len() as u64 > max)
+                    {
+                        return Some(ExecutionFailureReason::ToolCallLimit);
+                    }
+                }
+            }
+            _ => {}
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use executors::logs::{
+        ActionType, NormalizedEntry, ToolStatus, utils::patch::ConversationPatch,
+    };
+
+    use super::*;
+
+    fn tool_use(index: usize) -> LogMsg {
+        LogMsg::JsonPatch(ConversationPatch::add_normalized_entry(
+            index,
+            NormalizedEntry {
+                timestamp: None,
+                entry_type: NormalizedEntryType::ToolUse {
+                    tool_name: "bash".to_string(),
+                    action_type: ActionType::Other {
+                        description: "ls".to_string(),
+                    },
+                    status: ToolStatus::Created,
+                },
+                content: "ls".to_string(),
+                metadata: None,
+            },
+        ))
+    }
+
+    #[test]
+    fn log_limit_counts_stdout_and_stderr() {
+        let mut guard = ExecutionGuard::new(ExecutionLimits {
+            max_log_bytes: Some(8),
+            ..Default::default()
+        });
+        assert_eq!(guard.observe(&LogMsg::Stdout("abcd".to_string())), None);
+        assert_eq!(guard.observe(&LogMsg::Stderr("efgh".to_string())), None);
+        assert_eq!(
+            guard.observe(&LogMsg::Stdout("i".to_string())),
+            Some(ExecutionFailureReason::LogLimit)
+        );
+    }
+
+    #[test]
+    fn tool_calls_are_counted_once_per_entry() {
+        let mut guard = ExecutionGuard::new(ExecutionLimits {
+            max_tool_calls: Some(2),
+            ..Default::default()
+        });
+        assert_eq!(guard.observe(&tool_use(0)), None);
+        assert_eq!(guard.observe(&tool_use(0)), None);
+        assert_eq!(guard.observe(&tool_use(1)), None);
+        assert_eq!(
+            guard.observe(&tool_use(2)),
+            Some(ExecutionFailureReason::ToolCallLimit)
+        );
+    }
+}
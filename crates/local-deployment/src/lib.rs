@@ -30,10 +30,12 @@ use utils::{
 };
 use uuid::Uuid;
 
-use crate::container::LocalContainerService;
+use crate::{container::LocalContainerService, oci::OciBackend};
 mod command;
 pub mod container;
 mod copy;
+mod guard;
+pub mod oci;
 
 #[derive(Clone)]
 pub struct LocalDeployment {
@@ -125,8 +127,8 @@ impl Deployment for LocalDeployment {
             });
         }
 
-        let approvals = Approvals::new(msg_stores.clone());
-        let queued_message_service = QueuedMessageService::new();
+        let approvals = Approvals::new(db.clone(), msg_stores.clone());
+        let queued_message_service = QueuedMessageService::new(db.clone());
 
         let share_config = ShareConfig::from_env();
 
@@ -172,6 +174,8 @@ impl Deployment for LocalDeployment {
             user_id: user_id.clone(),
             analytics_service: s.clone(),
         });
+        // Refuse to start rather than silently fall back to running agents on the host
+        let oci = OciBackend::from_env().map_err(|e| DeploymentError::Other(e.into()))?;
         let container = LocalContainerService::new(
             db.clone(),
             msg_stores.clone(),
@@ -182,6 +186,7 @@ impl Deployment for LocalDeployment {
             approvals.clone(),
             queued_message_service.clone(),
             share_publisher.clone(),
+            oci,
         )
         .await;
 
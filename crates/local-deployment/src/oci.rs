@@ -0,0 +1,313 @@
+//! Optional OCI backend that runs workspace processes inside a Docker/Podman container.
+//!
+//! Each workspace gets one long-lived container with the worktree (and the repositories
+//! backing it) bind-mounted at identical paths, so host-side git operations and the
+//! in-container agent see the same files. Selected with `VK_CONTAINER_BACKEND`.
+
+use std::{path::PathBuf, process::Stdio, str::FromStr};
+
+use executors::env::ContainerExec;
+use thiserror::Error;
+use tokio::process::Command;
+use uuid::Uuid;
+
+const DEFAULT_IMAGE: &str = "node:22-bookworm";
+const DEFAULT_NETWORK: &str = "none";
+
+/// Agent credentials and settings under the host home directory. Those that exist are
+/// mounted read-only under `HOME_SEED_DIR` and copied into the container's HOME when it
+/// starts, so agents keep their logins and configuration without being able to change
+/// the host's copies.
+const AGENT_HOME_ENTRIES: &[&str] = &[
+    ".claude",
+    ".claude.json",
+    ".codex",
+    ".gemini",
+    ".qwen",
+    ".copilot",
+    ".cursor",
+    ".factory",
+    ".config/amp",
+    ".config/opencode",
+    ".local/share/opencode",
+    ".gitconfig",
+];
+
+const HOME_SEED_DIR: &str = "/opt/vk-home";
+
+#[derive(Debug, Error)]
+pub enum OciError {
+    #[error("Io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("`{engine} {action}` failed: {stderr}")]
+    CommandFailed {
+        engine: &'static str,
+        action: &'static str,
+        stderr: String,
+    },
+    #[error("Unknown container backend '{0}', expected one of: local, docker, podman")]
+    UnknownBackend(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OciEngine {
+    Docker,
+    Podman,
+}
+
+impl OciEngine {
+    pub fn cli(&self) -> &'static str {
+        match self {
+            OciEngine::Docker => "docker",
+            OciEngine::Podman => "podman",
+        }
+    }
+}
+
+impl FromStr for OciEngine {
+    type Err = OciError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "docker" => Ok(OciEngine::Docker),
+            "podman" => Ok(OciEngine::Podman),
+            other => Err(OciError::UnknownBackend(other.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct OciBackend {
+    engine: OciEngine,
+    image: String,
+    network: String,
+}
+
+impl OciBackend {
+    pub fn new(engine: OciEngine, image: impl Into<String>, network: impl Into<String>) -> Self {
+        Self {
+            engine,
+            image: image.into(),
+            network: network.into(),
+        }
+    }
+
+    /// Read the backend from `VK_CONTAINER_BACKEND` (`local` | `docker` | `podman`).
+    /// `VK_CONTAINER_IMAGE` and `VK_CONTAINER_NETWORK` override the image and network.
+    /// The network defaults to `none`, which also cuts agents off from their model APIs;
+    /// set it to `bridge`, or to a network whose egress is restricted to those APIs, to
+    /// let agents run.
+    pub fn from_env() -> Result<Option<Self>, OciError> {
+        let backend = std::env::var("VK_CONTAINER_BACKEND").unwrap_or_default();
+        if backend.trim().is_empty() || backend.trim().eq_ignore_ascii_case("local") {
+            return Ok(None);
+        }
+
+        let engine = OciEngine::from_str(&backend)?;
+        let image = std::env::var("VK_CONTAINER_IMAGE")
+            .ok()
+            .filter(|s| !s.trim().is_empty())
+            .unwrap_or_else(|| DEFAULT_IMAGE.to_string());
+        let network = std::env::var("VK_CONTAINER_NETWORK")
+            .ok()
+            .filter(|s| !s.trim().is_empty())
+            .unwrap_or_else(|| DEFAULT_NETWORK.to_string());
+        if network == "none" {
+            tracing::warn!(
+                "Workspace containers have no network access, so coding agents cannot reach their model APIs; set VK_CONTAINER_NETWORK to allow it"
+            );
+        }
+
+        Ok(Some(Self::new(engine, image, network)))
+    }
+
+    pub fn engine(&self) -> OciEngine {
+        self.engine
+    }
+
+    pub fn container_name(workspace_id: &Uuid) -> String {
+        format!("vk-{workspace_id}")
+    }
+
+    fn pid_file(execution_id: &Uuid) -> String {
+        format!("/tmp/vk-exec-{execution_id}.pid")
+    }
+
+    /// Exec target for an execution process running in the workspace container
+    pub fn exec_target(&self, workspace_id: &Uuid, execution_id: &Uuid) -> ContainerExec {
+        ContainerExec {
+            engine: self.engine.cli().to_string(),
+            container: Self::container_name(workspace_id),
+            pid_file: Self::pid_file(execution_id),
+        }
+    }
+
+    /// Make sure the workspace container exists and is running. `mounts` are bind-mounted
+    /// read-write at the same path inside the container. HOME is the host's home directory,
+    /// backed by a scratch tmpfs seeded with the agent entries of `AGENT_HOME_ENTRIES`.
+    pub async fn ensure_running(
+        &self,
+        workspace_id: &Uuid,
+        mounts: &[PathBuf],
+    ) -> Result<String, OciError> {
+        let name = Self::container_name(workspace_id);
+
+        let inspect = Command::new(self.engine.cli())
+            .args(["container", "inspect", "-f", "{{.State.Running}}", &name])
+            .stdin(Stdio::null())
+            .output()
+            .await?;
+        if inspect.status.success() {
+            if String::from_utf8_lossy(&inspect.stdout).trim() != "true" {
+                self.run("start", ["start", name.as_str()]).await?;
+            }
+            return Ok(name);
+        }
+
+        let mut args = vec![
+            "run".to_string(),
+            "-d".to_string(),
+            "--name".to_string(),
+            name.clone(),
+            "--label".to_string(),
+            format!("vibe-kanban.workspace-id={workspace_id}"),
+            "--network".to_string(),
+            self.network.clone(),
+        ];
+        args.extend(self.user_args(mounts.first()));
+        let home_mounts = match std::env::var_os("HOME").map(PathBuf::from) {
+            Some(home) => {
+                args.extend([
+                    "-e".to_string(),
+                    format!("HOME={}", home.to_string_lossy()),
+                    "--tmpfs".to_string(),
+                    format!("{}:exec,mode=1777", home.to_string_lossy()),
+                ]);
+                AGENT_HOME_ENTRIES
+                    .iter()
+                    .map(|entry| (*entry, home.join(entry)))
+                    .filter(|(_, path)| path.exists())
+                    .collect()
+            }
+            None => {
+                args.extend(["-e".to_string(), "HOME=/tmp".to_string()]);
+                Vec::new()
+            }
+        };
+        for mount in mounts {
+            let path = mount.to_string_lossy();
+            args.push("-v".to_string());
+            args.push(format!("{path}:{path}"));
+        }
+        for (entry, path) in &home_mounts {
+            args.push("-v".to_string());
+            args.push(format!(
+                "{}:{HOME_SEED_DIR}/{entry}:ro",
+                path.to_string_lossy()
+            ));
+        }
+        // The tmpfs HOME is empty on every start, so seed it again each time
+        args.extend([
+            "--entrypoint".to_string(),
+            "sh".to_string(),
+            self.image.clone(),
+            "-c".to_string(),
+            format!(r#"cp -a {HOME_SEED_DIR}/. "$HOME"/ 2>/dev/null; exec sleep infinity"#),
+        ]);
+
+        self.run("run", args.iter().map(String::as_str)).await?;
+        tracing::info!(
+            "Started {} container {} for workspace {}",
+            self.engine.cli(),
+            name,
+            workspace_id
+        );
+        Ok(name)
+    }
+
+    /// Remove the workspace container. Missing containers are not an error.
+    pub async fn remove(&self, workspace_id: &Uuid) -> Result<(), OciError> {
+        let name = Self::container_name(workspace_id);
+        let output = Command::new(self.engine.cli())
+            .args(["rm", "-f", &name])
+            .stdin(Stdio::null())
+            .output()
+            .await?;
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if !output.status.success() && !stderr.to_ascii_lowercase().contains("no such container") {
+            return Err(OciError::CommandFailed {
+                engine: self.engine.cli(),
+                action: "rm",
+                stderr: stderr.trim().to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Kill the process tree started for `execution_id`. Killing the local `exec` client
+    /// does not reliably stop the process inside the container, so signal it directly.
+    pub async fn kill_execution(
+        &self,
+        workspace_id: &Uuid,
+        execution_id: &Uuid,
+    ) -> Result<(), OciError> {
+        let name = Self::container_name(workspace_id);
+        let pid_file = Self::pid_file(execution_id);
+        self.run(
+            "exec",
+            [
+                "exec",
+                name.as_str(),
+                "sh",
+                "-c",
+                r#"[ -f "$0" ] || exit 0; pid=$(cat "$0"); kill -KILL -- "-$pid" 2>/dev/null || kill -KILL "$pid" 2>/dev/null; rm -f "$0""#,
+                pid_file.as_str(),
+            ],
+        )
+        .await
+    }
+
+    /// Run processes as the owner of the workspace so files written by the agent stay
+    /// editable on the host.
+    fn user_args(&self, workspace_dir: Option<&PathBuf>) -> Vec<String> {
+        match self.engine {
+            OciEngine::Podman => vec!["--userns=keep-id".to_string()],
+            OciEngine::Docker => {
+                #[cfg(unix)]
+                {
+                    use std::os::unix::fs::MetadataExt;
+
+                    if let Some(metadata) = workspace_dir.and_then(|dir| dir.metadata().ok()) {
+                        return vec![
+                            "--user".to_string(),
+                            format!("{}:{}", metadata.uid(), metadata.gid()),
+                        ];
+                    }
+                }
+                #[cfg(not(unix))]
+                let _ = workspace_dir;
+                Vec::new()
+            }
+        }
+    }
+
+    async fn run<'a>(
+        &self,
+        action: &'static str,
+        args: impl IntoIterator<Item = &'a str>,
+    ) -> Result<(), OciError> {
+        let output = Command::new(self.engine.cli())
+            .args(args)
+            .stdin(Stdio::null())
+            .output()
+            .await?;
+        if !output.status.success() {
+            return Err(OciError::CommandFailed {
+                engine: self.engine.cli(),
+                action,
+                stderr: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            });
+        }
+        Ok(())
+    }
+}
@@ -36,6 +36,42 @@ fn generate_types_content() -> String {
         db::models::task::TaskRelationships::decl(),
         db::models::task::CreateTask::decl(),
         db::models::task::UpdateTask::decl(),
+        db::models::task_dependency::TaskDependency::decl(),
+        db::models::task_dependency::CreateTaskDependency::decl(),
+        db::models::task_dependency::TaskDependencies::decl(),
+        db::models::task_auto_start::TaskAutoStart::decl(),
+        db::models::task_auto_start::UpsertTaskAutoStart::decl(),
+        db::models::task_schedule::TaskSchedule::decl(),
+        db::models::task_schedule::CreateTaskSchedule::decl(),
+        db::models::task_schedule::UpdateTaskSchedule::decl(),
+        db::models::task_schedule::TaskScheduleRun::decl(),
+        db::models::task_event::TaskEventActor::decl(),
+        db::models::task_event::TaskEvent::decl(),
+        db::models::attempt_group::AttemptGroup::decl(),
+        db::models::attempt_group::AttemptGroupMember::decl(),
+        db::models::search::SearchDocumentKind::decl(),
+        db::models::search::FullTextSearchQuery::decl(),
+        db::models::search::SearchHit::decl(),
+        db::models::analytics::AnalyticsFilter::decl(),
+        db::models::webhook::WebhookEventType::decl(),
+        db::models::webhook::WebhookDeliveryStatus::decl(),
+        db::models::webhook::Webhook::decl(),
+        db::models::webhook::CreateWebhook::decl(),
+        db::models::webhook::UpdateWebhook::decl(),
+        server::routes::webhooks::CreatedWebhook::decl(),
+        db::models::webhook::WebhookDelivery::decl(),
+        db::models::api_token::ApiTokenScope::decl(),
+        db::models::api_token::ApiToken::decl(),
+        db::models::api_token::CreateApiToken::decl(),
+        db::models::api_token::ApiTokenAuditEntry::decl(),
+        server::routes::api_tokens::CreatedApiToken::decl(),
+        db::models::approval::ApprovalDecision::decl(),
+        db::models::approval::ApprovalActor::decl(),
+        db::models::approval::Approval::decl(),
+        db::models::approval_rule::ApprovalRuleAction::decl(),
+        db::models::approval_rule::ApprovalRule::decl(),
+        db::models::approval_rule::CreateApprovalRule::decl(),
+        db::models::approval_rule::UpdateApprovalRule::decl(),
         db::models::scratch::DraftFollowUpData::decl(),
         db::models::scratch::ScratchPayload::decl(),
         db::models::scratch::ScratchType::decl(),
@@ -49,6 +85,7 @@ fn generate_types_content() -> String {
         db::models::execution_process::ExecutionProcess::decl(),
         db::models::execution_process::ExecutionProcessStatus::decl(),
         db::models::execution_process::ExecutionProcessRunReason::decl(),
+        db::models::execution_process::ExecutionFailureReason::decl(),
         db::models::execution_process_repo_state::ExecutionProcessRepoState::decl(),
         db::models::merge::Merge::decl(),
         db::models::merge::DirectMerge::decl(),
@@ -58,6 +95,7 @@ fn generate_types_content() -> String {
         utils::approvals::ApprovalStatus::decl(),
         utils::approvals::CreateApprovalRequest::decl(),
         utils::approvals::ApprovalResponse::decl(),
+        utils::approvals::MatchedApprovalRule::decl(),
         utils::diff::Diff::decl(),
         utils::diff::DiffChangeKind::decl(),
         utils::response::ApiResponse::<()>::decl(),
@@ -123,6 +161,13 @@ fn generate_types_content() -> String {
         server::routes::task_attempts::WorkspaceRepoInput::decl(),
         server::routes::task_attempts::RunAgentSetupRequest::decl(),
         server::routes::task_attempts::RunAgentSetupResponse::decl(),
+        server::routes::attempt_groups::CreateAttemptGroupBody::decl(),
+        server::routes::attempt_groups::SelectAttemptWinnerRequest::decl(),
+        server::routes::attempt_groups::AttemptGroupWithMembers::decl(),
+        server::routes::attempt_groups::AttemptDiffStats::decl(),
+        server::routes::attempt_groups::AttemptProcessOutcome::decl(),
+        server::routes::attempt_groups::AttemptSummary::decl(),
+        server::routes::attempt_groups::AttemptComparison::decl(),
         server::routes::task_attempts::gh_cli_setup::GhCliSetupError::decl(),
         server::routes::task_attempts::RebaseTaskAttemptRequest::decl(),
         server::routes::task_attempts::AbortConflictsRequest::decl(),
@@ -131,6 +176,14 @@ fn generate_types_content() -> String {
         server::routes::task_attempts::pr::CreatePrError::decl(),
         server::routes::task_attempts::BranchStatus::decl(),
         server::routes::task_attempts::RunScriptError::decl(),
+        server::routes::task_attempts::quality_gates::RunQualityGatesRequest::decl(),
+        server::routes::task_attempts::stack::CreateStackedWorkspaceRequest::decl(),
+        services::services::workspace_stack::RestackOutcome::decl(),
+        services::services::workspace_stack::RestackStep::decl(),
+        services::services::workspace_stack::RestackResult::decl(),
+        server::routes::task_attempts::stack::CreateStackedPrsRequest::decl(),
+        server::routes::task_attempts::stack::StackedPrOutcome::decl(),
+        server::routes::task_attempts::stack::StackedPr::decl(),
         server::routes::task_attempts::pr::AttachPrResponse::decl(),
         server::routes::task_attempts::pr::AttachExistingPrRequest::decl(),
         server::routes::task_attempts::pr::PrCommentsResponse::decl(),
@@ -150,9 +203,35 @@ fn generate_types_content() -> String {
         services::services::config::SoundFile::decl(),
         services::services::config::UiLanguage::decl(),
         services::services::config::ShowcaseState::decl(),
+        services::services::config::LogStorageConfig::decl(),
+        services::services::config::ForgeKind::decl(),
+        services::services::config::ForgeHostConfig::decl(),
+        services::services::config::ModelTokenPrice::decl(),
+        services::services::usage::UsageGroupBy::decl(),
+        services::services::usage::UsageQuery::decl(),
+        services::services::usage::UsageTotals::decl(),
+        services::services::usage::ModelUsage::decl(),
+        services::services::usage::UsageGroup::decl(),
+        services::services::usage::UsageReport::decl(),
+        services::services::project_analytics::DurationStats::decl(),
+        services::services::project_analytics::StatusDurations::decl(),
+        services::services::project_analytics::AnalyticsMetrics::decl(),
+        services::services::project_analytics::ProjectMetrics::decl(),
+        services::services::project_analytics::ExecutorProfileMetrics::decl(),
+        services::services::project_analytics::AnalyticsReport::decl(),
+        services::services::project_bundle::ExportProjectOptions::decl(),
+        services::services::project_bundle::ImportRepoPath::decl(),
+        services::services::project_bundle::ImportProjectOptions::decl(),
+        services::services::project_bundle::ProjectBundleSummary::decl(),
+        services::services::project_bundle::BundledRepoSummary::decl(),
+        services::services::workspace_stack::StackedWorkspace::decl(),
+        services::services::workspace_stack::WorkspaceStack::decl(),
+        services::services::quality_gate::QualityGateState::decl(),
+        services::services::quality_gate::RepoQualityGate::decl(),
+        services::services::quality_gate::QualityGateError::decl(),
         services::services::git::GitBranch::decl(),
         services::services::share::SharedTaskDetails::decl(),
-        services::services::queued_message::QueuedMessage::decl(),
+        db::models::queued_message::QueuedMessage::decl(),
         services::services::queued_message::QueueStatus::decl(),
         services::services::git::ConflictOp::decl(),
         executors::actions::ExecutorAction::decl(),
@@ -185,7 +264,11 @@ fn generate_types_content() -> String {
         executors::executors::droid::Droid::decl(),
         executors::executors::droid::Autonomy::decl(),
         executors::executors::droid::ReasoningEffortLevel::decl(),
+        executors::executors::custom::CustomAgent::decl(),
+        executors::executors::custom::CustomOutputFormat::decl(),
+        executors::executors::custom::JsonlFieldMappings::decl(),
         executors::executors::AppendPrompt::decl(),
+        executors::command::ExecutionLimits::decl(),
         executors::actions::coding_agent_initial::CodingAgentInitialRequest::decl(),
         executors::actions::coding_agent_follow_up::CodingAgentFollowUpRequest::decl(),
         executors::logs::CommandExitStatus::decl(),
@@ -286,6 +369,10 @@ fn generate_schemas() -> Result<HashMap<&'static str, String>, serde_json::Error
             "droid",
             generate_json_schema::<executors::executors::droid::Droid>()?,
         ),
+        (
+            "custom_agent",
+            generate_json_schema::<executors::executors::custom::CustomAgent>()?,
+        ),
     ]);
     println!(
         "✅ JSON schemas generated. {} schemas created.",
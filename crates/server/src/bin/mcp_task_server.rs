@@ -52,7 +52,13 @@ fn main() -> anyhow::Result<()> {
                 url
             };
 
+            // Needed when the backend runs in headless mode
+            let api_token = std::env::var("VK_API_TOKEN")
+                .ok()
+                .filter(|token| !token.trim().is_empty());
+
             let service = TaskServer::new(&base_url)
+                .with_api_token(api_token.as_deref().map(str::trim))
                 .init()
                 .await
                 .serve(stdio())
@@ -0,0 +1,287 @@
+use futures_util::StreamExt;
+use reqwest::{Client, RequestBuilder, StatusCode};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use thiserror::Error;
+use tokio_tungstenite::tungstenite::{self, Message, client::IntoClientRequest, http::HeaderValue};
+use utils::{log_msg::LogMsg, port_file::read_port_file};
+
+#[derive(Debug, Error)]
+pub enum CliError {
+    #[error(
+        "Could not find a running Vibe Kanban server. Start it first, or pass --url / set VK_URL."
+    )]
+    ServerNotFound,
+    #[error("Failed to reach the Vibe Kanban server: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("The server requires an API token. Pass --token or set VK_API_TOKEN.")]
+    Unauthorized,
+    #[error("{message}")]
+    Api {
+        message: String,
+        /// Typed error details, e.g. merge conflicts
+        error_data: Option<serde_json::Value>,
+    },
+    #[error("Log stream failed: {0}")]
+    WebSocket(#[from] tungstenite::Error),
+    #[error("{0}")]
+    Invalid(String),
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiResponseEnvelope<T> {
+    success: bool,
+    data: Option<T>,
+    message: Option<String>,
+    error_data: Option<serde_json::Value>,
+}
+
+/// Client for the local REST API
+pub struct VkClient {
+    client: Client,
+    base_url: String,
+    token: Option<String>,
+}
+
+impl VkClient {
+    /// Uses `url` if given, otherwise the port file written by the running server
+    pub async fn discover(url: Option<String>, token: Option<String>) -> Result<Self, CliError> {
+        let base_url = server_url(url, "vibe-kanban").await?;
+        Ok(Self::new(&base_url, token))
+    }
+
+    fn new(base_url: &str, token: Option<String>) -> Self {
+        Self {
+            client: Client::new(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            token: token.filter(|token| !token.trim().is_empty()),
+        }
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}/api/{}", self.base_url, path.trim_start_matches('/'))
+    }
+
+    fn authorize(&self, rb: RequestBuilder) -> RequestBuilder {
+        match &self.token {
+            Some(token) => rb.bearer_auth(token.trim()),
+            None => rb,
+        }
+    }
+
+    async fn send<T: DeserializeOwned>(&self, rb: RequestBuilder) -> Result<T, CliError> {
+        self.send_envelope(rb).await?.ok_or_else(|| CliError::Api {
+            message: "The server returned no data".to_string(),
+            error_data: None,
+        })
+    }
+
+    async fn send_envelope<T: DeserializeOwned>(
+        &self,
+        rb: RequestBuilder,
+    ) -> Result<Option<T>, CliError> {
+        let resp = self.authorize(rb).send().await?;
+        let status = resp.status();
+        if status == StatusCode::UNAUTHORIZED {
+            return Err(CliError::Unauthorized);
+        }
+
+        let envelope = resp
+            .json::<ApiResponseEnvelope<T>>()
+            .await
+            .map_err(|e| CliError::Api {
+                message: format!("Unexpected response ({status}): {e}"),
+                error_data: None,
+            })?;
+        if !envelope.success || !status.is_success() {
+            return Err(CliError::Api {
+                message: envelope
+                    .message
+                    .unwrap_or_else(|| format!("Request failed with status {status}")),
+                error_data: envelope.error_data,
+            });
+        }
+        Ok(envelope.data)
+    }
+
+    pub async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T, CliError> {
+        self.send(self.client.get(self.url(path))).await
+    }
+
+    pub async fn get_query<T: DeserializeOwned, Q: Serialize + ?Sized>(
+        &self,
+        path: &str,
+        query: &Q,
+    ) -> Result<T, CliError> {
+        self.send(self.client.get(self.url(path)).query(query))
+            .await
+    }
+
+    pub async fn post<T: DeserializeOwned, B: Serialize + ?Sized>(
+        &self,
+        path: &str,
+        body: &B,
+    ) -> Result<T, CliError> {
+        self.send(self.client.post(self.url(path)).json(body)).await
+    }
+
+    /// For routes that respond with `data: null` on success, such as merge
+    pub async fn post_empty<B: Serialize + ?Sized>(
+        &self,
+        path: &str,
+        body: &B,
+    ) -> Result<(), CliError> {
+        self.send_envelope::<serde_json::Value>(self.client.post(self.url(path)).json(body))
+            .await
+            .map(|_| ())
+    }
+
+    pub async fn put<T: DeserializeOwned, B: Serialize + ?Sized>(
+        &self,
+        path: &str,
+        body: &B,
+    ) -> Result<T, CliError> {
+        self.send(self.client.put(self.url(path)).json(body)).await
+    }
+
+    /// For the few routes that return their body without the `ApiResponse` envelope
+    pub async fn post_plain<T: DeserializeOwned, B: Serialize + ?Sized>(
+        &self,
+        path: &str,
+        body: &B,
+    ) -> Result<T, CliError> {
+        let resp = self
+            .authorize(self.client.post(self.url(path)).json(body))
+            .send()
+            .await?;
+        match resp.status() {
+            StatusCode::UNAUTHORIZED => Err(CliError::Unauthorized),
+            status if !status.is_success() => Err(CliError::Api {
+                message: format!("Request failed with status {status}"),
+                error_data: None,
+            }),
+            _ => Ok(resp.json().await?),
+        }
+    }
+
+    pub async fn delete(&self, path: &str) -> Result<(), CliError> {
+        self.send_envelope::<serde_json::Value>(self.client.delete(self.url(path)))
+            .await
+            .map(|_| ())
+    }
+
+    /// Streams the `LogMsg`s of a WebSocket route until the server sends `finished` or closes
+    pub async fn stream_ws(
+        &self,
+        path: &str,
+        mut on_msg: impl FnMut(LogMsg),
+    ) -> Result<(), CliError> {
+        let ws_url = self
+            .url(path)
+            .replacen("http://", "ws://", 1)
+            .replacen("https://", "wss://", 1);
+        let mut request = ws_url.into_client_request()?;
+        if let Some(token) = &self.token {
+            let value = HeaderValue::from_str(&format!("Bearer {}", token.trim()))
+                .map_err(|_| CliError::Invalid("Invalid API token".to_string()))?;
+            request.headers_mut().insert("Authorization", value);
+        }
+
+        let (mut socket, _) = match tokio_tungstenite::connect_async(request).await {
+            Ok(connection) => connection,
+            Err(tungstenite::Error::Http(resp)) if resp.status() == StatusCode::UNAUTHORIZED => {
+                return Err(CliError::Unauthorized);
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        while let Some(message) = socket.next().await {
+            let text = match message? {
+                Message::Text(text) => text,
+                Message::Close(_) => break,
+                _ => continue,
+            };
+            let value: serde_json::Value = match serde_json::from_str(&text) {
+                Ok(value) => value,
+                Err(_) => continue,
+            };
+            if value.get("finished").and_then(serde_json::Value::as_bool) == Some(true) {
+                break;
+            }
+            if let Ok(msg) = serde_json::from_value::<LogMsg>(value) {
+                on_msg(msg);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// `url` if given, otherwise the local server whose port `app_name` wrote to its port file
+async fn server_url(url: Option<String>, app_name: &str) -> Result<String, CliError> {
+    if let Some(url) = url {
+        return Ok(url);
+    }
+    let port = read_port_file(app_name)
+        .await
+        .map_err(|_| CliError::ServerNotFound)?;
+    Ok(format!("http://127.0.0.1:{port}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::header::AUTHORIZATION;
+    use uuid::Uuid;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn server_url_falls_back_to_the_port_file() {
+        let app_name = format!("vk-test-{}", Uuid::new_v4());
+        assert!(matches!(
+            server_url(None, &app_name).await,
+            Err(CliError::ServerNotFound)
+        ));
+
+        let dir = std::env::temp_dir().join(&app_name);
+        tokio::fs::create_dir_all(&dir).await.unwrap();
+        let port_file = dir.join(format!("{app_name}.port"));
+        tokio::fs::write(&port_file, "not a port").await.unwrap();
+        assert!(matches!(
+            server_url(None, &app_name).await,
+            Err(CliError::ServerNotFound)
+        ));
+
+        tokio::fs::write(&port_file, "4321\n").await.unwrap();
+        assert_eq!(
+            server_url(None, &app_name).await.unwrap(),
+            "http://127.0.0.1:4321"
+        );
+        // An explicit URL wins over the port file
+        assert_eq!(
+            server_url(Some("https://vk.example.com/".to_string()), &app_name)
+                .await
+                .unwrap(),
+            "https://vk.example.com/"
+        );
+
+        tokio::fs::remove_dir_all(&dir).await.unwrap();
+    }
+
+    #[test]
+    fn requests_send_the_trimmed_token() {
+        let client = VkClient::new("https://vk.example.com/", Some(" secret\n".to_string()));
+        assert_eq!(client.url("/tasks"), "https://vk.example.com/api/tasks");
+        let request = client
+            .authorize(client.client.get(client.url("tasks")))
+            .build()
+            .unwrap();
+        assert_eq!(request.headers()[AUTHORIZATION], "Bearer secret");
+
+        // A blank token, e.g. an empty VK_API_TOKEN, is not sent
+        let client = VkClient::new("https://vk.example.com", Some("  ".to_string()));
+        let request = client
+            .authorize(client.client.get(client.url("tasks")))
+            .build()
+            .unwrap();
+        assert!(request.headers().get(AUTHORIZATION).is_none());
+    }
+}
@@ -0,0 +1,850 @@
+//! `vk`: a command-line client for a running Vibe Kanban server, for scripting it from a shell
+//! or CI. The server is found through the port file it writes, unless `--url` is given.
+
+mod client;
+
+use std::{process::ExitCode, str::FromStr};
+
+use clap::{Args, Parser, Subcommand};
+use client::{CliError, VkClient};
+use db::models::{
+    approval::{Approval, ApprovalDecision},
+    execution_process::{ExecutionProcess, ExecutionProcessRunReason},
+    project::Project,
+    repo::Repo,
+    session::Session,
+    task::{CreateTask, Task, TaskStatus, TaskWithAttemptStatus, UpdateTask},
+    workspace::Workspace,
+    workspace_repo::RepoWithTargetBranch,
+};
+use executors::{
+    executors::BaseCodingAgent,
+    logs::{NormalizedEntry, NormalizedEntryType, utils::patch::NormalizedEntryFolder},
+    profile::ExecutorProfileId,
+};
+use serde::{Deserialize, Serialize};
+use server::routes::{
+    sessions::CreateFollowUpAttempt,
+    task_attempts::{
+        CreateTaskAttemptBody, MergeTaskAttemptRequest, WorkspaceRepoInput,
+        pr::CreateGitHubPrRequest,
+    },
+};
+use utils::{
+    approvals::{ApprovalResponse, ApprovalStatus},
+    diff::{Diff, create_unified_diff},
+};
+use uuid::Uuid;
+
+#[derive(Parser, Debug)]
+#[command(
+    name = "vk",
+    version,
+    about = "Command-line client for a running Vibe Kanban server"
+)]
+struct Cli {
+    /// Server URL, e.g. http://127.0.0.1:3000; found through the port file if unset
+    #[arg(long, global = true, env = "VK_URL")]
+    url: Option<String>,
+
+    /// API token, for servers running in headless mode
+    #[arg(long, global = true, env = "VK_API_TOKEN", hide_env_values = true)]
+    token: Option<String>,
+
+    /// Print API responses as JSON instead of text
+    #[arg(long, global = true)]
+    json: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// List projects
+    Projects,
+    /// List the repositories of a project
+    Repos { project_id: Uuid },
+    /// Create, list and update tasks
+    #[command(subcommand)]
+    Tasks(TaskCommand),
+    /// Start a workspace for a task with a coding agent
+    Start(StartArgs),
+    /// List the workspaces of a task
+    Workspaces { task_id: Uuid },
+    /// Print the normalized logs of a workspace's latest coding agent run, following it while
+    /// it runs
+    Logs {
+        workspace_id: Uuid,
+        /// Show this execution process instead of the latest coding agent run
+        #[arg(long)]
+        process: Option<Uuid>,
+    },
+    /// Send a follow-up prompt to a workspace's coding agent
+    FollowUp {
+        workspace_id: Uuid,
+        prompt: String,
+        /// Executor variant to use for this turn
+        #[arg(long)]
+        variant: Option<String>,
+    },
+    /// List and answer tool approvals
+    #[command(subcommand)]
+    Approvals(ApprovalCommand),
+    /// Show a workspace's changes against its target branches
+    Diff {
+        workspace_id: Uuid,
+        /// Only list changed files with line counts
+        #[arg(long)]
+        stat: bool,
+    },
+    /// Merge a workspace into its target branch
+    Merge {
+        workspace_id: Uuid,
+        /// Repository to merge; required when the workspace has several
+        #[arg(long)]
+        repo: Option<Uuid>,
+    },
+    /// Push a workspace's branch and open a pull request
+    Pr(PrArgs),
+}
+
+#[derive(Subcommand, Debug)]
+enum TaskCommand {
+    /// List the tasks of a project
+    List {
+        project_id: Uuid,
+        /// Only tasks with this status (todo, inprogress, inreview, done, cancelled)
+        #[arg(long)]
+        status: Option<String>,
+    },
+    /// Show a task
+    Show { task_id: Uuid },
+    /// Create a task
+    Create {
+        project_id: Uuid,
+        title: String,
+        #[arg(long)]
+        description: Option<String>,
+    },
+    /// Update a task's title, description or status
+    Update {
+        task_id: Uuid,
+        #[arg(long)]
+        title: Option<String>,
+        #[arg(long)]
+        description: Option<String>,
+        #[arg(long)]
+        status: Option<String>,
+    },
+    /// Delete a task
+    Delete { task_id: Uuid },
+}
+
+#[derive(Args, Debug)]
+struct StartArgs {
+    task_id: Uuid,
+    /// Coding agent to run, e.g. CLAUDE_CODE, CODEX, GEMINI
+    #[arg(long)]
+    executor: String,
+    /// Executor variant, e.g. PLAN
+    #[arg(long)]
+    variant: Option<String>,
+    /// Repository and base branch as REPO_ID[:BRANCH]; defaults to every repository of the
+    /// project on its current branch
+    #[arg(long = "repo")]
+    repos: Vec<String>,
+}
+
+#[derive(Subcommand, Debug)]
+enum ApprovalCommand {
+    /// List the approvals of a task
+    List { task_id: Uuid },
+    /// Approve a pending tool call
+    Approve {
+        task_id: Uuid,
+        /// Approval to answer; required when several are pending
+        #[arg(long)]
+        id: Option<String>,
+    },
+    /// Deny a pending tool call
+    Deny {
+        task_id: Uuid,
+        /// Approval to answer; required when several are pending
+        #[arg(long)]
+        id: Option<String>,
+        /// Reason passed back to the coding agent
+        #[arg(long)]
+        reason: Option<String>,
+    },
+}
+
+#[derive(Args, Debug)]
+struct PrArgs {
+    workspace_id: Uuid,
+    #[arg(long)]
+    title: String,
+    #[arg(long)]
+    body: Option<String>,
+    /// Repository to open the pull request for; required when the workspace has several
+    #[arg(long)]
+    repo: Option<Uuid>,
+    /// Base branch of the pull request; defaults to the workspace's target branch
+    #[arg(long)]
+    target_branch: Option<String>,
+    #[arg(long)]
+    draft: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct TaskAttemptQuery {
+    task_id: Uuid,
+}
+
+#[derive(Debug, Serialize)]
+struct DiffQuery {
+    stats_only: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct SessionQuery {
+    workspace_id: Uuid,
+}
+
+#[derive(Debug, Serialize)]
+struct ExecutionProcessQuery {
+    session_id: Uuid,
+}
+
+/// The fields of a `GitBranch` needed to pick a base branch
+#[derive(Debug, Deserialize)]
+struct Branch {
+    name: String,
+    is_current: bool,
+    is_remote: bool,
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let result = match VkClient::discover(cli.url.clone(), cli.token.clone()).await {
+        Ok(client) => run(&client, cli.json, cli.command).await,
+        Err(e) => Err(e),
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(CliError::Api {
+            message,
+            error_data,
+        }) => {
+            eprintln!("error: {message}");
+            if let Some(error_data) = error_data {
+                eprintln!("{}", pretty(&error_data));
+            }
+            ExitCode::FAILURE
+        }
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+async fn run(client: &VkClient, json: bool, command: Command) -> Result<(), CliError> {
+    match command {
+        Command::Projects => {
+            let projects: Vec<Project> = client.get("projects").await?;
+            print_or_json(json, &projects, |projects| {
+                for project in projects {
+                    println!("{}  {}", project.id, project.name);
+                }
+            });
+        }
+        Command::Repos { project_id } => {
+            let repos: Vec<Repo> = client
+                .get(&format!("projects/{project_id}/repositories"))
+                .await?;
+            print_or_json(json, &repos, |repos| {
+                for repo in repos {
+                    println!("{}  {}  {}", repo.id, repo.name, repo.path.display());
+                }
+            });
+        }
+        Command::Tasks(command) => run_task_command(client, json, command).await?,
+        Command::Start(args) => {
+            let workspace = start_workspace(client, args).await?;
+            print_or_json(json, &workspace, |workspace| {
+                println!(
+                    "Started workspace {} on branch {}",
+                    workspace.id, workspace.branch
+                );
+            });
+        }
+        Command::Workspaces { task_id } => {
+            let workspaces: Vec<Workspace> = client
+                .get_query("task-attempts", &TaskAttemptQuery { task_id })
+                .await?;
+            print_or_json(json, &workspaces, |workspaces| {
+                for workspace in workspaces {
+                    println!(
+                        "{}  {}  {}",
+                        workspace.id,
+                        workspace.branch,
+                        workspace.created_at.format("%Y-%m-%d %H:%M")
+                    );
+                }
+            });
+        }
+        Command::Logs {
+            workspace_id,
+            process,
+        } => {
+            let process_id = match process {
+                Some(process_id) => process_id,
+                None => latest_coding_agent_process(client, workspace_id).await?.id,
+            };
+            tail_logs(client, json, process_id).await?;
+        }
+        Command::FollowUp {
+            workspace_id,
+            prompt,
+            variant,
+        } => {
+            if prompt.trim().is_empty() {
+                return Err(CliError::Invalid(
+                    "The prompt must not be empty".to_string(),
+                ));
+            }
+            let session = latest_session(client, workspace_id).await?;
+            let payload = CreateFollowUpAttempt {
+                prompt,
+                variant: variant.filter(|variant| !variant.trim().is_empty()),
+                retry_process_id: None,
+                force_when_dirty: None,
+                perform_git_reset: None,
+            };
+            let process: ExecutionProcess = client
+                .post(&format!("sessions/{}/follow-up", session.id), &payload)
+                .await?;
+            print_or_json(json, &process, |process| {
+                println!("Started execution process {}", process.id);
+            });
+        }
+        Command::Approvals(command) => run_approval_command(client, json, command).await?,
+        Command::Diff { workspace_id, stat } => {
+            let diffs: Vec<Diff> = client
+                .get_query(
+                    &format!("task-attempts/{workspace_id}/diff"),
+                    &DiffQuery { stats_only: stat },
+                )
+                .await?;
+            print_or_json(json, &diffs, |diffs| print_diffs(diffs, stat));
+        }
+        Command::Merge { workspace_id, repo } => {
+            let repo_id = workspace_repo_id(client, workspace_id, repo).await?;
+            client
+                .post_empty(
+                    &format!("task-attempts/{workspace_id}/merge"),
+                    &MergeTaskAttemptRequest { repo_id },
+                )
+                .await?;
+            print_or_json(json, &serde_json::json!({ "merged": true }), |_| {
+                println!("Merged workspace {workspace_id}");
+            });
+        }
+        Command::Pr(args) => {
+            let repo_id = workspace_repo_id(client, args.workspace_id, args.repo).await?;
+            let payload = CreateGitHubPrRequest {
+                title: args.title,
+                body: args.body,
+                target_branch: args.target_branch,
+                draft: Some(args.draft),
+                repo_id,
+                auto_generate_description: false,
+            };
+            let pr_url: String = client
+                .post(&format!("task-attempts/{}/pr", args.workspace_id), &payload)
+                .await?;
+            print_or_json(json, &pr_url, |pr_url| println!("{pr_url}"));
+        }
+    }
+    Ok(())
+}
+
+async fn run_task_command(
+    client: &VkClient,
+    json: bool,
+    command: TaskCommand,
+) -> Result<(), CliError> {
+    match command {
+        TaskCommand::List { project_id, status } => {
+            let status = status.as_deref().map(parse_status).transpose()?;
+            let tasks: Vec<TaskWithAttemptStatus> = client
+                .get(&format!("tasks?project_id={project_id}"))
+                .await?;
+            let tasks: Vec<_> = tasks
+                .into_iter()
+                .filter(|task| status.as_ref().is_none_or(|status| &task.status == status))
+                .collect();
+            print_or_json(json, &tasks, |tasks| {
+                for task in tasks {
+                    println!("{}  {:<10}  {}", task.id, task.status, task.title);
+                }
+            });
+        }
+        TaskCommand::Show { task_id } => {
+            let task: Task = client.get(&format!("tasks/{task_id}")).await?;
+            print_or_json(json, &task, print_task);
+        }
+        TaskCommand::Create {
+            project_id,
+            title,
+            description,
+        } => {
+            let payload = CreateTask::from_title_description(project_id, title, description);
+            let task: Task = client.post("tasks", &payload).await?;
+            print_or_json(json, &task, |task| println!("Created task {}", task.id));
+        }
+        TaskCommand::Update {
+            task_id,
+            title,
+            description,
+            status,
+        } => {
+            let payload = UpdateTask {
+                title,
+                description,
+                status: status.as_deref().map(parse_status).transpose()?,
+                parent_workspace_id: None,
+                image_ids: None,
+            };
+            let task: Task = client.put(&format!("tasks/{task_id}"), &payload).await?;
+            print_or_json(json, &task, print_task);
+        }
+        TaskCommand::Delete { task_id } => {
+            client.delete(&format!("tasks/{task_id}")).await?;
+            print_or_json(json, &serde_json::json!({ "deleted": task_id }), |_| {
+                println!("Deleted task {task_id}");
+            });
+        }
+    }
+    Ok(())
+}
+
+async fn run_approval_command(
+    client: &VkClient,
+    json: bool,
+    command: ApprovalCommand,
+) -> Result<(), CliError> {
+    let (task_id, id, status) = match command {
+        ApprovalCommand::List { task_id } => {
+            let approvals: Vec<Approval> =
+                client.get(&format!("tasks/{task_id}/approvals")).await?;
+            print_or_json(json, &approvals, |approvals| {
+                for approval in approvals {
+                    println!(
+                        "{}  {:<9}  {}  {}",
+                        approval.id,
+                        decision_label(approval.status),
+                        approval.tool_name,
+                        approval.tool_input.0
+                    );
+                }
+            });
+            return Ok(());
+        }
+        ApprovalCommand::Approve { task_id, id } => (task_id, id, ApprovalStatus::Approved),
+        ApprovalCommand::Deny {
+            task_id,
+            id,
+            reason,
+        } => (task_id, id, ApprovalStatus::Denied { reason }),
+    };
+
+    let approvals: Vec<Approval> = client.get(&format!("tasks/{task_id}/approvals")).await?;
+    let pending: Vec<_> = approvals
+        .into_iter()
+        .filter(|approval| approval.status == ApprovalDecision::Pending)
+        .filter(|approval| id.as_ref().is_none_or(|id| &approval.id == id))
+        .collect();
+    let approval = match pending.as_slice() {
+        [approval] => approval,
+        [] => {
+            return Err(CliError::Invalid(
+                "No matching pending approval".to_string(),
+            ));
+        }
+        _ => {
+            return Err(CliError::Invalid(
+                "Several approvals are pending; choose one with --id".to_string(),
+            ));
+        }
+    };
+
+    let response = ApprovalResponse {
+        execution_process_id: approval.execution_process_id,
+        status,
+    };
+    let status: ApprovalStatus = client
+        .post_plain(&format!("approvals/{}/respond", approval.id), &response)
+        .await?;
+    print_or_json(json, &status, |status| {
+        println!("{}: {}", approval.tool_name, pretty(status));
+    });
+    Ok(())
+}
+
+async fn start_workspace(client: &VkClient, args: StartArgs) -> Result<Workspace, CliError> {
+    let executor = args.executor.trim().replace('-', "_").to_ascii_uppercase();
+    let executor = BaseCodingAgent::from_str(&executor)
+        .map_err(|_| CliError::Invalid(format!("Unknown executor '{}'", args.executor)))?;
+    let executor_profile_id = ExecutorProfileId {
+        executor,
+        variant: args.variant.filter(|variant| !variant.trim().is_empty()),
+    };
+
+    let mut repos = Vec::new();
+    if args.repos.is_empty() {
+        let task: Task = client.get(&format!("tasks/{}", args.task_id)).await?;
+        let project_repos: Vec<Repo> = client
+            .get(&format!("projects/{}/repositories", task.project_id))
+            .await?;
+        for repo in project_repos {
+            repos.push(WorkspaceRepoInput {
+                repo_id: repo.id,
+                target_branch: current_branch(client, repo.id).await?,
+            });
+        }
+    } else {
+        for spec in &args.repos {
+            let (repo_id, branch) = parse_repo_spec(spec)?;
+            let target_branch = match branch {
+                Some(branch) => branch,
+                None => current_branch(client, repo_id).await?,
+            };
+            repos.push(WorkspaceRepoInput {
+                repo_id,
+                target_branch,
+            });
+        }
+    }
+
+    let payload = CreateTaskAttemptBody {
+        task_id: args.task_id,
+        executor_profile_id,
+        repos,
+    };
+    client.post("task-attempts", &payload).await
+}
+
+/// A `--repo` value: `REPO_ID` or `REPO_ID:BRANCH`
+fn parse_repo_spec(spec: &str) -> Result<(Uuid, Option<String>), CliError> {
+    let (repo_id, branch) = match spec.split_once(':') {
+        Some((repo_id, branch)) => (repo_id, Some(branch.to_string())),
+        None => (spec, None),
+    };
+    let repo_id = Uuid::parse_str(repo_id)
+        .map_err(|_| CliError::Invalid(format!("Invalid repository id in '{spec}'")))?;
+    Ok((repo_id, branch))
+}
+
+async fn current_branch(client: &VkClient, repo_id: Uuid) -> Result<String, CliError> {
+    let branches: Vec<Branch> = client.get(&format!("repos/{repo_id}/branches")).await?;
+    branches
+        .into_iter()
+        .find(|branch| branch.is_current && !branch.is_remote)
+        .map(|branch| branch.name)
+        .ok_or_else(|| {
+            CliError::Invalid(format!(
+                "Repository {repo_id} has no checked out branch; pass --repo {repo_id}:BRANCH"
+            ))
+        })
+}
+
+async fn latest_session(client: &VkClient, workspace_id: Uuid) -> Result<Session, CliError> {
+    let sessions: Vec<Session> = client
+        .get_query("sessions", &SessionQuery { workspace_id })
+        .await?;
+    // Sessions are returned newest first
+    sessions
+        .into_iter()
+        .next()
+        .ok_or_else(|| CliError::Invalid(format!("Workspace {workspace_id} has no sessions yet")))
+}
+
+async fn latest_coding_agent_process(
+    client: &VkClient,
+    workspace_id: Uuid,
+) -> Result<ExecutionProcess, CliError> {
+    let session = latest_session(client, workspace_id).await?;
+    let processes: Vec<ExecutionProcess> = client
+        .get_query(
+            "execution-processes",
+            &ExecutionProcessQuery {
+                session_id: session.id,
+            },
+        )
+        .await?;
+    // Oldest first
+    processes
+        .into_iter()
+        .rev()
+        .find(|process| process.run_reason == ExecutionProcessRunReason::CodingAgent)
+        .ok_or_else(|| {
+            CliError::Invalid(format!(
+                "Workspace {workspace_id} has no coding agent runs yet"
+            ))
+        })
+}
+
+/// The repository to merge or open a PR for: `repo`, or the workspace's only repository
+async fn workspace_repo_id(
+    client: &VkClient,
+    workspace_id: Uuid,
+    repo: Option<Uuid>,
+) -> Result<Uuid, CliError> {
+    if let Some(repo_id) = repo {
+        return Ok(repo_id);
+    }
+    let repos: Vec<RepoWithTargetBranch> = client
+        .get(&format!("task-attempts/{workspace_id}/repos"))
+        .await?;
+    match repos.as_slice() {
+        [repo] => Ok(repo.repo.id),
+        _ => {
+            let names: Vec<_> = repos
+                .iter()
+                .map(|repo| format!("{} ({})", repo.repo.name, repo.repo.id))
+                .collect();
+            Err(CliError::Invalid(format!(
+                "Choose a repository with --repo: {}",
+                names.join(", ")
+            )))
+        }
+    }
+}
+
+/// Prints entries once they can no longer change (a later entry was added), then the rest when
+/// the process finishes
+async fn tail_logs(client: &VkClient, json: bool, process_id: Uuid) -> Result<(), CliError> {
+    let mut folder = NormalizedEntryFolder::default();
+    let mut printed = 0;
+    let print_up_to = |folder: &NormalizedEntryFolder, printed: &mut usize, end: usize| {
+        for index in *printed..end {
+            if let Some(entry) = folder.get(index) {
+                print_entry(json, entry);
+            }
+        }
+        *printed = (*printed).max(end);
+    };
+
+    client
+        .stream_ws(
+            &format!("execution-processes/{process_id}/normalized-logs/ws"),
+            |msg| {
+                folder.apply(&msg);
+                let settled = folder.len().saturating_sub(1);
+                print_up_to(&folder, &mut printed, settled);
+            },
+        )
+        .await?;
+    print_up_to(&folder, &mut printed, folder.len());
+    Ok(())
+}
+
+fn print_entry(json: bool, entry: &NormalizedEntry) {
+    if json {
+        if let Ok(line) = serde_json::to_string(entry) {
+            println!("{line}");
+        }
+        return;
+    }
+    let label = match &entry.entry_type {
+        NormalizedEntryType::UserMessage => "user".to_string(),
+        NormalizedEntryType::UserFeedback { .. } => "feedback".to_string(),
+        NormalizedEntryType::AssistantMessage => "assistant".to_string(),
+        NormalizedEntryType::ToolUse { tool_name, .. } => format!("tool: {tool_name}"),
+        NormalizedEntryType::SystemMessage => "system".to_string(),
+        NormalizedEntryType::ErrorMessage { .. } => "error".to_string(),
+        NormalizedEntryType::Thinking => "thinking".to_string(),
+        NormalizedEntryType::Loading | NormalizedEntryType::NextAction { .. } => return,
+    };
+    println!("[{label}] {}", entry.content.trim_end());
+}
+
+fn print_diffs(diffs: &[Diff], stat: bool) {
+    for diff in diffs {
+        let path = diff
+            .new_path
+            .as_deref()
+            .or(diff.old_path.as_deref())
+            .unwrap_or_default();
+        if stat || diff.content_omitted {
+            println!(
+                "{path}  +{} -{}",
+                diff.additions.unwrap_or(0),
+                diff.deletions.unwrap_or(0)
+            );
+            continue;
+        }
+        print!(
+            "{}",
+            create_unified_diff(
+                path,
+                diff.old_content.as_deref().unwrap_or_default(),
+                diff.new_content.as_deref().unwrap_or_default(),
+            )
+        );
+    }
+}
+
+fn print_task(task: &Task) {
+    println!("{}  {}", task.id, task.title);
+    println!("status: {}", task.status);
+    if let Some(description) = &task.description {
+        println!("\n{description}");
+    }
+}
+
+fn parse_status(status: &str) -> Result<TaskStatus, CliError> {
+    TaskStatus::from_str(&status.trim().to_ascii_lowercase()).map_err(|_| {
+        CliError::Invalid(format!(
+            "Invalid status '{status}'. Valid values: todo, inprogress, inreview, done, cancelled"
+        ))
+    })
+}
+
+fn decision_label(decision: ApprovalDecision) -> &'static str {
+    match decision {
+        ApprovalDecision::Pending => "pending",
+        ApprovalDecision::Approved => "approved",
+        ApprovalDecision::Denied => "denied",
+        ApprovalDecision::TimedOut => "timed_out",
+    }
+}
+
+fn print_or_json<T: Serialize>(json: bool, value: &T, print: impl FnOnce(&T)) {
+    if json {
+        println!("{}", pretty(value));
+    } else {
+        print(value);
+    }
+}
+
+fn pretty<T: Serialize + ?Sized>(value: &T) -> String {
+    serde_json::to_string_pretty(value).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::CommandFactory;
+
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Cli, clap::Error> {
+        Cli::try_parse_from(std::iter::once("vk").chain(args.iter().copied()))
+    }
+
+    #[test]
+    fn cli_definition_is_valid() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn global_flags_are_accepted_after_the_subcommand() {
+        let task_id = Uuid::new_v4();
+        let cli = parse(&[
+            "tasks",
+            "update",
+            &task_id.to_string(),
+            "--status",
+            "done",
+            "--json",
+            "--url",
+            "http://127.0.0.1:4321",
+            "--token",
+            "secret",
+        ])
+        .unwrap();
+        assert!(cli.json);
+        assert_eq!(cli.url.as_deref(), Some("http://127.0.0.1:4321"));
+        assert_eq!(cli.token.as_deref(), Some("secret"));
+        match cli.command {
+            Command::Tasks(TaskCommand::Update {
+                task_id: id,
+                title,
+                status,
+                ..
+            }) => {
+                assert_eq!(id, task_id);
+                assert_eq!(title, None);
+                assert_eq!(status.as_deref(), Some("done"));
+            }
+            command => panic!("unexpected command {command:?}"),
+        }
+    }
+
+    #[test]
+    fn start_collects_every_repo() {
+        let task_id = Uuid::new_v4();
+        let cli = parse(&[
+            "start",
+            &task_id.to_string(),
+            "--executor",
+            "claude-code",
+            "--repo",
+            "a",
+            "--repo",
+            "b:main",
+        ])
+        .unwrap();
+        match cli.command {
+            Command::Start(args) => {
+                assert_eq!(args.task_id, task_id);
+                assert_eq!(args.executor, "claude-code");
+                assert_eq!(args.variant, None);
+                assert_eq!(args.repos, ["a", "b:main"]);
+            }
+            command => panic!("unexpected command {command:?}"),
+        }
+    }
+
+    #[test]
+    fn invalid_arguments_are_rejected() {
+        let workspace_id = Uuid::new_v4().to_string();
+        // --title is required
+        assert!(parse(&["pr", &workspace_id]).is_err());
+        assert!(parse(&["follow-up", "not-a-uuid", "continue"]).is_err());
+        assert!(parse(&["start", &workspace_id]).is_err());
+        assert!(parse(&["approvals", "approve"]).is_err());
+        assert!(parse(&["unknown"]).is_err());
+    }
+
+    #[test]
+    fn repo_specs_take_an_optional_branch() {
+        let repo_id = Uuid::new_v4();
+        assert_eq!(
+            parse_repo_spec(&repo_id.to_string()).unwrap(),
+            (repo_id, None)
+        );
+        assert_eq!(
+            parse_repo_spec(&format!("{repo_id}:feature/login")).unwrap(),
+            (repo_id, Some("feature/login".to_string()))
+        );
+        assert!(matches!(
+            parse_repo_spec("frontend:main"),
+            Err(CliError::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn statuses_are_parsed_case_insensitively() {
+        assert_eq!(parse_status("Done").unwrap(), TaskStatus::Done);
+        assert_eq!(parse_status(" inreview ").unwrap(), TaskStatus::InReview);
+        assert!(matches!(
+            parse_status("finished"),
+            Err(CliError::Invalid(_))
+        ));
+    }
+}
@@ -5,9 +5,11 @@ use axum::{
     response::{IntoResponse, Response},
 };
 use db::models::{
+    approval_rule::ApprovalRuleError, attempt_group::AttemptGroupError,
     execution_process::ExecutionProcessError, project::ProjectError,
-    project_repo::ProjectRepoError, repo::RepoError, scratch::ScratchError, session::SessionError,
-    workspace::WorkspaceError,
+    project_repo::ProjectRepoError, queued_message::QueuedMessageError, repo::RepoError,
+    scratch::ScratchError, session::SessionError, task_dependency::TaskDependencyError,
+    task_schedule::TaskScheduleError, webhook::WebhookError, workspace::WorkspaceError,
 };
 use deployment::{DeploymentError, RemoteClientNotConfigured};
 use executors::executors::ExecutorError;
@@ -15,10 +17,12 @@ use git2::Error as Git2Error;
 use services::services::{
     config::{ConfigError, EditorOpenError},
     container::ContainerError,
+    forge::ForgeError,
     git::GitServiceError,
     github::GitHubServiceError,
     image::ImageError,
     project::ProjectServiceError,
+    project_bundle::ProjectBundleError,
     remote_client::RemoteClientError,
     repo::RepoError as RepoServiceError,
     share::ShareError,
@@ -47,6 +51,8 @@ pub enum ApiError {
     #[error(transparent)]
     GitHubService(#[from] GitHubServiceError),
     #[error(transparent)]
+    Forge(#[from] ForgeError),
+    #[error(transparent)]
     Deployment(#[from] DeploymentError),
     #[error(transparent)]
     Container(#[from] ContainerError),
@@ -121,6 +127,10 @@ impl IntoResponse for ApiError {
                 _ => (StatusCode::INTERNAL_SERVER_ERROR, "GitServiceError"),
             },
             ApiError::GitHubService(_) => (StatusCode::INTERNAL_SERVER_ERROR, "GitHubServiceError"),
+            ApiError::Forge(forge_err) => match forge_err {
+                ForgeError::UnsupportedRemote(_) => (StatusCode::BAD_REQUEST, "ForgeError"),
+                _ => (StatusCode::INTERNAL_SERVER_ERROR, "ForgeError"),
+            },
             ApiError::Deployment(_) => (StatusCode::INTERNAL_SERVER_ERROR, "DeploymentError"),
             ApiError::Container(_) => (StatusCode::INTERNAL_SERVER_ERROR, "ContainerError"),
             ApiError::Executor(_) => (StatusCode::INTERNAL_SERVER_ERROR, "ExecutorError"),
@@ -348,6 +358,20 @@ impl From<ProjectServiceError> for ApiError {
     }
 }
 
+impl From<ProjectBundleError> for ApiError {
+    fn from(err: ProjectBundleError) -> Self {
+        match err {
+            ProjectBundleError::Database(db_err) => ApiError::Database(db_err),
+            ProjectBundleError::Io(io_err) => ApiError::Io(io_err),
+            ProjectBundleError::Image(image_err) => ApiError::Image(image_err),
+            ProjectBundleError::ProjectService(service_err) => ApiError::from(service_err),
+            err @ (ProjectBundleError::InvalidBundle(_)
+            | ProjectBundleError::UnsupportedVersion(_)
+            | ProjectBundleError::MissingRepoPath(_)) => ApiError::BadRequest(err.to_string()),
+        }
+    }
+}
+
 impl From<RepoServiceError> for ApiError {
     fn from(err: RepoServiceError) -> Self {
         match err {
@@ -389,3 +413,75 @@ impl From<ProjectRepoError> for ApiError {
         }
     }
 }
+
+impl From<TaskDependencyError> for ApiError {
+    fn from(err: TaskDependencyError) -> Self {
+        match err {
+            TaskDependencyError::Database(db_err) => ApiError::Database(db_err),
+            TaskDependencyError::TaskNotFound => ApiError::BadRequest("Task not found".to_string()),
+            TaskDependencyError::SelfDependency => {
+                ApiError::BadRequest("A task cannot depend on itself".to_string())
+            }
+            TaskDependencyError::ProjectMismatch => ApiError::BadRequest(
+                "Dependencies can only be created between tasks in the same project".to_string(),
+            ),
+            TaskDependencyError::Cycle => {
+                ApiError::Conflict("Adding this dependency would create a cycle".to_string())
+            }
+        }
+    }
+}
+
+impl From<QueuedMessageError> for ApiError {
+    fn from(err: QueuedMessageError) -> Self {
+        match err {
+            QueuedMessageError::Database(db_err) => ApiError::Database(db_err),
+            QueuedMessageError::NotFound => {
+                ApiError::BadRequest("Queued message not found".to_string())
+            }
+            QueuedMessageError::InvalidOrder => ApiError::BadRequest(
+                "The new order must contain every queued message of the session exactly once"
+                    .to_string(),
+            ),
+        }
+    }
+}
+
+impl From<TaskScheduleError> for ApiError {
+    fn from(err: TaskScheduleError) -> Self {
+        match err {
+            TaskScheduleError::Database(db_err) => ApiError::Database(db_err),
+            TaskScheduleError::InvalidCron(_)
+            | TaskScheduleError::NeverFires(_)
+            | TaskScheduleError::NoRepos => ApiError::BadRequest(err.to_string()),
+        }
+    }
+}
+
+impl From<AttemptGroupError> for ApiError {
+    fn from(err: AttemptGroupError) -> Self {
+        match err {
+            AttemptGroupError::Database(db_err) => ApiError::Database(db_err),
+            AttemptGroupError::NotAMember(_) => ApiError::BadRequest(err.to_string()),
+            AttemptGroupError::WinnerAlreadySelected => ApiError::Conflict(err.to_string()),
+        }
+    }
+}
+
+impl From<WebhookError> for ApiError {
+    fn from(err: WebhookError) -> Self {
+        match err {
+            WebhookError::Database(db_err) => ApiError::Database(db_err),
+            WebhookError::InvalidUrl => ApiError::BadRequest(err.to_string()),
+        }
+    }
+}
+
+impl From<ApprovalRuleError> for ApiError {
+    fn from(err: ApprovalRuleError) -> Self {
+        match err {
+            ApprovalRuleError::Database(db_err) => ApiError::Database(db_err),
+            ApprovalRuleError::EmptyToolPattern => ApiError::BadRequest(err.to_string()),
+        }
+    }
+}
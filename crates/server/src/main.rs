@@ -1,12 +1,13 @@
 use anyhow::{self, Error as AnyhowError};
 use deployment::{Deployment, DeploymentError};
-use server::{DeploymentImpl, routes};
+use server::{DeploymentImpl, middleware::auth::ApiAuth, routes};
 use services::services::container::ContainerService;
 use sqlx::Error as SqlxError;
 use strip_ansi_escapes::strip;
 use thiserror::Error;
 use tracing_subscriber::{EnvFilter, prelude::*};
 use utils::{
+    HEADLESS_ENV,
     assets::asset_dir,
     browser::open_browser,
     port_file::write_port_file,
@@ -52,6 +53,12 @@ async fn main() -> Result<(), VibeKanbanError> {
         .cleanup_orphan_executions()
         .await
         .map_err(DeploymentError::from)?;
+    if let Err(e) = deployment.approvals().expire_stale().await {
+        tracing::error!("Failed to expire stale approvals: {}", e);
+    }
+    if let Err(e) = deployment.container().drain_idle_queues().await {
+        tracing::error!("Failed to drain queued follow-up messages: {}", e);
+    }
     deployment
         .container()
         .backfill_before_head_commits()
@@ -63,6 +70,9 @@ async fn main() -> Result<(), VibeKanbanError> {
         .await
         .map_err(DeploymentError::from)?;
     deployment.spawn_pr_monitor_service().await;
+    deployment.spawn_task_scheduler_service().await;
+    deployment.spawn_log_compactor_service().await;
+    deployment.spawn_webhook_service().await;
     deployment
         .track_if_analytics_allowed("session_start", serde_json::json!({}))
         .await;
@@ -88,8 +98,6 @@ async fn main() -> Result<(), VibeKanbanError> {
         }
     });
 
-    let app_router = routes::router(deployment.clone());
-
     let port = std::env::var("BACKEND_PORT")
         .or_else(|_| std::env::var("PORT"))
         .ok()
@@ -106,7 +114,23 @@ async fn main() -> Result<(), VibeKanbanError> {
 
     let host = std::env::var("HOST").unwrap_or_else(|_| "127.0.0.1".to_string());
     let listener = tokio::net::TcpListener::bind(format!("{host}:{port}")).await?;
-    let actual_port = listener.local_addr()?.port(); // get → 53427 (example)
+    let local_addr = listener.local_addr()?;
+    let actual_port = local_addr.port(); // get → 53427 (example)
+    let backend_url = if local_addr.ip().is_unspecified() {
+        format!("http://127.0.0.1:{actual_port}")
+    } else {
+        format!("http://{local_addr}")
+    };
+    let auth = ApiAuth::from_env();
+    if auth.is_some() {
+        tracing::info!("Headless mode: API requests require a token");
+    } else if !local_addr.ip().is_loopback() {
+        tracing::warn!(
+            "Listening on {local_addr} without authentication; set {HEADLESS_ENV}=1 to require API tokens"
+        );
+    }
+    let headless = auth.is_some();
+    let app_router = routes::router(deployment.clone(), backend_url, auth);
 
     // Write port file for discovery if prod, warn on fail
     if let Err(e) = write_port_file(actual_port).await {
@@ -115,7 +139,7 @@ async fn main() -> Result<(), VibeKanbanError> {
 
     tracing::info!("Server running on http://{host}:{actual_port}");
 
-    if !cfg!(debug_assertions) {
+    if !cfg!(debug_assertions) && !headless {
         tracing::info!("Opening browser...");
         tokio::spawn(async move {
             if let Err(e) = open_browser(&format!("http://127.0.0.1:{actual_port}")).await {
@@ -0,0 +1,207 @@
+use axum::{
+    Router,
+    extract::{Request, State},
+    http::{StatusCode, header},
+    middleware::{Next, from_fn_with_state},
+    response::Response,
+};
+use db::models::api_token::{ApiToken, ApiTokenScope, hash_token};
+use deployment::Deployment;
+use rmcp::transport::streamable_http_server::{
+    StreamableHttpService, session::local::LocalSessionManager,
+};
+use sqlx::SqlitePool;
+
+use crate::{DeploymentImpl, mcp::task_server::TaskServer, middleware::auth::tokens_match};
+
+/// Used instead of the token created with `POST /api/mcp-token`, e.g. for headless deployments
+pub const MCP_TOKEN_ENV: &str = "VK_MCP_TOKEN";
+
+/// Serves the task tools over MCP Streamable HTTP at `/mcp`. Each session gets
+/// its own `TaskServer`, which proxies every call through the REST API at
+/// `backend_url` just like the stdio server does, authenticating with `api_token` when the
+/// API requires one.
+pub fn router(
+    deployment: &DeploymentImpl,
+    backend_url: String,
+    api_token: Option<String>,
+) -> Router<DeploymentImpl> {
+    let service = StreamableHttpService::new(
+        move || {
+            Ok(TaskServer::new(&backend_url)
+                .with_api_token(api_token.as_deref())
+                .without_context())
+        },
+        LocalSessionManager::default().into(),
+        Default::default(),
+    );
+
+    Router::new()
+        .nest_service("/mcp", service)
+        .layer(from_fn_with_state(deployment.clone(), require_mcp_token))
+}
+
+async fn require_mcp_token(
+    State(deployment): State<DeploymentImpl>,
+    request: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let provided = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::trim);
+    let env_token = std::env::var(MCP_TOKEN_ENV).ok();
+
+    authorize(&deployment.db().pool, env_token.as_deref(), provided).await?;
+    Ok(next.run(request).await)
+}
+
+/// Checks `provided` against `env_token` when that is set, or else against the active MCP
+/// tokens. Without either, the endpoint is not enabled and responds as if it did not exist.
+async fn authorize(
+    pool: &SqlitePool,
+    env_token: Option<&str>,
+    provided: Option<&str>,
+) -> Result<(), StatusCode> {
+    let authorized = match env_token.map(str::trim) {
+        Some(expected) if !expected.is_empty() => {
+            provided.is_some_and(|token| tokens_match(token, expected))
+        }
+        _ => {
+            let token = match provided {
+                Some(token) => {
+                    ApiToken::find_active_by_hash(pool, &hash_token(token), ApiTokenScope::Mcp)
+                        .await
+                        .map_err(|e| {
+                            tracing::error!("Failed to look up MCP token: {}", e);
+                            StatusCode::INTERNAL_SERVER_ERROR
+                        })?
+                }
+                None => None,
+            };
+            match token {
+                Some(token) => {
+                    if let Err(e) = ApiToken::touch(pool, token.id).await {
+                        tracing::warn!("Failed to update MCP token {}: {}", token.id, e);
+                    }
+                    true
+                }
+                None => {
+                    let enabled = ApiToken::has_active(pool, ApiTokenScope::Mcp)
+                        .await
+                        .map_err(|e| {
+                            tracing::error!("Failed to look up MCP token: {}", e);
+                            StatusCode::INTERNAL_SERVER_ERROR
+                        })?;
+                    if !enabled {
+                        return Err(StatusCode::NOT_FOUND);
+                    }
+                    false
+                }
+            }
+        }
+    };
+
+    if authorized {
+        Ok(())
+    } else {
+        tracing::warn!("Rejected MCP request with a missing or invalid token");
+        Err(StatusCode::UNAUTHORIZED)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use db::models::api_token::{CreateApiToken, generate_token, token_prefix};
+
+    use super::*;
+
+    async fn create_token(pool: &SqlitePool, scope: ApiTokenScope) -> (ApiToken, String) {
+        let token = generate_token();
+        let created = ApiToken::create(
+            pool,
+            &CreateApiToken {
+                name: "mcp".to_string(),
+                is_admin: false,
+                scope,
+            },
+            &hash_token(&token),
+            &token_prefix(&token),
+        )
+        .await
+        .unwrap();
+        (created, token)
+    }
+
+    #[sqlx::test(migrations = "../db/migrations")]
+    async fn endpoint_is_hidden_until_a_token_exists(pool: SqlitePool) {
+        assert_eq!(
+            authorize(&pool, None, None).await,
+            Err(StatusCode::NOT_FOUND)
+        );
+        assert_eq!(
+            authorize(&pool, Some(" "), Some("anything")).await,
+            Err(StatusCode::NOT_FOUND)
+        );
+        // API tokens do not enable the MCP endpoint
+        create_token(&pool, ApiTokenScope::Api).await;
+        assert_eq!(
+            authorize(&pool, None, None).await,
+            Err(StatusCode::NOT_FOUND)
+        );
+    }
+
+    #[sqlx::test(migrations = "../db/migrations")]
+    async fn checks_stored_mcp_tokens(pool: SqlitePool) {
+        let (mcp, mcp_token) = create_token(&pool, ApiTokenScope::Mcp).await;
+        let (_, api_token) = create_token(&pool, ApiTokenScope::Api).await;
+        let wrong = generate_token();
+
+        assert_eq!(
+            authorize(&pool, None, None).await,
+            Err(StatusCode::UNAUTHORIZED)
+        );
+        assert_eq!(
+            authorize(&pool, None, Some(wrong.as_str())).await,
+            Err(StatusCode::UNAUTHORIZED)
+        );
+        assert_eq!(
+            authorize(&pool, None, Some(api_token.as_str())).await,
+            Err(StatusCode::UNAUTHORIZED)
+        );
+        assert_eq!(
+            authorize(&pool, None, Some(mcp_token.as_str())).await,
+            Ok(())
+        );
+        let used = ApiToken::find_by_id(&pool, mcp.id).await.unwrap().unwrap();
+        assert!(used.last_used_at.is_some());
+
+        // Revoked tokens are rejected, and the endpoint disappears with the last one
+        ApiToken::revoke(&pool, mcp.id).await.unwrap();
+        assert_eq!(
+            authorize(&pool, None, Some(mcp_token.as_str())).await,
+            Err(StatusCode::NOT_FOUND)
+        );
+    }
+
+    #[sqlx::test(migrations = "../db/migrations")]
+    async fn environment_token_replaces_stored_tokens(pool: SqlitePool) {
+        let (_, stored) = create_token(&pool, ApiTokenScope::Mcp).await;
+        let env_token = generate_token();
+
+        assert_eq!(
+            authorize(&pool, Some(env_token.as_str()), Some(env_token.as_str())).await,
+            Ok(())
+        );
+        assert_eq!(
+            authorize(&pool, Some(env_token.as_str()), None).await,
+            Err(StatusCode::UNAUTHORIZED)
+        );
+        assert_eq!(
+            authorize(&pool, Some(env_token.as_str()), Some(stored.as_str())).await,
+            Err(StatusCode::UNAUTHORIZED)
+        );
+    }
+}
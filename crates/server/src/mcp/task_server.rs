@@ -1,13 +1,19 @@
-use std::{future::Future, str::FromStr};
+use std::{future::Future, str::FromStr, time::Duration};
 
 use db::models::{
+    execution_process::{ExecutionProcess, ExecutionProcessStatus},
     project::Project,
     repo::Repo,
+    session::Session,
     tag::Tag,
     task::{CreateTask, Task, TaskStatus, TaskWithAttemptStatus, UpdateTask},
     workspace::{Workspace, WorkspaceContext},
 };
-use executors::{executors::BaseCodingAgent, profile::ExecutorProfileId};
+use executors::{
+    executors::BaseCodingAgent,
+    logs::{NormalizedEntry, NormalizedEntryType},
+    profile::ExecutorProfileId,
+};
 use regex::Regex;
 use rmcp::{
     ErrorData, ServerHandler,
@@ -19,13 +25,24 @@ use rmcp::{
 };
 use serde::{Deserialize, Serialize, de::DeserializeOwned};
 use serde_json;
+use utils::diff::{Diff, compute_line_change_counts};
 use uuid::Uuid;
 
 use crate::routes::{
     containers::ContainerQuery,
-    task_attempts::{CreateTaskAttemptBody, WorkspaceRepoInput},
+    sessions::CreateFollowUpAttempt,
+    task_attempts::{
+        CreateTaskAttemptBody, MergeTaskAttemptRequest, RebaseTaskAttemptRequest,
+        WorkspaceRepoInput,
+        pr::{CreateGitHubPrRequest, GetPrCommentsQuery},
+    },
 };
 
+const DEFAULT_LOG_ENTRY_LIMIT: usize = 50;
+const DEFAULT_WAIT_TIMEOUT_SECS: u64 = 600;
+const MAX_WAIT_TIMEOUT_SECS: u64 = 3600;
+const WAIT_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
 pub struct CreateTaskRequest {
     #[schemars(description = "The ID of the project to create the task in. This is required!")]
@@ -119,6 +136,8 @@ pub struct TaskSummary {
     pub has_in_progress_attempt: Option<bool>,
     #[schemars(description = "Whether the last execution attempt failed")]
     pub last_attempt_failed: Option<bool>,
+    #[schemars(description = "Whether the task is waiting on dependencies that are not done yet")]
+    pub is_blocked: Option<bool>,
 }
 
 impl TaskSummary {
@@ -131,6 +150,7 @@ impl TaskSummary {
             updated_at: task.updated_at.to_rfc3339(),
             has_in_progress_attempt: Some(task.has_in_progress_attempt),
             last_attempt_failed: Some(task.last_attempt_failed),
+            is_blocked: Some(task.is_blocked),
         }
     }
 }
@@ -251,6 +271,268 @@ pub struct GetTaskResponse {
     pub task: TaskDetails,
 }
 
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct SendFollowUpRequest {
+    #[schemars(description = "The ID of the workspace whose latest session should continue")]
+    pub workspace_id: Uuid,
+    #[schemars(description = "The follow-up prompt for the coding agent")]
+    pub prompt: String,
+    #[schemars(description = "Optional executor variant, if needed")]
+    pub variant: Option<String>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct SendFollowUpResponse {
+    pub session_id: String,
+    pub execution_process_id: String,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ListExecutionProcessesRequest {
+    #[schemars(description = "The ID of the workspace whose latest session should be listed")]
+    pub workspace_id: Uuid,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct ExecutionProcessSummary {
+    #[schemars(description = "The unique identifier of the execution process")]
+    pub id: String,
+    #[schemars(
+        description = "Why the process ran ('setupscript', 'codingagent', 'cleanupscript', 'devserver')"
+    )]
+    pub run_reason: String,
+    #[schemars(description = "'running', 'completed', 'failed' or 'killed'")]
+    pub status: String,
+    pub exit_code: Option<i64>,
+    pub started_at: String,
+    pub completed_at: Option<String>,
+}
+
+impl ExecutionProcessSummary {
+    fn from_execution_process(process: ExecutionProcess) -> Self {
+        Self {
+            id: process.id.to_string(),
+            run_reason: enum_label(&process.run_reason),
+            status: enum_label(&process.status),
+            exit_code: process.exit_code,
+            started_at: process.started_at.to_rfc3339(),
+            completed_at: process.completed_at.map(|t| t.to_rfc3339()),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct ListExecutionProcessesResponse {
+    pub workspace_id: String,
+    pub session_id: String,
+    pub count: usize,
+    pub execution_processes: Vec<ExecutionProcessSummary>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct GetWorkspaceDiffRequest {
+    #[schemars(description = "The ID of the workspace to diff against its target branches")]
+    pub workspace_id: Uuid,
+    #[schemars(
+        description = "Include the old and new file contents, not just per-file line counts (default false)"
+    )]
+    pub include_content: Option<bool>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct McpDiffFile {
+    #[schemars(description = "Path of the file, prefixed with the repository name")]
+    pub path: String,
+    #[schemars(description = "Previous path, for renamed or copied files")]
+    pub old_path: Option<String>,
+    #[schemars(
+        description = "'added', 'deleted', 'modified', 'renamed', 'copied' or 'permissionChange'"
+    )]
+    pub change: String,
+    pub additions: Option<usize>,
+    pub deletions: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old_content: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new_content: Option<String>,
+}
+
+impl McpDiffFile {
+    fn from_diff(diff: Diff) -> Self {
+        let (additions, deletions) = match (diff.additions, diff.deletions) {
+            (None, None) if !diff.content_omitted => {
+                let (additions, deletions) = compute_line_change_counts(
+                    diff.old_content.as_deref().unwrap_or(""),
+                    diff.new_content.as_deref().unwrap_or(""),
+                );
+                (Some(additions), Some(deletions))
+            }
+            counts => counts,
+        };
+        let old_path = diff
+            .old_path
+            .filter(|old| diff.new_path.as_ref() != Some(old));
+        Self {
+            path: diff
+                .new_path
+                .or_else(|| old_path.clone())
+                .unwrap_or_default(),
+            old_path,
+            change: enum_label(&diff.change),
+            additions,
+            deletions,
+            old_content: diff.old_content,
+            new_content: diff.new_content,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct GetWorkspaceDiffResponse {
+    pub workspace_id: String,
+    pub count: usize,
+    pub files: Vec<McpDiffFile>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct GetExecutionLogsRequest {
+    #[schemars(description = "The ID of the execution process to read logs from")]
+    pub execution_process_id: Uuid,
+    #[schemars(description = "Maximum number of most recent entries to return (default: 50)")]
+    pub limit: Option<i32>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct McpLogEntry {
+    #[schemars(
+        description = "'user_message', 'assistant_message', 'tool_use', 'thinking', 'system_message', 'error_message', ..."
+    )]
+    pub entry_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_name: Option<String>,
+    pub content: String,
+    pub timestamp: Option<String>,
+}
+
+impl McpLogEntry {
+    fn from_entry(entry: NormalizedEntry) -> Self {
+        let tool_name = match &entry.entry_type {
+            NormalizedEntryType::ToolUse { tool_name, .. } => Some(tool_name.clone()),
+            _ => None,
+        };
+        let entry_type = serde_json::to_value(&entry.entry_type)
+            .ok()
+            .and_then(|v| v.get("type").and_then(|t| t.as_str()).map(str::to_string))
+            .unwrap_or_default();
+        Self {
+            entry_type,
+            tool_name,
+            content: entry.content,
+            timestamp: entry.timestamp,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct GetExecutionLogsResponse {
+    pub execution_process_id: String,
+    pub total_entries: usize,
+    pub entries: Vec<McpLogEntry>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct WaitForExecutionRequest {
+    #[schemars(description = "The ID of the execution process to wait for")]
+    pub execution_process_id: Uuid,
+    #[schemars(description = "How long to wait before giving up, in seconds (default: 600)")]
+    pub timeout_seconds: Option<u64>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct WaitForExecutionResponse {
+    #[schemars(description = "False if the process was still running when the timeout elapsed")]
+    pub finished: bool,
+    pub execution_process: ExecutionProcessSummary,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct MergeWorkspaceRequest {
+    #[schemars(description = "The ID of the workspace to merge")]
+    pub workspace_id: Uuid,
+    #[schemars(description = "The repository to merge into its target branch")]
+    pub repo_id: Uuid,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct RebaseWorkspaceRequest {
+    #[schemars(description = "The ID of the workspace to rebase")]
+    pub workspace_id: Uuid,
+    #[schemars(description = "The repository to rebase")]
+    pub repo_id: Uuid,
+    #[schemars(
+        description = "Optional new base branch. Defaults to the repository's current target branch"
+    )]
+    pub new_base_branch: Option<String>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct GitOperationResponse {
+    pub workspace_id: String,
+    pub repo_id: String,
+    pub operation: String,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct CreatePullRequestRequest {
+    #[schemars(description = "The ID of the workspace whose branch should be opened as a PR")]
+    pub workspace_id: Uuid,
+    #[schemars(description = "The repository to open the PR in")]
+    pub repo_id: Uuid,
+    #[schemars(description = "The title of the PR")]
+    pub title: String,
+    #[schemars(description = "Optional description of the PR")]
+    pub body: Option<String>,
+    #[schemars(
+        description = "Optional branch to merge into. Defaults to the repository's target branch"
+    )]
+    pub target_branch: Option<String>,
+    #[schemars(description = "Open the PR as a draft (default false)")]
+    pub draft: Option<bool>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct CreatePullRequestResponse {
+    pub pr_url: String,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct GetPrCommentsRequest {
+    #[schemars(description = "The ID of the workspace with an attached PR")]
+    pub workspace_id: Uuid,
+    #[schemars(description = "The repository the PR was opened in")]
+    pub repo_id: Uuid,
+}
+
+#[derive(Debug, Deserialize)]
+struct PrCommentsEnvelope {
+    comments: Vec<serde_json::Value>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct GetPrCommentsResponse {
+    pub count: usize,
+    #[schemars(description = "General and review comments, oldest first")]
+    pub comments: Vec<serde_json::Value>,
+}
+
+/// The serde name of a unit enum variant, e.g. `"codingagent"`
+fn enum_label<T: Serialize>(value: &T) -> String {
+    serde_json::to_value(value)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
 #[derive(Debug, Clone)]
 pub struct TaskServer {
     client: reqwest::Client,
@@ -306,6 +588,35 @@ impl TaskServer {
         self
     }
 
+    /// Sends `token` as a bearer token, for servers running in headless mode
+    pub fn with_api_token(mut self, token: Option<&str>) -> Self {
+        let Some(token) = token else {
+            return self;
+        };
+        let mut headers = reqwest::header::HeaderMap::new();
+        match reqwest::header::HeaderValue::from_str(&format!("Bearer {token}")) {
+            Ok(mut value) => {
+                value.set_sensitive(true);
+                headers.insert(reqwest::header::AUTHORIZATION, value);
+            }
+            Err(e) => {
+                tracing::warn!("Ignoring invalid API token: {}", e);
+                return self;
+            }
+        }
+        match reqwest::Client::builder().default_headers(headers).build() {
+            Ok(client) => self.client = client,
+            Err(e) => tracing::warn!("Failed to build authenticated HTTP client: {}", e),
+        }
+        self
+    }
+
+    /// For clients that are not running inside a workspace, e.g. over HTTP
+    pub fn without_context(mut self) -> Self {
+        self.tool_router.map.remove("get_context");
+        self
+    }
+
     async fn fetch_context_at_startup(&self) -> Option<McpContext> {
         let current_dir = std::env::current_dir().ok()?;
         let canonical_path = current_dir.canonicalize().unwrap_or(current_dir);
@@ -362,6 +673,7 @@ impl TaskServer {
 struct ApiResponseEnvelope<T> {
     success: bool,
     data: Option<T>,
+    error_data: Option<serde_json::Value>,
     message: Option<String>,
 }
 
@@ -392,6 +704,22 @@ impl TaskServer {
         &self,
         rb: reqwest::RequestBuilder,
     ) -> Result<T, CallToolResult> {
+        self.send_envelope(rb)
+            .await?
+            .ok_or_else(|| Self::err("VK API response missing data field", None).unwrap())
+    }
+
+    /// For routes that respond with `data: null` on success, such as delete, merge and rebase
+    async fn send_ok(&self, rb: reqwest::RequestBuilder) -> Result<(), CallToolResult> {
+        self.send_envelope::<serde_json::Value>(rb)
+            .await
+            .map(|_| ())
+    }
+
+    async fn send_envelope<T: DeserializeOwned>(
+        &self,
+        rb: reqwest::RequestBuilder,
+    ) -> Result<Option<T>, CallToolResult> {
         let resp = rb
             .send()
             .await
@@ -399,8 +727,13 @@ impl TaskServer {
 
         if !resp.status().is_success() {
             let status = resp.status();
+            let message = resp
+                .json::<ApiResponseEnvelope<serde_json::Value>>()
+                .await
+                .ok()
+                .and_then(|r| r.message);
             return Err(
-                Self::err(format!("VK API returned error status: {}", status), None).unwrap(),
+                Self::err(format!("VK API returned error status: {}", status), message).unwrap(),
             );
         }
 
@@ -410,12 +743,33 @@ impl TaskServer {
 
         if !api_response.success {
             let msg = api_response.message.as_deref().unwrap_or("Unknown error");
-            return Err(Self::err("VK API returned error", Some(msg)).unwrap());
+            let mut v = serde_json::json!({
+                "success": false,
+                "error": "VK API returned error",
+                "details": msg,
+            });
+            // Typed errors such as merge conflicts or a missing forge login
+            if let Some(error_data) = api_response.error_data {
+                v["error_data"] = error_data;
+            }
+            return Err(Self::err_value(v).unwrap());
         }
 
-        api_response
-            .data
-            .ok_or_else(|| Self::err("VK API response missing data field", None).unwrap())
+        Ok(api_response.data)
+    }
+
+    async fn latest_session(&self, workspace_id: Uuid) -> Result<Session, CallToolResult> {
+        let url = self.url(&format!("/api/sessions?workspace_id={}", workspace_id));
+        let sessions: Vec<Session> = self.send_json(self.client.get(&url)).await?;
+        // Sessions are returned newest first
+        sessions.into_iter().next().ok_or_else(|| {
+            Self::err(
+                "Workspace has no sessions yet. Start one with `start_workspace_session`."
+                    .to_string(),
+                Some(workspace_id.to_string()),
+            )
+            .unwrap()
+        })
     }
 
     fn url(&self, path: &str) -> String {
@@ -776,10 +1130,7 @@ impl TaskServer {
         Parameters(DeleteTaskRequest { task_id }): Parameters<DeleteTaskRequest>,
     ) -> Result<CallToolResult, ErrorData> {
         let url = self.url(&format!("/api/tasks/{}", task_id));
-        if let Err(e) = self
-            .send_json::<serde_json::Value>(self.client.delete(&url))
-            .await
-        {
+        if let Err(e) = self.send_ok(self.client.delete(&url)).await {
             return Ok(e);
         }
 
@@ -808,12 +1159,306 @@ impl TaskServer {
 
         TaskServer::success(&response)
     }
+    #[tool(
+        description = "Send a follow-up prompt to the coding agent of a workspace, continuing its latest session. Returns the `execution_process_id` of the new agent run. `workspace_id` and `prompt` are required!"
+    )]
+    async fn send_follow_up(
+        &self,
+        Parameters(SendFollowUpRequest {
+            workspace_id,
+            prompt,
+            variant,
+        }): Parameters<SendFollowUpRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        if prompt.trim().is_empty() {
+            return Self::err("Prompt must not be empty.".to_string(), None::<String>);
+        }
+
+        let session = match self.latest_session(workspace_id).await {
+            Ok(s) => s,
+            Err(e) => return Ok(e),
+        };
+
+        let payload = CreateFollowUpAttempt {
+            prompt: self.expand_tags(&prompt).await,
+            variant: variant.filter(|v| !v.trim().is_empty()),
+            retry_process_id: None,
+            force_when_dirty: None,
+            perform_git_reset: None,
+        };
+        let url = self.url(&format!("/api/sessions/{}/follow-up", session.id));
+        let process: ExecutionProcess =
+            match self.send_json(self.client.post(&url).json(&payload)).await {
+                Ok(p) => p,
+                Err(e) => return Ok(e),
+            };
+
+        TaskServer::success(&SendFollowUpResponse {
+            session_id: session.id.to_string(),
+            execution_process_id: process.id.to_string(),
+        })
+    }
+
+    #[tool(
+        description = "List the execution processes (setup script, coding agent runs, cleanup script) of a workspace's latest session, oldest first. `workspace_id` is required!"
+    )]
+    async fn list_execution_processes(
+        &self,
+        Parameters(ListExecutionProcessesRequest { workspace_id }): Parameters<
+            ListExecutionProcessesRequest,
+        >,
+    ) -> Result<CallToolResult, ErrorData> {
+        let session = match self.latest_session(workspace_id).await {
+            Ok(s) => s,
+            Err(e) => return Ok(e),
+        };
+
+        let url = self.url(&format!(
+            "/api/execution-processes?session_id={}",
+            session.id
+        ));
+        let processes: Vec<ExecutionProcess> = match self.send_json(self.client.get(&url)).await {
+            Ok(ps) => ps,
+            Err(e) => return Ok(e),
+        };
+
+        let summaries: Vec<ExecutionProcessSummary> = processes
+            .into_iter()
+            .map(ExecutionProcessSummary::from_execution_process)
+            .collect();
+
+        TaskServer::success(&ListExecutionProcessesResponse {
+            workspace_id: workspace_id.to_string(),
+            session_id: session.id.to_string(),
+            count: summaries.len(),
+            execution_processes: summaries,
+        })
+    }
+
+    #[tool(
+        description = "Get the changes a workspace has made compared to the target branch of each of its repositories, including uncommitted changes. `workspace_id` is required!"
+    )]
+    async fn get_workspace_diff(
+        &self,
+        Parameters(GetWorkspaceDiffRequest {
+            workspace_id,
+            include_content,
+        }): Parameters<GetWorkspaceDiffRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let stats_only = !include_content.unwrap_or(false);
+        let url = self.url(&format!(
+            "/api/task-attempts/{}/diff?stats_only={}",
+            workspace_id, stats_only
+        ));
+        let diffs: Vec<Diff> = match self.send_json(self.client.get(&url)).await {
+            Ok(d) => d,
+            Err(e) => return Ok(e),
+        };
+
+        let files: Vec<McpDiffFile> = diffs.into_iter().map(McpDiffFile::from_diff).collect();
+
+        TaskServer::success(&GetWorkspaceDiffResponse {
+            workspace_id: workspace_id.to_string(),
+            count: files.len(),
+            files,
+        })
+    }
+
+    #[tool(
+        description = "Read the normalized conversation (messages, tool calls, errors) of an execution process. Use `list_execution_processes` to find `execution_process_id`s. `execution_process_id` is required!"
+    )]
+    async fn get_execution_logs(
+        &self,
+        Parameters(GetExecutionLogsRequest {
+            execution_process_id,
+            limit,
+        }): Parameters<GetExecutionLogsRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let url = self.url(&format!(
+            "/api/execution-processes/{}/normalized-logs",
+            execution_process_id
+        ));
+        let entries: Vec<NormalizedEntry> = match self.send_json(self.client.get(&url)).await {
+            Ok(e) => e,
+            Err(e) => return Ok(e),
+        };
+
+        let total_entries = entries.len();
+        let limit = limit.map_or(DEFAULT_LOG_ENTRY_LIMIT, |l| l.max(0) as usize);
+        let entries: Vec<McpLogEntry> = entries
+            .into_iter()
+            .skip(total_entries.saturating_sub(limit))
+            .map(McpLogEntry::from_entry)
+            .collect();
+
+        TaskServer::success(&GetExecutionLogsResponse {
+            execution_process_id: execution_process_id.to_string(),
+            total_entries,
+            entries,
+        })
+    }
+
+    #[tool(
+        description = "Wait until an execution process stops running, then return its final status and exit code. `execution_process_id` is required!"
+    )]
+    async fn wait_for_execution(
+        &self,
+        Parameters(WaitForExecutionRequest {
+            execution_process_id,
+            timeout_seconds,
+        }): Parameters<WaitForExecutionRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let timeout = Duration::from_secs(
+            timeout_seconds
+                .unwrap_or(DEFAULT_WAIT_TIMEOUT_SECS)
+                .min(MAX_WAIT_TIMEOUT_SECS),
+        );
+        let deadline = tokio::time::Instant::now() + timeout;
+        let url = self.url(&format!(
+            "/api/execution-processes/{}",
+            execution_process_id
+        ));
+
+        loop {
+            let process: ExecutionProcess = match self.send_json(self.client.get(&url)).await {
+                Ok(p) => p,
+                Err(e) => return Ok(e),
+            };
+
+            let finished = process.status != ExecutionProcessStatus::Running;
+            if finished || tokio::time::Instant::now() >= deadline {
+                return TaskServer::success(&WaitForExecutionResponse {
+                    finished,
+                    execution_process: ExecutionProcessSummary::from_execution_process(process),
+                });
+            }
+
+            tokio::time::sleep_until(
+                deadline.min(tokio::time::Instant::now() + WAIT_POLL_INTERVAL),
+            )
+            .await;
+        }
+    }
+
+    #[tool(
+        description = "Merge a workspace's branch into the target branch of one of its repositories. `workspace_id` and `repo_id` are required!"
+    )]
+    async fn merge_workspace(
+        &self,
+        Parameters(MergeWorkspaceRequest {
+            workspace_id,
+            repo_id,
+        }): Parameters<MergeWorkspaceRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let url = self.url(&format!("/api/task-attempts/{}/merge", workspace_id));
+        if let Err(e) = self
+            .send_ok(
+                self.client
+                    .post(&url)
+                    .json(&MergeTaskAttemptRequest { repo_id }),
+            )
+            .await
+        {
+            return Ok(e);
+        }
+
+        TaskServer::success(&GitOperationResponse {
+            workspace_id: workspace_id.to_string(),
+            repo_id: repo_id.to_string(),
+            operation: "merge".to_string(),
+        })
+    }
+
+    #[tool(
+        description = "Rebase a workspace's branch onto the target branch of one of its repositories, optionally switching to a new base branch. Conflicts are reported in `error_data`. `workspace_id` and `repo_id` are required!"
+    )]
+    async fn rebase_workspace(
+        &self,
+        Parameters(RebaseWorkspaceRequest {
+            workspace_id,
+            repo_id,
+            new_base_branch,
+        }): Parameters<RebaseWorkspaceRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let payload = RebaseTaskAttemptRequest {
+            repo_id,
+            old_base_branch: None,
+            new_base_branch,
+            auto_resolve_conflicts: None,
+        };
+        let url = self.url(&format!("/api/task-attempts/{}/rebase", workspace_id));
+        if let Err(e) = self.send_ok(self.client.post(&url).json(&payload)).await {
+            return Ok(e);
+        }
+
+        TaskServer::success(&GitOperationResponse {
+            workspace_id: workspace_id.to_string(),
+            repo_id: repo_id.to_string(),
+            operation: "rebase".to_string(),
+        })
+    }
+
+    #[tool(
+        description = "Push a workspace's branch and open a pull request for one of its repositories. `workspace_id`, `repo_id` and `title` are required!"
+    )]
+    async fn create_pull_request(
+        &self,
+        Parameters(CreatePullRequestRequest {
+            workspace_id,
+            repo_id,
+            title,
+            body,
+            target_branch,
+            draft,
+        }): Parameters<CreatePullRequestRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let payload = CreateGitHubPrRequest {
+            title,
+            body,
+            target_branch,
+            draft,
+            repo_id,
+            auto_generate_description: false,
+        };
+        let url = self.url(&format!("/api/task-attempts/{}/pr", workspace_id));
+        let pr_url: String = match self.send_json(self.client.post(&url).json(&payload)).await {
+            Ok(u) => u,
+            Err(e) => return Ok(e),
+        };
+
+        TaskServer::success(&CreatePullRequestResponse { pr_url })
+    }
+
+    #[tool(
+        description = "List the comments and review comments on the pull request attached to a workspace. `workspace_id` and `repo_id` are required!"
+    )]
+    async fn get_pr_comments(
+        &self,
+        Parameters(GetPrCommentsRequest {
+            workspace_id,
+            repo_id,
+        }): Parameters<GetPrCommentsRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let url = self.url(&format!("/api/task-attempts/{}/pr/comments", workspace_id));
+        let response: PrCommentsEnvelope = match self
+            .send_json(self.client.get(&url).query(&GetPrCommentsQuery { repo_id }))
+            .await
+        {
+            Ok(r) => r,
+            Err(e) => return Ok(e),
+        };
+
+        TaskServer::success(&GetPrCommentsResponse {
+            count: response.comments.len(),
+            comments: response.comments,
+        })
+    }
 }
 
 #[tool_handler]
 impl ServerHandler for TaskServer {
     fn get_info(&self) -> ServerInfo {
-        let mut instruction = "A task and project management server. If you need to create or update tickets or tasks then use these tools. Most of them absolutely require that you pass the `project_id` of the project that you are currently working on. You can get project ids by using `list projects`. Call `list_tasks` to fetch the `task_ids` of all the tasks in a project`.. TOOLS: 'list_projects', 'list_tasks', 'create_task', 'start_workspace_session', 'get_task', 'update_task', 'delete_task', 'list_repos'. Make sure to pass `project_id` or `task_id` where required. You can use list tools to get the available ids.".to_string();
+        let mut instruction = "A task and project management server. If you need to create or update tickets or tasks then use these tools. Most of them absolutely require that you pass the `project_id` of the project that you are currently working on. You can get project ids by using `list projects`. Call `list_tasks` to fetch the `task_ids` of all the tasks in a project`.. TOOLS: 'list_projects', 'list_tasks', 'create_task', 'start_workspace_session', 'get_task', 'update_task', 'delete_task', 'list_repos'. To drive a running workspace use 'send_follow_up', 'list_execution_processes', 'get_execution_logs', 'wait_for_execution' and 'get_workspace_diff', then 'rebase_workspace', 'merge_workspace', 'create_pull_request' and 'get_pr_comments'. Make sure to pass `project_id`, `task_id` or `workspace_id` where required. You can use list tools to get the available ids.".to_string();
         if self.context.is_some() {
             let context_instruction = "Use 'get_context' to fetch project/task/workspace metadata for the active Vibe Kanban workspace session when available.";
             instruction = format!("{} {}", context_instruction, instruction);
@@ -830,3 +1475,480 @@ impl ServerHandler for TaskServer {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::{HashMap, VecDeque},
+        sync::{Arc, Mutex},
+    };
+
+    use axum::{
+        Json, Router,
+        extract::State,
+        http::{Method, StatusCode, Uri},
+    };
+    use chrono::Utc;
+    use db::models::execution_process::{ExecutionProcessRunReason, ExecutorActionField};
+    use executors::logs::{ActionType, ToolStatus};
+    use serde_json::{Value, json};
+    use utils::diff::DiffChangeKind;
+
+    use super::*;
+
+    /// A request the stub API received: `"GET /api/..."`, the query string and the JSON body
+    type Recorded = (String, Option<String>, Value);
+
+    /// Stands in for the REST API the tools proxy to. Each route answers with its queued
+    /// responses in turn and keeps repeating the last one.
+    #[derive(Clone, Default)]
+    struct StubApi {
+        responses: Arc<Mutex<HashMap<String, VecDeque<Value>>>>,
+        requests: Arc<Mutex<Vec<Recorded>>>,
+    }
+
+    impl StubApi {
+        fn respond(&self, route: &str, envelope: Value) -> &Self {
+            self.responses
+                .lock()
+                .unwrap()
+                .entry(route.to_string())
+                .or_default()
+                .push_back(envelope);
+            self
+        }
+
+        fn ok<T: Serialize>(&self, route: &str, data: T) -> &Self {
+            self.respond(route, json!({ "success": true, "data": data }))
+        }
+
+        fn requests(&self) -> Vec<Recorded> {
+            self.requests.lock().unwrap().clone()
+        }
+
+        async fn serve(&self) -> TaskServer {
+            let router = Router::new().fallback(handle).with_state(self.clone());
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let addr = listener.local_addr().unwrap();
+            tokio::spawn(async move { axum::serve(listener, router).await });
+            TaskServer::new(&format!("http://{addr}")).without_context()
+        }
+    }
+
+    async fn handle(
+        State(stub): State<StubApi>,
+        method: Method,
+        uri: Uri,
+        body: String,
+    ) -> (StatusCode, Json<Value>) {
+        let route = format!("{} {}", method, uri.path());
+        let body = serde_json::from_str(&body).unwrap_or(Value::Null);
+        stub.requests
+            .lock()
+            .unwrap()
+            .push((route.clone(), uri.query().map(str::to_string), body));
+
+        let mut responses = stub.responses.lock().unwrap();
+        match responses.get_mut(&route) {
+            Some(queue) if queue.len() > 1 => (StatusCode::OK, Json(queue.pop_front().unwrap())),
+            Some(queue) => (StatusCode::OK, Json(queue[0].clone())),
+            None => (
+                StatusCode::NOT_FOUND,
+                Json(json!({ "success": false, "message": format!("no stub for {route}") })),
+            ),
+        }
+    }
+
+    /// The JSON payload of a tool result and whether it was reported as an error
+    fn output(result: CallToolResult) -> (Value, bool) {
+        let result = serde_json::to_value(result).unwrap();
+        let text = result["content"][0]["text"].as_str().unwrap();
+        (
+            serde_json::from_str(text).unwrap(),
+            result["isError"].as_bool().unwrap_or(false),
+        )
+    }
+
+    fn session(workspace_id: Uuid) -> Session {
+        Session {
+            id: Uuid::new_v4(),
+            workspace_id,
+            executor: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn process(
+        id: Uuid,
+        session_id: Uuid,
+        status: ExecutionProcessStatus,
+        exit_code: Option<i64>,
+    ) -> ExecutionProcess {
+        ExecutionProcess {
+            id,
+            session_id,
+            run_reason: ExecutionProcessRunReason::CodingAgent,
+            executor_action: sqlx::types::Json(ExecutorActionField::Other(json!({}))),
+            completed_at: (status != ExecutionProcessStatus::Running).then(Utc::now),
+            status,
+            exit_code,
+            failure_reason: None,
+            dropped: false,
+            started_at: Utc::now(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn entry(entry_type: NormalizedEntryType, content: &str) -> NormalizedEntry {
+        NormalizedEntry {
+            timestamp: None,
+            entry_type,
+            content: content.to_string(),
+            metadata: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn send_follow_up_continues_the_latest_session() {
+        let stub = StubApi::default();
+        let workspace_id = Uuid::new_v4();
+        let latest = session(workspace_id);
+        let process_id = Uuid::new_v4();
+        stub.ok(
+            "GET /api/sessions",
+            vec![latest.clone(), session(workspace_id)],
+        )
+        .ok(
+            &format!("POST /api/sessions/{}/follow-up", latest.id),
+            process(process_id, latest.id, ExecutionProcessStatus::Running, None),
+        );
+        let server = stub.serve().await;
+
+        let (_, is_error) = output(
+            server
+                .send_follow_up(Parameters(SendFollowUpRequest {
+                    workspace_id,
+                    prompt: "   ".to_string(),
+                    variant: None,
+                }))
+                .await
+                .unwrap(),
+        );
+        assert!(is_error);
+        assert!(stub.requests().is_empty());
+
+        let (response, is_error) = output(
+            server
+                .send_follow_up(Parameters(SendFollowUpRequest {
+                    workspace_id,
+                    prompt: "Now fix the failing tests".to_string(),
+                    variant: Some(" ".to_string()),
+                }))
+                .await
+                .unwrap(),
+        );
+        assert!(!is_error);
+        assert_eq!(response["session_id"], latest.id.to_string());
+        assert_eq!(response["execution_process_id"], process_id.to_string());
+
+        let requests = stub.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(
+            requests[0].1.as_deref(),
+            Some(format!("workspace_id={workspace_id}").as_str())
+        );
+        let follow_up = &requests[1].2;
+        assert_eq!(follow_up["prompt"], "Now fix the failing tests");
+        assert_eq!(follow_up["variant"], Value::Null);
+    }
+
+    #[tokio::test]
+    async fn send_follow_up_requires_a_session() {
+        let stub = StubApi::default();
+        stub.ok("GET /api/sessions", Vec::<Session>::new());
+        let server = stub.serve().await;
+
+        let (response, is_error) = output(
+            server
+                .send_follow_up(Parameters(SendFollowUpRequest {
+                    workspace_id: Uuid::new_v4(),
+                    prompt: "continue".to_string(),
+                    variant: None,
+                }))
+                .await
+                .unwrap(),
+        );
+        assert!(is_error);
+        assert!(
+            response["error"]
+                .as_str()
+                .unwrap()
+                .contains("start_workspace_session")
+        );
+        assert_eq!(stub.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn get_execution_logs_returns_the_most_recent_entries() {
+        let stub = StubApi::default();
+        let process_id = Uuid::new_v4();
+        stub.ok(
+            &format!("GET /api/execution-processes/{process_id}/normalized-logs"),
+            vec![
+                entry(NormalizedEntryType::UserMessage, "add a README"),
+                entry(
+                    NormalizedEntryType::ToolUse {
+                        tool_name: "Write".to_string(),
+                        action_type: ActionType::FileRead {
+                            path: "README.md".to_string(),
+                        },
+                        status: ToolStatus::Success,
+                    },
+                    "README.md",
+                ),
+                entry(NormalizedEntryType::AssistantMessage, "Done."),
+            ],
+        );
+        let server = stub.serve().await;
+
+        let (response, is_error) = output(
+            server
+                .get_execution_logs(Parameters(GetExecutionLogsRequest {
+                    execution_process_id: process_id,
+                    limit: Some(2),
+                }))
+                .await
+                .unwrap(),
+        );
+        assert!(!is_error);
+        assert_eq!(response["total_entries"], 3);
+        assert_eq!(
+            response["entries"],
+            json!([
+                {
+                    "entry_type": "tool_use",
+                    "tool_name": "Write",
+                    "content": "README.md",
+                    "timestamp": null,
+                },
+                {
+                    "entry_type": "assistant_message",
+                    "content": "Done.",
+                    "timestamp": null,
+                },
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn wait_for_execution_polls_until_the_process_stops() {
+        let stub = StubApi::default();
+        let session_id = Uuid::new_v4();
+        let finished_id = Uuid::new_v4();
+        let running_id = Uuid::new_v4();
+        stub.ok(
+            &format!("GET /api/execution-processes/{finished_id}"),
+            process(
+                finished_id,
+                session_id,
+                ExecutionProcessStatus::Running,
+                None,
+            ),
+        )
+        .ok(
+            &format!("GET /api/execution-processes/{finished_id}"),
+            process(
+                finished_id,
+                session_id,
+                ExecutionProcessStatus::Completed,
+                Some(0),
+            ),
+        )
+        .ok(
+            &format!("GET /api/execution-processes/{running_id}"),
+            process(
+                running_id,
+                session_id,
+                ExecutionProcessStatus::Running,
+                None,
+            ),
+        );
+        let server = stub.serve().await;
+
+        let (response, is_error) = output(
+            server
+                .wait_for_execution(Parameters(WaitForExecutionRequest {
+                    execution_process_id: finished_id,
+                    timeout_seconds: Some(30),
+                }))
+                .await
+                .unwrap(),
+        );
+        assert!(!is_error);
+        assert_eq!(response["finished"], true);
+        assert_eq!(response["execution_process"]["status"], "completed");
+        assert_eq!(response["execution_process"]["exit_code"], 0);
+        assert_eq!(stub.requests().len(), 2);
+
+        // The process is still running when the timeout elapses
+        let (response, is_error) = output(
+            server
+                .wait_for_execution(Parameters(WaitForExecutionRequest {
+                    execution_process_id: running_id,
+                    timeout_seconds: Some(0),
+                }))
+                .await
+                .unwrap(),
+        );
+        assert!(!is_error);
+        assert_eq!(response["finished"], false);
+        assert_eq!(response["execution_process"]["status"], "running");
+    }
+
+    #[tokio::test]
+    async fn get_workspace_diff_reports_line_counts_per_file() {
+        let stub = StubApi::default();
+        let workspace_id = Uuid::new_v4();
+        stub.ok(
+            &format!("GET /api/task-attempts/{workspace_id}/diff"),
+            vec![
+                Diff {
+                    change: DiffChangeKind::Modified,
+                    old_path: Some("app/src/main.rs".to_string()),
+                    new_path: Some("app/src/main.rs".to_string()),
+                    old_content: Some("fn main() {}\n".to_string()),
+                    new_content: Some("fn main() {\n    run();\n}\n".to_string()),
+                    content_omitted: false,
+                    additions: None,
+                    deletions: None,
+                },
+                Diff {
+                    change: DiffChangeKind::Renamed,
+                    old_path: Some("app/old.rs".to_string()),
+                    new_path: Some("app/new.rs".to_string()),
+                    old_content: None,
+                    new_content: None,
+                    content_omitted: true,
+                    additions: Some(0),
+                    deletions: Some(0),
+                },
+            ],
+        );
+        let server = stub.serve().await;
+
+        let (response, is_error) = output(
+            server
+                .get_workspace_diff(Parameters(GetWorkspaceDiffRequest {
+                    workspace_id,
+                    include_content: None,
+                }))
+                .await
+                .unwrap(),
+        );
+        assert!(!is_error);
+        assert_eq!(stub.requests()[0].1.as_deref(), Some("stats_only=true"));
+        assert_eq!(response["count"], 2);
+        let files = &response["files"];
+        assert_eq!(files[0]["path"], "app/src/main.rs");
+        assert_eq!(files[0]["old_path"], Value::Null);
+        assert_eq!(files[0]["change"], "modified");
+        assert_eq!(files[0]["additions"], 3);
+        assert_eq!(files[0]["deletions"], 1);
+        assert_eq!(files[1]["path"], "app/new.rs");
+        assert_eq!(files[1]["old_path"], "app/old.rs");
+        assert_eq!(files[1]["change"], "renamed");
+    }
+
+    #[tokio::test]
+    async fn git_operations_post_to_the_workspace_routes() {
+        let stub = StubApi::default();
+        let workspace_id = Uuid::new_v4();
+        let repo_id = Uuid::new_v4();
+        stub.respond(
+            &format!("POST /api/task-attempts/{workspace_id}/rebase"),
+            json!({
+                "success": false,
+                "message": "Rebase has conflicts",
+                "error_data": { "type": "merge_conflicts", "conflicted_files": ["README.md"] },
+            }),
+        )
+        .ok(&format!("POST /api/task-attempts/{workspace_id}/merge"), ())
+        .ok(
+            &format!("POST /api/task-attempts/{workspace_id}/pr"),
+            "https://github.com/acme/app/pull/7",
+        )
+        .ok(
+            &format!("GET /api/task-attempts/{workspace_id}/pr/comments"),
+            json!({ "comments": [{ "author": "reviewer", "body": "Looks good" }] }),
+        );
+        let server = stub.serve().await;
+
+        let (response, is_error) = output(
+            server
+                .rebase_workspace(Parameters(RebaseWorkspaceRequest {
+                    workspace_id,
+                    repo_id,
+                    new_base_branch: Some("develop".to_string()),
+                }))
+                .await
+                .unwrap(),
+        );
+        assert!(is_error);
+        assert_eq!(response["details"], "Rebase has conflicts");
+        assert_eq!(response["error_data"]["type"], "merge_conflicts");
+
+        let (response, is_error) = output(
+            server
+                .merge_workspace(Parameters(MergeWorkspaceRequest {
+                    workspace_id,
+                    repo_id,
+                }))
+                .await
+                .unwrap(),
+        );
+        assert!(!is_error);
+        assert_eq!(response["operation"], "merge");
+
+        let (response, is_error) = output(
+            server
+                .create_pull_request(Parameters(CreatePullRequestRequest {
+                    workspace_id,
+                    repo_id,
+                    title: "Add README".to_string(),
+                    body: None,
+                    target_branch: None,
+                    draft: Some(true),
+                }))
+                .await
+                .unwrap(),
+        );
+        assert!(!is_error);
+        assert_eq!(response["pr_url"], "https://github.com/acme/app/pull/7");
+
+        let (response, is_error) = output(
+            server
+                .get_pr_comments(Parameters(GetPrCommentsRequest {
+                    workspace_id,
+                    repo_id,
+                }))
+                .await
+                .unwrap(),
+        );
+        assert!(!is_error);
+        assert_eq!(response["count"], 1);
+        assert_eq!(response["comments"][0]["body"], "Looks good");
+
+        let requests = stub.requests();
+        assert_eq!(requests[0].2["repo_id"], repo_id.to_string());
+        assert_eq!(requests[0].2["new_base_branch"], "develop");
+        assert_eq!(requests[1].2, json!({ "repo_id": repo_id }));
+        assert_eq!(requests[2].2["title"], "Add README");
+        assert_eq!(requests[2].2["draft"], true);
+        assert_eq!(
+            requests[3].1.as_deref(),
+            Some(format!("repo_id={repo_id}").as_str())
+        );
+    }
+}
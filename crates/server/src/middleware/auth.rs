@@ -0,0 +1,295 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{OriginalUri, Request, State},
+    http::{Method, StatusCode, header},
+    middleware::Next,
+    response::Response,
+};
+use db::models::api_token::{
+    ApiToken, ApiTokenAuditEntry, ApiTokenScope, generate_token, hash_token,
+};
+use deployment::Deployment;
+
+use crate::DeploymentImpl;
+
+/// Admin token accepted in headless mode, to create the first tokens with
+pub const ADMIN_TOKEN_ENV: &str = "VK_ADMIN_TOKEN";
+/// Cookie set by the token login route so the web UI, including its WebSockets, can authenticate
+pub const TOKEN_COOKIE: &str = "vk_api_token";
+
+/// Paths below `/api` that are not covered by token auth. `/mcp` has its own token.
+const PUBLIC_PATHS: &[&str] = &["/health", "/auth/token"];
+
+/// Token auth for the headless server mode
+#[derive(Clone)]
+pub struct ApiAuth {
+    inner: Arc<ApiAuthInner>,
+}
+
+struct ApiAuthInner {
+    admin_token: Option<String>,
+    /// Used by the server to call its own API, e.g. from the MCP HTTP endpoint
+    internal_token: String,
+}
+
+/// Who made an authenticated request
+#[derive(Debug, Clone)]
+pub enum ApiCaller {
+    Token(ApiToken),
+    BootstrapAdmin,
+    Internal,
+}
+
+impl ApiCaller {
+    fn is_admin(&self) -> bool {
+        match self {
+            ApiCaller::Token(token) => token.is_admin,
+            ApiCaller::BootstrapAdmin => true,
+            ApiCaller::Internal => false,
+        }
+    }
+}
+
+impl ApiAuth {
+    /// `Some` when headless mode is enabled
+    pub fn from_env() -> Option<Self> {
+        if !utils::is_headless() {
+            return None;
+        }
+
+        let admin_token = std::env::var(ADMIN_TOKEN_ENV)
+            .ok()
+            .map(|token| token.trim().to_string())
+            .filter(|token| !token.is_empty());
+        if admin_token.is_none() {
+            tracing::warn!(
+                "{ADMIN_TOKEN_ENV} is not set; only tokens created before starting in headless mode can be used"
+            );
+        }
+
+        Some(Self {
+            inner: Arc::new(ApiAuthInner {
+                admin_token,
+                internal_token: generate_token(),
+            }),
+        })
+    }
+
+    pub fn internal_token(&self) -> &str {
+        &self.inner.internal_token
+    }
+
+    async fn authenticate(
+        &self,
+        deployment: &DeploymentImpl,
+        provided: &str,
+    ) -> Result<Option<ApiCaller>, sqlx::Error> {
+        if tokens_match(provided, &self.inner.internal_token) {
+            return Ok(Some(ApiCaller::Internal));
+        }
+        if let Some(admin_token) = &self.inner.admin_token
+            && tokens_match(provided, admin_token)
+        {
+            return Ok(Some(ApiCaller::BootstrapAdmin));
+        }
+        let token = ApiToken::find_active_by_hash(
+            &deployment.db().pool,
+            &hash_token(provided),
+            ApiTokenScope::Api,
+        )
+        .await?;
+        Ok(token.map(ApiCaller::Token))
+    }
+}
+
+/// Compares without returning early so the response time does not leak the token
+pub fn tokens_match(provided: &str, expected: &str) -> bool {
+    provided.len() == expected.len()
+        && provided
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// The token from an `Authorization: Bearer` header, falling back to the login cookie
+fn provided_token(request: &Request) -> Option<&str> {
+    let headers = request.headers();
+    if let Some(token) = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+    {
+        return Some(token.trim());
+    }
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(';'))
+        .find_map(|cookie| {
+            let (name, value) = cookie.trim().split_once('=')?;
+            (name == TOKEN_COOKIE).then_some(value.trim())
+        })
+}
+
+/// Whether `path` (below `/api`) needs an admin token. Besides token management, this covers
+/// every route that changes how agents run or what they may do without asking: the config,
+/// executor profiles, MCP servers and approval rules. Agents are handed non-admin tokens.
+fn requires_admin(method: &Method, path: &str) -> bool {
+    let mutating = method != Method::GET && method != Method::HEAD;
+    path == "/admin"
+        || path.starts_with("/admin/")
+        || path == "/mcp-token"
+        || (mutating && matches!(path, "/config" | "/profiles" | "/mcp-config"))
+        || (mutating && (path == "/approval-rules" || path.starts_with("/approval-rules/")))
+}
+
+fn authorize(caller: &ApiCaller, method: &Method, path: &str) -> Result<(), StatusCode> {
+    if requires_admin(method, path) && !caller.is_admin() {
+        return Err(StatusCode::FORBIDDEN);
+    }
+    Ok(())
+}
+
+/// Requires a valid token on every request when headless mode is enabled. Admin routes (see
+/// `requires_admin`) reject other tokens, and requests made with a stored token are written
+/// to its audit log.
+pub async fn require_api_token(
+    State((deployment, auth)): State<(DeploymentImpl, ApiAuth)>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let path = request.uri().path();
+    if PUBLIC_PATHS.contains(&path) || path == "/mcp" || path.starts_with("/mcp/") {
+        return Ok(next.run(request).await);
+    }
+
+    let Some(provided) = provided_token(&request) else {
+        return Err(StatusCode::UNAUTHORIZED);
+    };
+    let caller = match auth.authenticate(&deployment, provided).await {
+        Ok(Some(caller)) => caller,
+        Ok(None) => {
+            tracing::warn!("Rejected API request with an invalid token");
+            return Err(StatusCode::UNAUTHORIZED);
+        }
+        Err(e) => {
+            tracing::error!("Failed to look up API token: {}", e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+    authorize(&caller, request.method(), path)?;
+
+    let method = request.method().to_string();
+    let full_path = request
+        .extensions()
+        .get::<OriginalUri>()
+        .map(|uri| uri.path().to_string())
+        .unwrap_or_else(|| path.to_string());
+    request.extensions_mut().insert(caller.clone());
+
+    let response = next.run(request).await;
+
+    match caller {
+        ApiCaller::Token(token) => {
+            let pool = &deployment.db().pool;
+            let status = i64::from(response.status().as_u16());
+            if let Err(e) =
+                ApiTokenAuditEntry::create(pool, token.id, &method, &full_path, status).await
+            {
+                tracing::warn!(
+                    "Failed to write audit log for API token {}: {}",
+                    token.id,
+                    e
+                );
+            }
+            if let Err(e) = ApiToken::touch(pool, token.id).await {
+                tracing::warn!("Failed to update API token {}: {}", token.id, e);
+            }
+        }
+        ApiCaller::BootstrapAdmin => {
+            tracing::info!(
+                "{ADMIN_TOKEN_ENV} request: {} {} -> {}",
+                method,
+                full_path,
+                response.status()
+            );
+        }
+        ApiCaller::Internal => {}
+    }
+
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compares_tokens() {
+        let token = generate_token();
+        assert!(tokens_match(&token, &token.clone()));
+        assert!(!tokens_match(&token, &token[..66]));
+        assert!(!tokens_match(&token, &generate_token()));
+    }
+
+    fn token(is_admin: bool) -> ApiCaller {
+        ApiCaller::Token(ApiToken {
+            id: uuid::Uuid::new_v4(),
+            name: "agent".to_string(),
+            token_prefix: "vk_1234".to_string(),
+            is_admin,
+            scope: ApiTokenScope::Api,
+            execution_process_id: None,
+            last_used_at: None,
+            revoked_at: None,
+            created_at: chrono::Utc::now(),
+        })
+    }
+
+    #[test]
+    fn mutating_config_routes_reject_non_admin_tokens() {
+        for (method, path) in [
+            (Method::PUT, "/config"),
+            (Method::PUT, "/profiles"),
+            (Method::POST, "/mcp-config"),
+            (Method::POST, "/approval-rules"),
+            (
+                Method::PUT,
+                "/approval-rules/8a3f0c1e-0000-0000-0000-000000000000",
+            ),
+            (
+                Method::DELETE,
+                "/approval-rules/8a3f0c1e-0000-0000-0000-000000000000",
+            ),
+            (Method::GET, "/admin/api-tokens"),
+            (Method::POST, "/mcp-token"),
+        ] {
+            for caller in [token(false), ApiCaller::Internal] {
+                assert_eq!(
+                    authorize(&caller, &method, path),
+                    Err(StatusCode::FORBIDDEN),
+                    "{method} {path}"
+                );
+            }
+            assert_eq!(authorize(&token(true), &method, path), Ok(()));
+            assert_eq!(authorize(&ApiCaller::BootstrapAdmin, &method, path), Ok(()));
+        }
+
+        for (method, path) in [
+            (Method::GET, "/profiles"),
+            (Method::GET, "/mcp-config"),
+            (Method::GET, "/approval-rules"),
+            (Method::POST, "/tasks"),
+            (Method::PUT, "/configs"),
+        ] {
+            assert_eq!(
+                authorize(&token(false), &method, path),
+                Ok(()),
+                "{method} {path}"
+            );
+        }
+    }
+}
@@ -5,14 +5,29 @@ use axum::{
     response::Response,
 };
 use db::models::{
-    execution_process::ExecutionProcess, project::Project, session::Session, tag::Tag, task::Task,
-    workspace::Workspace,
+    approval_rule::ApprovalRule, attempt_group::AttemptGroup, execution_process::ExecutionProcess,
+    project::Project, session::Session, tag::Tag, task::Task, task_schedule::TaskSchedule,
+    webhook::Webhook, workspace::Workspace,
 };
 use deployment::Deployment;
+use serde::Deserialize;
 use uuid::Uuid;
 
 use crate::DeploymentImpl;
 
+/// Task id of a route under `/{task_id}`, extracted by name so nested routes can capture
+/// further parameters
+#[derive(Deserialize)]
+pub struct TaskPathParams {
+    task_id: Uuid,
+}
+
+/// Session id of a route under `/{session_id}`, extracted by name like `TaskPathParams`
+#[derive(Deserialize)]
+pub struct SessionPathParams {
+    session_id: Uuid,
+}
+
 pub async fn load_project_middleware(
     State(deployment): State<DeploymentImpl>,
     Path(project_id): Path<Uuid>,
@@ -42,7 +57,7 @@ pub async fn load_project_middleware(
 
 pub async fn load_task_middleware(
     State(deployment): State<DeploymentImpl>,
-    Path(task_id): Path<Uuid>,
+    Path(TaskPathParams { task_id }): Path<TaskPathParams>,
     request: Request,
     next: Next,
 ) -> Result<Response, StatusCode> {
@@ -150,7 +165,7 @@ pub async fn load_tag_middleware(
 
 pub async fn load_session_middleware(
     State(deployment): State<DeploymentImpl>,
-    Path(session_id): Path<Uuid>,
+    Path(SessionPathParams { session_id }): Path<SessionPathParams>,
     mut request: Request,
     next: Next,
 ) -> Result<Response, StatusCode> {
@@ -169,3 +184,91 @@ pub async fn load_session_middleware(
     request.extensions_mut().insert(session);
     Ok(next.run(request).await)
 }
+
+pub async fn load_task_schedule_middleware(
+    State(deployment): State<DeploymentImpl>,
+    Path(schedule_id): Path<Uuid>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let schedule = match TaskSchedule::find_by_id(&deployment.db().pool, schedule_id).await {
+        Ok(Some(schedule)) => schedule,
+        Ok(None) => {
+            tracing::warn!("Task schedule {} not found", schedule_id);
+            return Err(StatusCode::NOT_FOUND);
+        }
+        Err(e) => {
+            tracing::error!("Failed to fetch task schedule {}: {}", schedule_id, e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    request.extensions_mut().insert(schedule);
+    Ok(next.run(request).await)
+}
+
+pub async fn load_attempt_group_middleware(
+    State(deployment): State<DeploymentImpl>,
+    Path(group_id): Path<Uuid>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let group = match AttemptGroup::find_by_id(&deployment.db().pool, group_id).await {
+        Ok(Some(group)) => group,
+        Ok(None) => {
+            tracing::warn!("Attempt group {} not found", group_id);
+            return Err(StatusCode::NOT_FOUND);
+        }
+        Err(e) => {
+            tracing::error!("Failed to fetch attempt group {}: {}", group_id, e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    request.extensions_mut().insert(group);
+    Ok(next.run(request).await)
+}
+
+pub async fn load_webhook_middleware(
+    State(deployment): State<DeploymentImpl>,
+    Path(webhook_id): Path<Uuid>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let webhook = match Webhook::find_by_id(&deployment.db().pool, webhook_id).await {
+        Ok(Some(webhook)) => webhook,
+        Ok(None) => {
+            tracing::warn!("Webhook {} not found", webhook_id);
+            return Err(StatusCode::NOT_FOUND);
+        }
+        Err(e) => {
+            tracing::error!("Failed to fetch webhook {}: {}", webhook_id, e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    request.extensions_mut().insert(webhook);
+    Ok(next.run(request).await)
+}
+
+pub async fn load_approval_rule_middleware(
+    State(deployment): State<DeploymentImpl>,
+    Path(rule_id): Path<Uuid>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let rule = match ApprovalRule::find_by_id(&deployment.db().pool, rule_id).await {
+        Ok(Some(rule)) => rule,
+        Ok(None) => {
+            tracing::warn!("Approval rule {} not found", rule_id);
+            return Err(StatusCode::NOT_FOUND);
+        }
+        Err(e) => {
+            tracing::error!("Failed to fetch approval rule {}: {}", rule_id, e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    request.extensions_mut().insert(rule);
+    Ok(next.run(request).await)
+}
@@ -0,0 +1,34 @@
+use axum::{
+    Router,
+    extract::{Query, State},
+    response::Json as ResponseJson,
+    routing::get,
+};
+use db::models::analytics::AnalyticsFilter;
+use deployment::Deployment;
+use services::services::project_analytics::{AnalyticsReport, analytics_report};
+use utils::response::ApiResponse;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+/// Attempt, merge and cycle-time metrics per project and executor profile for tasks created
+/// in the requested range
+pub async fn get_analytics(
+    State(deployment): State<DeploymentImpl>,
+    Query(filter): Query<AnalyticsFilter>,
+) -> Result<ResponseJson<ApiResponse<AnalyticsReport>>, ApiError> {
+    if let (Some(since), Some(until)) = (filter.since, filter.until)
+        && since >= until
+    {
+        return Err(ApiError::BadRequest(
+            "'since' must be before 'until'".to_string(),
+        ));
+    }
+
+    let report = analytics_report(&deployment.db().pool, &filter).await?;
+    Ok(ResponseJson(ApiResponse::success(report)))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new().route("/analytics", get(get_analytics))
+}
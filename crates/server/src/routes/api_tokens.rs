@@ -0,0 +1,328 @@
+use axum::{
+    Form, Json, Router,
+    extract::{Path, Query, State},
+    http::{StatusCode, header},
+    response::{Html, IntoResponse, Json as ResponseJson, Redirect, Response},
+    routing::{delete, get, post},
+};
+use db::models::api_token::{
+    ApiToken, ApiTokenAuditEntry, ApiTokenScope, CreateApiToken, generate_token, hash_token,
+    token_prefix,
+};
+use deployment::Deployment;
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError, middleware::auth::TOKEN_COOKIE};
+
+const DEFAULT_AUDIT_LIMIT: i64 = 100;
+
+/// Sign-in page for using the web UI against a headless server
+const TOKEN_LOGIN_PAGE: &str = r#"<!doctype html>
+<html>
+  <head><meta charset="utf-8"><title>Vibe Kanban</title></head>
+  <body>
+    <form method="post" action="/api/auth/token">
+      <label>API token <input type="password" name="token" autocomplete="off" required></label>
+      <button type="submit">Sign in</button>
+    </form>
+  </body>
+</html>
+"#;
+
+/// A newly created token. `token` is only ever returned here.
+#[derive(Debug, Serialize, TS)]
+pub struct CreatedApiToken {
+    #[serde(flatten)]
+    #[ts(flatten)]
+    pub api_token: ApiToken,
+    pub token: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AuditLogQuery {
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TokenLoginForm {
+    pub token: String,
+}
+
+pub async fn get_api_tokens(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<ApiToken>>>, ApiError> {
+    let tokens = ApiToken::find_all(&deployment.db().pool).await?;
+    Ok(ResponseJson(ApiResponse::success(tokens)))
+}
+
+pub async fn create_api_token(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateApiToken>,
+) -> Result<ResponseJson<ApiResponse<CreatedApiToken>>, ApiError> {
+    let created = issue_api_token(&deployment.db().pool, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(created)))
+}
+
+async fn issue_api_token(
+    pool: &SqlitePool,
+    payload: &CreateApiToken,
+) -> Result<CreatedApiToken, ApiError> {
+    if payload.name.trim().is_empty() {
+        return Err(ApiError::BadRequest(
+            "Token name must not be empty".to_string(),
+        ));
+    }
+    if payload.is_admin && payload.scope != ApiTokenScope::Api {
+        return Err(ApiError::BadRequest(
+            "Only API tokens can be admin tokens".to_string(),
+        ));
+    }
+
+    let token = generate_token();
+    let api_token =
+        ApiToken::create(pool, payload, &hash_token(&token), &token_prefix(&token)).await?;
+    tracing::info!(
+        "Created API token {} ({}, scope: {:?}, admin: {})",
+        api_token.id,
+        api_token.name,
+        api_token.scope,
+        api_token.is_admin
+    );
+
+    Ok(CreatedApiToken { api_token, token })
+}
+
+pub async fn revoke_api_token(
+    State(deployment): State<DeploymentImpl>,
+    Path(token_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let rows_affected = ApiToken::revoke(&deployment.db().pool, token_id).await?;
+    if rows_affected == 0 {
+        return Err(ApiError::Database(sqlx::Error::RowNotFound));
+    }
+    tracing::info!("Revoked API token {}", token_id);
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+/// Requests made with a token, most recent first
+pub async fn get_api_token_audit_log(
+    State(deployment): State<DeploymentImpl>,
+    Path(token_id): Path<Uuid>,
+    Query(query): Query<AuditLogQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<ApiTokenAuditEntry>>>, ApiError> {
+    let entries = find_audit_log(&deployment.db().pool, token_id, query.limit).await?;
+    Ok(ResponseJson(ApiResponse::success(entries)))
+}
+
+async fn find_audit_log(
+    pool: &SqlitePool,
+    token_id: Uuid,
+    limit: Option<i64>,
+) -> Result<Vec<ApiTokenAuditEntry>, ApiError> {
+    if ApiToken::find_by_id(pool, token_id).await?.is_none() {
+        return Err(ApiError::Database(sqlx::Error::RowNotFound));
+    }
+    let limit = limit.unwrap_or(DEFAULT_AUDIT_LIMIT).clamp(1, 1000);
+    Ok(ApiTokenAuditEntry::find_by_token_id(pool, token_id, limit).await?)
+}
+
+/// Enable the MCP Streamable HTTP endpoint with a new token, revoking the previous one
+pub async fn create_mcp_token(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<CreatedApiToken>>, ApiError> {
+    let pool = &deployment.db().pool;
+    ApiToken::revoke_scope(pool, ApiTokenScope::Mcp).await?;
+
+    let token = generate_token();
+    let api_token = ApiToken::create(
+        pool,
+        &CreateApiToken {
+            name: "MCP".to_string(),
+            is_admin: false,
+            scope: ApiTokenScope::Mcp,
+        },
+        &hash_token(&token),
+        &token_prefix(&token),
+    )
+    .await?;
+    tracing::info!("Created MCP token {}", api_token.id);
+
+    Ok(ResponseJson(ApiResponse::success(CreatedApiToken {
+        api_token,
+        token,
+    })))
+}
+
+/// Disable the MCP Streamable HTTP endpoint, unless `VK_MCP_TOKEN` is set
+pub async fn revoke_mcp_token(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let revoked = ApiToken::revoke_scope(&deployment.db().pool, ApiTokenScope::Mcp).await?;
+    if revoked > 0 {
+        tracing::info!("Revoked the MCP token");
+    }
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+pub async fn token_login_page() -> Html<&'static str> {
+    Html(TOKEN_LOGIN_PAGE)
+}
+
+/// Stores a token in a cookie and redirects to the web UI, so a browser can be used against a
+/// headless server. The token is posted from [`TOKEN_LOGIN_PAGE`] rather than passed in the URL,
+/// where it would end up in browser history and proxy logs.
+pub async fn token_login(
+    State(deployment): State<DeploymentImpl>,
+    Form(form): Form<TokenLoginForm>,
+) -> Result<Response, ApiError> {
+    login_with_token(&deployment.db().pool, form.token.trim()).await
+}
+
+async fn login_with_token(pool: &SqlitePool, token: &str) -> Result<Response, ApiError> {
+    let valid = ApiToken::find_active_by_hash(pool, &hash_token(token), ApiTokenScope::Api)
+        .await?
+        .is_some();
+    if !valid {
+        return Ok(StatusCode::UNAUTHORIZED.into_response());
+    }
+
+    let cookie = format!("{TOKEN_COOKIE}={token}; Path=/; HttpOnly; Secure; SameSite=Strict");
+    Ok(([(header::SET_COOKIE, cookie)], Redirect::to("/")).into_response())
+}
+
+/// Token administration and sign-in only exist in headless mode, where they are behind token
+/// auth. The MCP token can be managed either way.
+pub fn router(headless: bool) -> Router<DeploymentImpl> {
+    let router = Router::new().route(
+        "/mcp-token",
+        post(create_mcp_token).delete(revoke_mcp_token),
+    );
+    if !headless {
+        return router;
+    }
+
+    let admin = Router::new()
+        .route("/", get(get_api_tokens).post(create_api_token))
+        .route("/{token_id}", delete(revoke_api_token))
+        .route("/{token_id}/audit", get(get_api_token_audit_log));
+
+    router
+        .nest("/admin/api-tokens", admin)
+        .route("/auth/token", get(token_login_page).post(token_login))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_request(name: &str, is_admin: bool, scope: ApiTokenScope) -> CreateApiToken {
+        CreateApiToken {
+            name: name.to_string(),
+            is_admin,
+            scope,
+        }
+    }
+
+    #[sqlx::test(migrations = "../db/migrations")]
+    async fn creates_tokens_and_stores_only_their_hash(pool: SqlitePool) {
+        assert!(matches!(
+            issue_api_token(&pool, &create_request("  ", false, ApiTokenScope::Api)).await,
+            Err(ApiError::BadRequest(_))
+        ));
+        assert!(matches!(
+            issue_api_token(&pool, &create_request("mcp", true, ApiTokenScope::Mcp)).await,
+            Err(ApiError::BadRequest(_))
+        ));
+        assert!(ApiToken::find_all(&pool).await.unwrap().is_empty());
+
+        let created = issue_api_token(&pool, &create_request("ci", true, ApiTokenScope::Api))
+            .await
+            .unwrap();
+        assert!(created.api_token.is_admin);
+        assert!(created.token.starts_with(&created.api_token.token_prefix));
+        let stored =
+            ApiToken::find_active_by_hash(&pool, &hash_token(&created.token), ApiTokenScope::Api)
+                .await
+                .unwrap()
+                .unwrap();
+        assert_eq!(stored.id, created.api_token.id);
+        let serialized = serde_json::to_string(&ApiToken::find_all(&pool).await.unwrap()).unwrap();
+        assert!(!serialized.contains(&created.token));
+    }
+
+    #[sqlx::test(migrations = "../db/migrations")]
+    async fn audit_log_lists_requests_most_recent_first(pool: SqlitePool) {
+        let created = issue_api_token(&pool, &create_request("ci", false, ApiTokenScope::Api))
+            .await
+            .unwrap();
+        let token_id = created.api_token.id;
+        for path in ["/api/projects", "/api/tasks", "/api/config"] {
+            ApiTokenAuditEntry::create(&pool, token_id, "GET", path, 200)
+                .await
+                .unwrap();
+        }
+
+        let entries = find_audit_log(&pool, token_id, None).await.unwrap();
+        let paths: Vec<_> = entries.iter().map(|entry| entry.path.as_str()).collect();
+        assert_eq!(paths, ["/api/config", "/api/tasks", "/api/projects"]);
+        assert_eq!(
+            find_audit_log(&pool, token_id, Some(1))
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+        // Out of range limits are clamped rather than rejected
+        assert_eq!(
+            find_audit_log(&pool, token_id, Some(0))
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+
+        assert!(matches!(
+            find_audit_log(&pool, Uuid::new_v4(), None).await,
+            Err(ApiError::Database(sqlx::Error::RowNotFound))
+        ));
+    }
+
+    #[sqlx::test(migrations = "../db/migrations")]
+    async fn login_sets_a_secure_cookie_for_active_api_tokens(pool: SqlitePool) {
+        let api = issue_api_token(&pool, &create_request("ui", false, ApiTokenScope::Api))
+            .await
+            .unwrap();
+        let mcp = issue_api_token(&pool, &create_request("mcp", false, ApiTokenScope::Mcp))
+            .await
+            .unwrap();
+
+        let response = login_with_token(&pool, &api.token).await.unwrap();
+        assert_eq!(response.status(), StatusCode::SEE_OTHER);
+        let cookie = response
+            .headers()
+            .get(header::SET_COOKIE)
+            .unwrap()
+            .to_str()
+            .unwrap();
+        assert!(cookie.starts_with(&format!("{TOKEN_COOKIE}={};", api.token)));
+        for attribute in ["HttpOnly", "Secure", "SameSite=Strict"] {
+            assert!(cookie.contains(attribute), "{cookie}");
+        }
+
+        for token in [mcp.token.as_str(), "not-a-token"] {
+            let response = login_with_token(&pool, token).await.unwrap();
+            assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+            assert!(response.headers().get(header::SET_COOKIE).is_none());
+        }
+
+        // Revoked tokens can no longer sign in, and revoking twice finds nothing to revoke
+        assert_eq!(ApiToken::revoke(&pool, api.api_token.id).await.unwrap(), 1);
+        assert_eq!(ApiToken::revoke(&pool, api.api_token.id).await.unwrap(), 0);
+        let response = login_with_token(&pool, &api.token).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+}
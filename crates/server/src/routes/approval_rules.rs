@@ -0,0 +1,95 @@
+use axum::{
+    Extension, Json, Router,
+    extract::{Query, State},
+    middleware::from_fn_with_state,
+    response::Json as ResponseJson,
+    routing::get,
+};
+use db::models::approval_rule::{ApprovalRule, CreateApprovalRule, UpdateApprovalRule};
+use deployment::Deployment;
+use serde::Deserialize;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError, middleware::load_approval_rule_middleware};
+
+#[derive(Debug, Deserialize)]
+pub struct ApprovalRuleQuery {
+    /// Include the rules of this project alongside the global ones
+    pub project_id: Option<Uuid>,
+}
+
+pub async fn get_approval_rules(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<ApprovalRuleQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<ApprovalRule>>>, ApiError> {
+    let rules = ApprovalRule::find_all(&deployment.db().pool, query.project_id).await?;
+    Ok(ResponseJson(ApiResponse::success(rules)))
+}
+
+pub async fn get_approval_rule(
+    Extension(rule): Extension<ApprovalRule>,
+) -> Result<ResponseJson<ApiResponse<ApprovalRule>>, ApiError> {
+    Ok(ResponseJson(ApiResponse::success(rule)))
+}
+
+pub async fn create_approval_rule(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateApprovalRule>,
+) -> Result<ResponseJson<ApiResponse<ApprovalRule>>, ApiError> {
+    let rule = ApprovalRule::create(&deployment.db().pool, &payload).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "approval_rule_created",
+            serde_json::json!({
+                "rule_id": rule.id.to_string(),
+                "action": rule.action,
+                "project_scoped": rule.project_id.is_some(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(rule)))
+}
+
+pub async fn update_approval_rule(
+    Extension(rule): Extension<ApprovalRule>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<UpdateApprovalRule>,
+) -> Result<ResponseJson<ApiResponse<ApprovalRule>>, ApiError> {
+    let updated = ApprovalRule::update(&deployment.db().pool, rule.id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(updated)))
+}
+
+pub async fn delete_approval_rule(
+    Extension(rule): Extension<ApprovalRule>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let rows_affected = ApprovalRule::delete(&deployment.db().pool, rule.id).await?;
+    if rows_affected == 0 {
+        Err(ApiError::Database(sqlx::Error::RowNotFound))
+    } else {
+        Ok(ResponseJson(ApiResponse::success(())))
+    }
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let rule_router = Router::new()
+        .route(
+            "/",
+            get(get_approval_rule)
+                .put(update_approval_rule)
+                .delete(delete_approval_rule),
+        )
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_approval_rule_middleware,
+        ));
+
+    let inner = Router::new()
+        .route("/", get(get_approval_rules).post(create_approval_rule))
+        .nest("/{rule_id}", rule_router);
+
+    Router::new().nest("/approval-rules", inner)
+}
@@ -0,0 +1,414 @@
+use std::path::PathBuf;
+
+use axum::{
+    Extension, Json, Router,
+    extract::{Query, State},
+    middleware::from_fn_with_state,
+    response::Json as ResponseJson,
+    routing::{get, post},
+};
+use chrono::{DateTime, Utc};
+use db::models::{
+    attempt_group::{AttemptGroup, AttemptGroupMember},
+    execution_process::{
+        ExecutionFailureReason, ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus,
+    },
+    session::Session,
+    task::Task,
+    workspace::Workspace,
+    workspace_repo::{CreateWorkspaceRepo, WorkspaceRepo},
+};
+use deployment::Deployment;
+use executors::profile::ExecutorProfileId;
+use serde::{Deserialize, Serialize};
+use services::services::{container::ContainerService, git::DiffTarget};
+use sqlx::Error as SqlxError;
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl, error::ApiError, middleware::load_attempt_group_middleware,
+    routes::task_attempts::WorkspaceRepoInput,
+};
+
+#[derive(Debug, Deserialize)]
+pub struct AttemptGroupQuery {
+    pub task_id: Uuid,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct CreateAttemptGroupBody {
+    pub task_id: Uuid,
+    /// One workspace is started per profile; the same profile may appear more than once
+    pub executor_profile_ids: Vec<ExecutorProfileId>,
+    pub repos: Vec<WorkspaceRepoInput>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct SelectAttemptWinnerRequest {
+    pub workspace_id: Uuid,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct AttemptGroupWithMembers {
+    #[serde(flatten)]
+    pub group: AttemptGroup,
+    pub members: Vec<AttemptGroupMember>,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct AttemptDiffStats {
+    pub files_changed: usize,
+    pub additions: usize,
+    pub deletions: usize,
+}
+
+/// Latest run of one kind of process in an attempt
+#[derive(Debug, Serialize, TS)]
+pub struct AttemptProcessOutcome {
+    pub status: ExecutionProcessStatus,
+    pub exit_code: Option<i64>,
+    pub failure_reason: Option<ExecutionFailureReason>,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct AttemptSummary {
+    pub workspace: Workspace,
+    pub executor_profile_id: ExecutorProfileId,
+    pub is_winner: bool,
+    pub archived_at: Option<DateTime<Utc>>,
+    /// Changes against each repo's target branch, summed over repos. `None` once the
+    /// worktree has been archived or if it has not been created yet.
+    pub diff_stats: Option<AttemptDiffStats>,
+    pub setup_script: Option<AttemptProcessOutcome>,
+    pub coding_agent: Option<AttemptProcessOutcome>,
+    pub cleanup_script: Option<AttemptProcessOutcome>,
+    /// From the first process starting to the last one finishing, excluding dev servers.
+    /// `None` while a process is still running.
+    pub duration_secs: Option<i64>,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct AttemptComparison {
+    pub group: AttemptGroup,
+    pub attempts: Vec<AttemptSummary>,
+}
+
+pub async fn get_attempt_groups(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<AttemptGroupQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<AttemptGroup>>>, ApiError> {
+    let groups = AttemptGroup::find_by_task_id(&deployment.db().pool, query.task_id).await?;
+    Ok(ResponseJson(ApiResponse::success(groups)))
+}
+
+pub async fn get_attempt_group(
+    Extension(group): Extension<AttemptGroup>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<AttemptGroupWithMembers>>, ApiError> {
+    let members = AttemptGroupMember::find_by_group_id(&deployment.db().pool, group.id).await?;
+    Ok(ResponseJson(ApiResponse::success(
+        AttemptGroupWithMembers { group, members },
+    )))
+}
+
+/// Start one workspace per executor profile for the same task and repos
+pub async fn create_attempt_group(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateAttemptGroupBody>,
+) -> Result<ResponseJson<ApiResponse<AttemptGroupWithMembers>>, ApiError> {
+    if payload.executor_profile_ids.len() < 2 {
+        return Err(ApiError::BadRequest(
+            "At least two executor profiles are required".to_string(),
+        ));
+    }
+    if payload.repos.is_empty() {
+        return Err(ApiError::BadRequest(
+            "At least one repository is required".to_string(),
+        ));
+    }
+
+    let pool = &deployment.db().pool;
+    let task = Task::find_by_id(pool, payload.task_id)
+        .await?
+        .ok_or(SqlxError::RowNotFound)?;
+
+    let workspace_repos: Vec<CreateWorkspaceRepo> = payload
+        .repos
+        .iter()
+        .map(|r| CreateWorkspaceRepo {
+            repo_id: r.repo_id,
+            target_branch: r.target_branch.clone(),
+        })
+        .collect();
+
+    let group = AttemptGroup::create(pool, task.id).await?;
+    let mut members = Vec::with_capacity(payload.executor_profile_ids.len());
+
+    for executor_profile_id in &payload.executor_profile_ids {
+        let workspace = deployment
+            .container()
+            .create_workspace_for_task(&task, &workspace_repos)
+            .await?;
+        members.push(
+            AttemptGroupMember::create(pool, group.id, workspace.id, executor_profile_id).await?,
+        );
+
+        if let Err(err) = deployment
+            .container()
+            .start_workspace(&workspace, executor_profile_id.clone())
+            .await
+        {
+            tracing::error!(
+                "Failed to start attempt {} of group {}: {}",
+                workspace.id,
+                group.id,
+                err
+            );
+        }
+    }
+
+    deployment
+        .track_if_analytics_allowed(
+            "attempt_group_started",
+            serde_json::json!({
+                "task_id": task.id.to_string(),
+                "group_id": group.id.to_string(),
+                "attempt_count": members.len(),
+                "executors": payload
+                    .executor_profile_ids
+                    .iter()
+                    .map(|p| &p.executor)
+                    .collect::<Vec<_>>(),
+                "repository_count": payload.repos.len(),
+            }),
+        )
+        .await;
+
+    tracing::info!(
+        "Started {} attempts for task {} in group {}",
+        members.len(),
+        task.id,
+        group.id
+    );
+
+    Ok(ResponseJson(ApiResponse::success(
+        AttemptGroupWithMembers { group, members },
+    )))
+}
+
+pub async fn get_attempt_comparison(
+    Extension(group): Extension<AttemptGroup>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<AttemptComparison>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let members = AttemptGroupMember::find_by_group_id(pool, group.id).await?;
+
+    let mut attempts = Vec::with_capacity(members.len());
+    for member in members {
+        let Some(workspace) = Workspace::find_by_id(pool, member.workspace_id).await? else {
+            continue;
+        };
+
+        let diff_stats = if member.archived_at.is_none() {
+            attempt_diff_stats(&deployment, &workspace).await
+        } else {
+            None
+        };
+
+        let outcome = |process: Option<ExecutionProcess>| {
+            process.map(|p| AttemptProcessOutcome {
+                status: p.status,
+                exit_code: p.exit_code,
+                failure_reason: p.failure_reason,
+            })
+        };
+        let setup_script = ExecutionProcess::find_latest_by_workspace_and_run_reason(
+            pool,
+            workspace.id,
+            &ExecutionProcessRunReason::SetupScript,
+        )
+        .await?;
+        let coding_agent = ExecutionProcess::find_latest_by_workspace_and_run_reason(
+            pool,
+            workspace.id,
+            &ExecutionProcessRunReason::CodingAgent,
+        )
+        .await?;
+        let cleanup_script = ExecutionProcess::find_latest_by_workspace_and_run_reason(
+            pool,
+            workspace.id,
+            &ExecutionProcessRunReason::CleanupScript,
+        )
+        .await?;
+
+        let duration_secs = attempt_duration_secs(&deployment, workspace.id).await?;
+
+        attempts.push(AttemptSummary {
+            is_winner: group.winner_workspace_id == Some(workspace.id),
+            executor_profile_id: member.executor_profile_id.0,
+            archived_at: member.archived_at,
+            diff_stats,
+            setup_script: outcome(setup_script),
+            coding_agent: outcome(coding_agent),
+            cleanup_script: outcome(cleanup_script),
+            duration_secs,
+            workspace,
+        });
+    }
+
+    Ok(ResponseJson(ApiResponse::success(AttemptComparison {
+        group,
+        attempts,
+    })))
+}
+
+/// Keep one attempt and clean up the worktrees of all the others. Selecting the same winner
+/// again retries the attempts that could not be cleaned up the first time.
+pub async fn select_attempt_winner(
+    Extension(group): Extension<AttemptGroup>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<SelectAttemptWinnerRequest>,
+) -> Result<ResponseJson<ApiResponse<AttemptGroupWithMembers>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let group = AttemptGroup::set_winner(pool, group.id, payload.workspace_id).await?;
+
+    let members = AttemptGroupMember::find_by_group_id(pool, group.id).await?;
+    for member in members
+        .iter()
+        .filter(|m| m.workspace_id != payload.workspace_id && m.archived_at.is_none())
+    {
+        if let Some(workspace) = Workspace::find_by_id(pool, member.workspace_id).await? {
+            if let Err(err) = deployment.container().delete(&workspace).await {
+                tracing::error!(
+                    "Failed to archive attempt {} of group {}: {}",
+                    workspace.id,
+                    group.id,
+                    err
+                );
+                continue;
+            }
+        }
+        AttemptGroupMember::mark_archived(pool, group.id, member.workspace_id).await?;
+    }
+
+    deployment
+        .track_if_analytics_allowed(
+            "attempt_group_winner_selected",
+            serde_json::json!({
+                "task_id": group.task_id.to_string(),
+                "group_id": group.id.to_string(),
+                "workspace_id": payload.workspace_id.to_string(),
+            }),
+        )
+        .await;
+
+    let members = AttemptGroupMember::find_by_group_id(pool, group.id).await?;
+    Ok(ResponseJson(ApiResponse::success(
+        AttemptGroupWithMembers { group, members },
+    )))
+}
+
+async fn attempt_diff_stats(
+    deployment: &DeploymentImpl,
+    workspace: &Workspace,
+) -> Option<AttemptDiffStats> {
+    let workspace_dir = PathBuf::from(workspace.container_ref.as_ref()?);
+    let repos = match WorkspaceRepo::find_repos_with_target_branch_for_workspace(
+        &deployment.db().pool,
+        workspace.id,
+    )
+    .await
+    {
+        Ok(repos) => repos,
+        Err(e) => {
+            tracing::warn!("Failed to load repos for workspace {}: {}", workspace.id, e);
+            return None;
+        }
+    };
+
+    let mut stats = AttemptDiffStats {
+        files_changed: 0,
+        additions: 0,
+        deletions: 0,
+    };
+    for repo in repos {
+        let worktree_path = workspace_dir.join(&repo.repo.name);
+        let diffs = deployment
+            .git()
+            .get_base_commit(&repo.repo.path, &workspace.branch, &repo.target_branch)
+            .and_then(|base_commit| {
+                deployment.git().get_diffs(
+                    DiffTarget::Worktree {
+                        worktree_path: &worktree_path,
+                        base_commit: &base_commit,
+                    },
+                    None,
+                )
+            });
+        match diffs {
+            Ok(diffs) => {
+                stats.files_changed += diffs.len();
+                for diff in diffs {
+                    stats.additions += diff.additions.unwrap_or(0);
+                    stats.deletions += diff.deletions.unwrap_or(0);
+                }
+            }
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to diff repo {} of workspace {}: {}",
+                    repo.repo.name,
+                    workspace.id,
+                    e
+                );
+                return None;
+            }
+        }
+    }
+    Some(stats)
+}
+
+async fn attempt_duration_secs(
+    deployment: &DeploymentImpl,
+    workspace_id: Uuid,
+) -> Result<Option<i64>, ApiError> {
+    let pool = &deployment.db().pool;
+    let mut started_at: Option<DateTime<Utc>> = None;
+    let mut completed_at: Option<DateTime<Utc>> = None;
+
+    for session in Session::find_by_workspace_id(pool, workspace_id).await? {
+        for process in ExecutionProcess::find_by_session_id(pool, session.id, false).await? {
+            if process.run_reason == ExecutionProcessRunReason::DevServer {
+                continue;
+            }
+            let Some(process_completed_at) = process.completed_at else {
+                return Ok(None);
+            };
+            started_at = Some(started_at.map_or(process.started_at, |t| t.min(process.started_at)));
+            completed_at =
+                Some(completed_at.map_or(process_completed_at, |t| t.max(process_completed_at)));
+        }
+    }
+
+    Ok(started_at
+        .zip(completed_at)
+        .map(|(start, end)| (end - start).num_seconds()))
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let group_router = Router::new()
+        .route("/", get(get_attempt_group))
+        .route("/comparison", get(get_attempt_comparison))
+        .route("/winner", post(select_attempt_winner))
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_attempt_group_middleware,
+        ));
+
+    let inner = Router::new()
+        .route("/", get(get_attempt_groups).post(create_attempt_group))
+        .nest("/{group_id}", group_router);
+
+    Router::new().nest("/attempt-groups", inner)
+}
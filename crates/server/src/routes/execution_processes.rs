@@ -14,6 +14,7 @@ use db::models::{
     execution_process_repo_state::ExecutionProcessRepoState,
 };
 use deployment::Deployment;
+use executors::logs::{NormalizedEntry, utils::patch::fold_normalized_entries};
 use futures_util::{SinkExt, StreamExt, TryStreamExt};
 use serde::Deserialize;
 use services::services::container::ContainerService;
@@ -30,6 +31,24 @@ pub struct ExecutionProcessQuery {
     pub show_soft_deleted: Option<bool>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct SessionProcessesQuery {
+    pub session_id: Uuid,
+    #[serde(default)]
+    pub show_soft_deleted: bool,
+}
+
+pub async fn get_execution_processes(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<SessionProcessesQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<ExecutionProcess>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let processes =
+        ExecutionProcess::find_by_session_id(pool, query.session_id, query.show_soft_deleted)
+            .await?;
+    Ok(ResponseJson(ApiResponse::success(processes)))
+}
+
 pub async fn get_execution_process_by_id(
     Extension(execution_process): Extension<ExecutionProcess>,
     State(_deployment): State<DeploymentImpl>,
@@ -166,6 +185,37 @@ async fn handle_normalized_logs_ws(
     Ok(())
 }
 
+/// Snapshot of the normalized conversation an execution process has produced so far
+pub async fn get_normalized_logs(
+    Extension(execution_process): Extension<ExecutionProcess>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<NormalizedEntry>>>, ApiError> {
+    let container = deployment.container();
+    let messages = if let Some(store) = container.get_msg_store_by_id(&execution_process.id).await {
+        store.get_history()
+    } else {
+        // Stored logs are re-normalized on demand; the stream ends with `Finished` once done
+        let mut stream = container
+            .stream_normalized_logs(&execution_process.id)
+            .await
+            .ok_or_else(|| {
+                ApiError::ExecutionProcess(ExecutionProcessError::ExecutionProcessNotFound)
+            })?;
+        let mut messages = Vec::new();
+        while let Some(Ok(msg)) = stream.next().await {
+            if matches!(msg, LogMsg::Finished) {
+                break;
+            }
+            messages.push(msg);
+        }
+        messages
+    };
+
+    Ok(ResponseJson(ApiResponse::success(fold_normalized_entries(
+        &messages,
+    ))))
+}
+
 pub async fn stop_execution_process(
     Extension(execution_process): Extension<ExecutionProcess>,
     State(deployment): State<DeploymentImpl>,
@@ -249,6 +299,7 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         .route("/stop", post(stop_execution_process))
         .route("/repo-states", get(get_execution_process_repo_states))
         .route("/raw-logs/ws", get(stream_raw_logs_ws))
+        .route("/normalized-logs", get(get_normalized_logs))
         .route("/normalized-logs/ws", get(stream_normalized_logs_ws))
         .layer(from_fn_with_state(
             deployment.clone(),
@@ -256,6 +307,7 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         ));
 
     let workspaces_router = Router::new()
+        .route("/", get(get_execution_processes))
         .route("/stream/ws", get(stream_execution_processes_ws))
         .nest("/{id}", workspace_id_router);
 
@@ -1,11 +1,19 @@
 use axum::{
     Router,
+    middleware::from_fn_with_state,
     routing::{IntoMakeService, get},
 };
 
-use crate::DeploymentImpl;
+use crate::{
+    DeploymentImpl, mcp,
+    middleware::auth::{ApiAuth, require_api_token},
+};
 
+pub mod analytics;
+pub mod api_tokens;
+pub mod approval_rules;
 pub mod approvals;
+pub mod attempt_groups;
 pub mod config;
 pub mod containers;
 pub mod filesystem;
@@ -20,13 +28,24 @@ pub mod organizations;
 pub mod projects;
 pub mod repo;
 pub mod scratch;
+pub mod search;
 pub mod sessions;
 pub mod shared_tasks;
 pub mod tags;
 pub mod task_attempts;
+pub mod task_schedules;
 pub mod tasks;
+pub mod usage;
+pub mod webhooks;
 
-pub fn router(deployment: DeploymentImpl) -> IntoMakeService<Router> {
+/// `backend_url` is where the server itself can be reached, for the MCP endpoint to call back into.
+/// With `auth` set (headless mode), every API request, including WebSockets, needs a token.
+pub fn router(
+    deployment: DeploymentImpl,
+    backend_url: String,
+    auth: Option<ApiAuth>,
+) -> IntoMakeService<Router> {
+    let internal_token = auth.as_ref().map(|auth| auth.internal_token().to_string());
     // Create routers with different middleware layers
     let base_routes = Router::new()
         .route("/health", get(health::health_check))
@@ -36,18 +55,35 @@ pub fn router(deployment: DeploymentImpl) -> IntoMakeService<Router> {
         .merge(tasks::router(&deployment))
         .merge(shared_tasks::router())
         .merge(task_attempts::router(&deployment))
+        .merge(attempt_groups::router(&deployment))
         .merge(execution_processes::router(&deployment))
         .merge(tags::router(&deployment))
+        .merge(task_schedules::router(&deployment))
         .merge(oauth::router())
         .merge(organizations::router())
         .merge(filesystem::router())
         .merge(repo::router())
         .merge(events::router(&deployment))
         .merge(approvals::router())
+        .merge(approval_rules::router(&deployment))
         .merge(scratch::router(&deployment))
+        .merge(search::router())
         .merge(sessions::router(&deployment))
-        .nest("/images", images::routes())
-        .with_state(deployment);
+        .merge(webhooks::router(&deployment))
+        .merge(usage::router())
+        .merge(analytics::router())
+        .merge(api_tokens::router(auth.is_some()))
+        .merge(mcp::http::router(&deployment, backend_url, internal_token))
+        .nest("/images", images::routes());
+
+    let base_routes = match auth {
+        Some(auth) => base_routes.layer(from_fn_with_state(
+            (deployment.clone(), auth),
+            require_api_token,
+        )),
+        None => base_routes,
+    }
+    .with_state(deployment);
 
     Router::new()
         .route("/", get(frontend::serve_frontend_root))
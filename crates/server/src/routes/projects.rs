@@ -4,12 +4,12 @@ use anyhow;
 use axum::{
     Extension, Json, Router,
     extract::{
-        Path, Query, State,
+        DefaultBodyLimit, Multipart, Path, Query, State,
         ws::{WebSocket, WebSocketUpgrade},
     },
-    http::StatusCode,
+    http::{StatusCode, header},
     middleware::from_fn_with_state,
-    response::{IntoResponse, Json as ResponseJson},
+    response::{IntoResponse, Json as ResponseJson, Response},
     routing::{get, post},
 };
 use db::models::{
@@ -21,7 +21,12 @@ use deployment::Deployment;
 use futures_util::{SinkExt, StreamExt, TryStreamExt};
 use serde::Deserialize;
 use services::services::{
-    file_search_cache::SearchQuery, project::ProjectServiceError,
+    file_search_cache::SearchQuery,
+    project::ProjectServiceError,
+    project_bundle::{
+        ExportProjectOptions, ImportProjectOptions, ProjectBundle, ProjectBundleSummary,
+        export_project, import_project,
+    },
     remote_client::CreateRemoteProjectPayload,
 };
 use ts_rs::TS;
@@ -582,6 +587,112 @@ pub async fn update_project_repository(
     }
 }
 
+/// Bundles can carry execution logs and images, so allow much larger uploads than images
+const BUNDLE_UPLOAD_LIMIT: usize = 512 * 1024 * 1024;
+
+/// Downloads the project as a `.tar.gz` bundle that can be imported on another machine
+pub async fn export_project_bundle(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Query(options): Query<ExportProjectOptions>,
+) -> Result<Response, ApiError> {
+    let bundle = export_project(
+        &deployment.db().pool,
+        deployment.image(),
+        &project,
+        &options,
+    )
+    .await?;
+    let archive = tokio::task::spawn_blocking(move || bundle.to_archive())
+        .await
+        .map_err(|e| ApiError::Io(std::io::Error::other(e)))??;
+
+    let file_name: String = project
+        .name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/gzip".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{file_name}.vkbundle.tar.gz\""),
+            ),
+        ],
+        archive,
+    )
+        .into_response())
+}
+
+/// Reads the `bundle` file and optional `options` JSON field of an import upload
+async fn read_bundle_upload(
+    mut multipart: Multipart,
+) -> Result<(ProjectBundle, ImportProjectOptions), ApiError> {
+    let mut data = None;
+    let mut options = ImportProjectOptions::default();
+    while let Some(field) = multipart.next_field().await? {
+        match field.name() {
+            Some("bundle") => data = Some(field.bytes().await?),
+            Some("options") => {
+                options = serde_json::from_str(&field.text().await?)
+                    .map_err(|e| ApiError::BadRequest(format!("Invalid import options: {e}")))?;
+            }
+            _ => {}
+        }
+    }
+    let data = data.ok_or_else(|| ApiError::BadRequest("Missing bundle file".to_string()))?;
+    let bundle = tokio::task::spawn_blocking(move || ProjectBundle::from_archive(&data))
+        .await
+        .map_err(|e| ApiError::Io(std::io::Error::other(e)))??;
+    Ok((bundle, options))
+}
+
+/// Describes a bundle without importing it, so repository paths can be remapped first
+pub async fn preview_project_bundle(
+    multipart: Multipart,
+) -> Result<ResponseJson<ApiResponse<ProjectBundleSummary>>, ApiError> {
+    let (bundle, _) = read_bundle_upload(multipart).await?;
+    Ok(ResponseJson(ApiResponse::success(
+        bundle.manifest.summary(),
+    )))
+}
+
+pub async fn import_project_bundle(
+    State(deployment): State<DeploymentImpl>,
+    multipart: Multipart,
+) -> Result<ResponseJson<ApiResponse<Project>>, ApiError> {
+    let (bundle, options) = read_bundle_upload(multipart).await?;
+    let project = import_project(
+        &deployment.db().pool,
+        deployment.project(),
+        deployment.repo(),
+        deployment.image(),
+        &bundle,
+        &options,
+    )
+    .await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "project_imported",
+            serde_json::json!({
+                "project_id": project.id.to_string(),
+                "task_count": bundle.manifest.tasks.len(),
+                "import_history": options.import_history,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(project)))
+}
+
 pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     let project_id_router = Router::new()
         .route(
@@ -591,6 +702,7 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         .route("/remote/members", get(get_project_remote_members))
         .route("/search", get(search_project_files))
         .route("/open-editor", post(open_project_in_editor))
+        .route("/export", get(export_project_bundle))
         .route(
             "/link",
             post(link_project_to_existing_remote).delete(unlink_project),
@@ -614,6 +726,14 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
                 .delete(delete_project_repository),
         )
         .route("/stream/ws", get(stream_projects_ws))
+        .route(
+            "/import",
+            post(import_project_bundle).layer(DefaultBodyLimit::max(BUNDLE_UPLOAD_LIMIT)),
+        )
+        .route(
+            "/import/preview",
+            post(preview_project_bundle).layer(DefaultBodyLimit::max(BUNDLE_UPLOAD_LIMIT)),
+        )
         .nest("/{id}", project_id_router);
 
     Router::new().nest("/projects", projects_router).route(
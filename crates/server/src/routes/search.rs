@@ -0,0 +1,30 @@
+use axum::{
+    Router,
+    extract::{Query, State},
+    response::Json as ResponseJson,
+    routing::get,
+};
+use db::models::search::{FullTextSearchQuery, SearchHit};
+use deployment::Deployment;
+use utils::response::ApiResponse;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+/// Full-text search over tasks, coding agent prompts/summaries and agent conversations
+pub async fn search(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<FullTextSearchQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<SearchHit>>>, ApiError> {
+    if query.q.trim().is_empty() {
+        return Err(ApiError::BadRequest(
+            "Query parameter 'q' is required and cannot be empty".to_string(),
+        ));
+    }
+
+    let hits = SearchHit::search(&deployment.db().pool, &query).await?;
+    Ok(ResponseJson(ApiResponse::success(hits)))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new().route("/search", get(search))
+}
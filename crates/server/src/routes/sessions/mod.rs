@@ -21,7 +21,7 @@ use executors::{
     },
     profile::ExecutorProfileId,
 };
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use services::services::container::ContainerService;
 use sqlx::Error as SqlxError;
 use ts_rs::TS;
@@ -85,7 +85,7 @@ pub async fn create_session(
     Ok(ResponseJson(ApiResponse::success(session)))
 }
 
-#[derive(Debug, Deserialize, TS)]
+#[derive(Debug, Deserialize, Serialize, TS)]
 pub struct CreateFollowUpAttempt {
     pub prompt: String,
     pub variant: Option<String>,
@@ -1,24 +1,39 @@
 use axum::{
-    Extension, Json, Router, extract::State, middleware::from_fn_with_state,
-    response::Json as ResponseJson, routing::get,
+    Extension, Json, Router,
+    extract::{Path, State},
+    middleware::from_fn_with_state,
+    response::Json as ResponseJson,
+    routing::{get, put},
+};
+use db::models::{
+    scratch::{DraftFollowUpData, Scratch, ScratchType},
+    session::Session,
 };
-use db::models::{scratch::DraftFollowUpData, session::Session};
 use deployment::Deployment;
 use serde::Deserialize;
 use services::services::queued_message::QueueStatus;
 use ts_rs::TS;
 use utils::response::ApiResponse;
+use uuid::Uuid;
 
 use crate::{DeploymentImpl, error::ApiError, middleware::load_session_middleware};
 
-/// Request body for queueing a follow-up message
+/// Request body for queueing or editing a follow-up message
 #[derive(Debug, Deserialize, TS)]
 pub struct QueueMessageRequest {
     pub message: String,
     pub variant: Option<String>,
 }
 
-/// Queue a follow-up message to be executed when the current execution finishes
+/// Request body for reordering a session's queue
+#[derive(Debug, Deserialize, TS)]
+pub struct ReorderQueueRequest {
+    /// Every queued message id of the session, in the new order
+    pub message_ids: Vec<Uuid>,
+}
+
+/// Append a follow-up message to be executed after the queued ones once the current
+/// execution finishes
 pub async fn queue_message(
     Extension(session): Extension<Session>,
     State(deployment): State<DeploymentImpl>,
@@ -29,9 +44,18 @@ pub async fn queue_message(
         variant: payload.variant,
     };
 
-    let queued = deployment
+    deployment
         .queued_message_service()
-        .queue_message(session.id, data);
+        .queue_message(session.id, data)
+        .await?;
+
+    // The draft now lives in the queue, so the editor starts empty for the next message
+    Scratch::delete(
+        &deployment.db().pool,
+        session.id,
+        &ScratchType::DraftFollowUp,
+    )
+    .await?;
 
     deployment
         .track_if_analytics_allowed(
@@ -43,19 +67,22 @@ pub async fn queue_message(
         )
         .await;
 
-    Ok(ResponseJson(ApiResponse::success(QueueStatus::Queued {
-        message: queued,
-    })))
+    let status = deployment
+        .queued_message_service()
+        .get_status(session.id)
+        .await?;
+    Ok(ResponseJson(ApiResponse::success(status)))
 }
 
-/// Cancel a queued follow-up message
+/// Cancel every queued follow-up message
 pub async fn cancel_queued_message(
     Extension(session): Extension<Session>,
     State(deployment): State<DeploymentImpl>,
 ) -> Result<ResponseJson<ApiResponse<QueueStatus>>, ApiError> {
     deployment
         .queued_message_service()
-        .cancel_queued(session.id);
+        .cancel_queued(session.id)
+        .await?;
 
     deployment
         .track_if_analytics_allowed(
@@ -75,11 +102,62 @@ pub async fn get_queue_status(
     Extension(session): Extension<Session>,
     State(deployment): State<DeploymentImpl>,
 ) -> Result<ResponseJson<ApiResponse<QueueStatus>>, ApiError> {
-    let status = deployment.queued_message_service().get_status(session.id);
+    let status = deployment
+        .queued_message_service()
+        .get_status(session.id)
+        .await?;
 
     Ok(ResponseJson(ApiResponse::success(status)))
 }
 
+/// Change the order in which queued messages will be sent
+pub async fn reorder_queue(
+    Extension(session): Extension<Session>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<ReorderQueueRequest>,
+) -> Result<ResponseJson<ApiResponse<QueueStatus>>, ApiError> {
+    let service = deployment.queued_message_service();
+    service.reorder(session.id, &payload.message_ids).await?;
+
+    Ok(ResponseJson(ApiResponse::success(
+        service.get_status(session.id).await?,
+    )))
+}
+
+/// Edit the content of a queued message
+pub async fn update_queued_message(
+    Extension(session): Extension<Session>,
+    State(deployment): State<DeploymentImpl>,
+    Path((_session_id, message_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<QueueMessageRequest>,
+) -> Result<ResponseJson<ApiResponse<QueueStatus>>, ApiError> {
+    let data = DraftFollowUpData {
+        message: payload.message,
+        variant: payload.variant,
+    };
+
+    let service = deployment.queued_message_service();
+    service.update_queued(session.id, message_id, data).await?;
+
+    Ok(ResponseJson(ApiResponse::success(
+        service.get_status(session.id).await?,
+    )))
+}
+
+/// Remove a single message from the queue
+pub async fn delete_queued_message(
+    Extension(session): Extension<Session>,
+    State(deployment): State<DeploymentImpl>,
+    Path((_session_id, message_id)): Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<QueueStatus>>, ApiError> {
+    let service = deployment.queued_message_service();
+    service.remove_queued(session.id, message_id).await?;
+
+    Ok(ResponseJson(ApiResponse::success(
+        service.get_status(session.id).await?,
+    )))
+}
+
 pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     Router::new()
         .route(
@@ -88,6 +166,11 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
                 .post(queue_message)
                 .delete(cancel_queued_message),
         )
+        .route("/order", put(reorder_queue))
+        .route(
+            "/{message_id}",
+            put(update_queued_message).delete(delete_queued_message),
+        )
         .layer(from_fn_with_state(
             deployment.clone(),
             load_session_middleware,
@@ -3,11 +3,14 @@ pub mod cursor_setup;
 pub mod gh_cli_setup;
 pub mod images;
 pub mod pr;
+pub mod quality_gates;
+pub mod stack;
 pub mod util;
 
 use std::{
     collections::HashMap,
     path::{Path, PathBuf},
+    sync::{Arc, atomic::AtomicUsize},
 };
 
 use axum::{
@@ -28,6 +31,7 @@ use db::models::{
     repo::{Repo, RepoError},
     session::{CreateSession, Session},
     task::{Task, TaskRelationships, TaskStatus},
+    task_event::TaskEventActor,
     workspace::{CreateWorkspace, Workspace, WorkspaceError},
     workspace_repo::{CreateWorkspaceRepo, RepoWithTargetBranch, WorkspaceRepo},
 };
@@ -44,12 +48,13 @@ use git2::BranchType;
 use serde::{Deserialize, Serialize};
 use services::services::{
     container::ContainerService,
-    git::{ConflictOp, GitCliError, GitServiceError},
-    github::GitHubService,
+    diff_stream::apply_stream_omit_policy,
+    git::{ConflictOp, DiffTarget, GitCliError, GitServiceError},
+    quality_gate::QualityGateError,
 };
 use sqlx::Error as SqlxError;
 use ts_rs::TS;
-use utils::response::ApiResponse;
+use utils::{diff::Diff, response::ApiResponse};
 use uuid::Uuid;
 
 use crate::{
@@ -62,6 +67,11 @@ pub struct RebaseTaskAttemptRequest {
     pub repo_id: Uuid,
     pub old_base_branch: Option<String>,
     pub new_base_branch: Option<String>,
+    /// On conflicts, ask the coding agent to resolve them and continue the rebase, aborting it
+    /// if the agent fails
+    #[serde(default)]
+    #[ts(optional)]
+    pub auto_resolve_conflicts: Option<bool>,
 }
 
 #[derive(Debug, Deserialize, Serialize, TS)]
@@ -73,8 +83,17 @@ pub struct AbortConflictsRequest {
 #[serde(tag = "type", rename_all = "snake_case")]
 #[ts(tag = "type", rename_all = "snake_case")]
 pub enum GitOperationError {
-    MergeConflicts { message: String, op: ConflictOp },
+    MergeConflicts {
+        message: String,
+        op: ConflictOp,
+    },
     RebaseInProgress,
+    /// The conflicts were handed to the coding agent; the operation continues when it finishes
+    ConflictResolutionStarted {
+        message: String,
+        op: ConflictOp,
+        execution_process_id: Uuid,
+    },
 }
 
 #[derive(Debug, Deserialize)]
@@ -238,6 +257,53 @@ pub async fn run_agent_setup(
     Ok(ResponseJson(ApiResponse::success(RunAgentSetupResponse {})))
 }
 
+/// Snapshot of the workspace diff against each repository's target branch.
+/// Paths are prefixed with the repository name, as in the diff stream.
+pub async fn get_task_attempt_diff(
+    Query(params): Query<DiffStreamQuery>,
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<Diff>>>, ApiError> {
+    let pool = &deployment.db().pool;
+
+    let repositories =
+        WorkspaceRepo::find_repos_with_target_branch_for_workspace(pool, workspace.id).await?;
+    let container_ref = deployment
+        .container()
+        .ensure_container_exists(&workspace)
+        .await?;
+    let workspace_dir = PathBuf::from(&container_ref);
+
+    let sent_bytes = Arc::new(AtomicUsize::new(0));
+    let mut diffs = Vec::new();
+    for RepoWithTargetBranch {
+        repo,
+        target_branch,
+    } in repositories
+    {
+        let base_commit =
+            deployment
+                .git()
+                .get_base_commit(&repo.path, &workspace.branch, &target_branch)?;
+        let worktree_path = workspace_dir.join(&repo.name);
+        let repo_diffs = deployment.git().get_diffs(
+            DiffTarget::Worktree {
+                worktree_path: &worktree_path,
+                base_commit: &base_commit,
+            },
+            None,
+        )?;
+        for mut diff in repo_diffs {
+            apply_stream_omit_policy(&mut diff, &sent_bytes, params.stats_only);
+            diff.old_path = diff.old_path.map(|p| format!("{}/{p}", repo.name));
+            diff.new_path = diff.new_path.map(|p| format!("{}/{p}", repo.name));
+            diffs.push(diff);
+        }
+    }
+
+    Ok(ResponseJson(ApiResponse::success(diffs)))
+}
+
 #[axum::debug_handler]
 pub async fn stream_task_attempt_diff_ws(
     ws: WebSocketUpgrade,
@@ -315,7 +381,7 @@ pub async fn merge_task_attempt(
     Extension(workspace): Extension<Workspace>,
     State(deployment): State<DeploymentImpl>,
     Json(request): Json<MergeTaskAttemptRequest>,
-) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+) -> Result<ResponseJson<ApiResponse<(), QualityGateError>>, ApiError> {
     let pool = &deployment.db().pool;
 
     let workspace_repo =
@@ -334,6 +400,14 @@ pub async fn merge_task_attempt(
     let workspace_path = Path::new(&container_ref);
     let worktree_path = workspace_path.join(repo.name);
 
+    if let Some(gate_error) = deployment
+        .container()
+        .check_quality_gate(&workspace, workspace_repo.repo_id)
+        .await?
+    {
+        return Ok(ResponseJson(ApiResponse::error_with_data(gate_error)));
+    }
+
     let task = workspace
         .parent_task(pool)
         .await?
@@ -367,7 +441,7 @@ pub async fn merge_task_attempt(
         &merge_commit_id,
     )
     .await?;
-    Task::update_status(pool, task.id, TaskStatus::Done).await?;
+    Task::update_status(pool, task.id, TaskStatus::Done, TaskEventActor::User).await?;
 
     // Stop any running dev servers for this workspace
     let dev_servers =
@@ -410,6 +484,14 @@ pub async fn merge_task_attempt(
         );
     }
 
+    if let Err(e) = deployment
+        .container()
+        .start_unblocked_dependents(task.id)
+        .await
+    {
+        tracing::error!("Failed to start tasks unblocked by task {}: {}", task.id, e);
+    }
+
     deployment
         .track_if_analytics_allowed(
             "task_attempt_merged",
@@ -430,9 +512,6 @@ pub async fn push_task_attempt_branch(
 ) -> Result<ResponseJson<ApiResponse<(), PushError>>, ApiError> {
     let pool = &deployment.db().pool;
 
-    let github_service = GitHubService::new()?;
-    github_service.check_token().await?;
-
     let workspace_repo =
         WorkspaceRepo::find_by_workspace_and_repo_id(pool, workspace.id, request.repo_id)
             .await?
@@ -442,6 +521,9 @@ pub async fn push_task_attempt_branch(
         .await?
         .ok_or(RepoError::NotFound)?;
 
+    let (_, forge) = pr::resolve_forge(&deployment, &repo.path).await?;
+    forge.check_auth().await?;
+
     let container_ref = deployment
         .container()
         .ensure_container_exists(&workspace)
@@ -468,9 +550,6 @@ pub async fn force_push_task_attempt_branch(
 ) -> Result<ResponseJson<ApiResponse<(), PushError>>, ApiError> {
     let pool = &deployment.db().pool;
 
-    let github_service = GitHubService::new()?;
-    github_service.check_token().await?;
-
     let workspace_repo =
         WorkspaceRepo::find_by_workspace_and_repo_id(pool, workspace.id, request.repo_id)
             .await?
@@ -480,6 +559,9 @@ pub async fn force_push_task_attempt_branch(
         .await?
         .ok_or(RepoError::NotFound)?;
 
+    let (_, forge) = pr::resolve_forge(&deployment, &repo.path).await?;
+    forge.check_auth().await?;
+
     let container_ref = deployment
         .container()
         .ensure_container_exists(&workspace)
@@ -1031,6 +1113,32 @@ pub async fn rebase_task_attempt(
     if let Err(e) = result {
         use services::services::git::GitServiceError;
         return match e {
+            GitServiceError::MergeConflicts(msg)
+                if payload.auto_resolve_conflicts.unwrap_or(false) =>
+            {
+                let process = deployment
+                    .container()
+                    .start_conflict_resolution(&workspace, &repo.name)
+                    .await?;
+                deployment
+                    .track_if_analytics_allowed(
+                        "task_attempt_conflict_resolution_started",
+                        serde_json::json!({
+                            "workspace_id": workspace.id.to_string(),
+                            "repo_id": payload.repo_id.to_string(),
+                        }),
+                    )
+                    .await;
+                Ok(ResponseJson(
+                    ApiResponse::<(), GitOperationError>::error_with_data(
+                        GitOperationError::ConflictResolutionStarted {
+                            message: msg,
+                            op: ConflictOp::Rebase,
+                            execution_process_id: process.id,
+                        },
+                    ),
+                ))
+            }
             GitServiceError::MergeConflicts(msg) => Ok(ResponseJson(ApiResponse::<
                 (),
                 GitOperationError,
@@ -1258,6 +1366,7 @@ pub async fn stop_task_attempt_execution(
 pub enum RunScriptError {
     NoScriptConfigured,
     ProcessAlreadyRunning,
+    UncommittedChanges,
 }
 
 #[axum::debug_handler]
@@ -1485,7 +1594,10 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         .route("/start-dev-server", post(start_dev_server))
         .route("/run-setup-script", post(run_setup_script))
         .route("/run-cleanup-script", post(run_cleanup_script))
+        .route("/quality-gates", get(quality_gates::get_quality_gates))
+        .route("/quality-gates/run", post(quality_gates::run_quality_gates))
         .route("/branch-status", get(get_task_attempt_branch_status))
+        .route("/diff", get(get_task_attempt_diff))
         .route("/diff/ws", get(stream_task_attempt_diff_ws))
         .route("/merge", post(merge_task_attempt))
         .route("/push", post(push_task_attempt_branch))
@@ -1497,6 +1609,12 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         .route("/pr/comments", get(pr::get_pr_comments))
         .route("/open-editor", post(open_task_attempt_in_editor))
         .route("/children", get(get_task_attempt_children))
+        .route(
+            "/stack",
+            get(stack::get_workspace_stack).post(stack::create_stacked_workspace),
+        )
+        .route("/stack/restack", post(stack::restack_workspace_stack))
+        .route("/stack/pr", post(stack::create_stacked_prs))
         .route("/stop", post(stop_task_attempt_execution))
         .route("/change-target-branch", post(change_target_branch))
         .route("/rename-branch", post(rename_branch))
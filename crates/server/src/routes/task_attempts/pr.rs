@@ -1,4 +1,4 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use axum::{
     Extension, Json,
@@ -11,6 +11,7 @@ use db::models::{
     repo::{Repo, RepoError},
     session::{CreateSession, Session},
     task::{Task, TaskStatus},
+    task_event::TaskEventActor,
     workspace::{Workspace, WorkspaceError},
     workspace_repo::WorkspaceRepo,
 };
@@ -22,9 +23,12 @@ use executors::actions::{
 use git2::BranchType;
 use serde::{Deserialize, Serialize};
 use services::services::{
+    config::ForgeKind,
     container::ContainerService,
+    forge::{ForgeError, ForgeProvider, ForgeRepoInfo, ForgeService},
     git::{GitCliError, GitServiceError},
-    github::{CreatePrRequest, GitHubService, GitHubServiceError, UnifiedPrComment},
+    github::{CreatePrRequest, GitHubServiceError, UnifiedPrComment},
+    quality_gate::QualityGateError,
 };
 use ts_rs::TS;
 use utils::response::ApiResponse;
@@ -51,7 +55,71 @@ pub enum CreatePrError {
     GithubCliNotLoggedIn,
     GitCliNotLoggedIn,
     GitCliNotInstalled,
-    TargetBranchNotFound { branch: String },
+    TargetBranchNotFound {
+        branch: String,
+    },
+    ForgeNotAuthenticated {
+        host: String,
+    },
+    UnsupportedRemote {
+        remote_url: String,
+    },
+    QualityGateRunning {
+        repo_name: String,
+        execution_process_id: Uuid,
+    },
+    QualityGateFailed {
+        repo_name: String,
+        execution_process_id: Uuid,
+    },
+    QualityGateUncommittedChanges {
+        repo_name: String,
+    },
+}
+
+impl From<QualityGateError> for CreatePrError {
+    fn from(err: QualityGateError) -> Self {
+        match err {
+            QualityGateError::QualityGateRunning {
+                repo_name,
+                execution_process_id,
+            } => Self::QualityGateRunning {
+                repo_name,
+                execution_process_id,
+            },
+            QualityGateError::QualityGateFailed {
+                repo_name,
+                execution_process_id,
+            } => Self::QualityGateFailed {
+                repo_name,
+                execution_process_id,
+            },
+            QualityGateError::QualityGateUncommittedChanges { repo_name } => {
+                Self::QualityGateUncommittedChanges { repo_name }
+            }
+        }
+    }
+}
+
+impl CreatePrError {
+    /// Errors the user can fix themselves, surfaced as structured error data
+    fn from_forge_error(err: &ForgeError) -> Option<Self> {
+        match err {
+            ForgeError::GitHub(GitHubServiceError::GhCliNotInstalled(_)) => {
+                Some(Self::GithubCliNotInstalled)
+            }
+            ForgeError::GitHub(GitHubServiceError::AuthFailed(_)) => {
+                Some(Self::GithubCliNotLoggedIn)
+            }
+            ForgeError::MissingToken { host, .. } | ForgeError::AuthFailed(host) => {
+                Some(Self::ForgeNotAuthenticated { host: host.clone() })
+            }
+            ForgeError::UnsupportedRemote(remote_url) => Some(Self::UnsupportedRemote {
+                remote_url: remote_url.clone(),
+            }),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, Serialize, TS)]
@@ -79,13 +147,47 @@ pub enum GetPrCommentsError {
     NoPrAttached,
     GithubCliNotInstalled,
     GithubCliNotLoggedIn,
+    ForgeNotAuthenticated { host: String },
+    UnsupportedRemote { remote_url: String },
 }
 
-#[derive(Debug, Deserialize, TS)]
+impl GetPrCommentsError {
+    fn from_forge_error(err: &ForgeError) -> Option<Self> {
+        match err {
+            ForgeError::GitHub(GitHubServiceError::GhCliNotInstalled(_)) => {
+                Some(Self::GithubCliNotInstalled)
+            }
+            ForgeError::GitHub(GitHubServiceError::AuthFailed(_)) => {
+                Some(Self::GithubCliNotLoggedIn)
+            }
+            ForgeError::MissingToken { host, .. } | ForgeError::AuthFailed(host) => {
+                Some(Self::ForgeNotAuthenticated { host: host.clone() })
+            }
+            ForgeError::UnsupportedRemote(remote_url) => Some(Self::UnsupportedRemote {
+                remote_url: remote_url.clone(),
+            }),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, TS)]
 pub struct GetPrCommentsQuery {
     pub repo_id: Uuid,
 }
 
+/// Resolve the forge hosting a repository from its remote URL
+pub(crate) async fn resolve_forge(
+    deployment: &DeploymentImpl,
+    repo_path: &Path,
+) -> Result<(ForgeRepoInfo, Box<dyn ForgeProvider>), ForgeError> {
+    let remote_url = deployment
+        .git()
+        .get_remote_url(repo_path)
+        .map_err(|_| ForgeError::UnsupportedRemote(repo_path.display().to_string()))?;
+    ForgeService::new(&deployment.config().read().await.forges).for_url(&remote_url)
+}
+
 pub const DEFAULT_PR_DESCRIPTION_PROMPT: &str = r#"Update the GitHub PR that was just created with a better title and description.
 The PR number is #{pr_number} and the URL is {pr_url}.
 
@@ -188,6 +290,20 @@ pub async fn create_github_pr(
     State(deployment): State<DeploymentImpl>,
     Json(request): Json<CreateGitHubPrRequest>,
 ) -> Result<ResponseJson<ApiResponse<String, CreatePrError>>, ApiError> {
+    match create_workspace_pr(&deployment, &workspace, request, true).await? {
+        Ok(url) => Ok(ResponseJson(ApiResponse::success(url))),
+        Err(error) => Ok(ResponseJson(ApiResponse::error_with_data(error))),
+    }
+}
+
+/// Push the workspace branch and open a PR for one of its repos, returning the PR URL.
+/// Problems the user can fix are returned as a `CreatePrError`.
+pub(crate) async fn create_workspace_pr(
+    deployment: &DeploymentImpl,
+    workspace: &Workspace,
+    request: CreateGitHubPrRequest,
+    open_in_browser: bool,
+) -> Result<Result<String, CreatePrError>, ApiError> {
     let pool = &deployment.db().pool;
 
     let workspace_repo =
@@ -208,52 +324,60 @@ pub async fn create_github_pr(
 
     let container_ref = deployment
         .container()
-        .ensure_container_exists(&workspace)
+        .ensure_container_exists(workspace)
         .await?;
     let workspace_path = PathBuf::from(&container_ref);
     let worktree_path = workspace_path.join(repo.name);
 
+    if let Some(gate_error) = deployment
+        .container()
+        .check_quality_gate(workspace, workspace_repo.repo_id)
+        .await?
+    {
+        return Ok(Err(gate_error.into()));
+    }
+
     match deployment
         .git()
         .check_remote_branch_exists(&repo_path, &target_branch)
     {
         Ok(false) => {
-            return Ok(ResponseJson(ApiResponse::error_with_data(
-                CreatePrError::TargetBranchNotFound {
-                    branch: target_branch.clone(),
-                },
-            )));
+            return Ok(Err(CreatePrError::TargetBranchNotFound {
+                branch: target_branch.clone(),
+            }));
         }
         Err(GitServiceError::GitCLI(GitCliError::AuthFailed(_))) => {
-            return Ok(ResponseJson(ApiResponse::error_with_data(
-                CreatePrError::GitCliNotLoggedIn,
-            )));
+            return Ok(Err(CreatePrError::GitCliNotLoggedIn));
         }
         Err(GitServiceError::GitCLI(GitCliError::NotAvailable)) => {
-            return Ok(ResponseJson(ApiResponse::error_with_data(
-                CreatePrError::GitCliNotInstalled,
-            )));
+            return Ok(Err(CreatePrError::GitCliNotInstalled));
         }
         Err(e) => return Err(ApiError::GitService(e)),
         Ok(true) => {}
     }
 
-    // Push the branch to GitHub first
+    let (repo_info, forge) = match resolve_forge(deployment, &repo_path).await {
+        Ok(resolved) => resolved,
+        Err(e) => {
+            return match CreatePrError::from_forge_error(&e) {
+                Some(error) => Ok(Err(error)),
+                None => Err(ApiError::Forge(e)),
+            };
+        }
+    };
+
+    // Push the branch to the remote first
     if let Err(e) = deployment
         .git()
         .push_to_github(&worktree_path, &workspace.branch, false)
     {
-        tracing::error!("Failed to push branch to GitHub: {}", e);
+        tracing::error!("Failed to push branch to remote: {}", e);
         match e {
             GitServiceError::GitCLI(GitCliError::AuthFailed(_)) => {
-                return Ok(ResponseJson(ApiResponse::error_with_data(
-                    CreatePrError::GitCliNotLoggedIn,
-                )));
+                return Ok(Err(CreatePrError::GitCliNotLoggedIn));
             }
             GitServiceError::GitCLI(GitCliError::NotAvailable) => {
-                return Ok(ResponseJson(ApiResponse::error_with_data(
-                    CreatePrError::GitCliNotInstalled,
-                )));
+                return Ok(Err(CreatePrError::GitCliNotInstalled));
             }
             _ => return Err(ApiError::GitService(e)),
         }
@@ -278,7 +402,6 @@ pub async fn create_github_pr(
     } else {
         target_branch
     };
-    // Create the PR using GitHub service
     let pr_request = CreatePrRequest {
         title: request.title.clone(),
         body: request.body.clone(),
@@ -286,12 +409,7 @@ pub async fn create_github_pr(
         base_branch: norm_target_branch_name.clone(),
         draft: request.draft,
     };
-    // Use GitService to get the remote URL, then create GitHubRepoInfo
-    let repo_info = deployment.git().get_github_repo_info(&repo_path)?;
-
-    // Use GitHubService to create the PR
-    let github_service = GitHubService::new()?;
-    match github_service.create_pr(&repo_info, &pr_request).await {
+    match forge.create_pr(&repo_info, &pr_request).await {
         Ok(pr_info) => {
             // Update the workspace with PR information
             if let Err(e) = Merge::create_pr(
@@ -308,7 +426,7 @@ pub async fn create_github_pr(
             }
 
             // Auto-open PR in browser
-            if let Err(e) = utils::browser::open_browser(&pr_info.url).await {
+            if open_in_browser && let Err(e) = utils::browser::open_browser(&pr_info.url).await {
                 tracing::warn!("Failed to open PR in browser: {}", e);
             }
             deployment
@@ -316,15 +434,18 @@ pub async fn create_github_pr(
                     "github_pr_created",
                     serde_json::json!({
                         "workspace_id": workspace.id.to_string(),
+                        "forge": repo_info.kind,
                     }),
                 )
                 .await;
 
-            // Trigger auto-description follow-up if enabled
+            // Trigger auto-description follow-up if enabled. The agent edits the PR
+            // through the gh CLI, so this is only available on GitHub.
             if request.auto_generate_description
+                && repo_info.kind == ForgeKind::GitHub
                 && let Err(e) = trigger_pr_description_follow_up(
-                    &deployment,
-                    &workspace,
+                    deployment,
+                    workspace,
                     pr_info.number,
                     &pr_info.url,
                 )
@@ -337,22 +458,18 @@ pub async fn create_github_pr(
                 );
             }
 
-            Ok(ResponseJson(ApiResponse::success(pr_info.url)))
+            Ok(Ok(pr_info.url))
         }
         Err(e) => {
             tracing::error!(
-                "Failed to create GitHub PR for attempt {}: {}",
+                "Failed to create PR on {} for attempt {}: {}",
+                repo_info.host,
                 workspace.id,
                 e
             );
-            match &e {
-                GitHubServiceError::GhCliNotInstalled(_) => Ok(ResponseJson(
-                    ApiResponse::error_with_data(CreatePrError::GithubCliNotInstalled),
-                )),
-                GitHubServiceError::AuthFailed(_) => Ok(ResponseJson(
-                    ApiResponse::error_with_data(CreatePrError::GithubCliNotLoggedIn),
-                )),
-                _ => Err(ApiError::GitHubService(e)),
+            match CreatePrError::from_forge_error(&e) {
+                Some(error) => Ok(Err(error)),
+                None => Err(ApiError::Forge(e)),
             }
         }
     }
@@ -390,12 +507,11 @@ pub async fn attach_existing_pr(
         })));
     }
 
-    let github_service = GitHubService::new()?;
-    let repo_info = deployment.git().get_github_repo_info(&repo.path)?;
+    let (repo_info, forge) = resolve_forge(&deployment, &repo.path).await?;
 
     // List all PRs for branch (open, closed, and merged)
-    let prs = github_service
-        .list_all_prs_for_branch(&repo_info, &workspace.branch)
+    let prs = forge
+        .list_prs_for_branch(&repo_info, &workspace.branch)
         .await?;
 
     // Take the first PR (prefer open, but also accept merged/closed)
@@ -424,7 +540,7 @@ pub async fn attach_existing_pr(
 
         // If PR is merged, mark task as done
         if matches!(pr_info.status, MergeStatus::Merged) {
-            Task::update_status(pool, task.id, TaskStatus::Done).await?;
+            Task::update_status(pool, task.id, TaskStatus::Done, TaskEventActor::User).await?;
 
             // Try broadcast update to other users in organization
             if let Ok(publisher) = deployment.share_publisher() {
@@ -441,6 +557,14 @@ pub async fn attach_existing_pr(
                     task.id
                 );
             }
+
+            if let Err(e) = deployment
+                .container()
+                .start_unblocked_dependents(task.id)
+                .await
+            {
+                tracing::error!("Failed to start tasks unblocked by task {}: {}", task.id, e);
+            }
         }
 
         Ok(ResponseJson(ApiResponse::success(AttachPrResponse {
@@ -466,13 +590,8 @@ pub async fn get_pr_comments(
 ) -> Result<ResponseJson<ApiResponse<PrCommentsResponse, GetPrCommentsError>>, ApiError> {
     let pool = &deployment.db().pool;
 
-    // Look up the specific repo using the multi-repo pattern
-    let workspace_repo =
-        WorkspaceRepo::find_by_workspace_and_repo_id(pool, workspace.id, query.repo_id)
-            .await?
-            .ok_or(RepoError::NotFound)?;
-
-    let repo = Repo::find_by_id(pool, workspace_repo.repo_id)
+    // Ensure the repo belongs to this workspace
+    WorkspaceRepo::find_by_workspace_and_repo_id(pool, workspace.id, query.repo_id)
         .await?
         .ok_or(RepoError::NotFound)?;
 
@@ -489,14 +608,19 @@ pub async fn get_pr_comments(
         }
     };
 
-    let github_service = GitHubService::new()?;
-    let repo_info = deployment.git().get_github_repo_info(&repo.path)?;
+    // The PR URL identifies the forge even if the remote changed since it was attached
+    let forge_service = ForgeService::new(&deployment.config().read().await.forges);
+    let (repo_info, forge) = match forge_service.for_url(&pr_info.url) {
+        Ok(resolved) => resolved,
+        Err(e) => {
+            return match GetPrCommentsError::from_forge_error(&e) {
+                Some(error) => Ok(ResponseJson(ApiResponse::error_with_data(error))),
+                None => Err(ApiError::Forge(e)),
+            };
+        }
+    };
 
-    // Fetch comments from GitHub
-    match github_service
-        .get_pr_comments(&repo_info, pr_info.number)
-        .await
-    {
+    match forge.get_pr_comments(&repo_info, pr_info.number).await {
         Ok(comments) => Ok(ResponseJson(ApiResponse::success(PrCommentsResponse {
             comments,
         }))),
@@ -507,14 +631,9 @@ pub async fn get_pr_comments(
                 pr_info.number,
                 e
             );
-            match &e {
-                GitHubServiceError::GhCliNotInstalled(_) => Ok(ResponseJson(
-                    ApiResponse::error_with_data(GetPrCommentsError::GithubCliNotInstalled),
-                )),
-                GitHubServiceError::AuthFailed(_) => Ok(ResponseJson(
-                    ApiResponse::error_with_data(GetPrCommentsError::GithubCliNotLoggedIn),
-                )),
-                _ => Err(ApiError::GitHubService(e)),
+            match GetPrCommentsError::from_forge_error(&e) {
+                Some(error) => Ok(ResponseJson(ApiResponse::error_with_data(error))),
+                None => Err(ApiError::Forge(e)),
             }
         }
     }
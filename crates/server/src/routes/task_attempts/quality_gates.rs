@@ -0,0 +1,97 @@
+use axum::{Extension, Json, extract::State, response::Json as ResponseJson};
+use db::models::{execution_process::ExecutionProcess, workspace::Workspace};
+use deployment::Deployment;
+use serde::{Deserialize, Serialize};
+use services::services::{
+    container::ContainerService,
+    quality_gate::{QualityGateState, RepoQualityGate},
+};
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError, routes::task_attempts::RunScriptError};
+
+#[derive(Debug, Deserialize, Serialize, TS)]
+pub struct RunQualityGatesRequest {
+    /// Only run this repository's gate; runs every gate that is not already running if unset
+    pub repo_id: Option<Uuid>,
+}
+
+pub async fn get_quality_gates(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<RepoQualityGate>>>, ApiError> {
+    let gates = deployment.container().quality_gates(&workspace).await?;
+    Ok(ResponseJson(ApiResponse::success(gates)))
+}
+
+pub async fn run_quality_gates(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Json(request): Json<RunQualityGatesRequest>,
+) -> Result<ResponseJson<ApiResponse<Vec<ExecutionProcess>, RunScriptError>>, ApiError> {
+    deployment
+        .container()
+        .ensure_container_exists(&workspace)
+        .await?;
+
+    let gates: Vec<_> = deployment
+        .container()
+        .quality_gates(&workspace)
+        .await?
+        .into_iter()
+        .filter(|gate| {
+            request
+                .repo_id
+                .is_none_or(|repo_id| gate.repo_id == repo_id)
+        })
+        .collect();
+    if gates.is_empty() {
+        return Ok(ResponseJson(ApiResponse::error_with_data(
+            RunScriptError::NoScriptConfigured,
+        )));
+    }
+    if gates
+        .iter()
+        .all(|gate| gate.state == QualityGateState::Running)
+    {
+        return Ok(ResponseJson(ApiResponse::error_with_data(
+            RunScriptError::ProcessAlreadyRunning,
+        )));
+    }
+    // A run on uncommitted changes would not vouch for what gets merged
+    if gates
+        .iter()
+        .any(|gate| gate.state == QualityGateState::UncommittedChanges)
+    {
+        return Ok(ResponseJson(ApiResponse::error_with_data(
+            RunScriptError::UncommittedChanges,
+        )));
+    }
+
+    let mut processes = Vec::new();
+    for gate in gates
+        .iter()
+        .filter(|gate| gate.state != QualityGateState::Running)
+    {
+        processes.push(
+            deployment
+                .container()
+                .start_quality_gate(&workspace, gate.repo_id)
+                .await?,
+        );
+    }
+
+    deployment
+        .track_if_analytics_allowed(
+            "quality_gates_executed",
+            serde_json::json!({
+                "workspace_id": workspace.id.to_string(),
+                "repo_count": processes.len(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(processes)))
+}
@@ -0,0 +1,325 @@
+use std::{collections::HashMap, path::Path};
+
+use axum::{Extension, Json, extract::State, response::Json as ResponseJson};
+use db::models::{
+    execution_process::ExecutionProcess,
+    merge::{Merge, MergeStatus},
+    task::Task,
+    workspace::Workspace,
+    workspace_repo::{CreateWorkspaceRepo, WorkspaceRepo},
+};
+use deployment::Deployment;
+use executors::profile::ExecutorProfileId;
+use serde::{Deserialize, Serialize};
+use services::services::{
+    container::ContainerService,
+    workspace_stack::{
+        self, RestackChild, RestackOutcome, RestackRepo, RestackResult, RestackStep, WorkspaceStack,
+    },
+};
+use sqlx::Error as SqlxError;
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    routes::task_attempts::pr::{CreateGitHubPrRequest, CreatePrError, create_workspace_pr},
+};
+
+#[derive(Debug, Deserialize, TS)]
+pub struct CreateStackedWorkspaceRequest {
+    /// Task the new workspace works on; must belong to the same project
+    pub task_id: Uuid,
+    pub executor_profile_id: ExecutorProfileId,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct CreateStackedPrsRequest {
+    pub repo_id: Uuid,
+    pub draft: Option<bool>,
+}
+
+#[derive(Debug, Serialize, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[ts(tag = "type", rename_all = "snake_case")]
+pub enum StackedPrOutcome {
+    Created {
+        url: String,
+    },
+    /// The workspace already had an open PR for the repository
+    Existing {
+        url: String,
+    },
+    /// `error` is set for problems the user can fix, `message` for any other failure
+    Failed {
+        error: Option<CreatePrError>,
+        message: Option<String>,
+    },
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct StackedPr {
+    pub workspace_id: Uuid,
+    pub target_branch: String,
+    pub outcome: StackedPrOutcome,
+}
+
+pub async fn get_workspace_stack(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<WorkspaceStack>>, ApiError> {
+    let stack = workspace_stack::load_stack(&deployment.db().pool, &workspace).await?;
+    Ok(ResponseJson(ApiResponse::success(stack)))
+}
+
+/// Start a workspace on top of this one: it branches from this workspace's branch in every
+/// repository and targets it, so its changes and PRs only contain its own work
+pub async fn create_stacked_workspace(
+    Extension(parent): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateStackedWorkspaceRequest>,
+) -> Result<ResponseJson<ApiResponse<Workspace>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let task = Task::find_by_id(pool, payload.task_id)
+        .await?
+        .ok_or(SqlxError::RowNotFound)?;
+    let parent_task = parent
+        .parent_task(pool)
+        .await?
+        .ok_or(SqlxError::RowNotFound)?;
+    if task.project_id != parent_task.project_id {
+        return Err(ApiError::BadRequest(
+            "A workspace can only be stacked on one from the same project".to_string(),
+        ));
+    }
+
+    let parent_repos =
+        WorkspaceRepo::find_repos_with_target_branch_for_workspace(pool, parent.id).await?;
+    for parent_repo in &parent_repos {
+        if !deployment
+            .git()
+            .check_branch_exists(&parent_repo.repo.path, &parent.branch)?
+        {
+            return Err(ApiError::BadRequest(format!(
+                "Branch '{}' does not exist in '{}' yet; start the workspace first",
+                parent.branch, parent_repo.repo.name
+            )));
+        }
+    }
+
+    let workspace_repos: Vec<CreateWorkspaceRepo> = parent_repos
+        .iter()
+        .map(|r| CreateWorkspaceRepo {
+            repo_id: r.repo.id,
+            target_branch: parent.branch.clone(),
+        })
+        .collect();
+    let mut workspace = deployment
+        .container()
+        .create_workspace_for_task(&task, &workspace_repos)
+        .await?;
+    Workspace::set_stack_parent(pool, workspace.id, Some(parent.id)).await?;
+    workspace.stack_parent_id = Some(parent.id);
+
+    if let Err(err) = deployment
+        .container()
+        .start_workspace(&workspace, payload.executor_profile_id.clone())
+        .await
+    {
+        tracing::error!("Failed to start stacked task attempt: {}", err);
+    }
+
+    deployment
+        .track_if_analytics_allowed(
+            "task_attempt_stacked",
+            serde_json::json!({
+                "task_id": task.id.to_string(),
+                "workspace_id": workspace.id.to_string(),
+                "parent_workspace_id": parent.id.to_string(),
+                "executor": &payload.executor_profile_id.executor,
+                "repository_count": workspace_repos.len(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(workspace)))
+}
+
+/// Rebase every workspace stacked on this one onto its parent's branch, parents first.
+/// Repositories whose target branch was changed away from the parent's branch are left
+/// alone. Refused while any of the workspaces has a running process, and stops at the first
+/// rebase that does not succeed.
+pub async fn restack_workspace_stack(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<RestackResult>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let ordered = workspace_stack::stack_from(pool, &workspace).await?;
+    let branches: HashMap<Uuid, String> = ordered
+        .iter()
+        .map(|(w, _)| (w.id, w.branch.clone()))
+        .collect();
+
+    let mut children = Vec::new();
+    for (child, _) in ordered.iter().skip(1) {
+        let Some(parent_branch) = child.stack_parent_id.and_then(|id| branches.get(&id)) else {
+            continue;
+        };
+        let repos: Vec<_> =
+            WorkspaceRepo::find_repos_with_target_branch_for_workspace(pool, child.id)
+                .await?
+                .into_iter()
+                .filter(|r| &r.target_branch == parent_branch)
+                .collect();
+        if repos.is_empty() {
+            continue;
+        }
+        // A coding agent still working in the worktree would race the rebase
+        if ExecutionProcess::has_running_non_dev_server_processes_for_workspace(pool, child.id)
+            .await?
+        {
+            return Err(ApiError::Conflict(format!(
+                "Workspace on branch '{}' has a running process; stop it before restacking",
+                child.branch
+            )));
+        }
+        children.push((child, parent_branch, repos));
+    }
+
+    let mut restack_children = Vec::new();
+    let mut failed_step = None;
+    for (child, parent_branch, repos) in children {
+        let container_ref = match deployment.container().ensure_container_exists(child).await {
+            Ok(container_ref) => container_ref,
+            Err(e) => {
+                failed_step = Some(RestackStep {
+                    workspace_id: child.id,
+                    repo_id: repos[0].repo.id,
+                    repo_name: repos[0].repo.name.clone(),
+                    onto_branch: parent_branch.clone(),
+                    outcome: RestackOutcome::Failed {
+                        message: e.to_string(),
+                    },
+                });
+                break;
+            }
+        };
+        restack_children.push(RestackChild {
+            workspace_id: child.id,
+            branch: child.branch.clone(),
+            onto_branch: parent_branch.clone(),
+            repos: repos
+                .into_iter()
+                .map(|repo| RestackRepo {
+                    repo_id: repo.repo.id,
+                    worktree_path: Path::new(&container_ref).join(&repo.repo.name),
+                    repo_name: repo.repo.name,
+                    repo_path: repo.repo.path,
+                })
+                .collect(),
+        });
+    }
+
+    let mut result = workspace_stack::restack(deployment.git(), &restack_children);
+    // Workspaces up to the one whose container could not be created were still rebased
+    if result.completed
+        && let Some(step) = failed_step
+    {
+        result.steps.push(step);
+        result.completed = false;
+    }
+
+    deployment
+        .track_if_analytics_allowed(
+            "workspace_stack_restacked",
+            serde_json::json!({
+                "workspace_id": workspace.id.to_string(),
+                "steps": result.steps.len(),
+                "completed": result.completed,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(result)))
+}
+
+/// Open a PR for this workspace and each one stacked on it, parents first, every PR targeting
+/// its parent's branch. Workspaces with an open PR keep it, so the request can be repeated
+/// after a failure; it stops at the first PR that could not be created.
+pub async fn create_stacked_prs(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateStackedPrsRequest>,
+) -> Result<ResponseJson<ApiResponse<Vec<StackedPr>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let mut prs = Vec::new();
+
+    for (member, _) in workspace_stack::stack_from(pool, &workspace).await? {
+        let Some(workspace_repo) =
+            WorkspaceRepo::find_by_workspace_and_repo_id(pool, member.id, payload.repo_id).await?
+        else {
+            continue;
+        };
+
+        let existing = Merge::find_by_workspace_and_repo_id(pool, member.id, payload.repo_id)
+            .await?
+            .into_iter()
+            .find_map(|merge| match merge {
+                Merge::Pr(pr) if matches!(pr.pr_info.status, MergeStatus::Open) => {
+                    Some(pr.pr_info.url)
+                }
+                _ => None,
+            });
+        let outcome = if let Some(url) = existing {
+            StackedPrOutcome::Existing { url }
+        } else {
+            let task = member
+                .parent_task(pool)
+                .await?
+                .ok_or(SqlxError::RowNotFound)?;
+            let request = CreateGitHubPrRequest {
+                title: task.title,
+                body: task.description,
+                target_branch: None,
+                draft: payload.draft,
+                repo_id: payload.repo_id,
+                auto_generate_description: false,
+            };
+            match create_workspace_pr(&deployment, &member, request, false).await {
+                Ok(Ok(url)) => StackedPrOutcome::Created { url },
+                Ok(Err(error)) => StackedPrOutcome::Failed {
+                    error: Some(error),
+                    message: None,
+                },
+                Err(e) => StackedPrOutcome::Failed {
+                    error: None,
+                    message: Some(e.to_string()),
+                },
+            }
+        };
+
+        let failed = matches!(outcome, StackedPrOutcome::Failed { .. });
+        prs.push(StackedPr {
+            workspace_id: member.id,
+            target_branch: workspace_repo.target_branch,
+            outcome,
+        });
+        if failed {
+            break;
+        }
+    }
+
+    deployment
+        .track_if_analytics_allowed(
+            "stacked_prs_created",
+            serde_json::json!({
+                "workspace_id": workspace.id.to_string(),
+                "pr_count": prs.len(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(prs)))
+}
@@ -0,0 +1,107 @@
+use axum::{
+    Extension, Json, Router,
+    extract::{Query, State},
+    middleware::from_fn_with_state,
+    response::Json as ResponseJson,
+    routing::get,
+};
+use db::models::task_schedule::{
+    CreateTaskSchedule, TaskSchedule, TaskScheduleRun, UpdateTaskSchedule,
+};
+use deployment::Deployment;
+use serde::Deserialize;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError, middleware::load_task_schedule_middleware};
+
+#[derive(Debug, Deserialize)]
+pub struct TaskScheduleQuery {
+    pub project_id: Uuid,
+}
+
+pub async fn get_task_schedules(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<TaskScheduleQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<TaskSchedule>>>, ApiError> {
+    let schedules =
+        TaskSchedule::find_by_project_id(&deployment.db().pool, query.project_id).await?;
+    Ok(ResponseJson(ApiResponse::success(schedules)))
+}
+
+pub async fn get_task_schedule(
+    Extension(schedule): Extension<TaskSchedule>,
+) -> Result<ResponseJson<ApiResponse<TaskSchedule>>, ApiError> {
+    Ok(ResponseJson(ApiResponse::success(schedule)))
+}
+
+pub async fn create_task_schedule(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateTaskSchedule>,
+) -> Result<ResponseJson<ApiResponse<TaskSchedule>>, ApiError> {
+    let schedule = TaskSchedule::create(&deployment.db().pool, &payload).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "task_schedule_created",
+            serde_json::json!({
+                "schedule_id": schedule.id.to_string(),
+                "project_id": schedule.project_id.to_string(),
+                "cron_expression": schedule.cron_expression,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(schedule)))
+}
+
+pub async fn update_task_schedule(
+    Extension(schedule): Extension<TaskSchedule>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<UpdateTaskSchedule>,
+) -> Result<ResponseJson<ApiResponse<TaskSchedule>>, ApiError> {
+    let updated = TaskSchedule::update(&deployment.db().pool, schedule.id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(updated)))
+}
+
+pub async fn delete_task_schedule(
+    Extension(schedule): Extension<TaskSchedule>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let rows_affected = TaskSchedule::delete(&deployment.db().pool, schedule.id).await?;
+    if rows_affected == 0 {
+        Err(ApiError::Database(sqlx::Error::RowNotFound))
+    } else {
+        Ok(ResponseJson(ApiResponse::success(())))
+    }
+}
+
+/// Tasks spawned by a schedule, most recent first
+pub async fn get_task_schedule_runs(
+    Extension(schedule): Extension<TaskSchedule>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<TaskScheduleRun>>>, ApiError> {
+    let runs = TaskScheduleRun::find_by_schedule_id(&deployment.db().pool, schedule.id).await?;
+    Ok(ResponseJson(ApiResponse::success(runs)))
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let schedule_router = Router::new()
+        .route(
+            "/",
+            get(get_task_schedule)
+                .put(update_task_schedule)
+                .delete(delete_task_schedule),
+        )
+        .route("/runs", get(get_task_schedule_runs))
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_task_schedule_middleware,
+        ));
+
+    let inner = Router::new()
+        .route("/", get(get_task_schedules).post(create_task_schedule))
+        .nest("/{schedule_id}", schedule_router);
+
+    Router::new().nest("/task-schedules", inner)
+}
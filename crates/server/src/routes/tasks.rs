@@ -4,7 +4,7 @@ use anyhow;
 use axum::{
     Extension, Json, Router,
     extract::{
-        Query, State,
+        Path, Query, State,
         ws::{WebSocket, WebSocketUpgrade},
     },
     http::StatusCode,
@@ -13,10 +13,14 @@ use axum::{
     routing::{delete, get, post, put},
 };
 use db::models::{
+    approval::Approval,
     image::TaskImage,
     project::{Project, ProjectError},
     repo::Repo,
-    task::{CreateTask, Task, TaskWithAttemptStatus, UpdateTask},
+    task::{CreateTask, Task, TaskStatus, TaskWithAttemptStatus, UpdateTask},
+    task_auto_start::{TaskAutoStart, UpsertTaskAutoStart},
+    task_dependency::{CreateTaskDependency, TaskDependencies, TaskDependency},
+    task_event::{TaskEvent, TaskEventActor},
     workspace::{CreateWorkspace, Workspace},
     workspace_repo::{CreateWorkspaceRepo, WorkspaceRepo},
 };
@@ -119,7 +123,7 @@ pub async fn create_task(
         payload.project_id
     );
 
-    let task = Task::create(&deployment.db().pool, &payload, id).await?;
+    let task = Task::create(&deployment.db().pool, &payload, id, TaskEventActor::User).await?;
 
     if let Some(image_ids) = &payload.image_ids {
         TaskImage::associate_many_dedup(&deployment.db().pool, task.id, image_ids).await?;
@@ -160,7 +164,7 @@ pub async fn create_task_and_start(
     let pool = &deployment.db().pool;
 
     let task_id = Uuid::new_v4();
-    let task = Task::create(pool, &payload.task, task_id).await?;
+    let task = Task::create(pool, &payload.task, task_id, TaskEventActor::User).await?;
 
     if let Some(image_ids) = &payload.task.image_ids {
         TaskImage::associate_many_dedup(pool, task.id, image_ids).await?;
@@ -237,11 +241,14 @@ pub async fn create_task_and_start(
         .await?
         .ok_or(ApiError::Database(SqlxError::RowNotFound))?;
 
+    let is_blocked = TaskDependency::is_blocked(pool, task.id).await?;
+
     tracing::info!("Started attempt for task {}", task.id);
     Ok(ResponseJson(ApiResponse::success(TaskWithAttemptStatus {
         task,
         has_in_progress_attempt: is_attempt_running,
         last_attempt_failed: false,
+        is_blocked,
         executor: payload.executor_profile_id.executor.to_string(),
     })))
 }
@@ -266,7 +273,7 @@ pub async fn update_task(
         .parent_workspace_id
         .or(existing_task.parent_workspace_id);
 
-    let task = Task::update(
+    let (task, old_status) = Task::update(
         &deployment.db().pool,
         existing_task.id,
         existing_task.project_id,
@@ -274,6 +281,7 @@ pub async fn update_task(
         description,
         status,
         parent_workspace_id,
+        TaskEventActor::User,
     )
     .await?;
 
@@ -290,6 +298,16 @@ pub async fn update_task(
         publisher.update_shared_task(&task).await?;
     }
 
+    if task.status == TaskStatus::Done
+        && old_status != TaskStatus::Done
+        && let Err(e) = deployment
+            .container()
+            .start_unblocked_dependents(task.id)
+            .await
+    {
+        tracing::error!("Failed to start tasks unblocked by task {}: {}", task.id, e);
+    }
+
     Ok(ResponseJson(ApiResponse::success(task)))
 }
 
@@ -460,14 +478,174 @@ pub async fn share_task(
     })))
 }
 
+pub async fn get_task_dependencies(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<TaskDependencies>>, ApiError> {
+    let dependencies = TaskDependency::find_for_task(&deployment.db().pool, task.id).await?;
+    Ok(ResponseJson(ApiResponse::success(dependencies)))
+}
+
+/// Every tool approval requested while working on the task and how it was decided
+pub async fn get_task_approvals(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<Approval>>>, ApiError> {
+    let approvals = Approval::find_by_task_id(&deployment.db().pool, task.id).await?;
+    Ok(ResponseJson(ApiResponse::success(approvals)))
+}
+
+/// Status history of the task, oldest first
+pub async fn get_task_events(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<TaskEvent>>>, ApiError> {
+    let events = TaskEvent::find_by_task_id(&deployment.db().pool, task.id).await?;
+    Ok(ResponseJson(ApiResponse::success(events)))
+}
+
+pub async fn stream_task_events_ws(
+    ws: WebSocketUpgrade,
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+) -> impl IntoResponse {
+    ws.on_upgrade(move |socket| async move {
+        if let Err(e) = handle_task_events_ws(socket, deployment, task.id).await {
+            tracing::warn!("task events WS closed: {}", e);
+        }
+    })
+}
+
+async fn handle_task_events_ws(
+    socket: WebSocket,
+    deployment: DeploymentImpl,
+    task_id: Uuid,
+) -> anyhow::Result<()> {
+    let mut stream = deployment
+        .events()
+        .stream_task_events_raw(task_id)
+        .await?
+        .map_ok(|msg| msg.to_ws_message_unchecked());
+
+    let (mut sender, mut receiver) = socket.split();
+    tokio::spawn(async move { while let Some(Ok(_)) = receiver.next().await {} });
+
+    while let Some(item) = stream.next().await {
+        match item {
+            Ok(msg) => {
+                if sender.send(msg).await.is_err() {
+                    break;
+                }
+            }
+            Err(e) => {
+                tracing::error!("stream error: {}", e);
+                break;
+            }
+        }
+    }
+    Ok(())
+}
+
+pub async fn add_task_dependency(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateTaskDependency>,
+) -> Result<ResponseJson<ApiResponse<TaskDependency>>, ApiError> {
+    let dependency =
+        TaskDependency::create(&deployment.db().pool, task.id, payload.depends_on_task_id).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "task_dependency_added",
+            serde_json::json!({
+                "task_id": task.id.to_string(),
+                "depends_on_task_id": payload.depends_on_task_id.to_string(),
+                "project_id": task.project_id.to_string(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(dependency)))
+}
+
+pub async fn remove_task_dependency(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+    Path((_task_id, depends_on_task_id)): Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let rows_affected =
+        TaskDependency::delete(&deployment.db().pool, task.id, depends_on_task_id).await?;
+    if rows_affected == 0 {
+        return Err(ApiError::Database(SqlxError::RowNotFound));
+    }
+
+    // Removing the last open blocker unblocks the task
+    if let Err(e) = deployment.container().start_if_unblocked(&task).await {
+        tracing::error!(
+            "Failed to start task {} after removing a blocker: {}",
+            task.id,
+            e
+        );
+    }
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+pub async fn upsert_task_auto_start(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<UpsertTaskAutoStart>,
+) -> Result<ResponseJson<ApiResponse<TaskAutoStart>>, ApiError> {
+    if payload.repos.is_empty() {
+        return Err(ApiError::BadRequest(
+            "At least one repository is required".to_string(),
+        ));
+    }
+
+    let pool = &deployment.db().pool;
+    let auto_start = TaskAutoStart::upsert(pool, task.id, &payload).await?;
+
+    // Blockers that are already done will not complete again, so start the task now
+    if !TaskDependency::find_blockers(pool, task.id)
+        .await?
+        .is_empty()
+        && let Err(e) = deployment.container().start_if_unblocked(&task).await
+    {
+        tracing::error!("Failed to auto-start task {}: {}", task.id, e);
+    }
+    Ok(ResponseJson(ApiResponse::success(auto_start)))
+}
+
+pub async fn delete_task_auto_start(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    TaskAutoStart::delete(&deployment.db().pool, task.id).await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
 pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     let task_actions_router = Router::new()
         .route("/", put(update_task))
         .route("/", delete(delete_task))
-        .route("/share", post(share_task));
+        .route("/share", post(share_task))
+        .route(
+            "/dependencies",
+            get(get_task_dependencies).post(add_task_dependency),
+        )
+        .route(
+            "/dependencies/{depends_on_task_id}",
+            delete(remove_task_dependency),
+        )
+        .route(
+            "/auto-start",
+            put(upsert_task_auto_start).delete(delete_task_auto_start),
+        );
 
     let task_id_router = Router::new()
         .route("/", get(get_task))
+        .route("/approvals", get(get_task_approvals))
+        .route("/events", get(get_task_events))
+        .route("/events/ws", get(stream_task_events_ws))
         .merge(task_actions_router)
         .layer(from_fn_with_state(deployment.clone(), load_task_middleware));
 
@@ -0,0 +1,25 @@
+use axum::{
+    Router,
+    extract::{Query, State},
+    response::Json as ResponseJson,
+    routing::get,
+};
+use deployment::Deployment;
+use services::services::usage::{UsageQuery, UsageReport, usage_report};
+use utils::response::ApiResponse;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+/// Token usage and estimated cost of coding agent turns, priced with the configured token prices
+pub async fn get_usage(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<UsageQuery>,
+) -> Result<ResponseJson<ApiResponse<UsageReport>>, ApiError> {
+    let prices = deployment.config().read().await.token_prices.clone();
+    let report = usage_report(&deployment.db().pool, &query, &prices).await?;
+    Ok(ResponseJson(ApiResponse::success(report)))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new().route("/usage", get(get_usage))
+}
@@ -0,0 +1,128 @@
+use axum::{
+    Extension, Json, Router,
+    extract::{Query, State},
+    middleware::from_fn_with_state,
+    response::Json as ResponseJson,
+    routing::{get, post},
+};
+use db::models::webhook::{CreateWebhook, UpdateWebhook, Webhook, WebhookDelivery};
+use deployment::Deployment;
+use serde::{Deserialize, Serialize};
+use services::services::webhooks::WebhookService;
+use ts_rs::TS;
+use utils::response::ApiResponse;
+
+use crate::{DeploymentImpl, error::ApiError, middleware::load_webhook_middleware};
+
+const DEFAULT_DELIVERY_LIMIT: i64 = 50;
+
+/// A newly created webhook. `secret` is only ever returned here.
+#[derive(Debug, Serialize, TS)]
+pub struct CreatedWebhook {
+    #[serde(flatten)]
+    #[ts(flatten)]
+    pub webhook: Webhook,
+    pub secret: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WebhookDeliveryQuery {
+    pub limit: Option<i64>,
+}
+
+pub async fn get_webhooks(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<Webhook>>>, ApiError> {
+    let webhooks = Webhook::find_all(&deployment.db().pool).await?;
+    Ok(ResponseJson(ApiResponse::success(webhooks)))
+}
+
+pub async fn get_webhook(
+    Extension(webhook): Extension<Webhook>,
+) -> Result<ResponseJson<ApiResponse<Webhook>>, ApiError> {
+    Ok(ResponseJson(ApiResponse::success(webhook)))
+}
+
+pub async fn create_webhook(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateWebhook>,
+) -> Result<ResponseJson<ApiResponse<CreatedWebhook>>, ApiError> {
+    let webhook = Webhook::create(&deployment.db().pool, &payload).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "webhook_created",
+            serde_json::json!({
+                "webhook_id": webhook.id.to_string(),
+                "event_count": webhook.events.len(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(CreatedWebhook {
+        secret: webhook.secret.clone(),
+        webhook,
+    })))
+}
+
+pub async fn update_webhook(
+    Extension(webhook): Extension<Webhook>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<UpdateWebhook>,
+) -> Result<ResponseJson<ApiResponse<Webhook>>, ApiError> {
+    let updated = Webhook::update(&deployment.db().pool, webhook.id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(updated)))
+}
+
+pub async fn delete_webhook(
+    Extension(webhook): Extension<Webhook>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let rows_affected = Webhook::delete(&deployment.db().pool, webhook.id).await?;
+    if rows_affected == 0 {
+        Err(ApiError::Database(sqlx::Error::RowNotFound))
+    } else {
+        Ok(ResponseJson(ApiResponse::success(())))
+    }
+}
+
+/// Delivery log of a webhook, most recent first
+pub async fn get_webhook_deliveries(
+    Extension(webhook): Extension<Webhook>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<WebhookDeliveryQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<WebhookDelivery>>>, ApiError> {
+    let limit = query.limit.unwrap_or(DEFAULT_DELIVERY_LIMIT).clamp(1, 500);
+    let deliveries =
+        WebhookDelivery::find_by_webhook_id(&deployment.db().pool, webhook.id, limit).await?;
+    Ok(ResponseJson(ApiResponse::success(deliveries)))
+}
+
+/// Queue a `ping` delivery; it is sent by the webhook service within a few seconds
+pub async fn test_webhook(
+    Extension(webhook): Extension<Webhook>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<WebhookDelivery>>, ApiError> {
+    let delivery = WebhookService::queue_ping(deployment.db(), &webhook).await?;
+    Ok(ResponseJson(ApiResponse::success(delivery)))
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let webhook_router = Router::new()
+        .route(
+            "/",
+            get(get_webhook).put(update_webhook).delete(delete_webhook),
+        )
+        .route("/deliveries", get(get_webhook_deliveries))
+        .route("/test", post(test_webhook))
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_webhook_middleware,
+        ));
+
+    let inner = Router::new()
+        .route("/", get(get_webhooks).post(create_webhook))
+        .nest("/{webhook_id}", webhook_router);
+
+    Router::new().nest("/webhooks", inner)
+}
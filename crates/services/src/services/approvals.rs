@@ -3,9 +3,14 @@ pub mod executor_approvals;
 use std::{collections::HashMap, sync::Arc, time::Duration as StdDuration};
 
 use dashmap::DashMap;
-use db::models::{
-    execution_process::ExecutionProcess,
-    task::{Task, TaskStatus},
+use db::{
+    DBService,
+    models::{
+        approval::{Approval, ApprovalActor, ApprovalDecision},
+        execution_process::ExecutionProcess,
+        task::{Task, TaskStatus},
+        task_event::TaskEventActor,
+    },
 };
 use executors::{
     approvals::ToolCallMetadata,
@@ -42,8 +47,11 @@ pub struct ToolContext {
     pub execution_process_id: Uuid,
 }
 
+/// Tool approval requests awaiting a decision. Requests and decisions are also written
+/// to the `approvals` table as an audit trail.
 #[derive(Clone)]
 pub struct Approvals {
+    db: DBService,
     pending: Arc<DashMap<String, PendingApproval>>,
     completed: Arc<DashMap<String, ApprovalStatus>>,
     msg_stores: Arc<RwLock<HashMap<Uuid, Arc<MsgStore>>>>,
@@ -66,8 +74,9 @@ pub enum ApprovalError {
 }
 
 impl Approvals {
-    pub fn new(msg_stores: Arc<RwLock<HashMap<Uuid, Arc<MsgStore>>>>) -> Self {
+    pub fn new(db: DBService, msg_stores: Arc<RwLock<HashMap<Uuid, Arc<MsgStore>>>>) -> Self {
         Self {
+            db,
             pending: Arc::new(DashMap::new()),
             completed: Arc::new(DashMap::new()),
             msg_stores,
@@ -85,6 +94,8 @@ impl Approvals {
             .shared();
         let req_id = request.id.clone();
 
+        Approval::create(&self.db.pool, &request, &ApprovalStatus::Pending, None).await?;
+
         if let Some(store) = self.msg_store_by_id(&request.execution_process_id).await {
             // Find the matching tool use entry by name and input
             let matching_tool = find_matching_tool_use(store.clone(), &request.tool_call_id);
@@ -133,16 +144,73 @@ impl Approvals {
         Ok((request, waiter))
     }
 
+    /// Record a request that an approval rule decided, and mark its tool use entry so the
+    /// decision shows up in the conversation
+    pub async fn record_rule_decision(
+        &self,
+        request: &ApprovalRequest,
+        status: &ApprovalStatus,
+        rule_id: Uuid,
+        tool_status: ToolStatus,
+    ) -> Result<(), ApprovalError> {
+        Approval::create(&self.db.pool, request, status, Some(rule_id)).await?;
+
+        let store = self
+            .msg_store_by_id(&request.execution_process_id)
+            .await
+            .ok_or_else(|| {
+                ApprovalError::NoExecutorSession(request.execution_process_id.to_string())
+            })?;
+        let (idx, matching_tool) = find_matching_tool_use(store.clone(), &request.tool_call_id)
+            .ok_or(ApprovalError::NoToolUseEntry)?;
+        let updated_entry = matching_tool
+            .with_tool_status(tool_status)
+            .ok_or(ApprovalError::NoToolUseEntry)?;
+        store.push_patch(ConversationPatch::replace(idx, updated_entry));
+        Ok(())
+    }
+
+    /// Time out approvals left pending by a previous run, called at startup. Their agents
+    /// were waiting in the previous server process, so nothing can answer them anymore.
+    pub async fn expire_stale(&self) -> Result<(), ApprovalError> {
+        let pool = &self.db.pool;
+        for approval in Approval::find_pending(pool).await? {
+            if self.pending.contains_key(&approval.id) {
+                continue;
+            }
+            tracing::info!(
+                "Timing out approval {} for tool '{}' left pending by a previous run",
+                approval.id,
+                approval.tool_name
+            );
+            Approval::update_status(
+                pool,
+                &approval.id,
+                &ApprovalStatus::TimedOut,
+                ApprovalActor::Timeout,
+            )
+            .await?;
+            self.completed
+                .insert(approval.id.clone(), ApprovalStatus::TimedOut);
+        }
+        Ok(())
+    }
+
     #[tracing::instrument(skip(self, id, req))]
     pub async fn respond(
         &self,
-        pool: &SqlitePool,
         id: &str,
         req: ApprovalResponse,
     ) -> Result<(ApprovalStatus, ToolContext), ApprovalError> {
+        let pool = &self.db.pool;
         if let Some((_, p)) = self.pending.remove(id) {
             self.completed.insert(id.to_string(), req.status.clone());
             let _ = p.response_tx.send(req.status.clone());
+            if let Err(e) =
+                Approval::update_status(pool, id, &req.status, ApprovalActor::User).await
+            {
+                tracing::error!("Failed to record decision for approval {}: {}", id, e);
+            }
 
             if let Some(store) = self.msg_store_by_id(&p.execution_process_id).await {
                 let status = ToolStatus::from_approval_status(&req.status).ok_or(
@@ -173,7 +241,13 @@ impl Approvals {
             ) && let Ok(ctx) =
                 ExecutionProcess::load_context(pool, tool_ctx.execution_process_id).await
                 && ctx.task.status == TaskStatus::InReview
-                && let Err(e) = Task::update_status(pool, ctx.task.id, TaskStatus::InProgress).await
+                && let Err(e) = Task::update_status(
+                    pool,
+                    ctx.task.id,
+                    TaskStatus::InProgress,
+                    TaskEventActor::User,
+                )
+                .await
             {
                 tracing::warn!(
                     "Failed to update task status to InProgress after approval response: {}",
@@ -185,7 +259,14 @@ impl Approvals {
         } else if self.completed.contains_key(id) {
             Err(ApprovalError::AlreadyCompleted)
         } else {
-            Err(ApprovalError::NotFound)
+            match Approval::find_by_id(pool, id).await? {
+                // Decided before, possibly by a previous run of the server
+                Some(approval) if approval.status != ApprovalDecision::Pending => {
+                    Err(ApprovalError::AlreadyCompleted)
+                }
+                // Pending rows nothing waits on, e.g. without a tool use entry to show them in
+                _ => Err(ApprovalError::NotFound),
+            }
         }
     }
 
@@ -199,6 +280,7 @@ impl Approvals {
         let pending = self.pending.clone();
         let completed = self.completed.clone();
         let msg_stores = self.msg_stores.clone();
+        let db = self.db.clone();
 
         let now = chrono::Utc::now();
         let to_wait = (timeout_at - now)
@@ -217,6 +299,13 @@ impl Approvals {
             let is_timeout = matches!(&status, ApprovalStatus::TimedOut);
             completed.insert(id.clone(), status.clone());
 
+            if is_timeout
+                && let Err(e) =
+                    Approval::update_status(&db.pool, &id, &status, ApprovalActor::Timeout).await
+            {
+                tracing::warn!("Failed to record timeout of approval '{}': {}", id, e);
+            }
+
             if is_timeout && let Some((_, pending_approval)) = pending.remove(&id) {
                 if pending_approval.response_tx.send(status.clone()).is_err() {
                     tracing::debug!("approval '{}' timeout notification receiver dropped", id);
@@ -261,7 +350,13 @@ impl Approvals {
 pub(crate) async fn ensure_task_in_review(pool: &SqlitePool, execution_process_id: Uuid) {
     if let Ok(ctx) = ExecutionProcess::load_context(pool, execution_process_id).await
         && ctx.task.status == TaskStatus::InProgress
-        && let Err(e) = Task::update_status(pool, ctx.task.id, TaskStatus::InReview).await
+        && let Err(e) = Task::update_status(
+            pool,
+            ctx.task.id,
+            TaskStatus::InReview,
+            TaskEventActor::Agent,
+        )
+        .await
     {
         tracing::warn!(
             "Failed to update task status to InReview for approval request: {}",
@@ -312,6 +407,16 @@ fn find_matching_tool_use(
 mod tests {
     use std::sync::Arc;
 
+    use db::models::{
+        approval_rule::{ApprovalRule, ApprovalRuleAction, CreateApprovalRule},
+        execution_process::{
+            CreateImportedExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus,
+        },
+        project::{CreateProject, Project},
+        session::{CreateSession, Session},
+        task::CreateTask,
+        workspace::{CreateWorkspace, Workspace},
+    };
     use executors::logs::{ActionType, NormalizedEntry, NormalizedEntryType, ToolStatus};
     use utils::msg_store::MsgStore;
 
@@ -398,4 +503,301 @@ mod tests {
             "Should not match different tool ids"
         );
     }
+
+    async fn create_process(pool: &SqlitePool) -> Uuid {
+        let project = Project::create(
+            pool,
+            &CreateProject {
+                name: "approvals".to_string(),
+                repositories: vec![],
+            },
+            Uuid::new_v4(),
+        )
+        .await
+        .unwrap();
+        let data = CreateTask::from_title_description(project.id, "task".to_string(), None);
+        let task = Task::create(pool, &data, Uuid::new_v4(), TaskEventActor::User)
+            .await
+            .unwrap();
+        let workspace = Workspace::create(
+            pool,
+            &CreateWorkspace {
+                branch: "vk/approvals".to_string(),
+                agent_working_dir: None,
+            },
+            Uuid::new_v4(),
+            task.id,
+        )
+        .await
+        .unwrap();
+        let session = Session::create(
+            pool,
+            &CreateSession { executor: None },
+            Uuid::new_v4(),
+            workspace.id,
+        )
+        .await
+        .unwrap();
+        let process_id = Uuid::new_v4();
+        ExecutionProcess::create_imported(
+            pool,
+            &CreateImportedExecutionProcess {
+                session_id: session.id,
+                executor_action: serde_json::json!({}),
+                run_reason: ExecutionProcessRunReason::CodingAgent,
+                status: ExecutionProcessStatus::Running,
+                exit_code: None,
+                dropped: false,
+                started_at: chrono::Utc::now(),
+                completed_at: None,
+            },
+            process_id,
+        )
+        .await
+        .unwrap();
+        process_id
+    }
+
+    /// A service with a conversation containing one tool use entry per tool call id
+    async fn approvals_with_tool_uses(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        tool_call_ids: &[&str],
+    ) -> (Approvals, Arc<MsgStore>) {
+        let store = Arc::new(MsgStore::new());
+        for (idx, id) in tool_call_ids.iter().enumerate() {
+            let entry = create_tool_use_entry("Bash", "foo.rs", id, ToolStatus::Created);
+            store.push_patch(ConversationPatch::add_normalized_entry(idx, entry));
+        }
+        let msg_stores = Arc::new(RwLock::new(HashMap::from([(
+            execution_process_id,
+            store.clone(),
+        )])));
+        let approvals = Approvals::new(DBService { pool: pool.clone() }, msg_stores);
+        (approvals, store)
+    }
+
+    fn approval_request(execution_process_id: Uuid, tool_call_id: &str) -> ApprovalRequest {
+        let now = chrono::Utc::now();
+        ApprovalRequest {
+            id: Uuid::new_v4().to_string(),
+            tool_name: "Bash".to_string(),
+            tool_input: serde_json::json!({ "command": "ls" }),
+            tool_call_id: tool_call_id.to_string(),
+            execution_process_id,
+            created_at: now,
+            timeout_at: now + chrono::Duration::hours(1),
+        }
+    }
+
+    fn last_tool_status(store: &MsgStore) -> ToolStatus {
+        store
+            .get_history()
+            .iter()
+            .rev()
+            .find_map(|msg| match msg {
+                LogMsg::JsonPatch(patch) => extract_normalized_entry_from_patch(patch),
+                _ => None,
+            })
+            .and_then(|(_, entry)| match entry.entry_type {
+                NormalizedEntryType::ToolUse { status, .. } => Some(status),
+                _ => None,
+            })
+            .unwrap()
+    }
+
+    #[sqlx::test(migrations = "../db/migrations")]
+    async fn persists_requests_and_user_decisions(pool: SqlitePool) {
+        let process_id = create_process(&pool).await;
+        let (approvals, store) = approvals_with_tool_uses(&pool, process_id, &["call-1"]).await;
+
+        let (request, waiter) = approvals
+            .create_with_waiter(approval_request(process_id, "call-1"))
+            .await
+            .unwrap();
+        let stored = Approval::find_by_id(&pool, &request.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored.status, ApprovalDecision::Pending);
+        assert_eq!(stored.actor, None);
+        assert_eq!(stored.tool_input.0, serde_json::json!({ "command": "ls" }));
+        assert!(matches!(
+            last_tool_status(&store),
+            ToolStatus::PendingApproval { approval_id, .. } if approval_id == request.id
+        ));
+
+        let (status, ctx) = approvals
+            .respond(
+                &request.id,
+                ApprovalResponse {
+                    execution_process_id: process_id,
+                    status: ApprovalStatus::Denied {
+                        reason: Some("not now".to_string()),
+                    },
+                },
+            )
+            .await
+            .unwrap();
+        assert!(matches!(status, ApprovalStatus::Denied { .. }));
+        assert_eq!(ctx.execution_process_id, process_id);
+        assert!(matches!(waiter.await, ApprovalStatus::Denied { .. }));
+
+        let stored = Approval::find_by_id(&pool, &request.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored.status, ApprovalDecision::Denied);
+        assert_eq!(stored.denial_reason.as_deref(), Some("not now"));
+        assert_eq!(stored.actor, Some(ApprovalActor::User));
+        assert!(stored.responded_at.is_some());
+
+        assert!(matches!(
+            approvals
+                .respond(
+                    &request.id,
+                    ApprovalResponse {
+                        execution_process_id: process_id,
+                        status: ApprovalStatus::Approved,
+                    },
+                )
+                .await,
+            Err(ApprovalError::AlreadyCompleted)
+        ));
+    }
+
+    #[sqlx::test(migrations = "../db/migrations")]
+    async fn restart_expires_stale_approvals_and_keeps_decisions(pool: SqlitePool) {
+        let process_id = create_process(&pool).await;
+        let stale = approval_request(process_id, "call-1");
+        let decided = approval_request(process_id, "call-2");
+        Approval::create(&pool, &stale, &ApprovalStatus::Pending, None)
+            .await
+            .unwrap();
+        Approval::create(&pool, &decided, &ApprovalStatus::Approved, None)
+            .await
+            .unwrap();
+
+        // A fresh service, as after a restart, with a new request of its own
+        let (approvals, _store) = approvals_with_tool_uses(&pool, process_id, &["call-3"]).await;
+        let (live, _waiter) = approvals
+            .create_with_waiter(approval_request(process_id, "call-3"))
+            .await
+            .unwrap();
+        approvals.expire_stale().await.unwrap();
+
+        let stale_row = Approval::find_by_id(&pool, &stale.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(stale_row.status, ApprovalDecision::TimedOut);
+        assert_eq!(stale_row.actor, Some(ApprovalActor::Timeout));
+        let decided_row = Approval::find_by_id(&pool, &decided.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(decided_row.status, ApprovalDecision::Approved);
+        assert_eq!(decided_row.actor, Some(ApprovalActor::User));
+        let live_row = Approval::find_by_id(&pool, &live.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(live_row.status, ApprovalDecision::Pending);
+
+        for id in [&stale.id, &decided.id] {
+            assert!(matches!(
+                approvals
+                    .respond(
+                        id,
+                        ApprovalResponse {
+                            execution_process_id: process_id,
+                            status: ApprovalStatus::Approved,
+                        },
+                    )
+                    .await,
+                Err(ApprovalError::AlreadyCompleted)
+            ));
+        }
+        assert!(matches!(
+            approvals
+                .respond(
+                    "unknown",
+                    ApprovalResponse {
+                        execution_process_id: process_id,
+                        status: ApprovalStatus::Approved,
+                    },
+                )
+                .await,
+            Err(ApprovalError::NotFound)
+        ));
+    }
+
+    #[sqlx::test(migrations = "../db/migrations")]
+    async fn records_rule_decisions(pool: SqlitePool) {
+        let process_id = create_process(&pool).await;
+        let (approvals, store) = approvals_with_tool_uses(&pool, process_id, &["call-1"]).await;
+        let rule = ApprovalRule::create(
+            &pool,
+            &CreateApprovalRule {
+                project_id: None,
+                executor: None,
+                variant: None,
+                action: ApprovalRuleAction::Allow,
+                tool_pattern: "Bash".to_string(),
+                input_pattern: None,
+                description: None,
+                enabled: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        let request = approval_request(process_id, "call-1");
+        approvals
+            .record_rule_decision(
+                &request,
+                &ApprovalStatus::Approved,
+                rule.id,
+                ToolStatus::AutoApproved {
+                    rule: rule.to_match(),
+                },
+            )
+            .await
+            .unwrap();
+
+        let stored = Approval::find_by_id(&pool, &request.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored.status, ApprovalDecision::Approved);
+        assert_eq!(stored.rule_id, Some(rule.id));
+        assert_eq!(stored.actor, Some(ApprovalActor::Rule));
+        assert!(stored.responded_at.is_some());
+        assert!(matches!(
+            last_tool_status(&store),
+            ToolStatus::AutoApproved { rule: matched } if matched.rule_id == rule.id
+        ));
+
+        // Without a tool use entry to mark, the decision is still recorded
+        let unmatched = approval_request(process_id, "missing");
+        assert!(matches!(
+            approvals
+                .record_rule_decision(
+                    &unmatched,
+                    &ApprovalStatus::Approved,
+                    rule.id,
+                    ToolStatus::AutoApproved {
+                        rule: rule.to_match(),
+                    },
+                )
+                .await,
+            Err(ApprovalError::NoToolUseEntry)
+        ));
+        assert!(
+            Approval::find_by_id(&pool, &unmatched.id)
+                .await
+                .unwrap()
+                .is_some()
+        );
+    }
 }
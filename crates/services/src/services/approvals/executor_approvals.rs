@@ -1,9 +1,19 @@
 use std::sync::Arc;
 
 use async_trait::async_trait;
-use db::{self, DBService};
-use executors::approvals::{ExecutorApprovalError, ExecutorApprovalService};
-use serde_json::Value;
+use db::{
+    self, DBService,
+    models::{
+        approval_rule::{ApprovalRule, ApprovalRuleAction},
+        execution_process::ExecutionProcess,
+        webhook::{WebhookEvent, WebhookEventType},
+    },
+};
+use executors::{
+    approvals::{ExecutorApprovalError, ExecutorApprovalService},
+    logs::ToolStatus,
+};
+use serde_json::{Value, json};
 use utils::approvals::{ApprovalRequest, ApprovalStatus, CreateApprovalRequest};
 use uuid::Uuid;
 
@@ -30,6 +40,33 @@ impl ExecutorApprovalBridge {
             execution_process_id,
         })
     }
+
+    /// The approval rule deciding this tool call, if any. Failing to load rules falls
+    /// back to asking the user.
+    async fn matching_rule(&self, tool_name: &str, tool_input: &Value) -> Option<ApprovalRule> {
+        let pool = &self.db.pool;
+        let ctx = ExecutionProcess::load_context(pool, self.execution_process_id)
+            .await
+            .inspect_err(|e| tracing::warn!("Failed to load context for approval rules: {}", e))
+            .ok()?;
+        let profile = ctx
+            .execution_process
+            .executor_action()
+            .ok()?
+            .executor_profile_id()?;
+
+        let rules = ApprovalRule::find_applicable(
+            pool,
+            ctx.project.id,
+            profile.executor,
+            profile.variant.as_deref(),
+        )
+        .await
+        .inspect_err(|e| tracing::warn!("Failed to load approval rules: {}", e))
+        .ok()?;
+
+        ApprovalRule::evaluate(&rules, tool_name, tool_input).cloned()
+    }
 }
 
 #[async_trait]
@@ -40,8 +77,7 @@ impl ExecutorApprovalService for ExecutorApprovalBridge {
         tool_input: Value,
         tool_call_id: &str,
     ) -> Result<ApprovalStatus, ExecutorApprovalError> {
-        super::ensure_task_in_review(&self.db.pool, self.execution_process_id).await;
-
+        let rule = self.matching_rule(tool_name, &tool_input).await;
         let request = ApprovalRequest::from_create(
             CreateApprovalRequest {
                 tool_name: tool_name.to_string(),
@@ -51,12 +87,67 @@ impl ExecutorApprovalService for ExecutorApprovalBridge {
             self.execution_process_id,
         );
 
-        let (_, waiter) = self
+        if let Some(rule) = rule {
+            let matched = rule.to_match();
+            let (status, tool_status) = match rule.action {
+                ApprovalRuleAction::Allow => (
+                    ApprovalStatus::Approved,
+                    ToolStatus::AutoApproved { rule: matched },
+                ),
+                ApprovalRuleAction::Deny => {
+                    let reason = Some(format!("Denied by approval rule: {}", matched.summary));
+                    (
+                        ApprovalStatus::Denied {
+                            reason: reason.clone(),
+                        },
+                        ToolStatus::Denied {
+                            reason,
+                            rule: Some(matched),
+                        },
+                    )
+                }
+            };
+            tracing::info!(
+                "Approval rule {} decided tool '{}' ({:?}) for execution process {}",
+                rule.id,
+                tool_name,
+                rule.action,
+                self.execution_process_id
+            );
+            if let Err(e) = self
+                .approvals
+                .record_rule_decision(&request, &status, rule.id, tool_status)
+                .await
+            {
+                tracing::warn!("Failed to record approval rule decision: {}", e);
+            }
+            return Ok(status);
+        }
+
+        super::ensure_task_in_review(&self.db.pool, self.execution_process_id).await;
+
+        let (request, waiter) = self
             .approvals
             .create_with_waiter(request)
             .await
             .map_err(ExecutorApprovalError::request_failed)?;
 
+        if let Err(e) = WebhookEvent::create(
+            &self.db.pool,
+            WebhookEventType::ApprovalRequested,
+            self.execution_process_id,
+            &json!({
+                "approval_id": request.id,
+                "tool_name": request.tool_name,
+                "tool_input": request.tool_input,
+                "timeout_at": request.timeout_at,
+            }),
+        )
+        .await
+        {
+            tracing::warn!("Failed to raise approval webhook event: {}", e);
+        }
+
         // Play notification sound when approval is needed
         self.notification_service
             .notify(
@@ -17,6 +17,57 @@ fn default_pr_auto_description_enabled() -> bool {
     true
 }
 
+/// How execution logs are stored once their process has completed
+#[derive(Clone, Debug, Default, Serialize, Deserialize, TS)]
+pub struct LogStorageConfig {
+    /// Write compacted logs of at least 64 KiB to files under the asset directory instead of
+    /// the database
+    #[serde(default)]
+    pub spill_to_files: bool,
+    /// Delete logs this many days after their process completed. Coding agent prompts
+    /// and summaries are kept. `None` keeps logs forever.
+    #[serde(default)]
+    pub retention_days: Option<u32>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "lowercase")]
+pub enum ForgeKind {
+    GitHub,
+    GitLab,
+    Gitea,
+}
+
+/// A GitLab or Gitea instance that pull/merge requests are created on. github.com and
+/// hosts named like gitlab.* or gitea.* are recognised without an entry, but still need
+/// one (or a `GITLAB_TOKEN`/`GITEA_TOKEN` environment variable) for the API token.
+#[derive(Clone, Debug, Serialize, Deserialize, TS)]
+pub struct ForgeHostConfig {
+    /// Host as it appears in remote URLs, with the port if there is one, e.g.
+    /// `gitlab.example.com` or `localhost:3000`
+    pub host: String,
+    pub kind: ForgeKind,
+    /// Web URL of the instance when it is not `https://{host}`
+    #[serde(default)]
+    pub base_url: Option<String>,
+    #[serde(default)]
+    pub token: Option<String>,
+}
+
+/// What a model costs, in USD per million tokens. Cache prices default to the input price.
+#[derive(Clone, Debug, Serialize, Deserialize, TS)]
+pub struct ModelTokenPrice {
+    /// Model name, or a prefix of it, e.g. `claude-sonnet-4-5` also matches
+    /// `claude-sonnet-4-5-20250929`. The longest matching entry wins.
+    pub model: String,
+    pub input: f64,
+    pub output: f64,
+    #[serde(default)]
+    pub cache_read: Option<f64>,
+    #[serde(default)]
+    pub cache_write: Option<f64>,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, TS)]
 pub struct Config {
     pub config_version: String,
@@ -41,6 +92,13 @@ pub struct Config {
     pub pr_auto_description_enabled: bool,
     #[serde(default)]
     pub pr_auto_description_prompt: Option<String>,
+    #[serde(default)]
+    pub log_storage: LogStorageConfig,
+    #[serde(default)]
+    pub forges: Vec<ForgeHostConfig>,
+    /// Prices used to estimate what coding agent usage cost
+    #[serde(default)]
+    pub token_prices: Vec<ModelTokenPrice>,
 }
 
 impl Config {
@@ -66,6 +124,9 @@ impl Config {
             showcases: old_config.showcases,
             pr_auto_description_enabled: true,
             pr_auto_description_prompt: None,
+            log_storage: LogStorageConfig::default(),
+            forges: Vec::new(),
+            token_prices: Vec::new(),
         }
     }
 
@@ -116,6 +177,9 @@ impl Default for Config {
             showcases: ShowcaseState::default(),
             pr_auto_description_enabled: true,
             pr_auto_description_prompt: None,
+            log_storage: LogStorageConfig::default(),
+            forges: Vec::new(),
+            token_prices: Vec::new(),
         }
     }
 }
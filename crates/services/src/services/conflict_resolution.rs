@@ -0,0 +1,176 @@
+use std::path::{Path, PathBuf};
+
+use db::models::conflict_resolution::{ConflictResolution, CreateConflictResolution};
+use uuid::Uuid;
+
+use super::git::ConflictOp;
+
+/// Agent runs per conflict before giving up, since continuing a rebase can stop at the next
+/// conflicting commit
+pub const MAX_RESOLUTION_ROUNDS: u32 = 5;
+
+/// Commit subjects listed per side in the prompt
+const PROMPT_SUBJECTS: usize = 10;
+
+/// A coding agent run that is resolving conflicts in one repository of a workspace
+#[derive(Debug, Clone)]
+pub struct PendingConflictResolution {
+    pub workspace_id: Uuid,
+    pub repo_name: String,
+    pub worktree_path: PathBuf,
+    pub op: ConflictOp,
+    pub conflicted_files: Vec<String>,
+    /// 1 for the first agent run on this operation
+    pub round: u32,
+}
+
+impl PendingConflictResolution {
+    /// `None` if the record names an operation this version does not know
+    pub fn from_record(record: ConflictResolution) -> Option<Self> {
+        Some(Self {
+            workspace_id: record.workspace_id,
+            repo_name: record.repo_name,
+            worktree_path: PathBuf::from(record.worktree_path),
+            op: ConflictOp::from_subcommand(&record.op)?,
+            conflicted_files: record.conflicted_files.0,
+            round: u32::try_from(record.round).unwrap_or(MAX_RESOLUTION_ROUNDS),
+        })
+    }
+
+    pub fn to_record(&self) -> CreateConflictResolution {
+        CreateConflictResolution {
+            workspace_id: self.workspace_id,
+            repo_name: self.repo_name.clone(),
+            worktree_path: self.worktree_path.to_string_lossy().to_string(),
+            op: self.op.subcommand().to_string(),
+            conflicted_files: self.conflicted_files.clone(),
+            round: i64::from(self.round),
+        }
+    }
+}
+
+/// Conflicts the agent is asked to resolve
+#[derive(Debug, Clone)]
+pub struct ConflictDetails<'a> {
+    pub repo_name: &'a str,
+    pub op: &'a ConflictOp,
+    pub conflicted_files: &'a [String],
+    /// Commits on `HEAD`, newest first
+    pub head_subjects: &'a [String],
+    /// Commits leading to the commit being applied, newest first
+    pub incoming_subjects: &'a [String],
+}
+
+fn op_description(op: &ConflictOp) -> &'static str {
+    match op {
+        ConflictOp::Rebase => "rebasing this branch onto its target branch",
+        ConflictOp::Merge => "merging",
+        ConflictOp::CherryPick => "cherry-picking a commit",
+        ConflictOp::Revert => "reverting a commit",
+    }
+}
+
+fn subject_list(subjects: &[String]) -> String {
+    if subjects.is_empty() {
+        return "- (none touching the conflicted files)".to_string();
+    }
+    let mut list: Vec<String> = subjects
+        .iter()
+        .take(PROMPT_SUBJECTS)
+        .map(|subject| format!("- {subject}"))
+        .collect();
+    if subjects.len() > PROMPT_SUBJECTS {
+        list.push(format!(
+            "- ... and {} more",
+            subjects.len() - PROMPT_SUBJECTS
+        ));
+    }
+    list.join("\n")
+}
+
+/// Follow-up prompt asking the coding agent to resolve the conflicts of a stopped operation
+pub fn resolution_prompt(details: &ConflictDetails) -> String {
+    let (head_side, incoming_side) = match details.op {
+        // During a rebase HEAD is the target branch plus the commits replayed so far
+        ConflictOp::Rebase => ("the target branch", "this branch"),
+        _ => ("this branch", "the incoming changes"),
+    };
+    let files = details
+        .conflicted_files
+        .iter()
+        .map(|file| format!("- {file}"))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    format!(
+        "Git stopped with conflicts while {} in the `{}` repository (`git {}`). \
+         Resolve them by editing the conflicted files so that the intent of both sides is kept, \
+         and remove every conflict marker (`<<<<<<<`, `=======`, `>>>>>>>`). \
+         Do not run `git {} --continue`, `git commit` or `git {} --abort`; the operation is \
+         continued for you once you stop.\n\n\
+         Conflicted files:\n{files}\n\n\
+         Commits on {head_side}:\n{}\n\n\
+         Commits from {incoming_side}:\n{}",
+        op_description(details.op),
+        details.repo_name,
+        details.op.subcommand(),
+        details.op.subcommand(),
+        details.op.subcommand(),
+        subject_list(details.head_subjects),
+        subject_list(details.incoming_subjects),
+    )
+}
+
+/// True if `contents` still has a line git writes to delimit a conflict
+pub fn has_conflict_markers(contents: &str) -> bool {
+    contents.lines().any(|line| {
+        line.starts_with("<<<<<<< ") || line.starts_with(">>>>>>> ") || line == "======="
+    })
+}
+
+/// The files among `files` that still contain conflict markers. Deleted files count as
+/// resolved.
+pub fn files_with_conflict_markers(worktree_path: &Path, files: &[String]) -> Vec<String> {
+    files
+        .iter()
+        .filter(|file| {
+            std::fs::read(worktree_path.join(file))
+                .map(|bytes| has_conflict_markers(&String::from_utf8_lossy(&bytes)))
+                .unwrap_or(false)
+        })
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_conflict_markers() {
+        assert!(has_conflict_markers(
+            "a\n<<<<<<< HEAD\nb\n=======\nc\n>>>>>>> 1234abc (Add c)\n"
+        ));
+        assert!(!has_conflict_markers("a\n==========\n<<<<<<<no space\n"));
+        assert!(!has_conflict_markers("fn main() {}\n"));
+    }
+
+    #[test]
+    fn prompt_lists_files_and_both_sides() {
+        let files = vec!["src/lib.rs".to_string()];
+        let head = vec!["Rename config loader".to_string()];
+        let incoming: Vec<String> = (0..12).map(|i| format!("Commit {i}")).collect();
+        let prompt = resolution_prompt(&ConflictDetails {
+            repo_name: "web",
+            op: &ConflictOp::Rebase,
+            conflicted_files: &files,
+            head_subjects: &head,
+            incoming_subjects: &incoming,
+        });
+        assert!(prompt.contains("`git rebase`"));
+        assert!(prompt.contains("- src/lib.rs"));
+        assert!(prompt.contains("Commits on the target branch:\n- Rename config loader"));
+        assert!(prompt.contains("- Commit 9\n- ... and 2 more"));
+        assert!(!prompt.contains("Commit 10"));
+    }
+}
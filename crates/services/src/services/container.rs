@@ -9,7 +9,9 @@ use async_trait::async_trait;
 use db::{
     DBService,
     models::{
+        api_token::ApiToken,
         coding_agent_turn::{CodingAgentTurn, CreateCodingAgentTurn},
+        conflict_resolution::ConflictResolution,
         execution_process::{
             CreateExecutionProcess, ExecutionContext, ExecutionProcess, ExecutionProcessRunReason,
             ExecutionProcessStatus,
@@ -23,8 +25,11 @@ use db::{
         repo::Repo,
         session::{CreateSession, Session, SessionError},
         task::{Task, TaskStatus},
-        workspace::{Workspace, WorkspaceError},
-        workspace_repo::WorkspaceRepo,
+        task_auto_start::TaskAutoStart,
+        task_dependency::TaskDependency,
+        task_event::TaskEventActor,
+        workspace::{CreateWorkspace, Workspace, WorkspaceError},
+        workspace_repo::{CreateWorkspaceRepo, WorkspaceRepo},
     },
 };
 use executors::{
@@ -33,8 +38,12 @@ use executors::{
         coding_agent_initial::CodingAgentInitialRequest,
         script::{ScriptContext, ScriptRequest, ScriptRequestLanguage},
     },
-    executors::{ExecutorError, StandardCodingAgentExecutor},
-    logs::{NormalizedEntry, NormalizedEntryError, NormalizedEntryType, utils::ConversationPatch},
+    executors::{BaseCodingAgent, ExecutorError, StandardCodingAgentExecutor},
+    logs::{
+        NormalizedEntry, NormalizedEntryError, NormalizedEntryType,
+        fixture::{self, LogFixture},
+        utils::ConversationPatch,
+    },
     profile::{ExecutorConfigs, ExecutorProfileId},
 };
 use futures::{StreamExt, future};
@@ -51,6 +60,7 @@ use uuid::Uuid;
 use crate::services::{
     git::{GitService, GitServiceError},
     notification::NotificationService,
+    quality_gate::{self, QualityGateError, QualityGateState, RepoQualityGate},
     share::SharePublisher,
     workspace_manager::WorkspaceError as WorkspaceManagerError,
     worktree_manager::WorktreeError,
@@ -82,7 +92,7 @@ pub enum ContainerError {
 }
 
 #[async_trait]
-pub trait ContainerService {
+pub trait ContainerService: Clone + Send + Sync + 'static {
     fn msg_stores(&self) -> &Arc<RwLock<HashMap<Uuid, Arc<MsgStore>>>>;
 
     fn db(&self) -> &DBService;
@@ -99,6 +109,10 @@ pub trait ContainerService {
 
     async fn kill_all_running_processes(&self) -> Result<(), ContainerError>;
 
+    /// Send the next queued follow-up of sessions left idle with a non-empty queue, e.g.
+    /// because the server stopped before their last execution drained it. Call at startup.
+    async fn drain_idle_queues(&self) -> Result<(), ContainerError>;
+
     async fn delete(&self, workspace: &Workspace) -> Result<(), ContainerError>;
 
     /// Check if a task has any running execution processes
@@ -125,14 +139,14 @@ pub trait ContainerService {
 
     /// A context is finalized when
     /// - Always when the execution process has failed or been killed
-    /// - Never when the run reason is DevServer
+    /// - Never when the run reason is DevServer or QualityGate
     /// - Never when a setup script has no next_action (parallel mode)
     /// - The next action is None (no follow-up actions)
     fn should_finalize(&self, ctx: &ExecutionContext) -> bool {
-        // Never finalize DevServer processes
+        // Never finalize DevServer or QualityGate processes
         if matches!(
             ctx.execution_process.run_reason,
-            ExecutionProcessRunReason::DevServer
+            ExecutionProcessRunReason::DevServer | ExecutionProcessRunReason::QualityGate
         ) {
             return false;
         }
@@ -167,7 +181,14 @@ pub trait ContainerService {
         share_publisher: Option<&SharePublisher>,
         ctx: &ExecutionContext,
     ) {
-        match Task::update_status(&self.db().pool, ctx.task.id, TaskStatus::InReview).await {
+        match Task::update_status(
+            &self.db().pool,
+            ctx.task.id,
+            TaskStatus::InReview,
+            TaskEventActor::Agent,
+        )
+        .await
+        {
             Ok(_) => {
                 if let Some(publisher) = share_publisher
                     && let Err(err) = publisher.update_shared_task_by_id(ctx.task.id).await
@@ -212,6 +233,31 @@ pub trait ContainerService {
 
     /// Cleanup executions marked as running in the db, call at startup
     async fn cleanup_orphan_executions(&self) -> Result<(), ContainerError> {
+        let revoked = ApiToken::revoke_all_for_executions(&self.db().pool).await?;
+        if revoked > 0 {
+            tracing::info!("Revoked {} API tokens of interrupted executions", revoked);
+        }
+        // Agents resolving conflicts did not finish, so leave their branches as they were
+        for resolution in ConflictResolution::take_all(&self.db().pool).await? {
+            let worktree_path = PathBuf::from(&resolution.worktree_path);
+            if !matches!(self.git().detect_conflict_op(&worktree_path), Ok(Some(_))) {
+                continue;
+            }
+            match self.git().abort_conflicts(&worktree_path) {
+                Ok(()) => tracing::info!(
+                    "Aborted git {} in {} of workspace {} after an interrupted conflict resolution",
+                    resolution.op,
+                    resolution.repo_name,
+                    resolution.workspace_id
+                ),
+                Err(e) => tracing::warn!(
+                    "Failed to abort git {} in {}: {}",
+                    resolution.op,
+                    worktree_path.display(),
+                    e
+                ),
+            }
+        }
         let running_processes = ExecutionProcess::find_running(&self.db().pool).await?;
         for process in running_processes {
             tracing::info!(
@@ -274,7 +320,14 @@ pub trait ContainerService {
                     Workspace::find_by_id(&self.db().pool, session.workspace_id).await
                 && let Ok(Some(task)) = workspace.parent_task(&self.db().pool).await
             {
-                match Task::update_status(&self.db().pool, task.id, TaskStatus::InReview).await {
+                match Task::update_status(
+                    &self.db().pool,
+                    task.id,
+                    TaskStatus::InReview,
+                    TaskEventActor::Agent,
+                )
+                .await
+                {
                     Ok(_) => {
                         if let Some(publisher) = self.share_publisher()
                             && let Err(err) = publisher.update_shared_task_by_id(task.id).await
@@ -530,6 +583,174 @@ pub trait ContainerService {
         chained
     }
 
+    fn gate_action_for_repo(repo: &ProjectRepoWithName) -> Option<ExecutorAction> {
+        repo.gate_script.as_ref().map(|script| {
+            ExecutorAction::new(
+                ExecutorActionType::ScriptRequest(ScriptRequest {
+                    script: script.clone(),
+                    language: ScriptRequestLanguage::Bash,
+                    context: ScriptContext::QualityGate,
+                    working_dir: Some(repo.repo_name.clone()),
+                }),
+                None,
+            )
+        })
+    }
+
+    /// Gate status of each workspace repository that has a gate script
+    async fn quality_gates(
+        &self,
+        workspace: &Workspace,
+    ) -> Result<Vec<RepoQualityGate>, ContainerError> {
+        let pool = &self.db().pool;
+        let task = workspace
+            .parent_task(pool)
+            .await?
+            .ok_or(SqlxError::RowNotFound)?;
+        let workspace_repos = WorkspaceRepo::find_repos_for_workspace(pool, workspace.id).await?;
+        let project_repos =
+            ProjectRepo::find_by_project_id_with_names(pool, task.project_id).await?;
+        let runs = ExecutionProcess::find_by_workspace_and_run_reason(
+            pool,
+            workspace.id,
+            &ExecutionProcessRunReason::QualityGate,
+        )
+        .await?;
+        let workspace_root = workspace.container_ref.as_ref().map(PathBuf::from);
+
+        let mut gates = Vec::new();
+        for repo in project_repos.iter().filter(|repo| {
+            repo.gate_script.is_some() && workspace_repos.iter().any(|r| r.id == repo.repo_id)
+        }) {
+            // Runs are newest first
+            let latest = runs
+                .iter()
+                .find(|run| quality_gate::gated_repo_name(run) == Some(repo.repo_name.as_str()));
+            let gated_head = match latest {
+                Some(run) => ExecutionProcessRepoState::find_by_execution_process_id(pool, run.id)
+                    .await?
+                    .into_iter()
+                    .find(|state| state.repo_id == repo.repo_id)
+                    .and_then(|state| state.before_head_commit),
+                None => None,
+            };
+            let worktree_path = workspace_root
+                .as_ref()
+                .map(|root| root.join(&repo.repo_name));
+            let current_head = worktree_path
+                .as_ref()
+                .and_then(|path| self.git().get_head_info(path).ok())
+                .map(|head| head.oid);
+            // Untracked files count too: the gate would build them but the merge leaves them out
+            let dirty = worktree_path
+                .as_ref()
+                .and_then(|path| self.git().get_worktree_change_counts(path).ok())
+                .is_some_and(|(tracked, untracked)| tracked + untracked > 0);
+
+            gates.push(RepoQualityGate {
+                repo_id: repo.repo_id,
+                repo_name: repo.repo_name.clone(),
+                state: quality_gate::gate_state(
+                    latest.map(|run| (&run.status, run.exit_code)),
+                    gated_head.as_deref(),
+                    current_head.as_deref(),
+                    dirty,
+                ),
+                execution_process_id: latest.map(|run| run.id),
+            });
+        }
+        Ok(gates)
+    }
+
+    /// Run a repository's gate script in the workspace's latest session
+    async fn start_quality_gate(
+        &self,
+        workspace: &Workspace,
+        repo_id: Uuid,
+    ) -> Result<ExecutionProcess, ContainerError> {
+        let pool = &self.db().pool;
+        let task = workspace
+            .parent_task(pool)
+            .await?
+            .ok_or(SqlxError::RowNotFound)?;
+        let action = ProjectRepo::find_by_project_id_with_names(pool, task.project_id)
+            .await?
+            .iter()
+            .find(|repo| repo.repo_id == repo_id)
+            .and_then(Self::gate_action_for_repo)
+            .ok_or_else(|| ContainerError::Other(anyhow!("No quality gate configured")))?;
+
+        let session = match Session::find_latest_by_workspace_id(pool, workspace.id).await? {
+            Some(session) => session,
+            None => {
+                Session::create(
+                    pool,
+                    &CreateSession {
+                        executor: Some("quality-gate".to_string()),
+                    },
+                    Uuid::new_v4(),
+                    workspace.id,
+                )
+                .await?
+            }
+        };
+
+        self.start_execution(
+            workspace,
+            &session,
+            &action,
+            &ExecutionProcessRunReason::QualityGate,
+        )
+        .await
+    }
+
+    /// Whether a repository's gate blocks merging it or opening a pull request. A gate that
+    /// has not run on the current HEAD is started, and blocks until it passes. Uncommitted
+    /// changes block without starting a run.
+    async fn check_quality_gate(
+        &self,
+        workspace: &Workspace,
+        repo_id: Uuid,
+    ) -> Result<Option<QualityGateError>, ContainerError> {
+        let Some(gate) = self
+            .quality_gates(workspace)
+            .await?
+            .into_iter()
+            .find(|gate| gate.repo_id == repo_id)
+        else {
+            return Ok(None);
+        };
+
+        let error = match (gate.state, gate.execution_process_id) {
+            (QualityGateState::Passed, _) => return Ok(None),
+            (QualityGateState::UncommittedChanges, _) => {
+                QualityGateError::QualityGateUncommittedChanges {
+                    repo_name: gate.repo_name,
+                }
+            }
+            (QualityGateState::Running, Some(execution_process_id)) => {
+                QualityGateError::QualityGateRunning {
+                    repo_name: gate.repo_name,
+                    execution_process_id,
+                }
+            }
+            (QualityGateState::Failed, Some(execution_process_id)) => {
+                QualityGateError::QualityGateFailed {
+                    repo_name: gate.repo_name,
+                    execution_process_id,
+                }
+            }
+            _ => {
+                let process = self.start_quality_gate(workspace, repo_id).await?;
+                QualityGateError::QualityGateRunning {
+                    repo_name: gate.repo_name,
+                    execution_process_id: process.id,
+                }
+            }
+        };
+        Ok(Some(error))
+    }
+
     async fn try_stop(&self, workspace: &Workspace, include_dev_server: bool) {
         // stop execution processes for this workspace's sessions
         let sessions = match Session::find_by_workspace_id(&self.db().pool, workspace.id).await {
@@ -587,6 +808,15 @@ pub trait ContainerService {
 
     async fn try_commit_changes(&self, ctx: &ExecutionContext) -> Result<bool, ContainerError>;
 
+    /// Continue the workspace's latest session with a coding agent run that resolves the
+    /// conflicts of the stopped git operation in `repo_name`. When it finishes without leaving
+    /// conflict markers the operation is continued, otherwise it is aborted.
+    async fn start_conflict_resolution(
+        &self,
+        workspace: &Workspace,
+        repo_name: &str,
+    ) -> Result<ExecutionProcess, ContainerError>;
+
     async fn copy_project_files(
         &self,
         source_dir: &Path,
@@ -773,16 +1003,16 @@ pub trait ContainerService {
             };
 
             // Spawn normalizer on populated store
-            match executor_action.typ() {
+            let normalizers = match executor_action.typ() {
                 ExecutorActionType::CodingAgentInitialRequest(request) => {
                     let executor = ExecutorConfigs::get_cached()
                         .get_coding_agent_or_default(&request.executor_profile_id);
-                    executor.normalize_logs(temp_store.clone(), &current_dir);
+                    executor.normalize_logs(temp_store.clone(), &current_dir)
                 }
                 ExecutorActionType::CodingAgentFollowUpRequest(request) => {
                     let executor = ExecutorConfigs::get_cached()
                         .get_coding_agent_or_default(&request.executor_profile_id);
-                    executor.normalize_logs(temp_store.clone(), &current_dir);
+                    executor.normalize_logs(temp_store.clone(), &current_dir)
                 }
                 _ => {
                     tracing::debug!(
@@ -791,11 +1021,22 @@ pub trait ContainerService {
                     );
                     return None;
                 }
+            };
+            // The stored output ends with `Finished`, so the normalizers run to completion
+            for result in future::join_all(normalizers).await {
+                if let Err(e) = result {
+                    tracing::error!("Log normalizer for execution {} failed: {}", id, e);
+                }
             }
+
+            let patches: Vec<Result<LogMsg, std::io::Error>> = temp_store
+                .get_history()
+                .into_iter()
+                .filter(|msg| matches!(msg, LogMsg::JsonPatch(..)))
+                .map(Ok)
+                .collect();
             Some(
-                temp_store
-                    .history_plus_stream()
-                    .filter(|msg| future::ready(matches!(msg, Ok(LogMsg::JsonPatch(..)))))
+                futures::stream::iter(patches)
                     .chain(futures::stream::once(async {
                         Ok::<_, std::io::Error>(LogMsg::Finished)
                     }))
@@ -868,6 +1109,18 @@ pub trait ContainerService {
                                 );
                             }
                         }
+                        LogMsg::TokenUsage(usage) => {
+                            if let Err(e) =
+                                CodingAgentTurn::update_token_usage(&db.pool, execution_id, usage)
+                                    .await
+                            {
+                                tracing::error!(
+                                    "Failed to update token usage for execution process {}: {}",
+                                    execution_id,
+                                    e
+                                );
+                            }
+                        }
                         LogMsg::Finished => {
                             break;
                         }
@@ -980,6 +1233,163 @@ pub trait ContainerService {
         Ok(execution_process)
     }
 
+    /// Launch dependents of `task_id` whose last blocker is now done and that carry an
+    /// auto-start configuration. The configuration is consumed so each task launches once,
+    /// and put back if the launch fails.
+    async fn start_unblocked_dependents(
+        &self,
+        task_id: Uuid,
+    ) -> Result<Vec<Workspace>, ContainerError> {
+        let dependents =
+            TaskDependency::find_unblocked_dependents(&self.db().pool, task_id).await?;
+
+        let mut started = Vec::new();
+        for task in dependents {
+            match self.auto_start_task(&task).await {
+                Ok(Some(workspace)) => {
+                    tracing::info!(
+                        "Auto-started task {} after blocker {} was completed",
+                        task.id,
+                        task_id
+                    );
+                    started.push(workspace);
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    tracing::error!("Failed to auto-start unblocked task {}: {}", task.id, e);
+                }
+            }
+        }
+
+        Ok(started)
+    }
+
+    /// Launch `task` through its auto-start configuration if none of its blockers is still
+    /// open, e.g. after a blocker was removed or the configuration was saved late
+    async fn start_if_unblocked(&self, task: &Task) -> Result<Option<Workspace>, ContainerError> {
+        if TaskDependency::is_blocked(&self.db().pool, task.id).await? {
+            return Ok(None);
+        }
+        self.auto_start_task(task).await
+    }
+
+    /// Claim the auto-start configuration of an unblocked `task` and launch it. `None` if the
+    /// task is not in `TaskStatus::Todo`, has no configuration or failed to launch, in which
+    /// case the configuration is put back.
+    async fn auto_start_task(&self, task: &Task) -> Result<Option<Workspace>, ContainerError> {
+        let pool = &self.db().pool;
+        if task.status != TaskStatus::Todo {
+            return Ok(None);
+        }
+        let Some(auto_start) = TaskAutoStart::claim(pool, task.id).await? else {
+            return Ok(None);
+        };
+
+        let result = if auto_start.repos.is_empty() {
+            tracing::warn!(
+                "Skipping auto-start for task {}: no repositories configured",
+                task.id
+            );
+            None
+        } else {
+            match self
+                .create_and_start_workspace(
+                    task,
+                    auto_start.executor_profile_id.0.clone(),
+                    &auto_start.repos,
+                )
+                .await
+            {
+                Ok(workspace) => Some(workspace),
+                Err(e) => {
+                    tracing::error!("Failed to auto-start unblocked task {}: {}", task.id, e);
+                    None
+                }
+            }
+        };
+
+        if result.is_none()
+            && let Err(e) = TaskAutoStart::restore(pool, &auto_start).await
+        {
+            tracing::error!(
+                "Failed to restore the auto-start configuration of task {}: {}",
+                task.id,
+                e
+            );
+        }
+        Ok(result)
+    }
+
+    /// Create a workspace for `task` on `repos` and start it with the given executor
+    /// profile, using the project's default agent working directory. The workspace is
+    /// removed again if it fails to start, so a retry does not leave a duplicate behind.
+    async fn create_and_start_workspace(
+        &self,
+        task: &Task,
+        executor_profile_id: ExecutorProfileId,
+        repos: &[CreateWorkspaceRepo],
+    ) -> Result<Workspace, ContainerError> {
+        let workspace = self.create_workspace_for_task(task, repos).await?;
+        if let Err(e) = self.start_workspace(&workspace, executor_profile_id).await {
+            self.discard_workspace(workspace.id).await;
+            return Err(e);
+        }
+        Ok(workspace)
+    }
+
+    /// Remove a workspace that never started, along with any container created for it
+    async fn discard_workspace(&self, workspace_id: Uuid) {
+        let pool = &self.db().pool;
+        match Workspace::find_by_id(pool, workspace_id).await {
+            Ok(Some(workspace)) => {
+                if let Err(e) = self.delete(&workspace).await {
+                    tracing::warn!("Failed to clean up workspace {}: {}", workspace_id, e);
+                }
+            }
+            Ok(None) => return,
+            Err(e) => tracing::warn!("Failed to load workspace {}: {}", workspace_id, e),
+        }
+        if let Err(e) = Workspace::delete(pool, workspace_id).await {
+            tracing::error!("Failed to delete workspace {}: {}", workspace_id, e);
+        }
+    }
+
+    /// Create (but don't start) a workspace for `task` on `repos`, using the project's
+    /// default agent working directory.
+    async fn create_workspace_for_task(
+        &self,
+        task: &Task,
+        repos: &[CreateWorkspaceRepo],
+    ) -> Result<Workspace, ContainerError> {
+        let pool = &self.db().pool;
+        let project = task
+            .parent_project(pool)
+            .await?
+            .ok_or(SqlxError::RowNotFound)?;
+        let agent_working_dir = project
+            .default_agent_working_dir
+            .as_ref()
+            .filter(|dir| !dir.is_empty())
+            .cloned();
+
+        let workspace_id = Uuid::new_v4();
+        let branch = self
+            .git_branch_from_workspace(&workspace_id, &task.title)
+            .await;
+        let workspace = Workspace::create(
+            pool,
+            &CreateWorkspace {
+                branch,
+                agent_working_dir,
+            },
+            workspace_id,
+            task.id,
+        )
+        .await?;
+        WorkspaceRepo::create_many(pool, workspace.id, repos).await?;
+        Ok(workspace)
+    }
+
     async fn start_execution(
         &self,
         workspace: &Workspace,
@@ -993,9 +1403,18 @@ pub trait ContainerService {
             .await?
             .ok_or(SqlxError::RowNotFound)?;
         if task.status != TaskStatus::InProgress
-            && run_reason != &ExecutionProcessRunReason::DevServer
+            && !matches!(
+                run_reason,
+                ExecutionProcessRunReason::DevServer | ExecutionProcessRunReason::QualityGate
+            )
         {
-            Task::update_status(&self.db().pool, task.id, TaskStatus::InProgress).await?;
+            Task::update_status(
+                &self.db().pool,
+                task.id,
+                TaskStatus::InProgress,
+                TaskEventActor::Agent,
+            )
+            .await?;
 
             if let Some(publisher) = self.share_publisher()
                 && let Err(err) = publisher.update_shared_task_by_id(task.id).await
@@ -1091,7 +1510,22 @@ pub trait ContainerService {
                     update_error
                 );
             }
-            Task::update_status(&self.db().pool, task.id, TaskStatus::InReview).await?;
+            if let Err(e) =
+                ApiToken::revoke_for_execution(&self.db().pool, execution_process.id).await
+            {
+                tracing::error!(
+                    "Failed to revoke API token of execution process {} after start error: {}",
+                    execution_process.id,
+                    e
+                );
+            }
+            Task::update_status(
+                &self.db().pool,
+                task.id,
+                TaskStatus::InReview,
+                TaskEventActor::Agent,
+            )
+            .await?;
 
             // Emit stderr error message
             let log_message = LogMsg::Stderr(format!("Failed to start execution: {start_error}"));
@@ -1145,7 +1579,24 @@ pub trait ContainerService {
             if let Some(executor) =
                 ExecutorConfigs::get_cached().get_coding_agent(executor_profile_id)
             {
-                executor.normalize_logs(msg_store, &self.workspace_to_current_dir(workspace));
+                let current_dir = self.workspace_to_current_dir(workspace);
+                if let Ok(dir) = std::env::var(fixture::RECORD_DIR_ENV) {
+                    let path = PathBuf::from(dir).join(format!(
+                        "{}-{}.jsonl",
+                        BaseCodingAgent::from(&executor).to_string().to_lowercase(),
+                        execution_process.id
+                    ));
+                    let (executor, current_dir, msg_store) =
+                        (executor.clone(), current_dir.clone(), msg_store.clone());
+                    tokio::spawn(async move {
+                        if let Err(e) =
+                            LogFixture::record(executor, current_dir, msg_store, path).await
+                        {
+                            tracing::warn!("Failed to record log fixture: {}", e);
+                        }
+                    });
+                }
+                executor.normalize_logs(msg_store, &current_dir);
             } else {
                 tracing::error!(
                     "Failed to resolve profile '{:?}' for normalization",
@@ -1191,3 +1642,224 @@ pub trait ContainerService {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use db::models::{
+        project::CreateProject, task::CreateTask, task_auto_start::UpsertTaskAutoStart,
+        task_event::TaskEventActor,
+    };
+    use sqlx::SqlitePool;
+
+    use super::*;
+    use crate::services::config::Config;
+
+    /// A container service whose workspaces never start
+    #[derive(Clone)]
+    struct FailingContainer {
+        db: DBService,
+        git: GitService,
+        msg_stores: Arc<RwLock<HashMap<Uuid, Arc<MsgStore>>>>,
+        notifications: NotificationService,
+        deleted: Arc<AtomicUsize>,
+    }
+
+    impl FailingContainer {
+        fn new(pool: SqlitePool) -> Self {
+            Self {
+                db: DBService { pool },
+                git: GitService::new(),
+                msg_stores: Arc::default(),
+                notifications: NotificationService::new(Arc::new(RwLock::new(Config::default()))),
+                deleted: Arc::default(),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl ContainerService for FailingContainer {
+        fn msg_stores(&self) -> &Arc<RwLock<HashMap<Uuid, Arc<MsgStore>>>> {
+            &self.msg_stores
+        }
+
+        fn db(&self) -> &DBService {
+            &self.db
+        }
+
+        fn git(&self) -> &GitService {
+            &self.git
+        }
+
+        fn share_publisher(&self) -> Option<&SharePublisher> {
+            None
+        }
+
+        fn notification_service(&self) -> &NotificationService {
+            &self.notifications
+        }
+
+        fn workspace_to_current_dir(&self, _workspace: &Workspace) -> PathBuf {
+            unimplemented!()
+        }
+
+        async fn create(&self, _workspace: &Workspace) -> Result<ContainerRef, ContainerError> {
+            Err(ContainerError::Other(anyhow!("no space left on device")))
+        }
+
+        async fn kill_all_running_processes(&self) -> Result<(), ContainerError> {
+            unimplemented!()
+        }
+
+        async fn drain_idle_queues(&self) -> Result<(), ContainerError> {
+            unimplemented!()
+        }
+
+        async fn delete(&self, _workspace: &Workspace) -> Result<(), ContainerError> {
+            self.deleted.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+
+        async fn ensure_container_exists(
+            &self,
+            _workspace: &Workspace,
+        ) -> Result<ContainerRef, ContainerError> {
+            unimplemented!()
+        }
+
+        async fn is_container_clean(&self, _workspace: &Workspace) -> Result<bool, ContainerError> {
+            unimplemented!()
+        }
+
+        async fn start_execution_inner(
+            &self,
+            _workspace: &Workspace,
+            _execution_process: &ExecutionProcess,
+            _executor_action: &ExecutorAction,
+        ) -> Result<(), ContainerError> {
+            unimplemented!()
+        }
+
+        async fn stop_execution(
+            &self,
+            _execution_process: &ExecutionProcess,
+            _status: ExecutionProcessStatus,
+        ) -> Result<(), ContainerError> {
+            unimplemented!()
+        }
+
+        async fn try_commit_changes(
+            &self,
+            _ctx: &ExecutionContext,
+        ) -> Result<bool, ContainerError> {
+            unimplemented!()
+        }
+
+        async fn start_conflict_resolution(
+            &self,
+            _workspace: &Workspace,
+            _repo_name: &str,
+        ) -> Result<ExecutionProcess, ContainerError> {
+            unimplemented!()
+        }
+
+        async fn copy_project_files(
+            &self,
+            _source_dir: &Path,
+            _target_dir: &Path,
+            _copy_files: &str,
+        ) -> Result<(), ContainerError> {
+            unimplemented!()
+        }
+
+        async fn stream_diff(
+            &self,
+            _workspace: &Workspace,
+            _stats_only: bool,
+        ) -> Result<
+            futures::stream::BoxStream<'static, Result<LogMsg, std::io::Error>>,
+            ContainerError,
+        > {
+            unimplemented!()
+        }
+
+        async fn git_branch_prefix(&self) -> String {
+            "vk".to_string()
+        }
+    }
+
+    async fn create_task(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        title: &str,
+        status: TaskStatus,
+    ) -> Task {
+        let mut data = CreateTask::from_title_description(project_id, title.to_string(), None);
+        data.status = Some(status);
+        Task::create(pool, &data, Uuid::new_v4(), TaskEventActor::User)
+            .await
+            .unwrap()
+    }
+
+    #[sqlx::test(migrations = "../db/migrations")]
+    async fn failed_auto_start_removes_the_workspace_and_keeps_the_config(pool: SqlitePool) {
+        let project = Project::create(
+            &pool,
+            &CreateProject {
+                name: "web".to_string(),
+                repositories: vec![],
+            },
+            Uuid::new_v4(),
+        )
+        .await
+        .unwrap();
+        let repo = Repo::find_or_create(&pool, Path::new("/tmp/web"), "web")
+            .await
+            .unwrap();
+        let blocker = create_task(&pool, project.id, "Add the API", TaskStatus::Done).await;
+        let dependent = create_task(&pool, project.id, "Use the API", TaskStatus::Todo).await;
+        TaskDependency::create(&pool, dependent.id, blocker.id)
+            .await
+            .unwrap();
+        TaskAutoStart::upsert(
+            &pool,
+            dependent.id,
+            &UpsertTaskAutoStart {
+                executor_profile_id: ExecutorProfileId::new(BaseCodingAgent::ClaudeCode),
+                repos: vec![CreateWorkspaceRepo {
+                    repo_id: repo.id,
+                    target_branch: "main".to_string(),
+                }],
+            },
+        )
+        .await
+        .unwrap();
+
+        let container = FailingContainer::new(pool.clone());
+        let started = container
+            .start_unblocked_dependents(blocker.id)
+            .await
+            .unwrap();
+        assert!(started.is_empty());
+        assert_eq!(container.deleted.load(Ordering::SeqCst), 1);
+        assert!(
+            Workspace::fetch_all(&pool, Some(dependent.id))
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        let workspace_repos: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM workspace_repos")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(workspace_repos, 0);
+        // The configuration is back, so the next unblock or retry launches the task
+        assert!(
+            TaskAutoStart::find_by_task_id(&pool, dependent.id)
+                .await
+                .unwrap()
+                .is_some()
+        );
+    }
+}
@@ -4,7 +4,7 @@ use db::{
     DBService,
     models::{
         execution_process::ExecutionProcess, project::Project, scratch::Scratch, task::Task,
-        workspace::Workspace,
+        task_dependency::TaskDependency, task_event::TaskEvent, workspace::Workspace,
     },
 };
 use serde_json::json;
@@ -21,7 +21,8 @@ mod streams;
 pub mod types;
 
 pub use patches::{
-    execution_process_patch, project_patch, scratch_patch, task_patch, workspace_patch,
+    execution_process_patch, project_patch, scratch_patch, task_event_patch, task_patch,
+    workspace_patch,
 };
 pub use types::{EventError, EventPatch, EventPatchInner, HookTables, RecordTypes};
 
@@ -172,6 +173,10 @@ impl EventService {
                                     // Deletions handled in preupdate hook for reliable data capture
                                     return;
                                 }
+                                (HookTables::TaskEvents, SqliteOperation::Delete) => {
+                                    // Events only go away with their task
+                                    return;
+                                }
                                 (HookTables::Tasks, _) => {
                                     match Task::find_by_rowid(&db.pool, rowid).await {
                                         Ok(Some(task)) => RecordTypes::Task(task),
@@ -232,6 +237,19 @@ impl EventService {
                                         }
                                     }
                                 }
+                                (HookTables::TaskEvents, _) => {
+                                    match TaskEvent::find_by_rowid(&db.pool, rowid).await {
+                                        Ok(Some(event)) => RecordTypes::TaskEvent(event),
+                                        Ok(None) => return,
+                                        Err(e) => {
+                                            tracing::error!(
+                                                "Failed to fetch task event: {:?}",
+                                                e
+                                            );
+                                            return;
+                                        }
+                                    }
+                                }
                                 (HookTables::Scratch, _) => {
                                     match Scratch::find_by_rowid(&db.pool, rowid).await {
                                         Ok(Some(scratch)) => RecordTypes::Scratch(scratch),
@@ -266,18 +284,32 @@ impl EventService {
                                         )
                                         .await
                                         && let Some(task_with_status) =
-                                            task_list.into_iter().find(|t| t.id == task.id)
+                                            task_list.iter().find(|t| t.id == task.id)
                                     {
                                         let patch = match hook.operation {
                                             SqliteOperation::Insert => {
-                                                task_patch::add(&task_with_status)
+                                                task_patch::add(task_with_status)
                                             }
                                             SqliteOperation::Update => {
-                                                task_patch::replace(&task_with_status)
+                                                task_patch::replace(task_with_status)
                                             }
-                                            _ => task_patch::replace(&task_with_status), // fallback
+                                            _ => task_patch::replace(task_with_status), // fallback
                                         };
                                         msg_store_for_hook.push_patch(patch);
+
+                                        // A status change can flip the blocked flag of dependents
+                                        if matches!(hook.operation, SqliteOperation::Update)
+                                            && let Ok(dependents) =
+                                                TaskDependency::find_dependents(&db.pool, task.id)
+                                                    .await
+                                        {
+                                            for dependent in task_list.iter().filter(|t| {
+                                                dependents.iter().any(|d| d.id == t.id)
+                                            }) {
+                                                msg_store_for_hook
+                                                    .push_patch(task_patch::replace(dependent));
+                                            }
+                                        }
                                         return;
                                     }
                                 }
@@ -298,6 +330,10 @@ impl EventService {
                                     msg_store_for_hook.push_patch(patch);
                                     return;
                                 }
+                                RecordTypes::TaskEvent(event) => {
+                                    msg_store_for_hook.push_patch(task_event_patch::add(event));
+                                    return;
+                                }
                                 RecordTypes::Scratch(scratch) => {
                                     let patch = match hook.operation {
                                         SqliteOperation::Insert => scratch_patch::add(scratch),
@@ -1,6 +1,6 @@
 use db::models::{
     execution_process::ExecutionProcess, project::Project, scratch::Scratch,
-    task::TaskWithAttemptStatus, workspace::Workspace,
+    task::TaskWithAttemptStatus, task_event::TaskEvent, workspace::Workspace,
 };
 use json_patch::{AddOperation, Patch, PatchOperation, RemoveOperation, ReplaceOperation};
 use uuid::Uuid;
@@ -48,6 +48,29 @@ pub mod task_patch {
     }
 }
 
+/// Helper functions for creating task status history patches. Events are never changed
+/// once recorded, so they are only ever added.
+pub mod task_event_patch {
+    use super::*;
+
+    fn task_event_path(event_id: Uuid) -> String {
+        format!(
+            "/task_events/{}",
+            escape_pointer_segment(&event_id.to_string())
+        )
+    }
+
+    /// Create patch for adding a new task event
+    pub fn add(event: &TaskEvent) -> Patch {
+        Patch(vec![PatchOperation::Add(AddOperation {
+            path: task_event_path(event.id)
+                .try_into()
+                .expect("Task event path should be valid"),
+            value: serde_json::to_value(event).expect("Task event serialization should not fail"),
+        })])
+    }
+}
+
 /// Helper functions for creating project-specific patches
 pub mod project_patch {
     use super::*;
@@ -4,6 +4,7 @@ use db::models::{
     scratch::Scratch,
     session::Session,
     task::{Task, TaskWithAttemptStatus},
+    task_event::TaskEvent,
 };
 use futures::StreamExt;
 use serde_json::json;
@@ -147,6 +148,54 @@ impl EventService {
         Ok(combined_stream)
     }
 
+    /// Stream the status history of a task with initial snapshot
+    pub async fn stream_task_events_raw(
+        &self,
+        task_id: Uuid,
+    ) -> Result<futures::stream::BoxStream<'static, Result<LogMsg, std::io::Error>>, EventError>
+    {
+        let events = TaskEvent::find_by_task_id(&self.db.pool, task_id).await?;
+
+        // Convert event array to object keyed by event ID
+        let events_map: serde_json::Map<String, serde_json::Value> = events
+            .into_iter()
+            .map(|event| (event.id.to_string(), serde_json::to_value(event).unwrap()))
+            .collect();
+
+        let initial_patch = json!([
+            {
+                "op": "replace",
+                "path": "/task_events",
+                "value": events_map
+            }
+        ]);
+        let initial_msg = LogMsg::JsonPatch(serde_json::from_value(initial_patch).unwrap());
+
+        let filtered_stream = BroadcastStream::new(self.msg_store.get_receiver()).filter_map(
+            move |msg_result| async move {
+                match msg_result {
+                    Ok(LogMsg::JsonPatch(patch)) => {
+                        if let Some(patch_op) = patch.0.first()
+                            && patch_op.path().starts_with("/task_events/")
+                            && let json_patch::PatchOperation::Add(op) = patch_op
+                            && let Ok(event) = serde_json::from_value::<TaskEvent>(op.value.clone())
+                            && event.task_id == task_id
+                        {
+                            return Some(Ok(LogMsg::JsonPatch(patch)));
+                        }
+                        None
+                    }
+                    Ok(other) => Some(Ok(other)),
+                    Err(_) => None,
+                }
+            },
+        );
+
+        let initial_stream = futures::stream::once(async move { Ok(initial_msg) });
+        let combined_stream = initial_stream.chain(filtered_stream).boxed();
+        Ok(combined_stream)
+    }
+
     /// Stream raw project messages with initial snapshot
     pub async fn stream_projects_raw(
         &self,
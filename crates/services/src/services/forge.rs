@@ -0,0 +1,473 @@
+//! Pull request operations across code forges. The forge of a repository is picked from
+//! its remote URL: GitHub goes through the `gh` CLI, GitLab and Gitea through their REST
+//! APIs with a personal access token.
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use backon::{ExponentialBuilder, Retryable};
+use db::models::merge::{MergeStatus, PullRequestInfo};
+use reqwest::{Method, StatusCode};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use thiserror::Error;
+use utils::text::truncate_to_char_boundary;
+
+mod gitea;
+mod gitlab;
+
+use gitea::GiteaProvider;
+use gitlab::GitLabProvider;
+
+use crate::services::{
+    config::{ForgeHostConfig, ForgeKind},
+    github::{
+        CreatePrRequest, GitHubRepoInfo, GitHubService, GitHubServiceError, UnifiedPrComment,
+    },
+};
+
+/// Page size for list endpoints, and the most pages fetched from one of them
+const PAGE_SIZE: usize = 50;
+const MAX_PAGES: usize = 20;
+
+#[derive(Debug, Error)]
+pub enum ForgeError {
+    #[error(transparent)]
+    GitHub(#[from] GitHubServiceError),
+    #[error(
+        "Cannot tell which forge hosts '{0}'. Add its host to the forge settings to use it with GitLab or Gitea."
+    )]
+    UnsupportedRemote(String),
+    #[error("No API token configured for {host}. Add one in the forge settings or set {env_var}.")]
+    MissingToken { host: String, env_var: &'static str },
+    #[error("{0} rejected the API token")]
+    AuthFailed(String),
+    #[error("{host} API request failed with status {status}: {message}")]
+    Api {
+        host: String,
+        status: u16,
+        message: String,
+    },
+    #[error(transparent)]
+    Http(#[from] reqwest::Error),
+}
+
+impl ForgeError {
+    pub fn should_retry(&self) -> bool {
+        match self {
+            ForgeError::GitHub(err) => err.should_retry(),
+            ForgeError::Api { status, .. } => {
+                *status == StatusCode::TOO_MANY_REQUESTS.as_u16() || *status >= 500
+            }
+            ForgeError::Http(err) => err.is_timeout() || err.is_connect(),
+            ForgeError::UnsupportedRemote(_)
+            | ForgeError::MissingToken { .. }
+            | ForgeError::AuthFailed(_) => false,
+        }
+    }
+}
+
+/// A repository on a forge, resolved from a remote or pull request URL
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ForgeRepoInfo {
+    pub kind: ForgeKind,
+    /// Host as it appears in the URL, with the port for HTTP(S) URLs
+    pub host: String,
+    /// Web URL of the instance, e.g. `https://gitlab.example.com`
+    pub base_url: String,
+    /// Owner for GitHub and Gitea; the (possibly nested) group path for GitLab
+    pub owner: String,
+    pub repo_name: String,
+}
+
+impl ForgeRepoInfo {
+    pub fn path(&self) -> String {
+        format!("{}/{}", self.owner, self.repo_name)
+    }
+}
+
+#[async_trait]
+pub trait ForgeProvider: Send + Sync {
+    /// Verify that the CLI or API token can be used
+    async fn check_auth(&self) -> Result<(), ForgeError>;
+
+    async fn create_pr(
+        &self,
+        repo: &ForgeRepoInfo,
+        request: &CreatePrRequest,
+    ) -> Result<PullRequestInfo, ForgeError>;
+
+    async fn get_pr(
+        &self,
+        repo: &ForgeRepoInfo,
+        number: i64,
+    ) -> Result<PullRequestInfo, ForgeError>;
+
+    /// Pull requests from `branch` in any state, open ones first
+    async fn list_prs_for_branch(
+        &self,
+        repo: &ForgeRepoInfo,
+        branch: &str,
+    ) -> Result<Vec<PullRequestInfo>, ForgeError>;
+
+    /// General and inline review comments, oldest first
+    async fn get_pr_comments(
+        &self,
+        repo: &ForgeRepoInfo,
+        number: i64,
+    ) -> Result<Vec<UnifiedPrComment>, ForgeError>;
+}
+
+/// Resolves repositories to their forge using the configured forge hosts
+#[derive(Debug, Clone)]
+pub struct ForgeService {
+    hosts: Vec<ForgeHostConfig>,
+}
+
+impl ForgeService {
+    pub fn new(hosts: &[ForgeHostConfig]) -> Self {
+        Self {
+            hosts: hosts.to_vec(),
+        }
+    }
+
+    fn host_config(&self, host: &str) -> Option<&ForgeHostConfig> {
+        let hostname = strip_port(host);
+        self.hosts
+            .iter()
+            .find(|config| config.host.eq_ignore_ascii_case(host))
+            .or_else(|| {
+                self.hosts
+                    .iter()
+                    .find(|config| config.host.eq_ignore_ascii_case(hostname))
+            })
+    }
+
+    /// Resolve a remote URL (HTTPS, SSH or scp-like) or a pull/merge request URL
+    pub fn repo_info(&self, url: &str) -> Result<ForgeRepoInfo, ForgeError> {
+        let unsupported = || ForgeError::UnsupportedRemote(url.to_string());
+        let parsed = ParsedUrl::parse(url).ok_or_else(unsupported)?;
+        let config = self.host_config(&parsed.host);
+
+        let kind = config
+            .map(|config| config.kind)
+            .or_else(|| detect_kind(strip_port(&parsed.host)))
+            .ok_or_else(unsupported)?;
+        let base_url = config
+            .and_then(|config| config.base_url.as_deref())
+            .map(|base_url| base_url.trim_end_matches('/').to_string())
+            .unwrap_or(parsed.base_url);
+        let (owner, repo_name) = split_repo_path(kind, &parsed.path).ok_or_else(unsupported)?;
+
+        Ok(ForgeRepoInfo {
+            kind,
+            host: parsed.host,
+            base_url,
+            owner,
+            repo_name,
+        })
+    }
+
+    pub fn provider(&self, repo: &ForgeRepoInfo) -> Result<Box<dyn ForgeProvider>, ForgeError> {
+        Ok(match repo.kind {
+            ForgeKind::GitHub => Box::new(GitHubService::new()?),
+            ForgeKind::GitLab => {
+                Box::new(GitLabProvider::new(repo, self.token(repo, "GITLAB_TOKEN")?))
+            }
+            ForgeKind::Gitea => {
+                Box::new(GiteaProvider::new(repo, self.token(repo, "GITEA_TOKEN")?))
+            }
+        })
+    }
+
+    /// Resolve `url` and build the provider for its forge
+    pub fn for_url(
+        &self,
+        url: &str,
+    ) -> Result<(ForgeRepoInfo, Box<dyn ForgeProvider>), ForgeError> {
+        let repo = self.repo_info(url)?;
+        let provider = self.provider(&repo)?;
+        Ok((repo, provider))
+    }
+
+    fn token(&self, repo: &ForgeRepoInfo, env_var: &'static str) -> Result<String, ForgeError> {
+        self.host_config(&repo.host)
+            .and_then(|config| config.token.clone())
+            .or_else(|| std::env::var(env_var).ok())
+            .filter(|token| !token.trim().is_empty())
+            .ok_or_else(|| ForgeError::MissingToken {
+                host: repo.host.clone(),
+                env_var,
+            })
+    }
+}
+
+#[derive(Debug)]
+struct ParsedUrl {
+    host: String,
+    base_url: String,
+    path: String,
+}
+
+impl ParsedUrl {
+    fn parse(url: &str) -> Option<Self> {
+        let url = url.trim();
+        if let Some((scheme, rest)) = url.split_once("://") {
+            let (authority, path) = rest.split_once('/').unwrap_or((rest, ""));
+            let authority = authority
+                .rsplit_once('@')
+                .map_or(authority, |(_, host)| host);
+            if authority.is_empty() {
+                return None;
+            }
+            return Some(match scheme {
+                "http" | "https" => Self {
+                    host: authority.to_string(),
+                    base_url: format!("{scheme}://{authority}"),
+                    path: path.to_string(),
+                },
+                // The port of an SSH URL says nothing about where the web UI lives
+                _ => Self {
+                    host: strip_port(authority).to_string(),
+                    base_url: format!("https://{}", strip_port(authority)),
+                    path: path.to_string(),
+                },
+            });
+        }
+
+        // scp-like syntax: [user@]host:path
+        let (authority, path) = url.split_once(':')?;
+        if authority.contains('/') {
+            return None;
+        }
+        let host = authority
+            .rsplit_once('@')
+            .map_or(authority, |(_, host)| host);
+        (!host.is_empty()).then(|| Self {
+            host: host.to_string(),
+            base_url: format!("https://{host}"),
+            path: path.to_string(),
+        })
+    }
+}
+
+fn strip_port(host: &str) -> &str {
+    host.rsplit_once(':')
+        .filter(|(_, port)| port.chars().all(|c| c.is_ascii_digit()))
+        .map_or(host, |(hostname, _)| hostname)
+}
+
+fn detect_kind(hostname: &str) -> Option<ForgeKind> {
+    let hostname = hostname.to_ascii_lowercase();
+    if hostname == "github.com" {
+        Some(ForgeKind::GitHub)
+    } else if hostname.split('.').any(|label| label == "gitlab") {
+        Some(ForgeKind::GitLab)
+    } else if hostname == "codeberg.org"
+        || hostname
+            .split('.')
+            .any(|label| label == "gitea" || label == "forgejo")
+    {
+        Some(ForgeKind::Gitea)
+    } else {
+        None
+    }
+}
+
+/// Split a URL path into owner and repository name, dropping `.git` and anything after
+/// the repository such as `/pull/12` or `/-/merge_requests/3`
+fn split_repo_path(kind: ForgeKind, path: &str) -> Option<(String, String)> {
+    let path = path.trim_matches('/');
+    let segments: Vec<&str> = match kind {
+        // GitLab groups nest, so the project is the last segment before `/-/`
+        ForgeKind::GitLab => path
+            .split("/-/")
+            .next()
+            .unwrap_or(path)
+            .split('/')
+            .collect(),
+        ForgeKind::GitHub | ForgeKind::Gitea => path.split('/').take(2).collect(),
+    };
+    let (repo_name, owner) = segments.split_last()?;
+    let repo_name = repo_name.strip_suffix(".git").unwrap_or(repo_name);
+    if owner.is_empty() || repo_name.is_empty() || owner.iter().any(|s| s.is_empty()) {
+        return None;
+    }
+    Some((owner.join("/"), repo_name.to_string()))
+}
+
+/// Sort pull requests so open ones come first, keeping the forge's order otherwise
+fn open_first(mut prs: Vec<PullRequestInfo>) -> Vec<PullRequestInfo> {
+    prs.sort_by_key(|pr| !matches!(pr.status, MergeStatus::Open));
+    prs
+}
+
+#[async_trait]
+impl ForgeProvider for GitHubService {
+    async fn check_auth(&self) -> Result<(), ForgeError> {
+        Ok(self.check_token().await?)
+    }
+
+    async fn create_pr(
+        &self,
+        repo: &ForgeRepoInfo,
+        request: &CreatePrRequest,
+    ) -> Result<PullRequestInfo, ForgeError> {
+        Ok(GitHubService::create_pr(self, &github_repo_info(repo), request).await?)
+    }
+
+    async fn get_pr(
+        &self,
+        repo: &ForgeRepoInfo,
+        number: i64,
+    ) -> Result<PullRequestInfo, ForgeError> {
+        Ok(self
+            .update_pr_status(&github_repo_info(repo), number)
+            .await?)
+    }
+
+    async fn list_prs_for_branch(
+        &self,
+        repo: &ForgeRepoInfo,
+        branch: &str,
+    ) -> Result<Vec<PullRequestInfo>, ForgeError> {
+        let prs = self
+            .list_all_prs_for_branch(&github_repo_info(repo), branch)
+            .await?;
+        Ok(open_first(prs))
+    }
+
+    async fn get_pr_comments(
+        &self,
+        repo: &ForgeRepoInfo,
+        number: i64,
+    ) -> Result<Vec<UnifiedPrComment>, ForgeError> {
+        Ok(GitHubService::get_pr_comments(self, &github_repo_info(repo), number).await?)
+    }
+}
+
+fn github_repo_info(repo: &ForgeRepoInfo) -> GitHubRepoInfo {
+    GitHubRepoInfo {
+        owner: repo.owner.clone(),
+        repo_name: repo.repo_name.clone(),
+    }
+}
+
+/// Minimal JSON REST client shared by the token-based forges
+#[derive(Debug, Clone)]
+struct ForgeApi {
+    client: reqwest::Client,
+    host: String,
+    api_url: String,
+    auth_header: &'static str,
+    auth_value: String,
+}
+
+impl ForgeApi {
+    fn new(
+        repo: &ForgeRepoInfo,
+        api_path: &str,
+        auth_header: &'static str,
+        auth_value: String,
+    ) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(30))
+            .user_agent(concat!("vibe-kanban/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .unwrap();
+        Self {
+            client,
+            host: repo.host.clone(),
+            api_url: format!("{}{}", repo.base_url, api_path),
+            auth_header,
+            auth_value,
+        }
+    }
+
+    async fn get<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &[(&str, String)],
+    ) -> Result<T, ForgeError> {
+        self.request(Method::GET, path, query, None).await
+    }
+
+    async fn post<T: DeserializeOwned>(&self, path: &str, body: &Value) -> Result<T, ForgeError> {
+        self.request(Method::POST, path, &[], Some(body)).await
+    }
+
+    /// Fetch every page of a list endpoint. `size_param` is the name of the page size
+    /// query parameter, which differs between forges.
+    async fn get_all<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &[(&str, String)],
+        size_param: &str,
+    ) -> Result<Vec<T>, ForgeError> {
+        let mut items = Vec::new();
+        for page in 1..=MAX_PAGES {
+            let mut page_query = query.to_vec();
+            page_query.push(("page", page.to_string()));
+            page_query.push((size_param, PAGE_SIZE.to_string()));
+
+            let batch: Vec<T> = self.get(path, &page_query).await?;
+            let done = batch.len() < PAGE_SIZE;
+            items.extend(batch);
+            if done {
+                break;
+            }
+        }
+        Ok(items)
+    }
+
+    async fn request<T: DeserializeOwned>(
+        &self,
+        method: Method,
+        path: &str,
+        query: &[(&str, String)],
+        body: Option<&Value>,
+    ) -> Result<T, ForgeError> {
+        (|| async {
+            let mut request = self
+                .client
+                .request(method.clone(), format!("{}{}", self.api_url, path))
+                .header(self.auth_header, &self.auth_value)
+                .query(query);
+            if let Some(body) = body {
+                request = request.json(body);
+            }
+
+            let response = request.send().await?;
+            let status = response.status();
+            if status == StatusCode::UNAUTHORIZED {
+                return Err(ForgeError::AuthFailed(self.host.clone()));
+            }
+            if !status.is_success() {
+                let message = response.text().await.unwrap_or_default();
+                return Err(ForgeError::Api {
+                    host: self.host.clone(),
+                    status: status.as_u16(),
+                    message: truncate_to_char_boundary(&message, 500).to_string(),
+                });
+            }
+            Ok(response.json::<T>().await?)
+        })
+        .retry(
+            &ExponentialBuilder::default()
+                .with_min_delay(Duration::from_secs(1))
+                .with_max_delay(Duration::from_secs(30))
+                .with_max_times(3)
+                .with_jitter(),
+        )
+        .when(|e: &ForgeError| e.should_retry())
+        .notify(|err: &ForgeError, dur: Duration| {
+            tracing::warn!(
+                "{} API call failed, retrying after {:.2}s: {}",
+                self.host,
+                dur.as_secs_f64(),
+                err
+            );
+        })
+        .await
+    }
+}
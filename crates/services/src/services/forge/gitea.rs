@@ -0,0 +1,230 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use db::models::merge::{MergeStatus, PullRequestInfo};
+use serde::Deserialize;
+use serde_json::json;
+use tracing::info;
+
+use super::{ForgeApi, ForgeError, ForgeProvider, ForgeRepoInfo, open_first};
+use crate::services::github::{CreatePrRequest, UnifiedPrComment};
+
+#[derive(Debug, Deserialize)]
+struct PullRequestBranch {
+    #[serde(rename = "ref")]
+    ref_name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct PullRequest {
+    number: i64,
+    html_url: String,
+    state: String,
+    #[serde(default)]
+    merged: bool,
+    merged_at: Option<DateTime<Utc>>,
+    merge_commit_sha: Option<String>,
+    head: PullRequestBranch,
+}
+
+impl From<PullRequest> for PullRequestInfo {
+    fn from(pr: PullRequest) -> Self {
+        let status = if pr.merged {
+            MergeStatus::Merged
+        } else {
+            match pr.state.as_str() {
+                "open" => MergeStatus::Open,
+                "closed" => MergeStatus::Closed,
+                _ => MergeStatus::Unknown,
+            }
+        };
+        Self {
+            number: pr.number,
+            url: pr.html_url,
+            status,
+            merged_at: pr.merged_at,
+            merge_commit_sha: pr.merge_commit_sha,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct User {
+    login: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct IssueComment {
+    id: i64,
+    body: String,
+    user: User,
+    created_at: DateTime<Utc>,
+    html_url: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Review {
+    id: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReviewComment {
+    id: i64,
+    body: String,
+    user: User,
+    created_at: DateTime<Utc>,
+    html_url: String,
+    path: String,
+    /// Line in the new file; 0 for comments on removed lines
+    #[serde(default)]
+    position: i64,
+    #[serde(default)]
+    diff_hunk: String,
+}
+
+/// Gitea (and Forgejo) pull requests through the v1 REST API
+pub(super) struct GiteaProvider {
+    api: ForgeApi,
+}
+
+impl GiteaProvider {
+    pub(super) fn new(repo: &ForgeRepoInfo, token: String) -> Self {
+        Self {
+            api: ForgeApi::new(repo, "/api/v1", "Authorization", format!("token {token}")),
+        }
+    }
+
+    fn repo_path(repo: &ForgeRepoInfo) -> String {
+        format!("/repos/{}/{}", repo.owner, repo.repo_name)
+    }
+}
+
+#[async_trait]
+impl ForgeProvider for GiteaProvider {
+    async fn check_auth(&self) -> Result<(), ForgeError> {
+        let _: serde_json::Value = self.api.get("/user", &[]).await?;
+        Ok(())
+    }
+
+    async fn create_pr(
+        &self,
+        repo: &ForgeRepoInfo,
+        request: &CreatePrRequest,
+    ) -> Result<PullRequestInfo, ForgeError> {
+        // Gitea marks pull requests as work in progress by title prefix
+        let title = if request.draft.unwrap_or(false) {
+            format!("WIP: {}", request.title)
+        } else {
+            request.title.clone()
+        };
+        let pr: PullRequest = self
+            .api
+            .post(
+                &format!("{}/pulls", Self::repo_path(repo)),
+                &json!({
+                    "head": request.head_branch,
+                    "base": request.base_branch,
+                    "title": title,
+                    "body": request.body,
+                }),
+            )
+            .await?;
+
+        info!(
+            "Created Gitea PR #{} for branch {} in {}",
+            pr.number,
+            request.head_branch,
+            repo.path()
+        );
+        Ok(pr.into())
+    }
+
+    async fn get_pr(
+        &self,
+        repo: &ForgeRepoInfo,
+        number: i64,
+    ) -> Result<PullRequestInfo, ForgeError> {
+        let pr: PullRequest = self
+            .api
+            .get(&format!("{}/pulls/{number}", Self::repo_path(repo)), &[])
+            .await?;
+        Ok(pr.into())
+    }
+
+    async fn list_prs_for_branch(
+        &self,
+        repo: &ForgeRepoInfo,
+        branch: &str,
+    ) -> Result<Vec<PullRequestInfo>, ForgeError> {
+        // The list endpoint cannot filter by head branch
+        let prs: Vec<PullRequest> = self
+            .api
+            .get_all(
+                &format!("{}/pulls", Self::repo_path(repo)),
+                &[("state", "all".to_string()), ("sort", "newest".to_string())],
+                "limit",
+            )
+            .await?;
+        Ok(open_first(
+            prs.into_iter()
+                .filter(|pr| pr.head.ref_name == branch)
+                .map(Into::into)
+                .collect(),
+        ))
+    }
+
+    async fn get_pr_comments(
+        &self,
+        repo: &ForgeRepoInfo,
+        number: i64,
+    ) -> Result<Vec<UnifiedPrComment>, ForgeError> {
+        let repo_path = Self::repo_path(repo);
+        let issue_comments: Vec<IssueComment> = self
+            .api
+            .get(&format!("{repo_path}/issues/{number}/comments"), &[])
+            .await?;
+        let reviews: Vec<Review> = self
+            .api
+            .get_all(&format!("{repo_path}/pulls/{number}/reviews"), &[], "limit")
+            .await?;
+
+        let mut comments: Vec<UnifiedPrComment> = issue_comments
+            .into_iter()
+            .map(|c| UnifiedPrComment::General {
+                id: c.id.to_string(),
+                author: c.user.login,
+                author_association: "NONE".to_string(),
+                body: c.body,
+                created_at: c.created_at,
+                url: c.html_url,
+            })
+            .collect();
+
+        for review in reviews {
+            let review_comments: Vec<ReviewComment> = self
+                .api
+                .get(
+                    &format!("{repo_path}/pulls/{number}/reviews/{}/comments", review.id),
+                    &[],
+                )
+                .await?;
+            comments.extend(
+                review_comments
+                    .into_iter()
+                    .map(|c| UnifiedPrComment::Review {
+                        id: c.id,
+                        author: c.user.login,
+                        author_association: "NONE".to_string(),
+                        body: c.body,
+                        created_at: c.created_at,
+                        url: c.html_url,
+                        path: c.path,
+                        line: (c.position > 0).then_some(c.position),
+                        diff_hunk: c.diff_hunk,
+                    }),
+            );
+        }
+
+        comments.sort_by_key(|c| c.created_at());
+        Ok(comments)
+    }
+}
@@ -0,0 +1,206 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use db::models::merge::{MergeStatus, PullRequestInfo};
+use serde::Deserialize;
+use serde_json::json;
+use tracing::info;
+
+use super::{ForgeApi, ForgeError, ForgeProvider, ForgeRepoInfo, open_first};
+use crate::services::github::{CreatePrRequest, UnifiedPrComment};
+
+#[derive(Debug, Deserialize)]
+struct MergeRequest {
+    iid: i64,
+    web_url: String,
+    state: String,
+    merged_at: Option<DateTime<Utc>>,
+    merge_commit_sha: Option<String>,
+    squash_commit_sha: Option<String>,
+}
+
+impl From<MergeRequest> for PullRequestInfo {
+    fn from(mr: MergeRequest) -> Self {
+        let status = match mr.state.as_str() {
+            "opened" | "locked" => MergeStatus::Open,
+            "merged" => MergeStatus::Merged,
+            "closed" => MergeStatus::Closed,
+            _ => MergeStatus::Unknown,
+        };
+        Self {
+            number: mr.iid,
+            url: mr.web_url,
+            status,
+            merged_at: mr.merged_at,
+            merge_commit_sha: mr.merge_commit_sha.or(mr.squash_commit_sha),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct NoteAuthor {
+    username: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct NotePosition {
+    new_path: Option<String>,
+    old_path: Option<String>,
+    new_line: Option<i64>,
+    old_line: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Note {
+    id: i64,
+    body: String,
+    author: NoteAuthor,
+    created_at: DateTime<Utc>,
+    /// Notes GitLab adds itself, e.g. "added 1 commit"
+    system: bool,
+    position: Option<NotePosition>,
+}
+
+/// GitLab merge requests through the v4 REST API
+pub(super) struct GitLabProvider {
+    api: ForgeApi,
+}
+
+impl GitLabProvider {
+    pub(super) fn new(repo: &ForgeRepoInfo, token: String) -> Self {
+        Self {
+            api: ForgeApi::new(repo, "/api/v4", "PRIVATE-TOKEN", token),
+        }
+    }
+
+    fn project_path(repo: &ForgeRepoInfo) -> String {
+        let id: String = url::form_urlencoded::byte_serialize(repo.path().as_bytes()).collect();
+        format!("/projects/{id}")
+    }
+}
+
+#[async_trait]
+impl ForgeProvider for GitLabProvider {
+    async fn check_auth(&self) -> Result<(), ForgeError> {
+        let _: serde_json::Value = self.api.get("/user", &[]).await?;
+        Ok(())
+    }
+
+    async fn create_pr(
+        &self,
+        repo: &ForgeRepoInfo,
+        request: &CreatePrRequest,
+    ) -> Result<PullRequestInfo, ForgeError> {
+        let title = if request.draft.unwrap_or(false) {
+            format!("Draft: {}", request.title)
+        } else {
+            request.title.clone()
+        };
+        let mr: MergeRequest = self
+            .api
+            .post(
+                &format!("{}/merge_requests", Self::project_path(repo)),
+                &json!({
+                    "source_branch": request.head_branch,
+                    "target_branch": request.base_branch,
+                    "title": title,
+                    "description": request.body,
+                }),
+            )
+            .await?;
+
+        info!(
+            "Created GitLab MR !{} for branch {} in {}",
+            mr.iid,
+            request.head_branch,
+            repo.path()
+        );
+        Ok(mr.into())
+    }
+
+    async fn get_pr(
+        &self,
+        repo: &ForgeRepoInfo,
+        number: i64,
+    ) -> Result<PullRequestInfo, ForgeError> {
+        let mr: MergeRequest = self
+            .api
+            .get(
+                &format!("{}/merge_requests/{number}", Self::project_path(repo)),
+                &[],
+            )
+            .await?;
+        Ok(mr.into())
+    }
+
+    async fn list_prs_for_branch(
+        &self,
+        repo: &ForgeRepoInfo,
+        branch: &str,
+    ) -> Result<Vec<PullRequestInfo>, ForgeError> {
+        let mrs: Vec<MergeRequest> = self
+            .api
+            .get_all(
+                &format!("{}/merge_requests", Self::project_path(repo)),
+                &[
+                    ("source_branch", branch.to_string()),
+                    ("state", "all".to_string()),
+                    ("order_by", "created_at".to_string()),
+                ],
+                "per_page",
+            )
+            .await?;
+        Ok(open_first(mrs.into_iter().map(Into::into).collect()))
+    }
+
+    async fn get_pr_comments(
+        &self,
+        repo: &ForgeRepoInfo,
+        number: i64,
+    ) -> Result<Vec<UnifiedPrComment>, ForgeError> {
+        let notes: Vec<Note> = self
+            .api
+            .get_all(
+                &format!("{}/merge_requests/{number}/notes", Self::project_path(repo)),
+                &[
+                    ("sort", "asc".to_string()),
+                    ("order_by", "created_at".to_string()),
+                ],
+                "per_page",
+            )
+            .await?;
+
+        let mr_url = format!(
+            "{}/{}/-/merge_requests/{number}",
+            repo.base_url,
+            repo.path()
+        );
+        Ok(notes
+            .into_iter()
+            .filter(|note| !note.system)
+            .map(|note| {
+                let url = format!("{mr_url}#note_{}", note.id);
+                match note.position {
+                    Some(position) => UnifiedPrComment::Review {
+                        id: note.id,
+                        author: note.author.username,
+                        author_association: "NONE".to_string(),
+                        body: note.body,
+                        created_at: note.created_at,
+                        url,
+                        path: position.new_path.or(position.old_path).unwrap_or_default(),
+                        line: position.new_line.or(position.old_line),
+                        diff_hunk: String::new(),
+                    },
+                    None => UnifiedPrComment::General {
+                        id: note.id.to_string(),
+                        author: note.author.username,
+                        author_association: "NONE".to_string(),
+                        body: note.body,
+                        created_at: note.created_at,
+                        url,
+                    },
+                }
+            })
+            .collect())
+    }
+}
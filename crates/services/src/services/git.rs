@@ -46,6 +46,8 @@ pub struct GitService {}
 // Max inline diff size for UI (in bytes). Files larger than this will have
 // their contents omitted from the diff stream to avoid UI crashes.
 const MAX_INLINE_DIFF_BYTES: usize = 2 * 1024 * 1024; // ~2MB
+/// Commit subjects listed per side of a conflict
+const CONFLICT_SUBJECTS_LIMIT: usize = 20;
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
@@ -57,6 +59,38 @@ pub enum ConflictOp {
     Revert,
 }
 
+impl ConflictOp {
+    /// The git subcommand that started the operation
+    pub fn subcommand(&self) -> &'static str {
+        match self {
+            ConflictOp::Rebase => "rebase",
+            ConflictOp::Merge => "merge",
+            ConflictOp::CherryPick => "cherry-pick",
+            ConflictOp::Revert => "revert",
+        }
+    }
+
+    pub fn from_subcommand(subcommand: &str) -> Option<Self> {
+        match subcommand {
+            "rebase" => Some(ConflictOp::Rebase),
+            "merge" => Some(ConflictOp::Merge),
+            "cherry-pick" => Some(ConflictOp::CherryPick),
+            "revert" => Some(ConflictOp::Revert),
+            _ => None,
+        }
+    }
+
+    /// The ref git points at the commit being applied while the operation is stopped
+    pub fn head_ref(&self) -> &'static str {
+        match self {
+            ConflictOp::Rebase => "REBASE_HEAD",
+            ConflictOp::Merge => "MERGE_HEAD",
+            ConflictOp::CherryPick => "CHERRY_PICK_HEAD",
+            ConflictOp::Revert => "REVERT_HEAD",
+        }
+    }
+}
+
 #[derive(Debug, Serialize, TS)]
 pub struct GitBranch {
     pub name: String,
@@ -1533,6 +1567,55 @@ impl GitService {
         })
     }
 
+    /// Subjects of the commits on each side of an in-progress conflict that touch `files`:
+    /// first those on `HEAD`, then those leading to the commit being applied. Newest first.
+    pub fn conflict_commit_subjects(
+        &self,
+        worktree_path: &Path,
+        op: &ConflictOp,
+        files: &[String],
+    ) -> Result<(Vec<String>, Vec<String>), GitServiceError> {
+        let git = GitCli::new();
+        git.side_commit_subjects(worktree_path, op.head_ref(), files, CONFLICT_SUBJECTS_LIMIT)
+            .map_err(|e| {
+                GitServiceError::InvalidRepository(format!("git log for conflicts failed: {e}"))
+            })
+    }
+
+    /// Stage all changes and continue the stopped operation. Returns `MergeConflicts` if it
+    /// stops again, e.g. when a rebase reaches another conflicting commit.
+    pub fn continue_conflicts(
+        &self,
+        worktree_path: &Path,
+        op: &ConflictOp,
+    ) -> Result<(), GitServiceError> {
+        self.ensure_cli_commit_identity(worktree_path)?;
+        let git = GitCli::new();
+        match git.continue_operation(worktree_path, op.subcommand()) {
+            Ok(()) => Ok(()),
+            Err(GitCliError::CommandFailed(stderr)) => {
+                let conflicts = git.get_conflicted_files(worktree_path).unwrap_or_default();
+                if conflicts.is_empty() {
+                    Err(GitServiceError::InvalidRepository(format!(
+                        "git {} --continue failed: {}",
+                        op.subcommand(),
+                        stderr.lines().next().unwrap_or("")
+                    )))
+                } else {
+                    Err(GitServiceError::MergeConflicts(format!(
+                        "git {} stopped at another conflict. Conflicted files: {}.",
+                        op.subcommand(),
+                        conflicts.join(", ")
+                    )))
+                }
+            }
+            Err(e) => Err(GitServiceError::InvalidRepository(format!(
+                "git {} --continue failed: {e}",
+                op.subcommand()
+            ))),
+        }
+    }
+
     /// Abort an in-progress rebase in this worktree (no-op if none).
     pub fn abort_rebase(&self, worktree_path: &Path) -> Result<(), GitServiceError> {
         let git = GitCli::new();
@@ -1596,20 +1679,26 @@ impl GitService {
     }
 
     /// Extract GitHub owner and repo name from git repo path
-    pub fn get_github_repo_info(
-        &self,
-        repo_path: &Path,
-    ) -> Result<GitHubRepoInfo, GitServiceError> {
+    /// URL of the default remote, used to tell which forge hosts the repository
+    pub fn get_remote_url(&self, repo_path: &Path) -> Result<String, GitServiceError> {
         let repo = self.open_repo(repo_path)?;
         let remote_name = self.default_remote_name(&repo);
         let remote = repo.find_remote(&remote_name).map_err(|_| {
             GitServiceError::InvalidRepository(format!("No '{remote_name}' remote found"))
         })?;
 
-        let url = remote
+        remote
             .url()
-            .ok_or_else(|| GitServiceError::InvalidRepository("Remote has no URL".to_string()))?;
-        GitHubRepoInfo::from_remote_url(url).map_err(|e| {
+            .map(str::to_string)
+            .ok_or_else(|| GitServiceError::InvalidRepository("Remote has no URL".to_string()))
+    }
+
+    pub fn get_github_repo_info(
+        &self,
+        repo_path: &Path,
+    ) -> Result<GitHubRepoInfo, GitServiceError> {
+        let url = self.get_remote_url(repo_path)?;
+        GitHubRepoInfo::from_remote_url(&url).map_err(|e| {
             GitServiceError::InvalidRepository(format!("Failed to parse remote URL: {e}"))
         })
     }
@@ -627,6 +627,49 @@ impl GitCli {
         self.git(worktree_path, ["revert", "--abort"]).map(|_| ())
     }
 
+    /// Subjects of commits from the merge base of `HEAD` and `head_ref` to each of them, limited
+    /// to commits touching `paths` and to `limit` per side.
+    pub fn side_commit_subjects(
+        &self,
+        worktree_path: &Path,
+        head_ref: &str,
+        paths: &[String],
+        limit: usize,
+    ) -> Result<(Vec<String>, Vec<String>), GitCliError> {
+        let base = self.git(worktree_path, ["merge-base", "HEAD", head_ref])?;
+        let base = base.trim();
+        let subjects = |tip: &str| -> Result<Vec<String>, GitCliError> {
+            let mut args = vec![
+                "log".to_string(),
+                format!("--max-count={limit}"),
+                "--format=%s".to_string(),
+                format!("{base}..{tip}"),
+                "--".to_string(),
+            ];
+            args.extend(paths.iter().cloned());
+            let out = self.git(worktree_path, args)?;
+            Ok(out
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .map(str::to_string)
+                .collect())
+        };
+        Ok((subjects("HEAD")?, subjects(head_ref)?))
+    }
+
+    /// Stage all changes and run `git <subcommand> --continue` without opening an editor.
+    pub fn continue_operation(
+        &self,
+        worktree_path: &Path,
+        subcommand: &str,
+    ) -> Result<(), GitCliError> {
+        self.add_all(worktree_path)?;
+        let envs = vec![(OsString::from("GIT_EDITOR"), OsString::from("true"))];
+        self.git_with_env(worktree_path, [subcommand, "--continue"], &envs)
+            .map(|_| ())
+    }
+
     /// List files currently in a conflicted (unmerged) state in the worktree.
     pub fn get_conflicted_files(&self, worktree_path: &Path) -> Result<Vec<String>, GitCliError> {
         // `--diff-filter=U` lists paths with unresolved conflicts
@@ -44,7 +44,7 @@ pub enum UnifiedPrComment {
 }
 
 impl UnifiedPrComment {
-    fn created_at(&self) -> DateTime<Utc> {
+    pub(crate) fn created_at(&self) -> DateTime<Utc> {
         match self {
             UnifiedPrComment::General { created_at, .. } => *created_at,
             UnifiedPrComment::Review { created_at, .. } => *created_at,
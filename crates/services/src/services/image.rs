@@ -47,6 +47,15 @@ impl ImageService {
         })
     }
 
+    #[cfg(test)]
+    pub(crate) fn with_cache_dir(pool: SqlitePool, cache_dir: PathBuf) -> Self {
+        Self {
+            cache_dir,
+            pool,
+            max_size_bytes: 20 * 1024 * 1024,
+        }
+    }
+
     pub async fn store_image(
         &self,
         data: &[u8],
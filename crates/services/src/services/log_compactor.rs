@@ -0,0 +1,138 @@
+use std::{sync::Arc, time::Duration};
+
+use chrono::Utc;
+use db::{
+    DBService,
+    models::execution_process_logs::{
+        ExecutionProcessLogArchive, ExecutionProcessLogs, ExecutionProcessLogsError,
+    },
+};
+use tokio::{sync::RwLock, time::interval};
+use tracing::{debug, error, info};
+
+use crate::services::config::{Config, LogStorageConfig};
+
+/// Processes are left alone for a while after completing so late log writes land first
+const COMPACTION_GRACE: chrono::Duration = chrono::Duration::minutes(10);
+const COMPACTION_BATCH_SIZE: i64 = 50;
+
+/// Service that compacts the per-line log rows of completed execution processes into
+/// compressed archives and deletes logs past the configured retention
+pub struct LogCompactorService {
+    db: DBService,
+    config: Arc<RwLock<Config>>,
+    poll_interval: Duration,
+}
+
+impl LogCompactorService {
+    pub async fn spawn(db: DBService, config: Arc<RwLock<Config>>) -> tokio::task::JoinHandle<()> {
+        let service = Self {
+            db,
+            config,
+            poll_interval: Duration::from_secs(15 * 60),
+        };
+        tokio::spawn(async move {
+            service.start().await;
+        })
+    }
+
+    async fn start(&self) {
+        info!(
+            "Starting log compactor service with interval {:?}",
+            self.poll_interval
+        );
+
+        let mut interval = interval(self.poll_interval);
+
+        loop {
+            interval.tick().await;
+            let log_storage = self.config.read().await.log_storage.clone();
+
+            if let Err(e) = self.compact_completed(&log_storage).await {
+                error!("Error compacting execution logs: {}", e);
+            }
+            if let Err(e) = self.apply_retention(&log_storage).await {
+                error!("Error applying log retention: {}", e);
+            }
+        }
+    }
+
+    async fn compact_completed(
+        &self,
+        log_storage: &LogStorageConfig,
+    ) -> Result<(), ExecutionProcessLogsError> {
+        let pool = &self.db.pool;
+        let completed_before = Utc::now() - COMPACTION_GRACE;
+        let mut compacted = 0;
+
+        loop {
+            let execution_ids = ExecutionProcessLogs::find_compactable(
+                pool,
+                completed_before,
+                COMPACTION_BATCH_SIZE,
+            )
+            .await?;
+            let batch_len = execution_ids.len() as i64;
+
+            for execution_id in execution_ids {
+                match ExecutionProcessLogs::compact(pool, execution_id, log_storage.spill_to_files)
+                    .await
+                {
+                    Ok(Some(archive)) => {
+                        compacted += 1;
+                        debug!(
+                            "Compacted {} log lines of execution {} ({} -> {} bytes)",
+                            archive.line_count,
+                            execution_id,
+                            archive.byte_size,
+                            archive.compressed_size
+                        );
+                    }
+                    Ok(None) => {}
+                    // Leave the rows in place; the next batch query would return this
+                    // process again, so stop here and retry on the next tick
+                    Err(e) => {
+                        error!(
+                            "Failed to compact logs of execution {}: {}",
+                            execution_id, e
+                        );
+                        return Ok(());
+                    }
+                }
+            }
+
+            if batch_len < COMPACTION_BATCH_SIZE {
+                break;
+            }
+        }
+
+        if compacted > 0 {
+            info!("Compacted logs of {} execution processes", compacted);
+        }
+        Ok(())
+    }
+
+    async fn apply_retention(
+        &self,
+        log_storage: &LogStorageConfig,
+    ) -> Result<(), ExecutionProcessLogsError> {
+        let pool = &self.db.pool;
+
+        if let Some(retention_days) = log_storage.retention_days {
+            let completed_before = Utc::now() - chrono::Duration::days(retention_days as i64);
+            let deleted = ExecutionProcessLogs::delete_expired(pool, completed_before).await?;
+            if deleted > 0 {
+                info!(
+                    "Deleted logs of {} execution processes older than {} days",
+                    deleted, retention_days
+                );
+            }
+        }
+
+        let removed = ExecutionProcessLogArchive::remove_orphaned_files(pool).await?;
+        if removed > 0 {
+            info!("Removed {} orphaned log archive files", removed);
+        }
+        Ok(())
+    }
+}
@@ -2,6 +2,7 @@ pub mod analytics;
 pub mod approvals;
 pub mod auth;
 pub mod config;
+pub mod conflict_resolution;
 pub mod container;
 pub mod diff_stream;
 pub mod events;
@@ -9,16 +10,25 @@ pub mod file_ranker;
 pub mod file_search_cache;
 pub mod filesystem;
 pub mod filesystem_watcher;
+pub mod forge;
 pub mod git;
 pub mod github;
 pub mod image;
+pub mod log_compactor;
 pub mod notification;
 pub mod oauth_credentials;
 pub mod pr_monitor;
 pub mod project;
+pub mod project_analytics;
+pub mod project_bundle;
+pub mod quality_gate;
 pub mod queued_message;
 pub mod remote_client;
 pub mod repo;
 pub mod share;
+pub mod task_scheduler;
+pub mod usage;
+pub mod webhooks;
 pub mod workspace_manager;
+pub mod workspace_stack;
 pub mod worktree_manager;
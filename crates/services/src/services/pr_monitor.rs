@@ -1,51 +1,61 @@
-use std::time::Duration;
+use std::{sync::Arc, time::Duration};
 
 use db::{
     DBService,
     models::{
         merge::{Merge, MergeStatus, PrMerge},
         task::{Task, TaskStatus},
+        task_event::TaskEventActor,
         workspace::{Workspace, WorkspaceError},
     },
 };
 use serde_json::json;
 use sqlx::error::Error as SqlxError;
 use thiserror::Error;
-use tokio::time::interval;
+use tokio::{sync::RwLock, time::interval};
 use tracing::{debug, error, info};
 
 use crate::services::{
     analytics::AnalyticsContext,
-    github::{GitHubRepoInfo, GitHubService, GitHubServiceError},
+    config::Config,
+    container::ContainerService,
+    forge::{ForgeError, ForgeService},
     share::SharePublisher,
 };
 
 #[derive(Debug, Error)]
 enum PrMonitorError {
     #[error(transparent)]
-    GitHubServiceError(#[from] GitHubServiceError),
+    Forge(#[from] ForgeError),
     #[error(transparent)]
     WorkspaceError(#[from] WorkspaceError),
     #[error(transparent)]
     Sqlx(#[from] SqlxError),
 }
 
-/// Service to monitor GitHub PRs and update task status when they are merged
-pub struct PrMonitorService {
+/// Service to monitor open PRs/MRs on every supported forge and update task status when
+/// they are merged
+pub struct PrMonitorService<C: ContainerService> {
     db: DBService,
+    container: C,
+    config: Arc<RwLock<Config>>,
     poll_interval: Duration,
     analytics: Option<AnalyticsContext>,
     publisher: Option<SharePublisher>,
 }
 
-impl PrMonitorService {
+impl<C: ContainerService> PrMonitorService<C> {
     pub async fn spawn(
         db: DBService,
+        container: C,
+        config: Arc<RwLock<Config>>,
         analytics: Option<AnalyticsContext>,
         publisher: Option<SharePublisher>,
     ) -> tokio::task::JoinHandle<()> {
         let service = Self {
             db,
+            container,
+            config,
             poll_interval: Duration::from_secs(60), // Check every minute
             analytics,
             publisher,
@@ -71,7 +81,7 @@ impl PrMonitorService {
         }
     }
 
-    /// Check all open PRs for updates with the provided GitHub token
+    /// Check all open PRs for updates
     async fn check_all_open_prs(&self) -> Result<(), PrMonitorError> {
         let open_prs = Merge::get_open_prs(&self.db.pool).await?;
 
@@ -82,8 +92,9 @@ impl PrMonitorService {
 
         info!("Checking {} open PRs", open_prs.len());
 
+        let forge = ForgeService::new(&self.config.read().await.forges);
         for pr_merge in open_prs {
-            if let Err(e) = self.check_pr_status(&pr_merge).await {
+            if let Err(e) = self.check_pr_status(&forge, &pr_merge).await {
                 error!(
                     "Error checking PR #{} for workspace {}: {}",
                     pr_merge.pr_info.number, pr_merge.workspace_id, e
@@ -94,14 +105,15 @@ impl PrMonitorService {
     }
 
     /// Check the status of a specific PR
-    async fn check_pr_status(&self, pr_merge: &PrMerge) -> Result<(), PrMonitorError> {
-        // GitHubService now uses gh CLI, no token needed
-        let github_service = GitHubService::new()?;
-        let repo_info = GitHubRepoInfo::from_remote_url(&pr_merge.pr_info.url)?;
+    async fn check_pr_status(
+        &self,
+        forge: &ForgeService,
+        pr_merge: &PrMerge,
+    ) -> Result<(), PrMonitorError> {
+        // The PR URL identifies the forge and repository the PR was opened on
+        let (repo_info, provider) = forge.for_url(&pr_merge.pr_info.url)?;
 
-        let pr_status = github_service
-            .update_pr_status(&repo_info, pr_merge.pr_info.number)
-            .await?;
+        let pr_status = provider.get_pr(&repo_info, pr_merge.pr_info.number).await?;
 
         debug!(
             "PR #{} status: {:?} (was open)",
@@ -110,7 +122,7 @@ impl PrMonitorService {
 
         // Update the PR status in the database
         if !matches!(&pr_status.status, MergeStatus::Open) {
-            // Update merge status with the latest information from GitHub
+            // Update merge status with the latest information from the forge
             Merge::update_status(
                 &self.db.pool,
                 pr_merge.id,
@@ -128,7 +140,13 @@ impl PrMonitorService {
                     "PR #{} was merged, updating task {} to done",
                     pr_merge.pr_info.number, workspace.task_id
                 );
-                Task::update_status(&self.db.pool, workspace.task_id, TaskStatus::Done).await?;
+                Task::update_status(
+                    &self.db.pool,
+                    workspace.task_id,
+                    TaskStatus::Done,
+                    TaskEventActor::PrMonitor,
+                )
+                .await?;
 
                 // Track analytics event
                 if let Some(analytics) = &self.analytics
@@ -154,6 +172,17 @@ impl PrMonitorService {
                         workspace.task_id
                     );
                 }
+
+                if let Err(e) = self
+                    .container
+                    .start_unblocked_dependents(workspace.task_id)
+                    .await
+                {
+                    error!(
+                        "Failed to start tasks unblocked by task {}: {}",
+                        workspace.task_id, e
+                    );
+                }
             }
         }
 
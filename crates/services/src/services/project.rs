@@ -73,18 +73,18 @@ impl ProjectService {
         Self
     }
 
-    pub async fn create_project(
+    /// Validates repository paths and checks for duplicate names and paths, returning the
+    /// repositories with normalized paths
+    pub fn normalize_repositories(
         &self,
-        pool: &SqlitePool,
         repo_service: &RepoService,
-        payload: CreateProject,
-    ) -> Result<Project> {
-        // Validate all repository paths and check for duplicates within the payload
+        repositories: &[CreateProjectRepo],
+    ) -> Result<Vec<CreateProjectRepo>> {
         let mut seen_names = HashSet::new();
         let mut seen_paths = HashSet::new();
         let mut normalized_repos = Vec::new();
 
-        for repo in &payload.repositories {
+        for repo in repositories {
             let path = repo_service.normalize_path(&repo.git_repo_path)?;
             repo_service.validate_git_repo_path(&path)?;
 
@@ -104,6 +104,17 @@ impl ProjectService {
             });
         }
 
+        Ok(normalized_repos)
+    }
+
+    pub async fn create_project(
+        &self,
+        pool: &SqlitePool,
+        repo_service: &RepoService,
+        payload: CreateProject,
+    ) -> Result<Project> {
+        let normalized_repos = self.normalize_repositories(repo_service, &payload.repositories)?;
+
         let id = Uuid::new_v4();
 
         let project = Project::create(pool, &payload, id)
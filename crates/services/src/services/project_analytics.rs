@@ -0,0 +1,555 @@
+//! Per-project and per-executor-profile metrics computed from tasks, attempts, execution
+//! processes and merges.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use db::models::{
+    analytics::{AnalyticsData, AnalyticsFilter, AnalyticsTask},
+    execution_process::{ExecutionProcessRunReason, ExecutionProcessStatus, ExecutorActionField},
+    merge::{MergeStatus, MergeType},
+    task::TaskStatus,
+    task_event::TaskEvent,
+};
+use executors::profile::ExecutorProfileId;
+use serde::Serialize;
+use sqlx::SqlitePool;
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, TS)]
+pub struct DurationStats {
+    pub count: u64,
+    pub median_seconds: Option<i64>,
+    pub p90_seconds: Option<i64>,
+}
+
+impl DurationStats {
+    fn from_samples(mut samples: Vec<i64>) -> Self {
+        samples.sort_unstable();
+        // Nearest-rank percentile
+        let percentile = |p: f64| {
+            let rank = ((p * samples.len() as f64).ceil() as usize).max(1);
+            samples.get(rank - 1).copied()
+        };
+        Self {
+            count: samples.len() as u64,
+            median_seconds: percentile(0.5),
+            p90_seconds: percentile(0.9),
+        }
+    }
+}
+
+/// Time tasks spent in each non-terminal status, summed over every stay. Taken from the
+/// status history; for tasks created before history was recorded it is derived from attempt
+/// and merge timestamps instead: a task is in `todo` until its first attempt starts,
+/// `inprogress` until its last coding agent run finishes, and `inreview` until it is merged
+/// or marked done.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, TS)]
+pub struct StatusDurations {
+    pub todo: DurationStats,
+    pub inprogress: DurationStats,
+    pub inreview: DurationStats,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, TS)]
+pub struct AnalyticsMetrics {
+    pub tasks: u64,
+    pub tasks_done: u64,
+    pub attempts: u64,
+    /// Attempts whose last coding agent run completed successfully
+    pub successful_attempts: u64,
+    pub attempt_success_rate: Option<f64>,
+    /// Extra attempts per task, over tasks that were attempted at all
+    pub retries_per_task: Option<f64>,
+    pub prs_opened: u64,
+    pub prs_merged: u64,
+    pub pr_merge_rate: Option<f64>,
+    pub direct_merges: u64,
+    pub setup_runs: u64,
+    pub setup_failures: u64,
+    pub setup_failure_rate: Option<f64>,
+    pub dev_server_runs: u64,
+    pub dev_server_failures: u64,
+    pub dev_server_failure_rate: Option<f64>,
+    /// From task creation to done
+    pub cycle_time: DurationStats,
+    pub time_in_status: StatusDurations,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct ProjectMetrics {
+    pub project_id: Uuid,
+    pub project_name: String,
+    pub metrics: AnalyticsMetrics,
+}
+
+/// Attempts are grouped by the profile they were started with; tasks by the profile of
+/// their latest attempt
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct ExecutorProfileMetrics {
+    pub executor_profile_id: ExecutorProfileId,
+    pub metrics: AnalyticsMetrics,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct AnalyticsReport {
+    pub overall: AnalyticsMetrics,
+    pub projects: Vec<ProjectMetrics>,
+    pub executor_profiles: Vec<ExecutorProfileMetrics>,
+}
+
+#[derive(Debug, Default)]
+struct AttemptSummary {
+    task_id: Uuid,
+    created_at: Option<DateTime<Utc>>,
+    executor_profile_id: Option<ExecutorProfileId>,
+    last_coding_agent_status: Option<ExecutionProcessStatus>,
+    coding_agent_finished_at: Option<DateTime<Utc>>,
+    setup_runs: u64,
+    setup_failures: u64,
+    dev_server_runs: u64,
+    dev_server_failures: u64,
+    prs_opened: u64,
+    prs_merged: u64,
+    direct_merges: u64,
+    merged_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Default)]
+struct TaskSummary {
+    attempts: u64,
+    done: bool,
+    executor_profile_id: Option<ExecutorProfileId>,
+    cycle_time: Option<i64>,
+    todo: Option<i64>,
+    in_progress: Option<i64>,
+    in_review: Option<i64>,
+}
+
+#[derive(Default)]
+struct MetricsBuilder {
+    metrics: AnalyticsMetrics,
+    attempted_tasks: u64,
+    retries: u64,
+    cycle_time: Vec<i64>,
+    todo: Vec<i64>,
+    in_progress: Vec<i64>,
+    in_review: Vec<i64>,
+}
+
+impl MetricsBuilder {
+    fn add_task(&mut self, task: &TaskSummary) {
+        self.metrics.tasks += 1;
+        if task.done {
+            self.metrics.tasks_done += 1;
+        }
+        if task.attempts > 0 {
+            self.attempted_tasks += 1;
+            self.retries += task.attempts - 1;
+        }
+        self.cycle_time.extend(task.cycle_time);
+        self.todo.extend(task.todo);
+        self.in_progress.extend(task.in_progress);
+        self.in_review.extend(task.in_review);
+    }
+
+    fn add_attempt(&mut self, attempt: &AttemptSummary) {
+        let metrics = &mut self.metrics;
+        metrics.attempts += 1;
+        if attempt.last_coding_agent_status == Some(ExecutionProcessStatus::Completed) {
+            metrics.successful_attempts += 1;
+        }
+        metrics.prs_opened += attempt.prs_opened;
+        metrics.prs_merged += attempt.prs_merged;
+        metrics.direct_merges += attempt.direct_merges;
+        metrics.setup_runs += attempt.setup_runs;
+        metrics.setup_failures += attempt.setup_failures;
+        metrics.dev_server_runs += attempt.dev_server_runs;
+        metrics.dev_server_failures += attempt.dev_server_failures;
+    }
+
+    fn build(self) -> AnalyticsMetrics {
+        let ratio = |part: u64, whole: u64| (whole > 0).then_some(part as f64 / whole as f64);
+        let metrics = self.metrics;
+        AnalyticsMetrics {
+            attempt_success_rate: ratio(metrics.successful_attempts, metrics.attempts),
+            retries_per_task: ratio(self.retries, self.attempted_tasks),
+            pr_merge_rate: ratio(metrics.prs_merged, metrics.prs_opened),
+            setup_failure_rate: ratio(metrics.setup_failures, metrics.setup_runs),
+            dev_server_failure_rate: ratio(metrics.dev_server_failures, metrics.dev_server_runs),
+            cycle_time: DurationStats::from_samples(self.cycle_time),
+            time_in_status: StatusDurations {
+                todo: DurationStats::from_samples(self.todo),
+                inprogress: DurationStats::from_samples(self.in_progress),
+                inreview: DurationStats::from_samples(self.in_review),
+            },
+            ..metrics
+        }
+    }
+}
+
+/// The first coding agent profile in an action chain; setup scripts chain into the agent
+fn executor_profile_id(action: &ExecutorActionField) -> Option<ExecutorProfileId> {
+    let ExecutorActionField::ExecutorAction(action) = action else {
+        return None;
+    };
+    let mut next = Some(action);
+    while let Some(action) = next {
+        if let Some(profile) = action.executor_profile_id() {
+            return Some(profile);
+        }
+        next = action.next_action();
+    }
+    None
+}
+
+fn seconds_between(from: DateTime<Utc>, to: DateTime<Utc>) -> Option<i64> {
+    let seconds = (to - from).num_seconds();
+    (seconds >= 0).then_some(seconds)
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct StatusTimes {
+    todo: Option<i64>,
+    in_progress: Option<i64>,
+    in_review: Option<i64>,
+    done_at: Option<DateTime<Utc>>,
+}
+
+impl StatusTimes {
+    fn add(&mut self, status: &TaskStatus, from: DateTime<Utc>, to: DateTime<Utc>) {
+        let slot = match status {
+            TaskStatus::Todo => &mut self.todo,
+            TaskStatus::InProgress => &mut self.in_progress,
+            TaskStatus::InReview => &mut self.in_review,
+            TaskStatus::Done | TaskStatus::Cancelled => return,
+        };
+        if let Some(seconds) = seconds_between(from, to) {
+            *slot = Some(slot.unwrap_or(0) + seconds);
+        }
+    }
+
+    /// Replay the recorded status changes of a task. Changes made before history was
+    /// recorded are attributed to the status the first event moved away from.
+    fn from_events(task: &AnalyticsTask, events: &[&TaskEvent], now: DateTime<Utc>) -> Self {
+        let mut times = Self::default();
+        let Some(first) = events.first() else {
+            return times;
+        };
+        let mut status = first.old_status.clone().unwrap_or(first.new_status.clone());
+        let mut since = task.created_at;
+        for event in events {
+            times.add(&status, since, event.created_at);
+            status = event.new_status.clone();
+            since = event.created_at;
+            if status == TaskStatus::Done {
+                times.done_at = Some(event.created_at);
+            }
+        }
+        times.add(&status, since, now);
+        if task.status != TaskStatus::Done {
+            times.done_at = None;
+        }
+        times
+    }
+
+    fn from_attempts(
+        task: &AnalyticsTask,
+        attempts: &[&AttemptSummary],
+        now: DateTime<Utc>,
+    ) -> Self {
+        let first_attempt_at = attempts.iter().filter_map(|a| a.created_at).min();
+        let agent_finished_at = attempts
+            .iter()
+            .filter_map(|a| a.coding_agent_finished_at)
+            .max();
+        let merged_at = attempts.iter().filter_map(|a| a.merged_at).min();
+        let done_at =
+            (task.status == TaskStatus::Done).then(|| merged_at.unwrap_or(task.updated_at));
+
+        let todo = match (first_attempt_at, &task.status) {
+            (Some(started), _) => seconds_between(task.created_at, started),
+            (None, TaskStatus::Todo) => seconds_between(task.created_at, now),
+            (None, _) => None,
+        };
+        let in_progress = match (first_attempt_at, agent_finished_at, &task.status) {
+            (Some(started), Some(finished), _) => seconds_between(started, finished),
+            (Some(started), None, TaskStatus::InProgress) => seconds_between(started, now),
+            _ => None,
+        };
+        let in_review = match (agent_finished_at, done_at, &task.status) {
+            (Some(finished), Some(done), _) => seconds_between(finished, done),
+            (Some(finished), None, TaskStatus::InReview) => seconds_between(finished, now),
+            _ => None,
+        };
+
+        Self {
+            todo,
+            in_progress,
+            in_review,
+            done_at,
+        }
+    }
+}
+
+fn summarize_task(
+    task: &AnalyticsTask,
+    attempts: &[&AttemptSummary],
+    events: &[&TaskEvent],
+    now: DateTime<Utc>,
+) -> TaskSummary {
+    let times = if events.is_empty() {
+        StatusTimes::from_attempts(task, attempts, now)
+    } else {
+        StatusTimes::from_events(task, events, now)
+    };
+
+    TaskSummary {
+        attempts: attempts.len() as u64,
+        done: task.status == TaskStatus::Done,
+        executor_profile_id: attempts
+            .iter()
+            .rev()
+            .find_map(|a| a.executor_profile_id.clone()),
+        cycle_time: times
+            .done_at
+            .and_then(|done| seconds_between(task.created_at, done)),
+        todo: times.todo,
+        in_progress: times.in_progress,
+        in_review: times.in_review,
+    }
+}
+
+fn profile_builder<'a>(
+    profiles: &'a mut Vec<(ExecutorProfileId, MetricsBuilder)>,
+    profile_index: &mut HashMap<ExecutorProfileId, usize>,
+    profile: &ExecutorProfileId,
+) -> &'a mut MetricsBuilder {
+    let index = *profile_index.entry(profile.clone()).or_insert_with(|| {
+        profiles.push((profile.clone(), MetricsBuilder::default()));
+        profiles.len() - 1
+    });
+    &mut profiles[index].1
+}
+
+pub fn build_report(data: &AnalyticsData, now: DateTime<Utc>) -> AnalyticsReport {
+    let mut attempts: HashMap<Uuid, AttemptSummary> = data
+        .attempts
+        .iter()
+        .map(|attempt| {
+            let summary = AttemptSummary {
+                task_id: attempt.task_id,
+                created_at: Some(attempt.created_at),
+                ..Default::default()
+            };
+            (attempt.id, summary)
+        })
+        .collect();
+
+    for process in &data.processes {
+        let Some(attempt) = attempts.get_mut(&process.workspace_id) else {
+            continue;
+        };
+        if attempt.executor_profile_id.is_none() {
+            attempt.executor_profile_id = executor_profile_id(&process.executor_action);
+        }
+        let failed = process.status == ExecutionProcessStatus::Failed;
+        match process.run_reason {
+            ExecutionProcessRunReason::CodingAgent => {
+                attempt.last_coding_agent_status = Some(process.status.clone());
+                attempt.coding_agent_finished_at =
+                    process.completed_at.or(attempt.coding_agent_finished_at);
+            }
+            ExecutionProcessRunReason::SetupScript => {
+                attempt.setup_runs += 1;
+                attempt.setup_failures += failed as u64;
+            }
+            ExecutionProcessRunReason::DevServer => {
+                attempt.dev_server_runs += 1;
+                attempt.dev_server_failures += failed as u64;
+            }
+            ExecutionProcessRunReason::CleanupScript | ExecutionProcessRunReason::QualityGate => {}
+        }
+    }
+
+    for merge in &data.merges {
+        let Some(attempt) = attempts.get_mut(&merge.workspace_id) else {
+            continue;
+        };
+        let merged_at = match merge.merge_type {
+            MergeType::Direct => {
+                attempt.direct_merges += 1;
+                Some(merge.created_at)
+            }
+            MergeType::Pr => {
+                attempt.prs_opened += 1;
+                if matches!(merge.pr_status, Some(MergeStatus::Merged)) {
+                    attempt.prs_merged += 1;
+                    Some(merge.pr_merged_at.unwrap_or(merge.created_at))
+                } else {
+                    None
+                }
+            }
+        };
+        attempt.merged_at = attempt.merged_at.into_iter().chain(merged_at).min();
+    }
+
+    // Attempts per task, oldest first
+    let mut task_attempts: HashMap<Uuid, Vec<&AttemptSummary>> = HashMap::new();
+    for attempt in &data.attempts {
+        if let Some(summary) = attempts.get(&attempt.id) {
+            task_attempts
+                .entry(summary.task_id)
+                .or_default()
+                .push(summary);
+        }
+    }
+
+    let mut task_events: HashMap<Uuid, Vec<&TaskEvent>> = HashMap::new();
+    for event in &data.events {
+        task_events.entry(event.task_id).or_default().push(event);
+    }
+
+    let mut overall = MetricsBuilder::default();
+    let mut projects: Vec<(Uuid, String, MetricsBuilder)> = Vec::new();
+    let mut profiles: Vec<(ExecutorProfileId, MetricsBuilder)> = Vec::new();
+    let mut project_index: HashMap<Uuid, usize> = HashMap::new();
+    let mut profile_index: HashMap<ExecutorProfileId, usize> = HashMap::new();
+
+    for task in &data.tasks {
+        let attempts = task_attempts.remove(&task.id).unwrap_or_default();
+        let events = task_events.remove(&task.id).unwrap_or_default();
+        let summary = summarize_task(task, &attempts, &events, now);
+
+        let project = *project_index.entry(task.project_id).or_insert_with(|| {
+            projects.push((
+                task.project_id,
+                task.project_name.clone(),
+                MetricsBuilder::default(),
+            ));
+            projects.len() - 1
+        });
+
+        overall.add_task(&summary);
+        projects[project].2.add_task(&summary);
+        if let Some(profile) = &summary.executor_profile_id {
+            profile_builder(&mut profiles, &mut profile_index, profile).add_task(&summary);
+        }
+        for attempt in attempts {
+            overall.add_attempt(attempt);
+            projects[project].2.add_attempt(attempt);
+            if let Some(profile) = &attempt.executor_profile_id {
+                profile_builder(&mut profiles, &mut profile_index, profile).add_attempt(attempt);
+            }
+        }
+    }
+
+    AnalyticsReport {
+        overall: overall.build(),
+        projects: projects
+            .into_iter()
+            .map(|(project_id, project_name, builder)| ProjectMetrics {
+                project_id,
+                project_name,
+                metrics: builder.build(),
+            })
+            .collect(),
+        executor_profiles: profiles
+            .into_iter()
+            .map(|(executor_profile_id, builder)| ExecutorProfileMetrics {
+                executor_profile_id,
+                metrics: builder.build(),
+            })
+            .collect(),
+    }
+}
+
+pub async fn analytics_report(
+    pool: &SqlitePool,
+    filter: &AnalyticsFilter,
+) -> Result<AnalyticsReport, sqlx::Error> {
+    let data = AnalyticsData::load(pool, filter).await?;
+    Ok(build_report(&data, Utc::now()))
+}
+
+#[cfg(test)]
+mod tests {
+    use db::models::task_event::TaskEventActor;
+
+    use super::*;
+
+    #[test]
+    fn nearest_rank_percentiles() {
+        let stats = DurationStats::from_samples(vec![50, 10, 40, 20, 30]);
+        assert_eq!(stats.count, 5);
+        assert_eq!(stats.median_seconds, Some(30));
+        assert_eq!(stats.p90_seconds, Some(50));
+        assert_eq!(
+            DurationStats::from_samples(vec![]),
+            DurationStats::default()
+        );
+    }
+
+    #[test]
+    fn status_durations_follow_attempt_and_merge_times() {
+        let at = |minutes: i64| DateTime::<Utc>::UNIX_EPOCH + chrono::Duration::minutes(minutes);
+        let task = AnalyticsTask {
+            id: Uuid::new_v4(),
+            project_id: Uuid::new_v4(),
+            project_name: "demo".to_string(),
+            status: TaskStatus::Done,
+            created_at: at(0),
+            updated_at: at(120),
+        };
+        let attempt = AttemptSummary {
+            created_at: Some(at(10)),
+            coding_agent_finished_at: Some(at(40)),
+            merged_at: Some(at(100)),
+            ..Default::default()
+        };
+
+        let summary = summarize_task(&task, &[&attempt], &[], at(500));
+        assert!(summary.done);
+        assert_eq!(summary.todo, Some(600));
+        assert_eq!(summary.in_progress, Some(1800));
+        assert_eq!(summary.in_review, Some(3600));
+        assert_eq!(summary.cycle_time, Some(6000));
+    }
+
+    #[test]
+    fn status_history_takes_precedence_and_sums_every_stay() {
+        let at = |minutes: i64| DateTime::<Utc>::UNIX_EPOCH + chrono::Duration::minutes(minutes);
+        let task = AnalyticsTask {
+            id: Uuid::new_v4(),
+            project_id: Uuid::new_v4(),
+            project_name: "demo".to_string(),
+            status: TaskStatus::Done,
+            created_at: at(0),
+            updated_at: at(200),
+        };
+        let event = |minutes, old_status, new_status| TaskEvent {
+            id: Uuid::new_v4(),
+            task_id: task.id,
+            old_status,
+            new_status,
+            actor: TaskEventActor::Agent,
+            created_at: at(minutes),
+        };
+        let events = [
+            event(0, None, TaskStatus::Todo),
+            event(5, Some(TaskStatus::Todo), TaskStatus::InProgress),
+            event(20, Some(TaskStatus::InProgress), TaskStatus::InReview),
+            event(30, Some(TaskStatus::InReview), TaskStatus::InProgress),
+            event(50, Some(TaskStatus::InProgress), TaskStatus::InReview),
+            event(90, Some(TaskStatus::InReview), TaskStatus::Done),
+        ];
+        let events: Vec<&TaskEvent> = events.iter().collect();
+
+        let summary = summarize_task(&task, &[], &events, at(500));
+        assert_eq!(summary.todo, Some(300));
+        assert_eq!(summary.in_progress, Some(2100));
+        assert_eq!(summary.in_review, Some(3000));
+        assert_eq!(summary.cycle_time, Some(5400));
+    }
+}
@@ -0,0 +1,1131 @@
+use std::{
+    collections::HashMap,
+    io::{Read, Write},
+    path::Path,
+};
+
+use chrono::{DateTime, Utc};
+use db::models::{
+    execution_process::{
+        CreateImportedExecutionProcess, ExecutionProcess, ExecutionProcessRunReason,
+        ExecutionProcessStatus,
+    },
+    execution_process_logs::{ExecutionProcessLogs, ExecutionProcessLogsError},
+    image::{Image, TaskImage},
+    project::{CreateProject, Project, UpdateProject},
+    project_repo::{CreateProjectRepo, ProjectRepo, ProjectRepoError, UpdateProjectRepo},
+    repo::Repo,
+    session::{CreateSession, Session, SessionError},
+    tag::{CreateTag, Tag},
+    task::{CreateTask, Task, TaskStatus},
+    task_event::TaskEventActor,
+    workspace::{CreateWorkspace, Workspace, WorkspaceError},
+    workspace_repo::{CreateWorkspaceRepo, WorkspaceRepo},
+};
+use flate2::{Compression, read::GzDecoder, write::GzEncoder};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::{SqliteConnection, SqlitePool};
+use thiserror::Error;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::{
+    image::{ImageError, ImageService},
+    project::{ProjectService, ProjectServiceError},
+    repo::RepoService,
+};
+
+/// Version of the bundle layout; bumped when a bundle can no longer be read by older versions
+pub const BUNDLE_FORMAT_VERSION: u32 = 1;
+
+const MANIFEST_PATH: &str = "manifest.json";
+const IMAGES_DIR: &str = "images";
+const LOGS_DIR: &str = "logs";
+const VIBE_IMAGES_PREFIX: &str = ".vibe-images/";
+/// Limit on the unpacked size of a bundle, which is read into memory
+const MAX_UNPACKED_BYTES: u64 = 1024 * 1024 * 1024;
+
+#[derive(Debug, Error)]
+pub enum ProjectBundleError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Image(#[from] ImageError),
+    #[error(transparent)]
+    ProjectService(#[from] ProjectServiceError),
+    #[error("Invalid project bundle: {0}")]
+    InvalidBundle(String),
+    #[error(
+        "Unsupported bundle version {0}; this version of Vibe Kanban reads version {BUNDLE_FORMAT_VERSION}"
+    )]
+    UnsupportedVersion(u32),
+    #[error("No path given for repository '{0}' and its original path does not exist")]
+    MissingRepoPath(String),
+}
+
+impl From<ProjectRepoError> for ProjectBundleError {
+    fn from(e: ProjectRepoError) -> Self {
+        match e {
+            ProjectRepoError::Database(e) => Self::Database(e),
+            e => Self::InvalidBundle(e.to_string()),
+        }
+    }
+}
+
+impl From<WorkspaceError> for ProjectBundleError {
+    fn from(e: WorkspaceError) -> Self {
+        match e {
+            WorkspaceError::Database(e) => Self::Database(e),
+            e => Self::InvalidBundle(e.to_string()),
+        }
+    }
+}
+
+impl From<SessionError> for ProjectBundleError {
+    fn from(e: SessionError) -> Self {
+        match e {
+            SessionError::Database(e) => Self::Database(e),
+            e => Self::InvalidBundle(e.to_string()),
+        }
+    }
+}
+
+impl From<ExecutionProcessLogsError> for ProjectBundleError {
+    fn from(e: ExecutionProcessLogsError) -> Self {
+        match e {
+            ExecutionProcessLogsError::Database(e) => Self::Database(e),
+            ExecutionProcessLogsError::Io(e) => Self::Io(e),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct ExportProjectOptions {
+    /// Include execution process logs, so history can be replayed after import
+    #[serde(default)]
+    pub include_logs: bool,
+    /// Include images attached to tasks
+    #[serde(default = "default_true")]
+    pub include_images: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct ImportRepoPath {
+    /// Repository id from the bundle
+    pub repo_id: Uuid,
+    /// Git repository on this machine to use instead of the original path
+    pub path: String,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, TS)]
+pub struct ImportProjectOptions {
+    /// Name of the imported project; defaults to the name in the bundle
+    pub name: Option<String>,
+    /// Repositories whose original path does not exist on this machine
+    #[serde(default)]
+    pub repo_paths: Vec<ImportRepoPath>,
+    /// Recreate workspaces and execution logs from the bundle as read-only history
+    #[serde(default)]
+    pub import_history: bool,
+}
+
+/// What a bundle contains, shown before importing it
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct ProjectBundleSummary {
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    pub project_name: String,
+    pub repos: Vec<BundledRepoSummary>,
+    pub task_count: usize,
+    pub tag_count: usize,
+    pub image_count: usize,
+    pub has_history: bool,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct BundledRepoSummary {
+    pub repo_id: Uuid,
+    pub display_name: String,
+    pub original_path: String,
+    /// Whether the original path is a directory on this machine
+    pub path_exists: bool,
+}
+
+/// `manifest.json` of a bundle
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectBundleManifest {
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    pub project: BundledProject,
+    pub repos: Vec<BundledRepo>,
+    pub tags: Vec<BundledTag>,
+    pub tasks: Vec<BundledTask>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundledProject {
+    pub name: String,
+    pub dev_script: Option<String>,
+    pub dev_script_working_dir: Option<String>,
+    pub default_agent_working_dir: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundledRepo {
+    pub id: Uuid,
+    pub display_name: String,
+    pub path: String,
+    pub setup_script: Option<String>,
+    pub cleanup_script: Option<String>,
+    pub copy_files: Option<String>,
+    pub parallel_setup_script: bool,
+    pub gate_script: Option<String>,
+    pub gate_follow_up_on_failure: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundledTag {
+    pub tag_name: String,
+    pub content: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundledTask {
+    pub id: Uuid,
+    pub title: String,
+    pub description: Option<String>,
+    pub status: TaskStatus,
+    pub created_at: DateTime<Utc>,
+    #[serde(default)]
+    pub images: Vec<BundledImage>,
+    #[serde(default)]
+    pub workspaces: Vec<BundledWorkspace>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundledImage {
+    /// Path inside the archive
+    pub archive_path: String,
+    /// File name the task description refers to as `.vibe-images/<file_path>`
+    pub file_path: String,
+    pub original_name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundledWorkspace {
+    pub branch: String,
+    pub agent_working_dir: Option<String>,
+    pub created_at: DateTime<Utc>,
+    /// Target branch per bundled repository id
+    pub repos: Vec<BundledWorkspaceRepo>,
+    pub sessions: Vec<BundledSession>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundledWorkspaceRepo {
+    pub repo_id: Uuid,
+    pub target_branch: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundledSession {
+    pub executor: Option<String>,
+    pub execution_processes: Vec<BundledExecutionProcess>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundledExecutionProcess {
+    pub run_reason: ExecutionProcessRunReason,
+    pub executor_action: serde_json::Value,
+    pub status: ExecutionProcessStatus,
+    pub exit_code: Option<i64>,
+    pub dropped: bool,
+    pub started_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+    /// JSONL log file inside the archive, if logs were exported
+    pub log_path: Option<String>,
+}
+
+impl ProjectBundleManifest {
+    pub fn summary(&self) -> ProjectBundleSummary {
+        ProjectBundleSummary {
+            version: self.version,
+            exported_at: self.exported_at,
+            project_name: self.project.name.clone(),
+            repos: self
+                .repos
+                .iter()
+                .map(|repo| BundledRepoSummary {
+                    repo_id: repo.id,
+                    display_name: repo.display_name.clone(),
+                    original_path: repo.path.clone(),
+                    path_exists: Path::new(&repo.path).is_dir(),
+                })
+                .collect(),
+            task_count: self.tasks.len(),
+            tag_count: self.tags.len(),
+            image_count: self.tasks.iter().map(|task| task.images.len()).sum(),
+            has_history: self.tasks.iter().any(|task| !task.workspaces.is_empty()),
+        }
+    }
+}
+
+/// A manifest together with the files it refers to
+pub struct ProjectBundle {
+    pub manifest: ProjectBundleManifest,
+    pub files: HashMap<String, Vec<u8>>,
+}
+
+impl ProjectBundle {
+    /// Writes the bundle as a gzipped tarball
+    pub fn to_archive(&self) -> Result<Vec<u8>, ProjectBundleError> {
+        let manifest = serde_json::to_vec_pretty(&self.manifest)
+            .map_err(|e| ProjectBundleError::InvalidBundle(e.to_string()))?;
+
+        let mut buffer = Vec::new();
+        {
+            let encoder = GzEncoder::new(&mut buffer, Compression::default());
+            let mut archive = tar::Builder::new(encoder);
+            append_file(&mut archive, MANIFEST_PATH, &manifest)?;
+            let mut paths: Vec<_> = self.files.keys().collect();
+            paths.sort();
+            for path in paths {
+                append_file(&mut archive, path, &self.files[path])?;
+            }
+            archive.into_inner()?.finish()?;
+        }
+        Ok(buffer)
+    }
+
+    pub fn from_archive(data: &[u8]) -> Result<Self, ProjectBundleError> {
+        Self::from_archive_with_limit(data, MAX_UNPACKED_BYTES)
+    }
+
+    fn from_archive_with_limit(
+        data: &[u8],
+        max_unpacked_bytes: u64,
+    ) -> Result<Self, ProjectBundleError> {
+        let mut archive = tar::Archive::new(GzDecoder::new(data));
+        let mut manifest = None;
+        let mut files = HashMap::new();
+        let mut remaining = max_unpacked_bytes;
+
+        for entry in archive.entries()? {
+            let mut entry = entry?;
+            if !entry.header().entry_type().is_file() {
+                continue;
+            }
+            let path = entry.path()?.to_string_lossy().to_string();
+            // Read one byte past the limit rather than trusting the size in the header
+            let mut contents = Vec::new();
+            (&mut entry)
+                .take(remaining + 1)
+                .read_to_end(&mut contents)?;
+            remaining = remaining
+                .checked_sub(contents.len() as u64)
+                .ok_or_else(|| {
+                    ProjectBundleError::InvalidBundle(format!(
+                        "unpacks to more than {max_unpacked_bytes} bytes"
+                    ))
+                })?;
+            if path == MANIFEST_PATH {
+                manifest = Some(contents);
+            } else {
+                files.insert(path, contents);
+            }
+        }
+
+        let manifest = manifest.ok_or_else(|| {
+            ProjectBundleError::InvalidBundle(format!("{MANIFEST_PATH} is missing"))
+        })?;
+        // Check the version before the layout, which may have changed
+        let version = serde_json::from_slice::<serde_json::Value>(&manifest)
+            .ok()
+            .and_then(|value| value.get("version").and_then(serde_json::Value::as_u64))
+            .ok_or_else(|| ProjectBundleError::InvalidBundle("missing version".to_string()))?;
+        if version != u64::from(BUNDLE_FORMAT_VERSION) {
+            return Err(ProjectBundleError::UnsupportedVersion(version as u32));
+        }
+        let manifest = serde_json::from_slice(&manifest)
+            .map_err(|e| ProjectBundleError::InvalidBundle(e.to_string()))?;
+
+        Ok(Self { manifest, files })
+    }
+}
+
+fn append_file<W: Write>(
+    archive: &mut tar::Builder<W>,
+    path: &str,
+    contents: &[u8],
+) -> std::io::Result<()> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(contents.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(Utc::now().timestamp().max(0) as u64);
+    header.set_cksum();
+    archive.append_data(&mut header, path, contents)
+}
+
+/// Replaces `.vibe-images/<old>` references with the file names images were stored under
+fn remap_image_references(text: &str, file_paths: &HashMap<String, String>) -> String {
+    file_paths
+        .iter()
+        .fold(text.to_string(), |text, (old, new)| {
+            text.replace(
+                &format!("{VIBE_IMAGES_PREFIX}{old}"),
+                &format!("{VIBE_IMAGES_PREFIX}{new}"),
+            )
+        })
+}
+
+/// Collects the project, its repository settings, tags and tasks with their images, and
+/// optionally workspaces with their execution logs
+pub async fn export_project(
+    pool: &SqlitePool,
+    image_service: &ImageService,
+    project: &Project,
+    options: &ExportProjectOptions,
+) -> Result<ProjectBundle, ProjectBundleError> {
+    let mut files = HashMap::new();
+
+    let repo_paths: HashMap<Uuid, Repo> = ProjectRepo::find_repos_for_project(pool, project.id)
+        .await?
+        .into_iter()
+        .map(|repo| (repo.id, repo))
+        .collect();
+    let repos = ProjectRepo::find_by_project_id(pool, project.id)
+        .await?
+        .into_iter()
+        .filter_map(|project_repo| {
+            let repo = repo_paths.get(&project_repo.repo_id)?;
+            Some(BundledRepo {
+                id: repo.id,
+                display_name: repo.display_name.clone(),
+                path: repo.path.to_string_lossy().to_string(),
+                setup_script: project_repo.setup_script,
+                cleanup_script: project_repo.cleanup_script,
+                copy_files: project_repo.copy_files,
+                parallel_setup_script: project_repo.parallel_setup_script,
+                gate_script: project_repo.gate_script,
+                gate_follow_up_on_failure: project_repo.gate_follow_up_on_failure,
+            })
+        })
+        .collect();
+
+    let tags = Tag::find_all(pool)
+        .await?
+        .into_iter()
+        .map(|tag| BundledTag {
+            tag_name: tag.tag_name,
+            content: tag.content,
+        })
+        .collect();
+
+    let mut tasks = Vec::new();
+    for task in Task::find_by_project_id_with_attempt_status(pool, project.id).await? {
+        let task = task.task;
+
+        let mut images = Vec::new();
+        if options.include_images {
+            for image in Image::find_by_task_id(pool, task.id).await? {
+                let path = image_service.get_absolute_path(&image);
+                let data = match tokio::fs::read(&path).await {
+                    Ok(data) => data,
+                    Err(e) => {
+                        tracing::warn!("Skipping missing image {}: {}", path.display(), e);
+                        continue;
+                    }
+                };
+                let archive_path = format!("{IMAGES_DIR}/{}", image.file_path);
+                files.insert(archive_path.clone(), data);
+                images.push(BundledImage {
+                    archive_path,
+                    file_path: image.file_path,
+                    original_name: image.original_name,
+                });
+            }
+        }
+
+        let mut workspaces = Vec::new();
+        if options.include_logs {
+            // Oldest first, so they are recreated in order
+            for workspace in Workspace::fetch_all(pool, Some(task.id))
+                .await?
+                .into_iter()
+                .rev()
+            {
+                workspaces.push(export_workspace(pool, &workspace, &mut files).await?);
+            }
+        }
+
+        tasks.push(BundledTask {
+            id: task.id,
+            title: task.title,
+            description: task.description,
+            status: task.status,
+            created_at: task.created_at,
+            images,
+            workspaces,
+        });
+    }
+
+    Ok(ProjectBundle {
+        manifest: ProjectBundleManifest {
+            version: BUNDLE_FORMAT_VERSION,
+            exported_at: Utc::now(),
+            project: BundledProject {
+                name: project.name.clone(),
+                dev_script: project.dev_script.clone(),
+                dev_script_working_dir: project.dev_script_working_dir.clone(),
+                default_agent_working_dir: project.default_agent_working_dir.clone(),
+            },
+            repos,
+            tags,
+            tasks,
+        },
+        files,
+    })
+}
+
+async fn export_workspace(
+    pool: &SqlitePool,
+    workspace: &Workspace,
+    files: &mut HashMap<String, Vec<u8>>,
+) -> Result<BundledWorkspace, ProjectBundleError> {
+    let repos = WorkspaceRepo::find_by_workspace_id(pool, workspace.id)
+        .await?
+        .into_iter()
+        .map(|repo| BundledWorkspaceRepo {
+            repo_id: repo.repo_id,
+            target_branch: repo.target_branch,
+        })
+        .collect();
+
+    let mut sessions = Vec::new();
+    // Newest first, so reverse to recreate them in order
+    for session in Session::find_by_workspace_id(pool, workspace.id)
+        .await?
+        .into_iter()
+        .rev()
+    {
+        let mut execution_processes = Vec::new();
+        for process in ExecutionProcess::find_by_session_id(pool, session.id, true).await? {
+            let records = ExecutionProcessLogs::find_by_execution_id(pool, process.id).await?;
+            let logs: String = records.iter().map(|record| record.logs.as_str()).collect();
+            let log_path = (!logs.is_empty()).then(|| {
+                let path = format!("{LOGS_DIR}/{}.jsonl", process.id);
+                files.insert(path.clone(), logs.into_bytes());
+                path
+            });
+
+            execution_processes.push(BundledExecutionProcess {
+                run_reason: process.run_reason,
+                executor_action: serde_json::to_value(&process.executor_action.0)
+                    .unwrap_or_default(),
+                status: process.status,
+                exit_code: process.exit_code,
+                dropped: process.dropped,
+                started_at: process.started_at,
+                completed_at: process.completed_at,
+                log_path,
+            });
+        }
+        sessions.push(BundledSession {
+            executor: session.executor,
+            execution_processes,
+        });
+    }
+
+    Ok(BundledWorkspace {
+        branch: workspace.branch.clone(),
+        agent_working_dir: workspace.agent_working_dir.clone(),
+        created_at: workspace.created_at,
+        repos,
+        sessions,
+    })
+}
+
+/// Creates a new project from a bundle. Repositories are looked up at the paths given in
+/// `options`, falling back to their original paths. All records are written in one
+/// transaction, so a failed import leaves nothing behind.
+pub async fn import_project(
+    pool: &SqlitePool,
+    project_service: &ProjectService,
+    repo_service: &RepoService,
+    image_service: &ImageService,
+    bundle: &ProjectBundle,
+    options: &ImportProjectOptions,
+) -> Result<Project, ProjectBundleError> {
+    let manifest = &bundle.manifest;
+
+    let mut repositories = Vec::new();
+    for repo in &manifest.repos {
+        let path = match options.repo_paths.iter().find(|p| p.repo_id == repo.id) {
+            Some(mapping) => mapping.path.clone(),
+            None if Path::new(&repo.path).is_dir() => repo.path.clone(),
+            None => {
+                return Err(ProjectBundleError::MissingRepoPath(
+                    repo.display_name.clone(),
+                ));
+            }
+        };
+        repositories.push(CreateProjectRepo {
+            display_name: repo.display_name.clone(),
+            git_repo_path: path,
+        });
+    }
+    let repositories = project_service.normalize_repositories(repo_service, &repositories)?;
+
+    // Images are written by the image service outside the transaction, so the ones it created
+    // are deleted again if the import fails
+    let mut created_images = Vec::new();
+    let result = import_bundle(
+        pool,
+        image_service,
+        bundle,
+        options,
+        &repositories,
+        &mut created_images,
+    )
+    .await;
+    if result.is_err() {
+        for image_id in created_images {
+            if let Err(e) = image_service.delete_image(image_id).await {
+                tracing::warn!(
+                    "Failed to delete image {} of a failed import: {}",
+                    image_id,
+                    e
+                );
+            }
+        }
+    }
+    result
+}
+
+async fn import_bundle(
+    pool: &SqlitePool,
+    image_service: &ImageService,
+    bundle: &ProjectBundle,
+    options: &ImportProjectOptions,
+    repositories: &[CreateProjectRepo],
+    created_images: &mut Vec<Uuid>,
+) -> Result<Project, ProjectBundleError> {
+    let manifest = &bundle.manifest;
+
+    let mut images: HashMap<&str, Image> = HashMap::new();
+    for image in manifest.tasks.iter().flat_map(|task| &task.images) {
+        if images.contains_key(image.archive_path.as_str()) {
+            continue;
+        }
+        let Some(data) = bundle.files.get(&image.archive_path) else {
+            tracing::warn!("Bundle is missing image {}", image.archive_path);
+            continue;
+        };
+        let hash = format!("{:x}", Sha256::digest(data));
+        let existed = Image::find_by_hash(pool, &hash).await?.is_some();
+        let stored = image_service
+            .store_image(data, &image.original_name)
+            .await?;
+        if !existed {
+            created_images.push(stored.id);
+        }
+        images.insert(&image.archive_path, stored);
+    }
+
+    let mut tx = pool.begin().await?;
+
+    let name = options
+        .name
+        .clone()
+        .filter(|name| !name.trim().is_empty())
+        .unwrap_or_else(|| manifest.project.name.clone());
+    let project = Project::create(
+        &mut *tx,
+        &CreateProject {
+            name,
+            repositories: repositories.to_vec(),
+        },
+        Uuid::new_v4(),
+    )
+    .await?;
+
+    // Bundle repo id -> new repo id; repositories are in the same order as the manifest
+    let mut repo_ids = HashMap::new();
+    let mut repo_names = Vec::new();
+    for (bundled, repo) in manifest.repos.iter().zip(repositories) {
+        let repo_entity =
+            Repo::find_or_create(&mut *tx, Path::new(&repo.git_repo_path), &repo.display_name)
+                .await?;
+        ProjectRepo::create(&mut *tx, project.id, repo_entity.id).await?;
+        ProjectRepo::update(
+            &mut *tx,
+            project.id,
+            repo_entity.id,
+            &UpdateProjectRepo {
+                setup_script: bundled.setup_script.clone(),
+                cleanup_script: bundled.cleanup_script.clone(),
+                copy_files: bundled.copy_files.clone(),
+                parallel_setup_script: Some(bundled.parallel_setup_script),
+                gate_script: bundled.gate_script.clone(),
+                gate_follow_up_on_failure: Some(bundled.gate_follow_up_on_failure),
+            },
+        )
+        .await?;
+        repo_ids.insert(bundled.id, repo_entity.id);
+        repo_names.push(repo_entity.name);
+    }
+
+    // Like a new project, a single repository is the default agent working directory
+    let default_agent_working_dir =
+        manifest
+            .project
+            .default_agent_working_dir
+            .clone()
+            .or(match repo_names.as_slice() {
+                [name] => Some(name.clone()),
+                _ => None,
+            });
+    let project = Project::update(
+        &mut *tx,
+        project.id,
+        &UpdateProject {
+            name: None,
+            dev_script: manifest.project.dev_script.clone(),
+            dev_script_working_dir: manifest.project.dev_script_working_dir.clone(),
+            default_agent_working_dir,
+        },
+    )
+    .await?;
+
+    // Tags are shared by all projects, so only add the ones that are missing
+    let existing_tags = Tag::find_all(&mut *tx).await?;
+    for tag in &manifest.tags {
+        if !existing_tags
+            .iter()
+            .any(|existing| existing.tag_name == tag.tag_name)
+        {
+            Tag::create(
+                &mut *tx,
+                &CreateTag {
+                    tag_name: tag.tag_name.clone(),
+                    content: tag.content.clone(),
+                },
+            )
+            .await?;
+        }
+    }
+
+    for bundled in &manifest.tasks {
+        import_task(
+            &mut tx,
+            bundle,
+            project.id,
+            bundled,
+            &images,
+            &repo_ids,
+            options.import_history,
+        )
+        .await?;
+    }
+
+    tx.commit().await?;
+    Ok(project)
+}
+
+async fn import_task(
+    conn: &mut SqliteConnection,
+    bundle: &ProjectBundle,
+    project_id: Uuid,
+    bundled: &BundledTask,
+    images: &HashMap<&str, Image>,
+    repo_ids: &HashMap<Uuid, Uuid>,
+    import_history: bool,
+) -> Result<(), ProjectBundleError> {
+    let mut image_ids = Vec::new();
+    let mut file_paths = HashMap::new();
+    for image in &bundled.images {
+        if let Some(stored) = images.get(image.archive_path.as_str()) {
+            file_paths.insert(image.file_path.clone(), stored.file_path.clone());
+            image_ids.push(stored.id);
+        }
+    }
+
+    let task = Task::create(
+        &mut *conn,
+        &CreateTask {
+            project_id,
+            title: bundled.title.clone(),
+            description: bundled
+                .description
+                .as_deref()
+                .map(|description| remap_image_references(description, &file_paths)),
+            status: Some(bundled.status.clone()),
+            parent_workspace_id: None,
+            image_ids: None,
+            shared_task_id: None,
+        },
+        Uuid::new_v4(),
+        TaskEventActor::User,
+    )
+    .await?;
+    TaskImage::associate_many_dedup(&mut *conn, task.id, &image_ids).await?;
+
+    if !import_history {
+        return Ok(());
+    }
+    for workspace in &bundled.workspaces {
+        import_workspace(&mut *conn, bundle, task.id, workspace, repo_ids).await?;
+    }
+    Ok(())
+}
+
+/// Recreates a workspace without a worktree, so its conversation can be read but agents
+/// cannot run in it until its branch exists in the repositories
+async fn import_workspace(
+    conn: &mut SqliteConnection,
+    bundle: &ProjectBundle,
+    task_id: Uuid,
+    bundled: &BundledWorkspace,
+    repo_ids: &HashMap<Uuid, Uuid>,
+) -> Result<(), ProjectBundleError> {
+    let workspace = Workspace::create(
+        &mut *conn,
+        &CreateWorkspace {
+            branch: bundled.branch.clone(),
+            agent_working_dir: bundled.agent_working_dir.clone(),
+        },
+        Uuid::new_v4(),
+        task_id,
+    )
+    .await?;
+    let repos: Vec<_> = bundled
+        .repos
+        .iter()
+        .filter_map(|repo| {
+            Some(CreateWorkspaceRepo {
+                repo_id: *repo_ids.get(&repo.repo_id)?,
+                target_branch: repo.target_branch.clone(),
+            })
+        })
+        .collect();
+    WorkspaceRepo::create_many(&mut *conn, workspace.id, &repos).await?;
+
+    for bundled_session in &bundled.sessions {
+        let session = Session::create(
+            &mut *conn,
+            &CreateSession {
+                executor: bundled_session.executor.clone(),
+            },
+            Uuid::new_v4(),
+            workspace.id,
+        )
+        .await?;
+
+        for process in &bundled_session.execution_processes {
+            let process_id = Uuid::new_v4();
+            ExecutionProcess::create_imported(
+                &mut *conn,
+                &CreateImportedExecutionProcess {
+                    session_id: session.id,
+                    executor_action: process.executor_action.clone(),
+                    run_reason: process.run_reason.clone(),
+                    status: imported_status(&process.status),
+                    exit_code: process.exit_code,
+                    dropped: process.dropped,
+                    started_at: process.started_at,
+                    completed_at: process.completed_at,
+                },
+                process_id,
+            )
+            .await?;
+
+            if let Some(logs) = process
+                .log_path
+                .as_ref()
+                .and_then(|path| bundle.files.get(path))
+            {
+                let logs = String::from_utf8_lossy(logs);
+                ExecutionProcessLogs::append_log_line(&mut *conn, process_id, &logs).await?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// A process that was still running when it was exported never finishes here, so it is
+/// recorded as killed
+fn imported_status(status: &ExecutionProcessStatus) -> ExecutionProcessStatus {
+    match status {
+        ExecutionProcessStatus::Running => ExecutionProcessStatus::Killed,
+        status => status.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest() -> ProjectBundleManifest {
+        ProjectBundleManifest {
+            version: BUNDLE_FORMAT_VERSION,
+            exported_at: Utc::now(),
+            project: BundledProject {
+                name: "web".to_string(),
+                dev_script: Some("npm run dev".to_string()),
+                dev_script_working_dir: None,
+                default_agent_working_dir: None,
+            },
+            repos: vec![],
+            tags: vec![BundledTag {
+                tag_name: "bug".to_string(),
+                content: "Fix it".to_string(),
+            }],
+            tasks: vec![],
+        }
+    }
+
+    #[test]
+    fn archive_round_trip() {
+        let bundle = ProjectBundle {
+            manifest: manifest(),
+            files: HashMap::from([("images/a.png".to_string(), vec![1, 2, 3])]),
+        };
+        let archive = bundle.to_archive().unwrap();
+        let read = ProjectBundle::from_archive(&archive).unwrap();
+        assert_eq!(read.manifest.project.name, "web");
+        assert_eq!(read.manifest.tags.len(), 1);
+        assert_eq!(read.files["images/a.png"], vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn rejects_other_versions() {
+        let mut manifest = manifest();
+        manifest.version = BUNDLE_FORMAT_VERSION + 1;
+        let archive = ProjectBundle {
+            manifest,
+            files: HashMap::new(),
+        }
+        .to_archive()
+        .unwrap();
+        assert!(matches!(
+            ProjectBundle::from_archive(&archive),
+            Err(ProjectBundleError::UnsupportedVersion(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_archives_that_unpack_too_large() {
+        let archive = ProjectBundle {
+            manifest: manifest(),
+            files: HashMap::from([("logs/a.jsonl".to_string(), vec![0; 4096])]),
+        }
+        .to_archive()
+        .unwrap();
+        assert!(ProjectBundle::from_archive_with_limit(&archive, 8192).is_ok());
+        assert!(matches!(
+            ProjectBundle::from_archive_with_limit(&archive, 4096),
+            Err(ProjectBundleError::InvalidBundle(_))
+        ));
+    }
+
+    #[test]
+    fn remaps_image_references() {
+        let file_paths = HashMap::from([("old.png".to_string(), "new.png".to_string())]);
+        assert_eq!(
+            remap_image_references("See ![shot](.vibe-images/old.png)", &file_paths),
+            "See ![shot](.vibe-images/new.png)"
+        );
+    }
+
+    struct ImportFixture {
+        bundle: ProjectBundle,
+        image_service: ImageService,
+        repo_dir: tempfile::TempDir,
+        cache_dir: tempfile::TempDir,
+    }
+
+    /// A bundle with one repository, one task with an image and a workspace whose agent was
+    /// still running when it was exported
+    fn import_fixture(pool: &SqlitePool) -> ImportFixture {
+        let repo_dir = tempfile::TempDir::new().unwrap();
+        git2::Repository::init(repo_dir.path()).unwrap();
+        let cache_dir = tempfile::TempDir::new().unwrap();
+        let repo_id = Uuid::new_v4();
+
+        let mut manifest = manifest();
+        manifest.repos = vec![BundledRepo {
+            id: repo_id,
+            display_name: "web".to_string(),
+            path: repo_dir.path().to_string_lossy().to_string(),
+            setup_script: Some("npm install".to_string()),
+            cleanup_script: None,
+            copy_files: Some(".env".to_string()),
+            parallel_setup_script: false,
+            gate_script: Some("npm test".to_string()),
+            gate_follow_up_on_failure: true,
+        }];
+        manifest.tasks = vec![BundledTask {
+            id: Uuid::new_v4(),
+            title: "Fix the login page".to_string(),
+            description: Some("See ![shot](.vibe-images/old.png)".to_string()),
+            status: TaskStatus::InProgress,
+            created_at: Utc::now(),
+            images: vec![BundledImage {
+                archive_path: "images/old.png".to_string(),
+                file_path: "old.png".to_string(),
+                original_name: "shot.png".to_string(),
+            }],
+            workspaces: vec![BundledWorkspace {
+                branch: "vk/fix-login".to_string(),
+                agent_working_dir: Some("web".to_string()),
+                created_at: Utc::now(),
+                repos: vec![BundledWorkspaceRepo {
+                    repo_id,
+                    target_branch: "main".to_string(),
+                }],
+                sessions: vec![BundledSession {
+                    executor: Some("CLAUDE_CODE".to_string()),
+                    execution_processes: vec![BundledExecutionProcess {
+                        run_reason: ExecutionProcessRunReason::CodingAgent,
+                        executor_action: serde_json::json!({}),
+                        status: ExecutionProcessStatus::Running,
+                        exit_code: None,
+                        dropped: false,
+                        started_at: Utc::now(),
+                        completed_at: None,
+                        log_path: Some("logs/run.jsonl".to_string()),
+                    }],
+                }],
+            }],
+        }];
+
+        ImportFixture {
+            bundle: ProjectBundle {
+                manifest,
+                files: HashMap::from([
+                    ("images/old.png".to_string(), vec![1, 2, 3]),
+                    (
+                        "logs/run.jsonl".to_string(),
+                        b"{\"Stdout\":\"hello\"}\n".to_vec(),
+                    ),
+                ]),
+            },
+            image_service: ImageService::with_cache_dir(
+                pool.clone(),
+                cache_dir.path().to_path_buf(),
+            ),
+            repo_dir,
+            cache_dir,
+        }
+    }
+
+    async fn import(
+        pool: &SqlitePool,
+        fixture: &ImportFixture,
+    ) -> Result<Project, ProjectBundleError> {
+        import_project(
+            pool,
+            &ProjectService::new(),
+            &RepoService::new(),
+            &fixture.image_service,
+            &fixture.bundle,
+            &ImportProjectOptions {
+                name: None,
+                repo_paths: vec![],
+                import_history: true,
+            },
+        )
+        .await
+    }
+
+    #[sqlx::test(migrations = "../db/migrations")]
+    async fn imports_a_project_with_history(pool: SqlitePool) {
+        let fixture = import_fixture(&pool);
+        let project = import(&pool, &fixture).await.unwrap();
+        assert_eq!(project.name, "web");
+        assert_eq!(project.dev_script.as_deref(), Some("npm run dev"));
+        // The only repository becomes the agent working directory, as for a new project
+        let repo_name = fixture
+            .repo_dir
+            .path()
+            .file_name()
+            .unwrap()
+            .to_string_lossy();
+        assert_eq!(
+            project.default_agent_working_dir.as_deref(),
+            Some(repo_name.as_ref())
+        );
+
+        let project_repos = ProjectRepo::find_by_project_id(&pool, project.id)
+            .await
+            .unwrap();
+        assert_eq!(project_repos.len(), 1);
+        assert_eq!(
+            project_repos[0].setup_script.as_deref(),
+            Some("npm install")
+        );
+        assert_eq!(project_repos[0].gate_script.as_deref(), Some("npm test"));
+
+        let tags = Tag::find_all(&pool).await.unwrap();
+        assert!(tags.iter().any(|tag| tag.tag_name == "bug"));
+
+        let tasks = Task::find_by_project_id_with_attempt_status(&pool, project.id)
+            .await
+            .unwrap();
+        assert_eq!(tasks.len(), 1);
+        let task = &tasks[0].task;
+        assert_eq!(task.status, TaskStatus::InProgress);
+        let images = Image::find_by_task_id(&pool, task.id).await.unwrap();
+        assert_eq!(images.len(), 1);
+        assert_eq!(
+            task.description.as_deref(),
+            Some(format!("See ![shot](.vibe-images/{})", images[0].file_path).as_str())
+        );
+        assert!(fixture.image_service.get_absolute_path(&images[0]).exists());
+
+        let (process_id, status): (Uuid, ExecutionProcessStatus) =
+            sqlx::query_as("SELECT id, status FROM execution_processes")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(status, ExecutionProcessStatus::Killed);
+        let logs = ExecutionProcessLogs::find_by_execution_id(&pool, process_id)
+            .await
+            .unwrap();
+        assert_eq!(logs.len(), 1);
+    }
+
+    #[sqlx::test(migrations = "../db/migrations")]
+    async fn failed_import_leaves_nothing_behind(pool: SqlitePool) {
+        let fixture = import_fixture(&pool);
+        // Fail on the last write, after the project, tags, task and image are created
+        sqlx::query(
+            "CREATE TRIGGER fail_log_insert BEFORE INSERT ON execution_process_logs
+             BEGIN SELECT RAISE(ABORT, 'log insert failed'); END",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        assert!(import(&pool, &fixture).await.is_err());
+        assert!(Project::find_all(&pool).await.unwrap().is_empty());
+        let tags = Tag::find_all(&pool).await.unwrap();
+        assert!(!tags.iter().any(|tag| tag.tag_name == "bug"));
+        let image_count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM images")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(image_count, 0);
+        assert_eq!(
+            std::fs::read_dir(fixture.cache_dir.path()).unwrap().count(),
+            0
+        );
+    }
+}
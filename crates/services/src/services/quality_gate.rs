@@ -0,0 +1,187 @@
+use db::models::execution_process::{ExecutionProcess, ExecutionProcessStatus};
+use executors::actions::{
+    ExecutorActionType,
+    script::{ScriptContext, ScriptRequest},
+};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utils::log_msg::LogMsg;
+use uuid::Uuid;
+
+/// Lines of gate output sent back to the coding agent after a failure
+const FOLLOW_UP_OUTPUT_LINES: usize = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum QualityGateState {
+    NotRun,
+    Running,
+    Passed,
+    Failed,
+    /// The last run finished on an older commit than the worktree's HEAD
+    Outdated,
+    /// The worktree has uncommitted changes, which a run would test but a merge would leave out
+    UncommittedChanges,
+}
+
+/// Gate status of one repository in a workspace
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct RepoQualityGate {
+    pub repo_id: Uuid,
+    pub repo_name: String,
+    pub state: QualityGateState,
+    /// The latest gate run for this repository
+    pub execution_process_id: Option<Uuid>,
+}
+
+/// Why a merge or pull request is blocked by a repository's quality gate
+#[derive(Debug, Serialize, Deserialize, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[ts(tag = "type", rename_all = "snake_case")]
+pub enum QualityGateError {
+    /// The gate is running on the current HEAD, possibly started by this request
+    QualityGateRunning {
+        repo_name: String,
+        execution_process_id: Uuid,
+    },
+    QualityGateFailed {
+        repo_name: String,
+        execution_process_id: Uuid,
+    },
+    /// The gate only vouches for committed code, so it does not run on a dirty worktree
+    QualityGateUncommittedChanges { repo_name: String },
+}
+
+/// The repository a gate process ran in, or `None` if it is not a gate process
+pub fn gated_repo_name(process: &ExecutionProcess) -> Option<&str> {
+    match process.executor_action().ok()?.typ() {
+        ExecutorActionType::ScriptRequest(ScriptRequest {
+            context: ScriptContext::QualityGate,
+            working_dir,
+            ..
+        }) => working_dir.as_deref(),
+        _ => None,
+    }
+}
+
+/// State of a gate from its latest run (status and exit code), the HEAD it ran on, the
+/// worktree's current HEAD and whether the worktree has uncommitted changes
+pub fn gate_state(
+    latest: Option<(&ExecutionProcessStatus, Option<i64>)>,
+    gated_head: Option<&str>,
+    current_head: Option<&str>,
+    dirty: bool,
+) -> QualityGateState {
+    let Some((status, exit_code)) = latest else {
+        return if dirty {
+            QualityGateState::UncommittedChanges
+        } else {
+            QualityGateState::NotRun
+        };
+    };
+    match status {
+        ExecutionProcessStatus::Running => QualityGateState::Running,
+        _ if dirty => QualityGateState::UncommittedChanges,
+        ExecutionProcessStatus::Killed => QualityGateState::NotRun,
+        _ if gated_head.is_none() || gated_head != current_head => QualityGateState::Outdated,
+        ExecutionProcessStatus::Completed if exit_code == Some(0) => QualityGateState::Passed,
+        _ => QualityGateState::Failed,
+    }
+}
+
+/// Follow-up prompt asking the coding agent to fix a failed gate, with the tail of its output
+pub fn failure_follow_up_prompt(repo_name: &str, script: &str, history: &[LogMsg]) -> String {
+    let output: String = history
+        .iter()
+        .filter_map(|msg| match msg {
+            LogMsg::Stdout(s) | LogMsg::Stderr(s) => Some(s.as_str()),
+            _ => None,
+        })
+        .collect();
+    let lines: Vec<&str> = output.lines().collect();
+    let tail = lines[lines.len().saturating_sub(FOLLOW_UP_OUTPUT_LINES)..].join("\n");
+
+    format!(
+        "The quality gate for the `{repo_name}` repository failed, so this work cannot be merged yet. \
+         Fix the problems it reports, then stop so the gate can run again.\n\n\
+         Gate script:\n```\n{}\n```\n\nOutput (last {FOLLOW_UP_OUTPUT_LINES} lines at most):\n```\n{}\n```",
+        script.trim(),
+        tail.trim_end()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gate_passes_only_on_the_current_head() {
+        let completed = ExecutionProcessStatus::Completed;
+        assert_eq!(
+            gate_state(Some((&completed, Some(0))), Some("abc"), Some("abc"), false),
+            QualityGateState::Passed
+        );
+        assert_eq!(
+            gate_state(Some((&completed, Some(0))), Some("abc"), Some("def"), false),
+            QualityGateState::Outdated
+        );
+        assert_eq!(
+            gate_state(
+                Some((&ExecutionProcessStatus::Failed, Some(1))),
+                Some("abc"),
+                Some("abc"),
+                false
+            ),
+            QualityGateState::Failed
+        );
+        assert_eq!(
+            gate_state(
+                Some((&ExecutionProcessStatus::Running, None)),
+                None,
+                None,
+                false
+            ),
+            QualityGateState::Running
+        );
+        assert_eq!(
+            gate_state(None, None, Some("abc"), false),
+            QualityGateState::NotRun
+        );
+    }
+
+    #[test]
+    fn gate_does_not_pass_on_a_dirty_worktree() {
+        let completed = ExecutionProcessStatus::Completed;
+        assert_eq!(
+            gate_state(Some((&completed, Some(0))), Some("abc"), Some("abc"), true),
+            QualityGateState::UncommittedChanges
+        );
+        assert_eq!(
+            gate_state(None, None, Some("abc"), true),
+            QualityGateState::UncommittedChanges
+        );
+        assert_eq!(
+            gate_state(
+                Some((&ExecutionProcessStatus::Running, None)),
+                Some("abc"),
+                Some("abc"),
+                true
+            ),
+            QualityGateState::Running
+        );
+    }
+
+    #[test]
+    fn follow_up_prompt_keeps_the_output_tail() {
+        let history: Vec<LogMsg> = (0..250)
+            .map(|i| LogMsg::Stdout(format!("line {i}\n")))
+            .chain([LogMsg::Stderr("error: build failed\n".to_string())])
+            .collect();
+        let prompt = failure_follow_up_prompt("web", "npm test\n", &history);
+        assert!(prompt.contains("`web`"));
+        assert!(prompt.contains("npm test\n```"));
+        assert!(prompt.contains("error: build failed\n```"));
+        assert!(prompt.contains("line 249"));
+        assert!(!prompt.contains("line 50\n"));
+    }
+}
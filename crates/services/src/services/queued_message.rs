@@ -1,24 +1,19 @@
-use std::sync::Arc;
+use std::future::Future;
 
-use chrono::{DateTime, Utc};
-use dashmap::DashMap;
-use db::models::scratch::DraftFollowUpData;
+use db::{
+    DBService,
+    models::{
+        execution_process::{
+            ExecutionContext, ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus,
+        },
+        queued_message::{QueuedMessage, QueuedMessageError},
+        scratch::DraftFollowUpData,
+    },
+};
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 use uuid::Uuid;
 
-/// Represents a queued follow-up message for a session
-#[derive(Debug, Clone, Serialize, Deserialize, TS)]
-#[ts(export)]
-pub struct QueuedMessage {
-    /// The session this message is queued for
-    pub session_id: Uuid,
-    /// The follow-up data (message + variant)
-    pub data: DraftFollowUpData,
-    /// Timestamp when the message was queued
-    pub queued_at: DateTime<Utc>,
-}
-
 /// Status of the queue for a session (for frontend display)
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[serde(tag = "status", rename_all = "snake_case")]
@@ -26,67 +21,422 @@ pub struct QueuedMessage {
 pub enum QueueStatus {
     /// No message queued
     Empty,
-    /// Message is queued and waiting for execution to complete
-    Queued { message: QueuedMessage },
+    /// Messages waiting for execution to complete, in the order they will be sent
+    Queued { messages: Vec<QueuedMessage> },
 }
 
-/// In-memory service for managing queued follow-up messages.
-/// One queued message per session.
+/// Durable FIFO of follow-up messages per session, backed by the `queued_messages` table.
+/// The container drains it one message per completed execution.
 #[derive(Clone)]
 pub struct QueuedMessageService {
-    queue: Arc<DashMap<Uuid, QueuedMessage>>,
+    db: DBService,
 }
 
 impl QueuedMessageService {
-    pub fn new() -> Self {
-        Self {
-            queue: Arc::new(DashMap::new()),
+    pub fn new(db: DBService) -> Self {
+        Self { db }
+    }
+
+    /// Append a message to the end of the session's queue
+    pub async fn queue_message(
+        &self,
+        session_id: Uuid,
+        data: DraftFollowUpData,
+    ) -> Result<QueuedMessage, sqlx::Error> {
+        QueuedMessage::create(&self.db.pool, session_id, &data).await
+    }
+
+    /// Replace the content of a queued message, keeping its position
+    pub async fn update_queued(
+        &self,
+        session_id: Uuid,
+        message_id: Uuid,
+        data: DraftFollowUpData,
+    ) -> Result<QueuedMessage, QueuedMessageError> {
+        QueuedMessage::update(&self.db.pool, session_id, message_id, &data).await
+    }
+
+    /// Remove a single queued message
+    pub async fn remove_queued(
+        &self,
+        session_id: Uuid,
+        message_id: Uuid,
+    ) -> Result<(), QueuedMessageError> {
+        if QueuedMessage::delete(&self.db.pool, session_id, message_id).await? == 0 {
+            return Err(QueuedMessageError::NotFound);
         }
+        Ok(())
     }
 
-    /// Queue a message for a session. Replaces any existing queued message.
-    pub fn queue_message(&self, session_id: Uuid, data: DraftFollowUpData) -> QueuedMessage {
-        let queued = QueuedMessage {
-            session_id,
-            data,
-            queued_at: Utc::now(),
-        };
-        self.queue.insert(session_id, queued.clone());
-        queued
+    /// Reorder the session's queue; `message_ids` must list every queued message once
+    pub async fn reorder(
+        &self,
+        session_id: Uuid,
+        message_ids: &[Uuid],
+    ) -> Result<(), QueuedMessageError> {
+        QueuedMessage::reorder(&self.db.pool, session_id, message_ids).await
+    }
+
+    /// Remove every queued message for a session
+    pub async fn cancel_queued(&self, session_id: Uuid) -> Result<u64, sqlx::Error> {
+        QueuedMessage::delete_by_session_id(&self.db.pool, session_id).await
     }
 
-    /// Cancel/remove a queued message for a session
-    pub fn cancel_queued(&self, session_id: Uuid) -> Option<QueuedMessage> {
-        self.queue.remove(&session_id).map(|(_, v)| v)
+    /// The message that will be sent next, without removing it.
+    /// Callers remove it with [`Self::remove_queued`] once the follow-up has started.
+    pub async fn peek_next(&self, session_id: Uuid) -> Result<Option<QueuedMessage>, sqlx::Error> {
+        QueuedMessage::find_next(&self.db.pool, session_id).await
     }
 
-    /// Get the queued message for a session (if any)
-    pub fn get_queued(&self, session_id: Uuid) -> Option<QueuedMessage> {
-        self.queue.get(&session_id).map(|r| r.clone())
+    /// Start the session's next queued message with `start` and drop it from the queue once
+    /// that succeeded. A failed start leaves the message at the head of the queue.
+    pub async fn start_next<T, E, F, Fut>(&self, session_id: Uuid, start: F) -> Result<Option<T>, E>
+    where
+        F: FnOnce(DraftFollowUpData) -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+        E: From<sqlx::Error>,
+    {
+        let Some(queued_msg) = self.peek_next(session_id).await? else {
+            return Ok(None);
+        };
+        let started = start(queued_msg.data.0).await?;
+        if let Err(e) = self.remove_queued(session_id, queued_msg.id).await {
+            tracing::warn!(
+                "Failed to remove consumed queued message {}: {}",
+                queued_msg.id,
+                e
+            );
+        }
+        Ok(Some(started))
     }
 
-    /// Take (remove and return) the queued message for a session.
-    /// Used by finalization flow to consume the queued message.
-    pub fn take_queued(&self, session_id: Uuid) -> Option<QueuedMessage> {
-        self.queue.remove(&session_id).map(|(_, v)| v)
+    /// Sessions with queued messages that no running execution will send: their latest coding
+    /// agent run completed and nothing else runs in the workspace, e.g. because the server
+    /// stopped in between. Returns the context of that latest run.
+    pub async fn find_idle_sessions(&self) -> Result<Vec<ExecutionContext>, sqlx::Error> {
+        let pool = &self.db.pool;
+        let mut idle = Vec::new();
+        for session_id in self.sessions_with_queued().await? {
+            let Some(latest) = ExecutionProcess::find_latest_by_session_and_run_reason(
+                pool,
+                session_id,
+                &ExecutionProcessRunReason::CodingAgent,
+            )
+            .await?
+            else {
+                continue;
+            };
+            // As on exit, failed and killed runs keep the queue for the next successful one
+            if latest.status != ExecutionProcessStatus::Completed {
+                continue;
+            }
+
+            let ctx = ExecutionProcess::load_context(pool, latest.id).await?;
+            if !ExecutionProcess::has_running_non_dev_server_processes_for_workspace(
+                pool,
+                ctx.workspace.id,
+            )
+            .await?
+            {
+                idle.push(ctx);
+            }
+        }
+        Ok(idle)
     }
 
-    /// Check if a session has a queued message
-    pub fn has_queued(&self, session_id: Uuid) -> bool {
-        self.queue.contains_key(&session_id)
+    /// Check if a session has any queued message
+    pub async fn has_queued(&self, session_id: Uuid) -> Result<bool, sqlx::Error> {
+        QueuedMessage::exists_for_session(&self.db.pool, session_id).await
+    }
+
+    /// Sessions with at least one queued message
+    pub async fn sessions_with_queued(&self) -> Result<Vec<Uuid>, sqlx::Error> {
+        QueuedMessage::find_session_ids(&self.db.pool).await
     }
 
     /// Get queue status for frontend display
-    pub fn get_status(&self, session_id: Uuid) -> QueueStatus {
-        match self.get_queued(session_id) {
-            Some(msg) => QueueStatus::Queued { message: msg },
-            None => QueueStatus::Empty,
-        }
+    pub async fn get_status(&self, session_id: Uuid) -> Result<QueueStatus, sqlx::Error> {
+        let messages = QueuedMessage::find_by_session_id(&self.db.pool, session_id).await?;
+        Ok(if messages.is_empty() {
+            QueueStatus::Empty
+        } else {
+            QueueStatus::Queued { messages }
+        })
     }
 }
 
-impl Default for QueuedMessageService {
-    fn default() -> Self {
-        Self::new()
+#[cfg(test)]
+mod tests {
+    use db::models::{
+        execution_process::CreateImportedExecutionProcess,
+        project::{CreateProject, Project},
+        session::{CreateSession, Session},
+        task::{CreateTask, Task},
+        task_event::TaskEventActor,
+        workspace::{CreateWorkspace, Workspace},
+    };
+    use sqlx::SqlitePool;
+
+    use super::*;
+
+    async fn create_session(pool: &SqlitePool) -> Uuid {
+        let project = Project::create(
+            pool,
+            &CreateProject {
+                name: "queue".to_string(),
+                repositories: vec![],
+            },
+            Uuid::new_v4(),
+        )
+        .await
+        .unwrap();
+        let data = CreateTask::from_title_description(project.id, "task".to_string(), None);
+        let task = Task::create(pool, &data, Uuid::new_v4(), TaskEventActor::User)
+            .await
+            .unwrap();
+        let workspace = Workspace::create(
+            pool,
+            &CreateWorkspace {
+                branch: "vk/queue".to_string(),
+                agent_working_dir: None,
+            },
+            Uuid::new_v4(),
+            task.id,
+        )
+        .await
+        .unwrap();
+        Session::create(
+            pool,
+            &CreateSession { executor: None },
+            Uuid::new_v4(),
+            workspace.id,
+        )
+        .await
+        .unwrap()
+        .id
+    }
+
+    async fn create_process(
+        pool: &SqlitePool,
+        session_id: Uuid,
+        run_reason: ExecutionProcessRunReason,
+        status: ExecutionProcessStatus,
+    ) -> Uuid {
+        let id = Uuid::new_v4();
+        let started_at = chrono::Utc::now();
+        let completed_at = (status != ExecutionProcessStatus::Running).then_some(started_at);
+        ExecutionProcess::create_imported(
+            pool,
+            &CreateImportedExecutionProcess {
+                session_id,
+                executor_action: serde_json::json!({}),
+                run_reason,
+                status,
+                exit_code: None,
+                dropped: false,
+                started_at,
+                completed_at,
+            },
+            id,
+        )
+        .await
+        .unwrap();
+        id
+    }
+
+    fn follow_up(message: &str) -> DraftFollowUpData {
+        DraftFollowUpData {
+            message: message.to_string(),
+            variant: None,
+        }
+    }
+
+    async fn queued_messages(service: &QueuedMessageService, session_id: Uuid) -> Vec<String> {
+        match service.get_status(session_id).await.unwrap() {
+            QueueStatus::Empty => Vec::new(),
+            QueueStatus::Queued { messages } => messages
+                .into_iter()
+                .map(|queued| queued.data.0.message)
+                .collect(),
+        }
+    }
+
+    #[sqlx::test(migrations = "../db/migrations")]
+    async fn sends_messages_in_fifo_order_and_keeps_failed_ones(pool: SqlitePool) {
+        let service = QueuedMessageService::new(DBService { pool: pool.clone() });
+        let session_id = create_session(&pool).await;
+        for message in ["first", "second", "third"] {
+            service
+                .queue_message(session_id, follow_up(message))
+                .await
+                .unwrap();
+        }
+        assert!(service.has_queued(session_id).await.unwrap());
+
+        let failed: Result<Option<()>, sqlx::Error> = service
+            .start_next(session_id, |_| async { Err(sqlx::Error::PoolClosed) })
+            .await;
+        assert!(failed.is_err());
+        assert_eq!(
+            queued_messages(&service, session_id).await,
+            ["first", "second", "third"]
+        );
+
+        let mut sent = Vec::new();
+        loop {
+            let started: Result<Option<String>, sqlx::Error> = service
+                .start_next(session_id, |data| async move { Ok(data.message) })
+                .await;
+            match started.unwrap() {
+                Some(message) => sent.push(message),
+                None => break,
+            }
+        }
+        assert_eq!(sent, ["first", "second", "third"]);
+        assert!(!service.has_queued(session_id).await.unwrap());
+        assert!(matches!(
+            service.get_status(session_id).await.unwrap(),
+            QueueStatus::Empty
+        ));
+    }
+
+    #[sqlx::test(migrations = "../db/migrations")]
+    async fn reorder_requires_exactly_the_sessions_messages(pool: SqlitePool) {
+        let service = QueuedMessageService::new(DBService { pool: pool.clone() });
+        let session_id = create_session(&pool).await;
+        let other_session_id = create_session(&pool).await;
+        let mut ids = Vec::new();
+        for message in ["a", "b", "c"] {
+            ids.push(
+                service
+                    .queue_message(session_id, follow_up(message))
+                    .await
+                    .unwrap()
+                    .id,
+            );
+        }
+        let foreign = service
+            .queue_message(other_session_id, follow_up("other"))
+            .await
+            .unwrap()
+            .id;
+
+        for invalid in [
+            vec![ids[2], ids[1]],
+            vec![ids[2], ids[1], ids[0], foreign],
+            vec![ids[2], ids[1], foreign],
+            vec![ids[2], ids[1], ids[1]],
+            vec![ids[2], ids[1], ids[0], Uuid::new_v4()],
+        ] {
+            assert!(matches!(
+                service.reorder(session_id, &invalid).await,
+                Err(QueuedMessageError::InvalidOrder)
+            ));
+        }
+        assert_eq!(queued_messages(&service, session_id).await, ["a", "b", "c"]);
+
+        service
+            .reorder(session_id, &[ids[2], ids[0], ids[1]])
+            .await
+            .unwrap();
+        assert_eq!(queued_messages(&service, session_id).await, ["c", "a", "b"]);
+        assert_eq!(
+            service
+                .peek_next(session_id)
+                .await
+                .unwrap()
+                .unwrap()
+                .data
+                .0
+                .message,
+            "c"
+        );
+        // Appending goes after the reordered queue, and the other session is untouched
+        service
+            .queue_message(session_id, follow_up("d"))
+            .await
+            .unwrap();
+        assert_eq!(
+            queued_messages(&service, session_id).await,
+            ["c", "a", "b", "d"]
+        );
+        assert_eq!(queued_messages(&service, other_session_id).await, ["other"]);
+        assert!(matches!(
+            service.remove_queued(session_id, foreign).await,
+            Err(QueuedMessageError::NotFound)
+        ));
+    }
+
+    #[sqlx::test(migrations = "../db/migrations")]
+    async fn finds_sessions_left_idle_with_a_queue(pool: SqlitePool) {
+        let service = QueuedMessageService::new(DBService { pool: pool.clone() });
+
+        let idle = create_session(&pool).await;
+        create_process(
+            &pool,
+            idle,
+            ExecutionProcessRunReason::CodingAgent,
+            ExecutionProcessStatus::Completed,
+        )
+        .await;
+        // A dev server does not keep the queue from being sent
+        create_process(
+            &pool,
+            idle,
+            ExecutionProcessRunReason::DevServer,
+            ExecutionProcessStatus::Running,
+        )
+        .await;
+
+        let failed = create_session(&pool).await;
+        create_process(
+            &pool,
+            failed,
+            ExecutionProcessRunReason::CodingAgent,
+            ExecutionProcessStatus::Failed,
+        )
+        .await;
+
+        let busy = create_session(&pool).await;
+        create_process(
+            &pool,
+            busy,
+            ExecutionProcessRunReason::CodingAgent,
+            ExecutionProcessStatus::Completed,
+        )
+        .await;
+        create_process(
+            &pool,
+            busy,
+            ExecutionProcessRunReason::CleanupScript,
+            ExecutionProcessStatus::Running,
+        )
+        .await;
+
+        let never_ran = create_session(&pool).await;
+        let empty = create_session(&pool).await;
+        create_process(
+            &pool,
+            empty,
+            ExecutionProcessRunReason::CodingAgent,
+            ExecutionProcessStatus::Completed,
+        )
+        .await;
+
+        for session_id in [idle, failed, busy, never_ran] {
+            service
+                .queue_message(session_id, follow_up("next"))
+                .await
+                .unwrap();
+        }
+
+        let sessions: Vec<Uuid> = service
+            .find_idle_sessions()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|ctx| ctx.session.id)
+            .collect();
+        assert_eq!(sessions, [idle]);
     }
 }
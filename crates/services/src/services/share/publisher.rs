@@ -3,6 +3,7 @@ use db::{
     models::{
         project::Project,
         task::{CreateTask, Task, TaskStatus},
+        task_event::TaskEventActor,
     },
 };
 use remote::routes::tasks::{
@@ -144,7 +145,13 @@ impl SharePublisher {
         );
 
         let id = Uuid::new_v4();
-        let task = Task::create(&self.db.pool, &create_task, id).await?;
+        let task = Task::create(
+            &self.db.pool,
+            &create_task,
+            id,
+            TaskEventActor::SharedTaskSync,
+        )
+        .await?;
 
         Ok(Some(task))
     }
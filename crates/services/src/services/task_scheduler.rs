@@ -0,0 +1,157 @@
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use db::{
+    DBService,
+    models::{
+        task::{CreateTask, Task},
+        task_event::TaskEventActor,
+        task_schedule::{TaskSchedule, TaskScheduleRun},
+    },
+};
+use sqlx::error::Error as SqlxError;
+use tokio::time::interval;
+use tracing::{debug, error, info, warn};
+use utils::cron::CronSchedule;
+use uuid::Uuid;
+
+use crate::services::container::ContainerService;
+
+/// Service that fires due task schedules: each firing creates a task from the schedule's
+/// template and starts a workspace for it
+pub struct TaskSchedulerService<C: ContainerService> {
+    db: DBService,
+    container: C,
+    poll_interval: Duration,
+}
+
+impl<C: ContainerService> TaskSchedulerService<C> {
+    pub async fn spawn(db: DBService, container: C) -> tokio::task::JoinHandle<()> {
+        let service = Self {
+            db,
+            container,
+            poll_interval: Duration::from_secs(30), // Cron has minute resolution
+        };
+        tokio::spawn(async move {
+            service.start().await;
+        })
+    }
+
+    async fn start(&self) {
+        info!(
+            "Starting task scheduler service with interval {:?}",
+            self.poll_interval
+        );
+
+        let mut interval = interval(self.poll_interval);
+
+        loop {
+            interval.tick().await;
+            if let Err(e) = self.run_due_schedules().await {
+                error!("Error running due task schedules: {}", e);
+            }
+        }
+    }
+
+    async fn run_due_schedules(&self) -> Result<(), SqlxError> {
+        let now = Utc::now();
+        let due = TaskSchedule::find_due(&self.db.pool, now).await?;
+
+        if due.is_empty() {
+            debug!("No task schedules due");
+            return Ok(());
+        }
+
+        info!("Running {} due task schedules", due.len());
+
+        for schedule in due {
+            if let Err(e) = self.fire(&schedule, now).await {
+                error!("Error firing task schedule {}: {}", schedule.id, e);
+            }
+        }
+        Ok(())
+    }
+
+    /// Create and start one task for `schedule`, recording the outcome as a run
+    async fn fire(&self, schedule: &TaskSchedule, now: DateTime<Utc>) -> Result<(), SqlxError> {
+        let pool = &self.db.pool;
+        let scheduled_for = schedule.next_run_at.unwrap_or(now);
+
+        // Advance before starting so a slow start can't fire the same slot twice.
+        // Slots missed while the server was down are skipped rather than replayed.
+        let next_run_at = match CronSchedule::parse(&schedule.cron_expression) {
+            Ok(cron) => cron.next_after(now),
+            Err(e) => {
+                warn!(
+                    "Task schedule {} will not fire again, invalid cron expression: {}",
+                    schedule.id, e
+                );
+                None
+            }
+        };
+        TaskSchedule::advance(pool, schedule.id, now, next_run_at).await?;
+
+        let task = match Task::create(
+            pool,
+            &CreateTask::from_title_description(
+                schedule.project_id,
+                schedule.title.clone(),
+                schedule.description.clone(),
+            ),
+            Uuid::new_v4(),
+            TaskEventActor::Scheduler,
+        )
+        .await
+        {
+            Ok(task) => task,
+            Err(e) => {
+                TaskScheduleRun::create(
+                    pool,
+                    schedule.id,
+                    scheduled_for,
+                    None,
+                    None,
+                    Some(e.to_string()),
+                )
+                .await?;
+                return Err(e);
+            }
+        };
+
+        let (workspace_id, error) = match self
+            .container
+            .create_and_start_workspace(
+                &task,
+                schedule.executor_profile_id.0.clone(),
+                &schedule.repos,
+            )
+            .await
+        {
+            Ok(workspace) => {
+                info!(
+                    "Task schedule {} started task {} in workspace {}",
+                    schedule.id, task.id, workspace.id
+                );
+                (Some(workspace.id), None)
+            }
+            Err(e) => {
+                error!(
+                    "Task schedule {} failed to start task {}: {}",
+                    schedule.id, task.id, e
+                );
+                (None, Some(e.to_string()))
+            }
+        };
+
+        TaskScheduleRun::create(
+            pool,
+            schedule.id,
+            scheduled_for,
+            Some(task.id),
+            workspace_id,
+            error,
+        )
+        .await?;
+        Ok(())
+    }
+}
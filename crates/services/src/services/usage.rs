@@ -0,0 +1,272 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use db::models::token_usage::{TokenUsageFilter, TurnTokenUsage};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::services::config::ModelTokenPrice;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum UsageGroupBy {
+    #[default]
+    Task,
+    Workspace,
+    Project,
+    /// UTC day the turn started
+    Day,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UsageQuery {
+    #[serde(default)]
+    pub group_by: UsageGroupBy,
+    pub project_id: Option<Uuid>,
+    pub task_id: Option<Uuid>,
+    pub workspace_id: Option<Uuid>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+}
+
+impl UsageQuery {
+    pub fn filter(&self) -> TokenUsageFilter {
+        TokenUsageFilter {
+            project_id: self.project_id,
+            task_id: self.task_id,
+            workspace_id: self.workspace_id,
+            since: self.since,
+            until: self.until,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, TS)]
+pub struct UsageTotals {
+    pub turns: u64,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub cache_write_tokens: u64,
+    /// Estimated cost of the turns whose model has a configured price
+    pub cost_usd: Option<f64>,
+    /// Turns left out of `cost_usd` because no price matches their model
+    pub unpriced_turns: u64,
+}
+
+impl UsageTotals {
+    fn add(&mut self, turn: &TurnTokenUsage, price: Option<&ModelTokenPrice>) {
+        self.turns += 1;
+        self.input_tokens += turn.input_tokens.max(0) as u64;
+        self.output_tokens += turn.output_tokens.max(0) as u64;
+        self.cache_read_tokens += turn.cache_read_tokens.max(0) as u64;
+        self.cache_write_tokens += turn.cache_write_tokens.max(0) as u64;
+        match price {
+            Some(price) => *self.cost_usd.get_or_insert(0.0) += turn_cost(turn, price),
+            None => self.unpriced_turns += 1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct ModelUsage {
+    pub model: Option<String>,
+    pub usage: UsageTotals,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct UsageGroup {
+    /// Task, workspace or project id, or the day as `YYYY-MM-DD`
+    pub key: String,
+    /// Task title, workspace branch, project name or day
+    pub label: String,
+    pub usage: UsageTotals,
+    pub models: Vec<ModelUsage>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct UsageReport {
+    pub group_by: UsageGroupBy,
+    pub total: UsageTotals,
+    /// In order of their first turn
+    pub groups: Vec<UsageGroup>,
+}
+
+/// The longest configured model name that is a prefix of `model`
+pub fn price_for<'a>(
+    model: Option<&str>,
+    prices: &'a [ModelTokenPrice],
+) -> Option<&'a ModelTokenPrice> {
+    let model = model?;
+    prices
+        .iter()
+        .filter(|price| !price.model.is_empty() && model.starts_with(&price.model))
+        .max_by_key(|price| price.model.len())
+}
+
+fn turn_cost(turn: &TurnTokenUsage, price: &ModelTokenPrice) -> f64 {
+    let per_token = |tokens: i64, per_million: f64| tokens.max(0) as f64 * per_million / 1e6;
+    per_token(turn.input_tokens, price.input)
+        + per_token(turn.output_tokens, price.output)
+        + per_token(
+            turn.cache_read_tokens,
+            price.cache_read.unwrap_or(price.input),
+        )
+        + per_token(
+            turn.cache_write_tokens,
+            price.cache_write.unwrap_or(price.input),
+        )
+}
+
+pub fn build_report(
+    turns: &[TurnTokenUsage],
+    group_by: UsageGroupBy,
+    prices: &[ModelTokenPrice],
+) -> UsageReport {
+    let mut total = UsageTotals::default();
+    let mut groups: Vec<UsageGroup> = Vec::new();
+    let mut group_index: HashMap<String, usize> = HashMap::new();
+
+    for turn in turns {
+        let price = price_for(turn.model.as_deref(), prices);
+        total.add(turn, price);
+
+        let (key, label) = match group_by {
+            UsageGroupBy::Task => (turn.task_id.to_string(), turn.task_title.clone()),
+            UsageGroupBy::Workspace => {
+                (turn.workspace_id.to_string(), turn.workspace_branch.clone())
+            }
+            UsageGroupBy::Project => (turn.project_id.to_string(), turn.project_name.clone()),
+            UsageGroupBy::Day => {
+                let day = turn.created_at.format("%Y-%m-%d").to_string();
+                (day.clone(), day)
+            }
+        };
+        let index = *group_index.entry(key.clone()).or_insert_with(|| {
+            groups.push(UsageGroup {
+                key,
+                label,
+                usage: UsageTotals::default(),
+                models: Vec::new(),
+            });
+            groups.len() - 1
+        });
+
+        let group = &mut groups[index];
+        group.usage.add(turn, price);
+        match group
+            .models
+            .iter_mut()
+            .find(|usage| usage.model == turn.model)
+        {
+            Some(model_usage) => model_usage.usage.add(turn, price),
+            None => {
+                let mut usage = UsageTotals::default();
+                usage.add(turn, price);
+                group.models.push(ModelUsage {
+                    model: turn.model.clone(),
+                    usage,
+                });
+            }
+        }
+    }
+
+    UsageReport {
+        group_by,
+        total,
+        groups,
+    }
+}
+
+pub async fn usage_report(
+    pool: &SqlitePool,
+    query: &UsageQuery,
+    prices: &[ModelTokenPrice],
+) -> Result<UsageReport, sqlx::Error> {
+    let turns = TurnTokenUsage::find(pool, &query.filter()).await?;
+    Ok(build_report(&turns, query.group_by, prices))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn price(model: &str, input: f64, output: f64) -> ModelTokenPrice {
+        ModelTokenPrice {
+            model: model.to_string(),
+            input,
+            output,
+            cache_read: None,
+            cache_write: None,
+        }
+    }
+
+    fn turn(task_id: Uuid, model: Option<&str>, day: &str) -> TurnTokenUsage {
+        TurnTokenUsage {
+            execution_process_id: Uuid::new_v4(),
+            task_id,
+            task_title: format!("task {task_id}"),
+            workspace_id: Uuid::new_v4(),
+            workspace_branch: "vk/demo".to_string(),
+            project_id: Uuid::nil(),
+            project_name: "demo".to_string(),
+            model: model.map(str::to_string),
+            input_tokens: 1_000_000,
+            output_tokens: 100_000,
+            cache_read_tokens: 2_000_000,
+            cache_write_tokens: 0,
+            created_at: format!("{day}T12:00:00Z").parse().unwrap(),
+        }
+    }
+
+    #[test]
+    fn longest_matching_prefix_sets_the_price() {
+        let prices = [
+            price("claude", 1.0, 1.0),
+            price("claude-opus-4", 15.0, 75.0),
+        ];
+        assert_eq!(
+            price_for(Some("claude-opus-4-1-20250805"), &prices).map(|p| p.input),
+            Some(15.0)
+        );
+        assert_eq!(
+            price_for(Some("claude-sonnet-4-5"), &prices).map(|p| p.input),
+            Some(1.0)
+        );
+        assert!(price_for(Some("gpt-5.2"), &prices).is_none());
+        assert!(price_for(None, &prices).is_none());
+    }
+
+    #[test]
+    fn groups_turns_and_prices_known_models() {
+        let prices = [ModelTokenPrice {
+            cache_read: Some(0.3),
+            ..price("claude-sonnet-4-5", 3.0, 15.0)
+        }];
+        let (task_a, task_b) = (Uuid::new_v4(), Uuid::new_v4());
+        let turns = [
+            turn(task_a, Some("claude-sonnet-4-5-20250929"), "2026-01-05"),
+            turn(task_b, Some("gpt-5.2"), "2026-01-05"),
+            turn(task_a, Some("claude-sonnet-4-5-20250929"), "2026-01-06"),
+        ];
+
+        let report = build_report(&turns, UsageGroupBy::Task, &prices);
+        assert_eq!(report.groups.len(), 2);
+        let group_a = &report.groups[0];
+        assert_eq!(group_a.key, task_a.to_string());
+        assert_eq!(group_a.usage.turns, 2);
+        // 1M input at $3, 100k output at $15, 2M cache reads at $0.30, twice
+        assert!((group_a.usage.cost_usd.unwrap() - 2.0 * (3.0 + 1.5 + 0.6)).abs() < 1e-9);
+        assert_eq!(report.groups[1].usage.cost_usd, None);
+        assert_eq!(report.groups[1].usage.unpriced_turns, 1);
+        assert_eq!(report.total.turns, 3);
+        assert_eq!(report.total.unpriced_turns, 1);
+
+        let report = build_report(&turns, UsageGroupBy::Day, &prices);
+        let days: Vec<_> = report.groups.iter().map(|g| g.key.as_str()).collect();
+        assert_eq!(days, ["2026-01-05", "2026-01-06"]);
+        assert_eq!(report.groups[0].models.len(), 2);
+    }
+}
@@ -0,0 +1,341 @@
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use db::{
+    DBService,
+    models::{
+        execution_process::ExecutionProcess,
+        task::Task,
+        webhook::{Webhook, WebhookDelivery, WebhookEvent, WebhookEventType},
+        workspace::Workspace,
+    },
+};
+use hmac::{Hmac, Mac};
+use serde_json::{Map, Value, json};
+use sha2::Sha256;
+use sqlx::error::Error as SqlxError;
+use tokio::time::{Instant, interval};
+use tracing::{debug, error, info, warn};
+use utils::text::truncate_to_char_boundary;
+use uuid::Uuid;
+
+type HmacSha256 = Hmac<Sha256>;
+
+const EVENT_BATCH_SIZE: i64 = 100;
+const DELIVERY_BATCH_SIZE: i64 = 20;
+/// Attempts per delivery, including the first one
+const MAX_ATTEMPTS: i64 = 6;
+const FIRST_RETRY_DELAY: chrono::Duration = chrono::Duration::seconds(30);
+const DELIVERY_LOG_RETENTION: chrono::Duration = chrono::Duration::days(30);
+const PRUNE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+const MAX_ERROR_LENGTH: usize = 500;
+
+/// HMAC-SHA256 of the request body in the `sha256=<hex>` form of the
+/// `X-Vibe-Kanban-Signature` header
+pub fn signature(secret: &str, body: &str) -> String {
+    let mut mac =
+        HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(body.as_bytes());
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Delay before the next attempt after `attempts` failed ones, or `None` once the
+/// delivery is out of retries. Backs off from 30s by a factor of four (up to ~2h).
+fn retry_delay(attempts: i64) -> Option<chrono::Duration> {
+    (attempts < MAX_ATTEMPTS).then(|| FIRST_RETRY_DELAY * 4_i32.pow(attempts as u32 - 1))
+}
+
+fn request_body(
+    delivery_id: Uuid,
+    event_type: WebhookEventType,
+    created_at: DateTime<Utc>,
+    data: Value,
+) -> String {
+    json!({
+        "id": delivery_id,
+        "event": event_type,
+        "created_at": created_at,
+        "data": data,
+    })
+    .to_string()
+}
+
+/// Service that turns raised webhook events into deliveries and POSTs them, retrying
+/// failed deliveries with exponential backoff
+pub struct WebhookService {
+    db: DBService,
+    client: reqwest::Client,
+    poll_interval: Duration,
+}
+
+impl WebhookService {
+    pub async fn spawn(db: DBService) -> tokio::task::JoinHandle<()> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .user_agent(concat!("vibe-kanban-webhooks/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .unwrap();
+        let service = Self {
+            db,
+            client,
+            poll_interval: Duration::from_secs(5),
+        };
+        tokio::spawn(async move {
+            service.start().await;
+        })
+    }
+
+    /// Queue a ping delivery to `webhook` so its endpoint and secret can be checked
+    pub async fn queue_ping(
+        db: &DBService,
+        webhook: &Webhook,
+    ) -> Result<WebhookDelivery, SqlxError> {
+        let delivery_id = Uuid::new_v4();
+        let body = request_body(
+            delivery_id,
+            WebhookEventType::Ping,
+            Utc::now(),
+            json!({ "webhook_id": webhook.id }),
+        );
+        WebhookDelivery::create(
+            &db.pool,
+            delivery_id,
+            webhook.id,
+            WebhookEventType::Ping,
+            &body,
+        )
+        .await
+    }
+
+    async fn start(&self) {
+        info!(
+            "Starting webhook service with interval {:?}",
+            self.poll_interval
+        );
+
+        let mut interval = interval(self.poll_interval);
+        let mut last_pruned: Option<Instant> = None;
+
+        loop {
+            interval.tick().await;
+            if let Err(e) = self.fan_out_events().await {
+                error!("Error fanning out webhook events: {}", e);
+            }
+            if let Err(e) = self.deliver_due().await {
+                error!("Error delivering webhooks: {}", e);
+            }
+
+            if last_pruned.is_none_or(|at| at.elapsed() >= PRUNE_INTERVAL) {
+                last_pruned = Some(Instant::now());
+                match WebhookDelivery::delete_finished_before(
+                    &self.db.pool,
+                    Utc::now() - DELIVERY_LOG_RETENTION,
+                )
+                .await
+                {
+                    Ok(0) => {}
+                    Ok(deleted) => info!("Pruned {} old webhook deliveries", deleted),
+                    Err(e) => error!("Error pruning webhook deliveries: {}", e),
+                }
+            }
+        }
+    }
+
+    async fn fan_out_events(&self) -> Result<(), SqlxError> {
+        let pool = &self.db.pool;
+        let events = WebhookEvent::find_pending(pool, EVENT_BATCH_SIZE).await?;
+        if events.is_empty() {
+            return Ok(());
+        }
+
+        let webhooks = Webhook::find_enabled(pool).await?;
+        for event in events {
+            let subscribers: Vec<&Webhook> = webhooks
+                .iter()
+                .filter(|webhook| webhook.subscribes_to(event.event_type))
+                .collect();
+
+            if !subscribers.is_empty() {
+                let data = self.event_data(&event).await?;
+                for webhook in subscribers {
+                    let delivery_id = Uuid::new_v4();
+                    let body = request_body(
+                        delivery_id,
+                        event.event_type,
+                        event.created_at,
+                        data.clone(),
+                    );
+                    WebhookDelivery::create(pool, delivery_id, webhook.id, event.event_type, &body)
+                        .await?;
+                }
+            }
+
+            WebhookEvent::delete(pool, event.id).await?;
+        }
+        Ok(())
+    }
+
+    /// Identifiers of the task, workspace and execution process an event is about, merged
+    /// with the event's own payload. Subjects deleted in the meantime are left out.
+    async fn event_data(&self, event: &WebhookEvent) -> Result<Value, SqlxError> {
+        let pool = &self.db.pool;
+        let mut data = Map::new();
+
+        match event.event_type {
+            WebhookEventType::TaskStatusChanged => {
+                if let Some(task) = Task::find_by_id(pool, event.subject_id).await? {
+                    insert_task(&mut data, &task);
+                }
+            }
+            WebhookEventType::ExecutionCompleted
+            | WebhookEventType::ExecutionFailed
+            | WebhookEventType::ApprovalRequested => {
+                match ExecutionProcess::load_context(pool, event.subject_id).await {
+                    Ok(ctx) => {
+                        insert_task(&mut data, &ctx.task);
+                        insert_workspace(&mut data, &ctx.workspace);
+                        data.insert("session_id".into(), json!(ctx.session.id));
+                        data.insert(
+                            "execution_process_id".into(),
+                            json!(ctx.execution_process.id),
+                        );
+                    }
+                    Err(SqlxError::RowNotFound) => {}
+                    Err(e) => return Err(e),
+                }
+            }
+            WebhookEventType::PrMerged => {
+                if let Some(workspace) = Workspace::find_by_id(pool, event.subject_id).await? {
+                    if let Some(task) = Task::find_by_id(pool, workspace.task_id).await? {
+                        insert_task(&mut data, &task);
+                    }
+                    insert_workspace(&mut data, &workspace);
+                }
+            }
+            WebhookEventType::Ping => {}
+        }
+
+        if let Value::Object(payload) = &event.payload.0 {
+            data.extend(payload.clone());
+        }
+        Ok(Value::Object(data))
+    }
+
+    async fn deliver_due(&self) -> Result<(), SqlxError> {
+        let pool = &self.db.pool;
+        let due = WebhookDelivery::find_due(pool, Utc::now(), DELIVERY_BATCH_SIZE).await?;
+
+        for delivery in due {
+            let webhook = match Webhook::find_by_id(pool, delivery.webhook_id).await? {
+                Some(webhook) if webhook.enabled => webhook,
+                // Deleted webhooks take their deliveries with them, so this is a
+                // webhook that was disabled while the delivery was pending
+                _ => {
+                    WebhookDelivery::record_failure(
+                        pool,
+                        delivery.id,
+                        None,
+                        "Webhook was disabled",
+                        None,
+                    )
+                    .await?;
+                    continue;
+                }
+            };
+
+            match self.send(&webhook, &delivery).await {
+                Ok(status) => {
+                    debug!(
+                        "Delivered {} webhook {} to {}",
+                        delivery.event_type, delivery.id, webhook.url
+                    );
+                    WebhookDelivery::mark_delivered(pool, delivery.id, status).await?;
+                }
+                Err((status, error)) => {
+                    let retry_at =
+                        retry_delay(delivery.attempts + 1).map(|delay| Utc::now() + delay);
+                    if retry_at.is_none() {
+                        warn!(
+                            "Giving up on webhook delivery {} to {} after {} attempts: {}",
+                            delivery.id, webhook.url, MAX_ATTEMPTS, error
+                        );
+                    }
+                    WebhookDelivery::record_failure(pool, delivery.id, status, &error, retry_at)
+                        .await?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// POST a delivery. Returns the response status on success, or the status (if any
+    /// response arrived) and an error description on failure.
+    async fn send(
+        &self,
+        webhook: &Webhook,
+        delivery: &WebhookDelivery,
+    ) -> Result<i64, (Option<i64>, String)> {
+        let response = self
+            .client
+            .post(&webhook.url)
+            .header("Content-Type", "application/json")
+            .header("X-Vibe-Kanban-Event", delivery.event_type.to_string())
+            .header("X-Vibe-Kanban-Delivery", delivery.id.to_string())
+            .header(
+                "X-Vibe-Kanban-Signature",
+                signature(&webhook.secret, &delivery.payload),
+            )
+            .body(delivery.payload.clone())
+            .send()
+            .await
+            .map_err(|e| (None, e.to_string()))?;
+
+        let status = response.status();
+        if status.is_success() {
+            return Ok(status.as_u16() as i64);
+        }
+
+        let body = response.text().await.unwrap_or_default();
+        let error = if body.is_empty() {
+            format!("HTTP {status}")
+        } else {
+            format!(
+                "HTTP {status}: {}",
+                truncate_to_char_boundary(&body, MAX_ERROR_LENGTH)
+            )
+        };
+        Err((Some(status.as_u16() as i64), error))
+    }
+}
+
+fn insert_task(data: &mut Map<String, Value>, task: &Task) {
+    data.insert("project_id".into(), json!(task.project_id));
+    data.insert("task_id".into(), json!(task.id));
+    data.insert("task_title".into(), json!(task.title));
+}
+
+fn insert_workspace(data: &mut Map<String, Value>, workspace: &Workspace) {
+    data.insert("workspace_id".into(), json!(workspace.id));
+    data.insert("branch".into(), json!(workspace.branch));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signature_is_hex_hmac_sha256_of_body() {
+        assert_eq!(
+            signature("key", "The quick brown fox jumps over the lazy dog"),
+            "sha256=f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8"
+        );
+    }
+
+    #[test]
+    fn retries_back_off_and_stop_after_max_attempts() {
+        assert_eq!(retry_delay(1), Some(chrono::Duration::seconds(30)));
+        assert_eq!(retry_delay(2), Some(chrono::Duration::minutes(2)));
+        assert_eq!(retry_delay(5), Some(chrono::Duration::seconds(30 * 256)));
+        assert_eq!(retry_delay(MAX_ATTEMPTS), None);
+    }
+}
@@ -0,0 +1,263 @@
+//! Stacks of workspaces, each created on top of its parent's branch and targeting it, so that
+//! the stack can be restacked and reviewed as a chain of PRs.
+
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+};
+
+use db::models::{task::Task, workspace::Workspace};
+use serde::Serialize;
+use sqlx::SqlitePool;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::git::{GitService, GitServiceError};
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct StackedWorkspace {
+    #[serde(flatten)]
+    #[ts(flatten)]
+    pub workspace: Workspace,
+    pub task_title: String,
+    /// 0 for the root of the stack
+    pub depth: u32,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct WorkspaceStack {
+    pub root_workspace_id: Uuid,
+    /// Every workspace in the stack, each parent before its children
+    pub workspaces: Vec<StackedWorkspace>,
+}
+
+#[derive(Debug, Serialize, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[ts(tag = "type", rename_all = "snake_case")]
+pub enum RestackOutcome {
+    Rebased,
+    /// The rebase stopped; the workspace is left mid-rebase for the conflicts to be resolved
+    MergeConflicts {
+        message: String,
+    },
+    RebaseInProgress,
+    Failed {
+        message: String,
+    },
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct RestackStep {
+    pub workspace_id: Uuid,
+    pub repo_id: Uuid,
+    pub repo_name: String,
+    /// Parent branch the workspace was rebased onto
+    pub onto_branch: String,
+    pub outcome: RestackOutcome,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct RestackResult {
+    pub steps: Vec<RestackStep>,
+    /// False if the restack stopped at a step that did not rebase cleanly; the workspaces
+    /// after it were left untouched
+    pub completed: bool,
+}
+
+/// A repository of a stacked workspace that targets its parent's branch
+#[derive(Debug, Clone)]
+pub struct RestackRepo {
+    pub repo_id: Uuid,
+    pub repo_name: String,
+    pub repo_path: PathBuf,
+    pub worktree_path: PathBuf,
+}
+
+/// A stacked workspace to rebase onto its parent's branch
+#[derive(Debug, Clone)]
+pub struct RestackChild {
+    pub workspace_id: Uuid,
+    pub branch: String,
+    pub onto_branch: String,
+    pub repos: Vec<RestackRepo>,
+}
+
+/// Rebase each child onto its parent's branch in the order given, which must put parents
+/// first. Stops at the first rebase that does not succeed.
+pub fn restack(git: &GitService, children: &[RestackChild]) -> RestackResult {
+    let mut steps = Vec::new();
+    for child in children {
+        for repo in &child.repos {
+            // The parent's branch is both bases: the fork point from its reflog finds where the
+            // child branched off even after the parent itself was rebased
+            let outcome = match git.rebase_branch(
+                &repo.repo_path,
+                &repo.worktree_path,
+                &child.onto_branch,
+                &child.onto_branch,
+                &child.branch,
+            ) {
+                Ok(_) => RestackOutcome::Rebased,
+                Err(GitServiceError::MergeConflicts(message)) => {
+                    RestackOutcome::MergeConflicts { message }
+                }
+                Err(GitServiceError::RebaseInProgress) => RestackOutcome::RebaseInProgress,
+                Err(e) => RestackOutcome::Failed {
+                    message: e.to_string(),
+                },
+            };
+            let rebased = matches!(outcome, RestackOutcome::Rebased);
+            steps.push(RestackStep {
+                workspace_id: child.workspace_id,
+                repo_id: repo.repo_id,
+                repo_name: repo.repo_name.clone(),
+                onto_branch: child.onto_branch.clone(),
+                outcome,
+            });
+            if !rebased {
+                return RestackResult {
+                    steps,
+                    completed: false,
+                };
+            }
+        }
+    }
+    RestackResult {
+        steps,
+        completed: true,
+    }
+}
+
+/// Depth-first order of `root` and its descendants, each parent before its children and
+/// siblings in the order given. Workspaces already visited are skipped, so a corrupt cycle
+/// cannot loop forever.
+fn stack_order(
+    root: Workspace,
+    children: &mut HashMap<Uuid, Vec<Workspace>>,
+) -> Vec<(Workspace, u32)> {
+    let mut ordered = Vec::new();
+    let mut visited = HashSet::new();
+    let mut pending = vec![(root, 0)];
+    while let Some((workspace, depth)) = pending.pop() {
+        if !visited.insert(workspace.id) {
+            continue;
+        }
+        if let Some(kids) = children.remove(&workspace.id) {
+            // Reversed so the first child is popped first
+            pending.extend(kids.into_iter().rev().map(|kid| (kid, depth + 1)));
+        }
+        ordered.push((workspace, depth));
+    }
+    ordered
+}
+
+/// The bottom of the stack `workspace` belongs to
+pub async fn find_stack_root(
+    pool: &SqlitePool,
+    workspace: &Workspace,
+) -> Result<Workspace, sqlx::Error> {
+    let mut current = workspace.clone();
+    let mut visited = HashSet::from([current.id]);
+    while let Some(parent_id) = current.stack_parent_id {
+        if !visited.insert(parent_id) {
+            break;
+        }
+        match Workspace::find_by_id(pool, parent_id).await? {
+            Some(parent) => current = parent,
+            None => break,
+        }
+    }
+    Ok(current)
+}
+
+/// `workspace` followed by everything stacked on it, each parent before its children, with
+/// depths relative to `workspace`
+pub async fn stack_from(
+    pool: &SqlitePool,
+    workspace: &Workspace,
+) -> Result<Vec<(Workspace, u32)>, sqlx::Error> {
+    let mut children = HashMap::new();
+    let mut queue = vec![workspace.id];
+    while let Some(id) = queue.pop() {
+        if children.contains_key(&id) {
+            continue;
+        }
+        let kids = Workspace::find_stack_children(pool, id).await?;
+        queue.extend(kids.iter().map(|kid| kid.id));
+        children.insert(id, kids);
+    }
+    Ok(stack_order(workspace.clone(), &mut children))
+}
+
+/// The whole stack `workspace` belongs to, from its root
+pub async fn load_stack(
+    pool: &SqlitePool,
+    workspace: &Workspace,
+) -> Result<WorkspaceStack, sqlx::Error> {
+    let root = find_stack_root(pool, workspace).await?;
+    let mut workspaces = Vec::new();
+    for (workspace, depth) in stack_from(pool, &root).await? {
+        let task_title = Task::find_by_id(pool, workspace.task_id)
+            .await?
+            .map(|task| task.title)
+            .unwrap_or_default();
+        workspaces.push(StackedWorkspace {
+            workspace,
+            task_title,
+            depth,
+        });
+    }
+    Ok(WorkspaceStack {
+        root_workspace_id: root.id,
+        workspaces,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+
+    fn workspace(branch: &str, stack_parent_id: Option<Uuid>) -> Workspace {
+        Workspace {
+            id: Uuid::new_v4(),
+            task_id: Uuid::new_v4(),
+            container_ref: None,
+            branch: branch.to_string(),
+            agent_working_dir: None,
+            stack_parent_id,
+            setup_completed_at: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn orders_parents_before_children_depth_first() {
+        let root = workspace("root", None);
+        let a = workspace("a", Some(root.id));
+        let b = workspace("b", Some(root.id));
+        let a1 = workspace("a1", Some(a.id));
+        let mut children = HashMap::from([
+            (root.id, vec![a.clone(), b.clone()]),
+            (a.id, vec![a1.clone()]),
+            // Cycle back to the root is ignored
+            (a1.id, vec![root.clone()]),
+        ]);
+
+        let order: Vec<(String, u32)> = stack_order(root, &mut children)
+            .into_iter()
+            .map(|(w, depth)| (w.branch, depth))
+            .collect();
+        assert_eq!(
+            order,
+            vec![
+                ("root".to_string(), 0),
+                ("a".to_string(), 1),
+                ("a1".to_string(), 2),
+                ("b".to_string(), 1),
+            ]
+        );
+    }
+}
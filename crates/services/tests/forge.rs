@@ -0,0 +1,156 @@
+use db::models::merge::MergeStatus;
+use services::services::{
+    config::{ForgeHostConfig, ForgeKind},
+    forge::{ForgeError, ForgeService},
+    github::CreatePrRequest,
+};
+
+fn local_gitea(token: Option<&str>) -> ForgeHostConfig {
+    ForgeHostConfig {
+        host: "localhost:3000".to_string(),
+        kind: ForgeKind::Gitea,
+        base_url: None,
+        token: token.map(str::to_string),
+    }
+}
+
+#[test]
+fn resolves_github_remotes() {
+    let forge = ForgeService::new(&[]);
+    for url in [
+        "https://github.com/BloopAI/vibe-kanban.git",
+        "git@github.com:BloopAI/vibe-kanban.git",
+        "ssh://git@github.com/BloopAI/vibe-kanban",
+        "https://github.com/BloopAI/vibe-kanban/pull/42",
+    ] {
+        let repo = forge.repo_info(url).unwrap();
+        assert_eq!(repo.kind, ForgeKind::GitHub, "{url}");
+        assert_eq!(repo.path(), "BloopAI/vibe-kanban", "{url}");
+    }
+}
+
+#[test]
+fn resolves_gitlab_nested_groups_and_merge_request_urls() {
+    let forge = ForgeService::new(&[]);
+
+    let repo = forge
+        .repo_info("git@gitlab.com:acme/platform/backend/api.git")
+        .unwrap();
+    assert_eq!(repo.kind, ForgeKind::GitLab);
+    assert_eq!(repo.owner, "acme/platform/backend");
+    assert_eq!(repo.repo_name, "api");
+    assert_eq!(repo.base_url, "https://gitlab.com");
+
+    let repo = forge
+        .repo_info("https://gitlab.com/acme/platform/backend/api/-/merge_requests/7")
+        .unwrap();
+    assert_eq!(repo.path(), "acme/platform/backend/api");
+}
+
+#[test]
+fn configured_hosts_take_precedence() {
+    let forge = ForgeService::new(&[
+        local_gitea(None),
+        ForgeHostConfig {
+            host: "git.example.com".to_string(),
+            kind: ForgeKind::GitLab,
+            base_url: Some("https://git.example.com/gitlab/".to_string()),
+            token: None,
+        },
+    ]);
+
+    let repo = forge
+        .repo_info("http://localhost:3000/vk/demo/pulls/3")
+        .unwrap();
+    assert_eq!(repo.kind, ForgeKind::Gitea);
+    assert_eq!(repo.host, "localhost:3000");
+    assert_eq!(repo.base_url, "http://localhost:3000");
+    assert_eq!(repo.path(), "vk/demo");
+
+    // SSH ports are not part of the configured host
+    let repo = forge
+        .repo_info("ssh://git@git.example.com:2222/team/service.git")
+        .unwrap();
+    assert_eq!(repo.kind, ForgeKind::GitLab);
+    assert_eq!(repo.base_url, "https://git.example.com/gitlab");
+    assert_eq!(repo.path(), "team/service");
+}
+
+#[test]
+fn rejects_unknown_hosts_and_missing_tokens() {
+    let forge = ForgeService::new(&[local_gitea(None)]);
+
+    assert!(matches!(
+        forge.repo_info("https://git.internal.example/team/repo.git"),
+        Err(ForgeError::UnsupportedRemote(_))
+    ));
+    assert!(matches!(
+        forge.repo_info("https://github.com/only-owner"),
+        Err(ForgeError::UnsupportedRemote(_))
+    ));
+
+    let forge = ForgeService::new(&[local_gitea(Some(" "))]);
+    let repo = forge
+        .repo_info("http://localhost:3000/vk/demo.git")
+        .unwrap();
+    // A blank configured token falls back to the environment
+    if std::env::var("GITEA_TOKEN").is_err() {
+        assert!(matches!(
+            forge.provider(&repo),
+            Err(ForgeError::MissingToken { .. })
+        ));
+    }
+}
+
+/// Runs against a local Gitea container, e.g.
+/// `docker run -p 3000:3000 gitea/gitea`, with a user, an access token and a
+/// throwaway repository whose `GITEA_TEST_BRANCH` branch is ahead of `main`:
+///
+/// `GITEA_TEST_TOKEN=... GITEA_TEST_REPO=owner/repo GITEA_TEST_BRANCH=feature
+/// cargo test -p services --test forge -- --ignored`
+#[tokio::test]
+#[ignore = "needs a local Gitea instance"]
+async fn gitea_pull_request_round_trip() {
+    let token = std::env::var("GITEA_TEST_TOKEN").expect("GITEA_TEST_TOKEN");
+    let repo_path = std::env::var("GITEA_TEST_REPO").expect("GITEA_TEST_REPO");
+    let branch = std::env::var("GITEA_TEST_BRANCH").expect("GITEA_TEST_BRANCH");
+    let base_url =
+        std::env::var("GITEA_TEST_URL").unwrap_or_else(|_| "http://localhost:3000".to_string());
+
+    let forge = ForgeService::new(&[local_gitea(Some(&token))]);
+    let (repo, provider) = forge
+        .for_url(&format!("{base_url}/{repo_path}.git"))
+        .unwrap();
+    provider.check_auth().await.unwrap();
+
+    let pr = match provider.list_prs_for_branch(&repo, &branch).await.unwrap() {
+        prs if prs
+            .first()
+            .is_some_and(|pr| matches!(pr.status, MergeStatus::Open)) =>
+        {
+            prs.into_iter().next().unwrap()
+        }
+        _ => provider
+            .create_pr(
+                &repo,
+                &CreatePrRequest {
+                    title: "Forge round trip".to_string(),
+                    body: Some("Created by the forge integration test".to_string()),
+                    head_branch: branch.clone(),
+                    base_branch: "main".to_string(),
+                    draft: Some(false),
+                },
+            )
+            .await
+            .unwrap(),
+    };
+    assert!(matches!(pr.status, MergeStatus::Open));
+
+    // The PR URL resolves back to the same repository
+    let (from_pr_url, _) = forge.for_url(&pr.url).unwrap();
+    assert_eq!(from_pr_url, repo);
+
+    let fetched = provider.get_pr(&repo, pr.number).await.unwrap();
+    assert_eq!(fetched.url, pr.url);
+    provider.get_pr_comments(&repo, pr.number).await.unwrap();
+}
@@ -5,8 +5,13 @@ use std::{
 };
 
 use git2::{PushOptions, Repository, build::CheckoutBuilder};
-use services::services::git::{GitCli, GitCliError, GitService};
+use services::services::{
+    conflict_resolution,
+    git::{ConflictOp, GitCli, GitCliError, GitService, GitServiceError},
+    workspace_stack::{self, RestackChild, RestackOutcome, RestackRepo},
+};
 use tempfile::TempDir;
+use uuid::Uuid;
 // Avoid direct git CLI usage in tests; exercise GitService instead.
 
 fn write_file<P: AsRef<Path>>(base: P, rel: &str, content: &str) {
@@ -1406,3 +1411,185 @@ fn merge_base_ahead_of_task_should_error() {
         "Merge should error when base branch is ahead of task branch"
     );
 }
+
+fn start_conflicting_rebase(repo_path: &Path, worktree_path: &Path) {
+    let service = GitService::new();
+    let res = service.rebase_branch(repo_path, worktree_path, "new-base", "old-base", "feature");
+    assert!(matches!(res, Err(GitServiceError::MergeConflicts(_))));
+    assert_eq!(
+        service.detect_conflict_op(worktree_path).unwrap(),
+        Some(ConflictOp::Rebase)
+    );
+}
+
+#[test]
+fn conflict_commit_subjects_lists_both_sides() {
+    let td = TempDir::new().unwrap();
+    let (repo_path, worktree_path) = setup_conflict_repo_with_worktree(&td);
+    start_conflicting_rebase(&repo_path, &worktree_path);
+
+    let service = GitService::new();
+    let files = service.get_conflicted_files(&worktree_path).unwrap();
+    assert_eq!(files, vec!["conflict.txt".to_string()]);
+    let (head, incoming) = service
+        .conflict_commit_subjects(&worktree_path, &ConflictOp::Rebase, &files)
+        .unwrap();
+    // During a rebase HEAD is the new base, and the incoming side is the branch being replayed
+    assert_eq!(head, vec!["new-base change".to_string()]);
+    assert_eq!(incoming[0], "feature conflicting change");
+}
+
+#[test]
+fn continue_conflicts_finishes_rebase_once_resolved() {
+    let td = TempDir::new().unwrap();
+    let (repo_path, worktree_path) = setup_conflict_repo_with_worktree(&td);
+    start_conflicting_rebase(&repo_path, &worktree_path);
+
+    write_file(&worktree_path, "conflict.txt", "resolved version\n");
+    let service = GitService::new();
+    service
+        .continue_conflicts(&worktree_path, &ConflictOp::Rebase)
+        .expect("rebase should continue");
+
+    assert_eq!(service.detect_conflict_op(&worktree_path).unwrap(), None);
+    assert_eq!(
+        fs::read_to_string(worktree_path.join("conflict.txt")).unwrap(),
+        "resolved version\n"
+    );
+    let wt_repo = Repository::open(&worktree_path).unwrap();
+    let head = wt_repo.head().unwrap().peel_to_commit().unwrap();
+    assert_eq!(head.summary(), Some("feature conflicting change"));
+    assert_eq!(
+        head.parent_id(0).unwrap().to_string(),
+        service.get_branch_oid(&repo_path, "new-base").unwrap()
+    );
+}
+
+#[test]
+fn continue_conflicts_stops_at_the_next_conflicting_commit() {
+    let td = TempDir::new().unwrap();
+    let (repo_path, worktree_path) = setup_conflict_repo_with_worktree(&td);
+    let wt_repo = Repository::open(&worktree_path).unwrap();
+    write_file(&worktree_path, "conflict.txt", "feature second version\n");
+    commit_all(&wt_repo, "feature second change");
+    start_conflicting_rebase(&repo_path, &worktree_path);
+
+    write_file(&worktree_path, "conflict.txt", "resolved version\n");
+    let service = GitService::new();
+    let res = service.continue_conflicts(&worktree_path, &ConflictOp::Rebase);
+    assert!(matches!(res, Err(GitServiceError::MergeConflicts(_))));
+    assert_eq!(
+        service.detect_conflict_op(&worktree_path).unwrap(),
+        Some(ConflictOp::Rebase)
+    );
+    assert_eq!(
+        service.get_conflicted_files(&worktree_path).unwrap(),
+        vec!["conflict.txt".to_string()]
+    );
+}
+
+#[test]
+fn abort_conflicts_restores_the_branch_when_markers_remain() {
+    let td = TempDir::new().unwrap();
+    let (repo_path, worktree_path) = setup_conflict_repo_with_worktree(&td);
+    let service = GitService::new();
+    let before = service.get_head_info(&worktree_path).unwrap().oid;
+    start_conflicting_rebase(&repo_path, &worktree_path);
+
+    let files = service.get_conflicted_files(&worktree_path).unwrap();
+    assert_eq!(
+        conflict_resolution::files_with_conflict_markers(&worktree_path, &files),
+        files
+    );
+    service.abort_conflicts(&worktree_path).unwrap();
+
+    assert_eq!(service.detect_conflict_op(&worktree_path).unwrap(), None);
+    assert_eq!(service.get_head_info(&worktree_path).unwrap().oid, before);
+    assert_eq!(
+        fs::read_to_string(worktree_path.join("conflict.txt")).unwrap(),
+        "feature version\n"
+    );
+}
+
+#[test]
+fn restack_stops_at_first_conflict_and_leaves_later_children_untouched() {
+    let td = TempDir::new().unwrap();
+    let repo_path = td.path().join("repo");
+    let child1_path = td.path().join("wt-child1");
+    let child2_path = td.path().join("wt-child2");
+
+    let service = GitService::new();
+    service
+        .initialize_repo_with_main_branch(&repo_path)
+        .expect("init repo");
+    let repo = Repository::open(&repo_path).unwrap();
+    configure_user(&repo);
+    checkout_branch(&repo, "main");
+    write_file(&repo_path, "shared.txt", "base\n");
+    commit_all(&repo, "initial main commit");
+    create_branch_from_head(&repo, "parent");
+    create_branch_from_head(&repo, "child1");
+
+    service
+        .add_worktree(&repo_path, &child1_path, "child1", false)
+        .expect("create child1 worktree");
+    let child1_repo = Repository::open(&child1_path).unwrap();
+    write_file(&child1_path, "shared.txt", "child1 version\n");
+    commit_all(&child1_repo, "child1 change");
+    create_branch_from_head(&child1_repo, "child2");
+
+    service
+        .add_worktree(&repo_path, &child2_path, "child2", false)
+        .expect("create child2 worktree");
+    let child2_repo = Repository::open(&child2_path).unwrap();
+    write_file(&child2_path, "child2.txt", "child2\n");
+    commit_all(&child2_repo, "child2 change");
+
+    // The parent moves on with a change that conflicts with child1
+    checkout_branch(&repo, "parent");
+    write_file(&repo_path, "shared.txt", "parent version\n");
+    commit_all(&repo, "parent change");
+
+    let child2_before = service.get_branch_oid(&repo_path, "child2").unwrap();
+    let repo_id = Uuid::new_v4();
+    let restack_repo = |worktree_path: &Path| RestackRepo {
+        repo_id,
+        repo_name: "repo".to_string(),
+        repo_path: repo_path.clone(),
+        worktree_path: worktree_path.to_path_buf(),
+    };
+    let child1_id = Uuid::new_v4();
+    let children = vec![
+        RestackChild {
+            workspace_id: child1_id,
+            branch: "child1".to_string(),
+            onto_branch: "parent".to_string(),
+            repos: vec![restack_repo(&child1_path)],
+        },
+        RestackChild {
+            workspace_id: Uuid::new_v4(),
+            branch: "child2".to_string(),
+            onto_branch: "child1".to_string(),
+            repos: vec![restack_repo(&child2_path)],
+        },
+    ];
+
+    let result = workspace_stack::restack(&service, &children);
+    assert!(!result.completed);
+    assert_eq!(result.steps.len(), 1);
+    assert_eq!(result.steps[0].workspace_id, child1_id);
+    assert!(matches!(
+        result.steps[0].outcome,
+        RestackOutcome::MergeConflicts { .. }
+    ));
+    // child1 is left mid-rebase for the conflicts to be resolved; child2 was never touched
+    assert_eq!(
+        service.detect_conflict_op(&child1_path).unwrap(),
+        Some(ConflictOp::Rebase)
+    );
+    assert_eq!(
+        service.get_branch_oid(&repo_path, "child2").unwrap(),
+        child2_before
+    );
+    assert_eq!(service.detect_conflict_op(&child2_path).unwrap(), None);
+}
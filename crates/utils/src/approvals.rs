@@ -58,3 +58,12 @@ pub struct ApprovalResponse {
     pub execution_process_id: Uuid,
     pub status: ApprovalStatus,
 }
+
+/// The approval rule that decided a tool call without asking the user
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct MatchedApprovalRule {
+    pub rule_id: Uuid,
+    /// The rule's description, or its patterns when it has none
+    pub summary: String,
+}
@@ -0,0 +1,306 @@
+//! Five-field cron expressions (`minute hour day-of-month month day-of-week`), evaluated in UTC.
+//!
+//! Supports `*`, lists (`1,15`), ranges (`1-5`), steps (`*/15`, `0-30/10`), three-letter
+//! month and weekday names, and the `@hourly`, `@daily`, `@weekly`, `@monthly` and `@yearly`
+//! shorthands. As in classic cron, when both day fields are restricted a day matches if
+//! either of them does.
+
+use std::{fmt, str::FromStr};
+
+use chrono::{DateTime, Datelike, Duration, TimeZone, Timelike, Utc};
+use thiserror::Error;
+
+const MONTH_NAMES: &[&str] = &[
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+const WEEKDAY_NAMES: &[&str] = &["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+
+/// How far ahead `next_after` searches before deciding an expression never fires
+const SEARCH_HORIZON_DAYS: i64 = 366 * 5;
+
+#[derive(Debug, Clone, Error, PartialEq, Eq)]
+#[error("Invalid cron expression '{expression}': {reason}")]
+pub struct CronError {
+    expression: String,
+    reason: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSchedule {
+    expression: String,
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    days_of_week: u64,
+    any_day_of_month: bool,
+    any_day_of_week: bool,
+}
+
+impl CronSchedule {
+    pub fn parse(expression: &str) -> Result<Self, CronError> {
+        let error = |reason: String| CronError {
+            expression: expression.to_string(),
+            reason,
+        };
+
+        let trimmed = expression.trim();
+        let expanded = match trimmed.to_ascii_lowercase().as_str() {
+            "@yearly" | "@annually" => "0 0 1 1 *".to_string(),
+            "@monthly" => "0 0 1 * *".to_string(),
+            "@weekly" => "0 0 * * 0".to_string(),
+            "@daily" | "@midnight" => "0 0 * * *".to_string(),
+            "@hourly" => "0 * * * *".to_string(),
+            _ => trimmed.to_string(),
+        };
+
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        let [minute, hour, day_of_month, month, day_of_week] = fields[..] else {
+            return Err(error(format!(
+                "expected 5 fields (minute hour day-of-month month day-of-week), got {}",
+                fields.len()
+            )));
+        };
+
+        let mut days_of_week = parse_field(day_of_week, 0, 7, WEEKDAY_NAMES, 0).map_err(error)?;
+        // Both 0 and 7 mean Sunday
+        if days_of_week & (1 << 7) != 0 {
+            days_of_week = (days_of_week & !(1 << 7)) | 1;
+        }
+
+        Ok(Self {
+            expression: trimmed.to_string(),
+            minutes: parse_field(minute, 0, 59, &[], 0).map_err(error)?,
+            hours: parse_field(hour, 0, 23, &[], 0).map_err(error)?,
+            days_of_month: parse_field(day_of_month, 1, 31, &[], 0).map_err(error)?,
+            months: parse_field(month, 1, 12, MONTH_NAMES, 1).map_err(error)?,
+            days_of_week,
+            any_day_of_month: day_of_month.starts_with('*'),
+            any_day_of_week: day_of_week.starts_with('*'),
+        })
+    }
+
+    pub fn expression(&self) -> &str {
+        &self.expression
+    }
+
+    /// The first matching minute strictly after `after`, or `None` if the expression
+    /// cannot fire (e.g. `0 0 30 2 *`).
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let mut t = after.with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        let horizon = t + Duration::days(SEARCH_HORIZON_DAYS);
+
+        while t <= horizon {
+            if !has_bit(self.months, t.month()) {
+                let (year, month) = if t.month() == 12 {
+                    (t.year() + 1, 1)
+                } else {
+                    (t.year(), t.month() + 1)
+                };
+                t = Utc.with_ymd_and_hms(year, month, 1, 0, 0, 0).single()?;
+                continue;
+            }
+            if !self.day_matches(&t) {
+                t = (t.date_naive() + Duration::days(1))
+                    .and_hms_opt(0, 0, 0)?
+                    .and_utc();
+                continue;
+            }
+            if !has_bit(self.hours, t.hour()) {
+                t = t.with_minute(0)? + Duration::hours(1);
+                continue;
+            }
+            if !has_bit(self.minutes, t.minute()) {
+                t += Duration::minutes(1);
+                continue;
+            }
+            return Some(t);
+        }
+
+        None
+    }
+
+    fn day_matches(&self, t: &DateTime<Utc>) -> bool {
+        let day_of_month = has_bit(self.days_of_month, t.day());
+        let day_of_week = has_bit(self.days_of_week, t.weekday().num_days_from_sunday());
+        if self.any_day_of_month || self.any_day_of_week {
+            day_of_month && day_of_week
+        } else {
+            day_of_month || day_of_week
+        }
+    }
+}
+
+impl FromStr for CronSchedule {
+    type Err = CronError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl fmt::Display for CronSchedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.expression)
+    }
+}
+
+fn has_bit(bits: u64, value: u32) -> bool {
+    bits & (1 << value) != 0
+}
+
+/// Parse one field into a bitset of allowed values. `names[i]` is an alias for
+/// `i + name_base`.
+fn parse_field(
+    spec: &str,
+    min: u32,
+    max: u32,
+    names: &[&str],
+    name_base: u32,
+) -> Result<u64, String> {
+    let mut bits = 0u64;
+    for part in spec.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step = step
+                    .parse::<u32>()
+                    .ok()
+                    .filter(|step| *step > 0)
+                    .ok_or_else(|| format!("invalid step '{step}'"))?;
+                (range, Some(step))
+            }
+            None => (part, None),
+        };
+
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            (
+                parse_value(start, min, max, names, name_base)?,
+                parse_value(end, min, max, names, name_base)?,
+            )
+        } else {
+            let value = parse_value(range, min, max, names, name_base)?;
+            // `5/15` means "from 5, every 15"
+            (value, if step.is_some() { max } else { value })
+        };
+        if start > end {
+            return Err(format!("invalid range '{range}'"));
+        }
+
+        for value in (start..=end).step_by(step.unwrap_or(1) as usize) {
+            bits |= 1 << value;
+        }
+    }
+    Ok(bits)
+}
+
+fn parse_value(
+    value: &str,
+    min: u32,
+    max: u32,
+    names: &[&str],
+    name_base: u32,
+) -> Result<u32, String> {
+    let parsed = match names
+        .iter()
+        .position(|name| name.eq_ignore_ascii_case(value))
+    {
+        Some(index) => index as u32 + name_base,
+        None => value
+            .parse::<u32>()
+            .map_err(|_| format!("invalid value '{value}'"))?,
+    };
+    if parsed < min || parsed > max {
+        return Err(format!("value {parsed} is outside {min}-{max}"));
+    }
+    Ok(parsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    fn next(expression: &str, after: &str) -> Option<DateTime<Utc>> {
+        CronSchedule::parse(expression)
+            .unwrap()
+            .next_after(at(after))
+    }
+
+    #[test]
+    fn steps_fire_strictly_after_the_given_time() {
+        assert_eq!(
+            next("*/15 * * * *", "2025-01-01T10:07:30Z"),
+            Some(at("2025-01-01T10:15:00Z"))
+        );
+        assert_eq!(
+            next("*/15 * * * *", "2025-01-01T10:15:00Z"),
+            Some(at("2025-01-01T10:30:00Z"))
+        );
+    }
+
+    #[test]
+    fn daily_rolls_over_to_the_next_day() {
+        assert_eq!(
+            next("30 2 * * *", "2025-01-01T03:00:00Z"),
+            Some(at("2025-01-02T02:30:00Z"))
+        );
+        assert_eq!(
+            next("@daily", "2025-12-31T12:00:00Z"),
+            Some(at("2026-01-01T00:00:00Z"))
+        );
+    }
+
+    #[test]
+    fn weekday_names_and_sunday_aliases() {
+        // 2025-01-04 is a Saturday
+        assert_eq!(
+            next("0 9 * * mon-fri", "2025-01-04T10:00:00Z"),
+            Some(at("2025-01-06T09:00:00Z"))
+        );
+        assert_eq!(
+            next("0 9 * * 7", "2025-01-04T10:00:00Z"),
+            Some(at("2025-01-05T09:00:00Z"))
+        );
+        assert_eq!(
+            next("0 0 1 jan *", "2025-03-01T00:00:00Z"),
+            Some(at("2026-01-01T00:00:00Z"))
+        );
+    }
+
+    #[test]
+    fn restricted_day_fields_match_either() {
+        // The 13th or any Friday; 2025-01-03 is a Friday
+        assert_eq!(
+            next("0 0 13 * fri", "2025-01-01T00:00:00Z"),
+            Some(at("2025-01-03T00:00:00Z"))
+        );
+    }
+
+    #[test]
+    fn impossible_dates_never_fire() {
+        assert_eq!(next("0 0 30 2 *", "2025-01-01T00:00:00Z"), None);
+    }
+
+    #[test]
+    fn rejects_malformed_expressions() {
+        for expression in [
+            "",
+            "* * * *",
+            "60 * * * *",
+            "*/0 * * * *",
+            "5-1 * * * *",
+            "* * * foo *",
+            "* * 0 * *",
+        ] {
+            assert!(
+                CronSchedule::parse(expression).is_err(),
+                "{expression:?} should be rejected"
+            );
+        }
+    }
+}
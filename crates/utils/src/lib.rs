@@ -6,6 +6,7 @@ pub mod api;
 pub mod approvals;
 pub mod assets;
 pub mod browser;
+pub mod cron;
 pub mod diff;
 pub mod git;
 pub mod jwt;
@@ -21,6 +22,14 @@ pub mod text;
 pub mod tokio;
 pub mod version;
 
+/// Set to run the server headless: every API request needs a token and no browser is opened
+pub const HEADLESS_ENV: &str = "VK_HEADLESS";
+
+/// Whether the server runs in headless mode, see [`HEADLESS_ENV`]
+pub fn is_headless() -> bool {
+    env::var(HEADLESS_ENV).is_ok_and(|value| !matches!(value.trim(), "" | "0" | "false"))
+}
+
 /// Cache for WSL2 detection result
 static WSL2_CACHE: OnceLock<bool> = OnceLock::new();
 
@@ -1,19 +1,52 @@
 use axum::{extract::ws::Message, response::sse::Event};
 use json_patch::Patch;
 use serde::{Deserialize, Serialize};
+use ts_rs::TS;
 
 pub const EV_STDOUT: &str = "stdout";
 pub const EV_STDERR: &str = "stderr";
 pub const EV_JSON_PATCH: &str = "json_patch";
 pub const EV_SESSION_ID: &str = "session_id";
+pub const EV_TOKEN_USAGE: &str = "token_usage";
 pub const EV_FINISHED: &str = "finished";
 
+/// Tokens a coding agent reports having used so far in an execution, as a running total.
+/// Input tokens exclude tokens read from or written to the prompt cache.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+pub struct TokenUsage {
+    pub model: Option<String>,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub cache_write_tokens: u64,
+}
+
+impl TokenUsage {
+    pub fn add(&mut self, other: &TokenUsage) {
+        if other.model.is_some() {
+            self.model = other.model.clone();
+        }
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+        self.cache_read_tokens += other.cache_read_tokens;
+        self.cache_write_tokens += other.cache_write_tokens;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.input_tokens == 0
+            && self.output_tokens == 0
+            && self.cache_read_tokens == 0
+            && self.cache_write_tokens == 0
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum LogMsg {
     Stdout(String),
     Stderr(String),
     JsonPatch(Patch),
     SessionId(String),
+    TokenUsage(TokenUsage),
     Finished,
 }
 
@@ -24,6 +57,7 @@ impl LogMsg {
             LogMsg::Stderr(_) => EV_STDERR,
             LogMsg::JsonPatch(_) => EV_JSON_PATCH,
             LogMsg::SessionId(_) => EV_SESSION_ID,
+            LogMsg::TokenUsage(_) => EV_TOKEN_USAGE,
             LogMsg::Finished => EV_FINISHED,
         }
     }
@@ -37,6 +71,10 @@ impl LogMsg {
                 Event::default().event(EV_JSON_PATCH).data(data)
             }
             LogMsg::SessionId(s) => Event::default().event(EV_SESSION_ID).data(s.clone()),
+            LogMsg::TokenUsage(usage) => {
+                let data = serde_json::to_string(usage).unwrap_or_else(|_| "{}".to_string());
+                Event::default().event(EV_TOKEN_USAGE).data(data)
+            }
             LogMsg::Finished => Event::default().event(EV_FINISHED).data(""),
         }
     }
@@ -73,6 +111,9 @@ impl LogMsg {
                 EV_JSON_PATCH.len() + json_len + OVERHEAD
             }
             LogMsg::SessionId(s) => EV_SESSION_ID.len() + s.len() + OVERHEAD,
+            LogMsg::TokenUsage(usage) => {
+                EV_TOKEN_USAGE.len() + usage.model.as_ref().map_or(0, String::len) + 32 + OVERHEAD
+            }
             LogMsg::Finished => EV_FINISHED.len() + OVERHEAD,
         }
     }
@@ -705,6 +705,10 @@ function DisplayConversationEntry({
       toolEntry.action_type.action === 'plan_presentation';
     const isPendingApproval = status.status === 'pending_approval';
     const defaultExpanded = isPendingApproval || isPlanPresentation;
+    const decidingRule =
+      status.status === 'auto_approved' || status.status === 'denied'
+        ? status.rule
+        : undefined;
 
     const body = (() => {
       if (isFileEdit(toolEntry.action_type)) {
@@ -753,6 +757,16 @@ function DisplayConversationEntry({
         className={`px-4 py-2 text-sm space-y-3 ${greyed ? 'opacity-50 pointer-events-none' : ''}`}
       >
         {body}
+        {decidingRule && (
+          <div className="text-xs text-muted-foreground">
+            {t(
+              status.status === 'auto_approved'
+                ? 'conversation.approvalRule.autoApproved'
+                : 'conversation.approvalRule.denied',
+              { rule: decidingRule.summary }
+            )}
+          </div>
+        )}
       </div>
     );
 
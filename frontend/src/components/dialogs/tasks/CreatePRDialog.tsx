@@ -206,6 +206,37 @@ const CreatePRDialogImpl = NiceModal.create<CreatePRDialogProps>(
           );
           setGhCliHelp(null);
           return;
+        } else if (result.error.type === 'forge_not_authenticated') {
+          setError(
+            t('createPrDialog.errors.forgeNotAuthenticated', {
+              host: result.error.host,
+            })
+          );
+          setGhCliHelp(null);
+          return;
+        } else if (result.error.type === 'unsupported_remote') {
+          setError(
+            t('createPrDialog.errors.unsupportedRemote', {
+              remoteUrl: result.error.remote_url,
+            })
+          );
+          setGhCliHelp(null);
+          return;
+        } else if (
+          result.error.type === 'quality_gate_running' ||
+          result.error.type === 'quality_gate_failed' ||
+          result.error.type === 'quality_gate_uncommitted_changes'
+        ) {
+          const qualityGateErrorKey = {
+            quality_gate_running: 'createPrDialog.errors.qualityGateRunning',
+            quality_gate_failed: 'createPrDialog.errors.qualityGateFailed',
+            quality_gate_uncommitted_changes:
+              'createPrDialog.errors.qualityGateUncommittedChanges',
+          }[result.error.type];
+
+          setError(t(qualityGateErrorKey, { repo: result.error.repo_name }));
+          setGhCliHelp(null);
+          return;
         }
       }
 
@@ -215,7 +215,9 @@ const GitHubCommentsDialogImpl = NiceModal.create<GitHubCommentsDialogProps>(
 function getErrorMessage(error: unknown): string {
   // Check if it's an API error with error_data
   if (error && typeof error === 'object' && 'error_data' in error) {
-    const errorData = (error as { error_data?: { type?: string } }).error_data;
+    const errorData = (
+      error as { error_data?: { type?: string; host?: string } }
+    ).error_data;
     if (errorData?.type === 'no_pr_attached') {
       return 'No PR is attached to this task attempt. Create a PR first to see comments.';
     }
@@ -225,6 +227,12 @@ function getErrorMessage(error: unknown): string {
     if (errorData?.type === 'github_cli_not_logged_in') {
       return 'GitHub CLI is not logged in. Please run "gh auth login" to authenticate.';
     }
+    if (errorData?.type === 'forge_not_authenticated') {
+      return `No valid API token for ${errorData.host}. Add one in the forge settings to fetch comments.`;
+    }
+    if (errorData?.type === 'unsupported_remote') {
+      return 'This repository is not hosted on a supported forge.';
+    }
   }
   return 'Failed to load PR comments. Please try again.';
 }
@@ -250,6 +250,11 @@ function ProcessesTab({ attemptId }: ProcessesTabProps) {
                           })}
                         </p>
                       )}
+                      {process.failure_reason && (
+                        <p className="text-xs text-destructive mt-1">
+                          {t(`processes.failureReason.${process.failure_reason}`)}
+                        </p>
+                      )}
                     </div>
                   </div>
                   <div className="mt-3 text-xs text-muted-foreground">
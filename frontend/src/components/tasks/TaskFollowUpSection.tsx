@@ -4,7 +4,6 @@ import {
   StopCircle,
   AlertCircle,
   Clock,
-  X,
   Paperclip,
   Terminal,
   MessageSquare,
@@ -42,6 +41,7 @@ import { useProject } from '@/contexts/ProjectContext';
 import { VariantSelector } from '@/components/tasks/VariantSelector';
 import { useAttemptBranch } from '@/hooks/useAttemptBranch';
 import { FollowUpConflictSection } from '@/components/tasks/follow-up/FollowUpConflictSection';
+import { QueuedMessagesList } from '@/components/tasks/follow-up/QueuedMessagesList';
 import { ClickedElementsBanner } from '@/components/tasks/ClickedElementsBanner';
 import WYSIWYGEditor from '@/components/ui/wysiwyg';
 import { useRetryUi } from '@/contexts/RetryUiContext';
@@ -259,10 +259,12 @@ export function TaskFollowUpSection({
 
   // Queue status for queuing follow-up messages while agent is running
   const {
-    isQueued,
-    queuedMessage,
+    queuedMessages,
     isLoading: isQueueLoading,
     queueMessage,
+    updateQueuedMessage,
+    removeQueuedMessage,
+    moveQueuedMessage,
     cancelQueue,
     refresh: refreshQueueStatus,
   } = useQueueStatus(sessionId);
@@ -298,10 +300,6 @@ export function TaskFollowUpSection({
     scratchData?.message,
   ]);
 
-  // When queued, display the queued message content so user can edit it
-  const displayMessage =
-    isQueued && queuedMessage ? queuedMessage.data.message : localMessage;
-
   // Check if there's a pending approval - users shouldn't be able to type during approvals
   const { entries } = useEntries();
   const hasPendingApproval = useMemo(() => {
@@ -341,7 +339,7 @@ export function TaskFollowUpSection({
 
     if (isRetryActive) return false; // disable typing while retry editor is active
     if (hasPendingApproval) return false; // disable typing during approval
-    // Note: isQueued no longer blocks typing - editing auto-cancels the queue
+    // Queued messages are edited in the queue list, so they never block typing
     return true;
   }, [
     workspaceId,
@@ -392,6 +390,15 @@ export function TaskFollowUpSection({
     }
   }, [workspaceId, isAttemptRunning]);
 
+  const handleRunQualityGates = useCallback(async () => {
+    if (!workspaceId || isAttemptRunning) return;
+    try {
+      await attemptsApi.runQualityGates(workspaceId, { repo_id: null });
+    } catch (error) {
+      console.error('Failed to run quality gates:', error);
+    }
+  }, [workspaceId, isAttemptRunning]);
+
   // Handler to queue the current message for execution after agent finishes
   const handleQueueMessage = useCallback(async () => {
     if (
@@ -417,6 +424,12 @@ export function TaskFollowUpSection({
     ].filter(Boolean);
     const combinedMessage = parts.join('\n\n');
     await queueMessage(combinedMessage, selectedVariant);
+
+    // The message now lives in the queue (the backend drops the draft), so start
+    // from an empty editor for the next instruction
+    setLocalMessage('');
+    clearComments();
+    clearClickedElements();
   }, [
     localMessage,
     conflictResolutionInstructions,
@@ -426,6 +439,8 @@ export function TaskFollowUpSection({
     queueMessage,
     cancelDebouncedSave,
     saveToScratch,
+    clearComments,
+    clearClickedElements,
   ]);
 
   // Keyboard shortcut handler - send follow-up or queue depending on state
@@ -433,15 +448,13 @@ export function TaskFollowUpSection({
     (e?: KeyboardEvent) => {
       e?.preventDefault();
       if (isAttemptRunning) {
-        // When running, CMD+Enter queues the message (if not already queued)
-        if (!isQueued) {
-          handleQueueMessage();
-        }
+        // When running, CMD+Enter appends the message to the queue
+        handleQueueMessage();
       } else {
         onSendFollowUp();
       }
     },
-    [isAttemptRunning, isQueued, handleQueueMessage, onSendFollowUp]
+    [isAttemptRunning, handleQueueMessage, onSendFollowUp]
   );
 
   // Ref to access setFollowUpMessage without adding it as a dependency
@@ -456,22 +469,6 @@ export function TaskFollowUpSection({
     followUpErrorRef.current = followUpError;
   }, [followUpError]);
 
-  // Refs for queue state to use in stable onChange handler
-  const isQueuedRef = useRef(isQueued);
-  useEffect(() => {
-    isQueuedRef.current = isQueued;
-  }, [isQueued]);
-
-  const cancelQueueRef = useRef(cancelQueue);
-  useEffect(() => {
-    cancelQueueRef.current = cancelQueue;
-  }, [cancelQueue]);
-
-  const queuedMessageRef = useRef(queuedMessage);
-  useEffect(() => {
-    queuedMessageRef.current = queuedMessage;
-  }, [queuedMessage]);
-
   // Handle image paste - upload to container and insert markdown
   const handlePasteFiles = useCallback(
     async (files: File[]) => {
@@ -483,24 +480,13 @@ export function TaskFollowUpSection({
           // Append markdown image to current message
           const imageMarkdown = `![${response.original_name}](${response.file_path})`;
 
-          // If queued, cancel queue and use queued message as base (same as editor change behavior)
-          if (isQueuedRef.current && queuedMessageRef.current) {
-            cancelQueueRef.current();
-            const base = queuedMessageRef.current.data.message;
-            const newMessage = base
-              ? `${base}\n\n${imageMarkdown}`
+          setLocalMessage((prev) => {
+            const newMessage = prev
+              ? `${prev}\n\n${imageMarkdown}`
               : imageMarkdown;
-            setLocalMessage(newMessage);
-            setFollowUpMessageRef.current(newMessage);
-          } else {
-            setLocalMessage((prev) => {
-              const newMessage = prev
-                ? `${prev}\n\n${imageMarkdown}`
-                : imageMarkdown;
-              setFollowUpMessageRef.current(newMessage); // Debounced save to scratch
-              return newMessage;
-            });
-          }
+            setFollowUpMessageRef.current(newMessage); // Debounced save to scratch
+            return newMessage;
+          });
         } catch (error) {
           console.error('Failed to upload image:', error);
         }
@@ -564,29 +550,17 @@ export function TaskFollowUpSection({
       const markdown = markdownBlocks.join('\n\n');
 
       // Same pattern as image paste
-      if (isQueuedRef.current && queuedMessageRef.current) {
-        cancelQueueRef.current();
-        const base = queuedMessageRef.current.data.message;
-        const newMessage = base ? `${base}\n\n${markdown}` : markdown;
-        setLocalMessage(newMessage);
+      setLocalMessage((prev) => {
+        const newMessage = prev ? `${prev}\n\n${markdown}` : markdown;
         setFollowUpMessageRef.current(newMessage);
-      } else {
-        setLocalMessage((prev) => {
-          const newMessage = prev ? `${prev}\n\n${markdown}` : markdown;
-          setFollowUpMessageRef.current(newMessage);
-          return newMessage;
-        });
-      }
+        return newMessage;
+      });
     }
   }, [workspaceId, getSelectedRepoId]);
 
   // Stable onChange handler for WYSIWYGEditor
   const handleEditorChange = useCallback(
     (value: string) => {
-      // Auto-cancel queue when user starts editing
-      if (isQueuedRef.current) {
-        cancelQueueRef.current();
-      }
       setLocalMessage(value); // Immediate update for UI responsiveness
       setFollowUpMessageRef.current(value); // Debounced save to scratch
       if (followUpErrorRef.current) setFollowUpError(null);
@@ -707,18 +681,15 @@ export function TaskFollowUpSection({
             {/* Clicked elements notice and actions */}
             <ClickedElementsBanner />
 
-            {/* Queued message indicator */}
-            {isQueued && queuedMessage && (
-              <div className="flex items-center gap-2 text-sm text-muted-foreground bg-muted p-3 rounded-md border">
-                <Clock className="h-4 w-4 flex-shrink-0" />
-                <div className="font-medium">
-                  {t(
-                    'followUp.queuedMessage',
-                    'Message queued - will execute when current run finishes'
-                  )}
-                </div>
-              </div>
-            )}
+            {/* Queued follow-ups, sent in order as each run finishes */}
+            <QueuedMessagesList
+              messages={queuedMessages}
+              disabled={isQueueLoading}
+              onUpdate={updateQueuedMessage}
+              onRemove={removeQueuedMessage}
+              onMove={moveQueuedMessage}
+              onClear={cancelQueue}
+            />
 
             <div
               className="flex flex-col gap-2"
@@ -823,51 +794,36 @@ export function TaskFollowUpSection({
                 <DropdownMenuItem onClick={handleRunCleanupScript}>
                   {t('followUp.runCleanupScript')}
                 </DropdownMenuItem>
+                <DropdownMenuItem onClick={handleRunQualityGates}>
+                  {t('followUp.runQualityGates')}
+                </DropdownMenuItem>
               </DropdownMenuContent>
             </DropdownMenu>
           )}
 
           {isAttemptRunning ? (
             <div className="flex items-center gap-2">
-              {/* Queue/Cancel Queue button when running */}
-              {isQueued ? (
-                <Button
-                  onClick={cancelQueue}
-                  disabled={isQueueLoading}
-                  size="sm"
-                  variant="outline"
-                >
-                  {isQueueLoading ? (
-                    <Loader2 className="animate-spin h-4 w-4 mr-2" />
-                  ) : (
-                    <>
-                      <X className="h-4 w-4 mr-2" />
-                      {t('followUp.cancelQueue', 'Cancel Queue')}
-                    </>
-                  )}
-                </Button>
-              ) : (
-                <Button
-                  onClick={handleQueueMessage}
-                  disabled={
-                    isQueueLoading ||
-                    (!localMessage.trim() &&
-                      !conflictResolutionInstructions &&
-                      !reviewMarkdown &&
-                      !clickedMarkdown)
-                  }
-                  size="sm"
-                >
-                  {isQueueLoading ? (
-                    <Loader2 className="animate-spin h-4 w-4 mr-2" />
-                  ) : (
-                    <>
-                      <Clock className="h-4 w-4 mr-2" />
-                      {t('followUp.queue', 'Queue')}
-                    </>
-                  )}
-                </Button>
-              )}
+              {/* Queue button when running; appends to the queue */}
+              <Button
+                onClick={handleQueueMessage}
+                disabled={
+                  isQueueLoading ||
+                  (!localMessage.trim() &&
+                    !conflictResolutionInstructions &&
+                    !reviewMarkdown &&
+                    !clickedMarkdown)
+                }
+                size="sm"
+              >
+                {isQueueLoading ? (
+                  <Loader2 className="animate-spin h-4 w-4 mr-2" />
+                ) : (
+                  <>
+                    <Clock className="h-4 w-4 mr-2" />
+                    {t('followUp.queue', 'Queue')}
+                  </>
+                )}
+              </Button>
               <Button
                 onClick={stopExecution}
                 disabled={isStopping}
@@ -0,0 +1,154 @@
+import { useState } from 'react';
+import {
+  ArrowDown,
+  ArrowUp,
+  Check,
+  Clock,
+  Pencil,
+  Trash2,
+  X,
+} from 'lucide-react';
+import { useTranslation } from 'react-i18next';
+import { Button } from '@/components/ui/button';
+import { Textarea } from '@/components/ui/textarea';
+import type { QueuedMessage } from 'shared/types';
+
+type Props = {
+  messages: QueuedMessage[];
+  disabled: boolean;
+  onUpdate: (
+    messageId: string,
+    message: string,
+    variant: string | null
+  ) => Promise<void>;
+  onRemove: (messageId: string) => Promise<void>;
+  onMove: (messageId: string, offset: -1 | 1) => Promise<void>;
+  onClear: () => Promise<void>;
+};
+
+export function QueuedMessagesList({
+  messages,
+  disabled,
+  onUpdate,
+  onRemove,
+  onMove,
+  onClear,
+}: Props) {
+  const { t } = useTranslation('tasks');
+  const [editingId, setEditingId] = useState<string | null>(null);
+  const [draft, setDraft] = useState('');
+
+  if (messages.length === 0) return null;
+
+  const saveEdit = async (message: QueuedMessage) => {
+    if (!draft.trim()) return;
+    await onUpdate(message.id, draft, message.data.variant ?? null);
+    setEditingId(null);
+  };
+
+  return (
+    <div className="flex flex-col gap-2 text-sm bg-muted p-3 rounded-md border">
+      <div className="flex items-center gap-2 text-muted-foreground">
+        <Clock className="h-4 w-4 flex-shrink-0" />
+        <div className="font-medium flex-1">
+          {t('followUp.queuedMessages', {
+            count: messages.length,
+            defaultValue:
+              '{{count}} queued - sent one at a time as each run finishes',
+          })}
+        </div>
+        <Button size="xs" variant="ghost" onClick={onClear} disabled={disabled}>
+          <X className="h-3 w-3 mr-1" />
+          {t('followUp.cancelQueue', 'Cancel Queue')}
+        </Button>
+      </div>
+      <ol className="flex flex-col gap-1">
+        {messages.map((message, index) => (
+          <li
+            key={message.id}
+            className="flex items-start gap-2 bg-background rounded border p-2"
+          >
+            <span className="text-xs text-muted-foreground pt-0.5">
+              {index + 1}.
+            </span>
+            {editingId === message.id ? (
+              <div className="flex-1 flex flex-col gap-1">
+                <Textarea
+                  value={draft}
+                  onChange={(e) => setDraft(e.target.value)}
+                  className="min-h-[60px]"
+                  autoFocus
+                />
+                <div className="flex justify-end gap-1">
+                  <Button
+                    size="xs"
+                    variant="ghost"
+                    onClick={() => setEditingId(null)}
+                    aria-label={t('followUp.cancelEdit', 'Cancel edit')}
+                  >
+                    <X className="h-3 w-3" />
+                  </Button>
+                  <Button
+                    size="xs"
+                    onClick={() => saveEdit(message)}
+                    disabled={disabled || !draft.trim()}
+                    aria-label={t('followUp.saveQueuedMessage', 'Save')}
+                  >
+                    <Check className="h-3 w-3" />
+                  </Button>
+                </div>
+              </div>
+            ) : (
+              <>
+                <div className="flex-1 min-w-0 whitespace-pre-wrap break-words line-clamp-3">
+                  {message.data.message}
+                </div>
+                <div className="flex gap-0.5 flex-shrink-0">
+                  <Button
+                    size="xs"
+                    variant="ghost"
+                    onClick={() => onMove(message.id, -1)}
+                    disabled={disabled || index === 0}
+                    aria-label={t('followUp.moveUp', 'Move up')}
+                  >
+                    <ArrowUp className="h-3 w-3" />
+                  </Button>
+                  <Button
+                    size="xs"
+                    variant="ghost"
+                    onClick={() => onMove(message.id, 1)}
+                    disabled={disabled || index === messages.length - 1}
+                    aria-label={t('followUp.moveDown', 'Move down')}
+                  >
+                    <ArrowDown className="h-3 w-3" />
+                  </Button>
+                  <Button
+                    size="xs"
+                    variant="ghost"
+                    onClick={() => {
+                      setDraft(message.data.message);
+                      setEditingId(message.id);
+                    }}
+                    disabled={disabled}
+                    aria-label={t('followUp.editQueuedMessage', 'Edit')}
+                  >
+                    <Pencil className="h-3 w-3" />
+                  </Button>
+                  <Button
+                    size="xs"
+                    variant="ghost"
+                    onClick={() => onRemove(message.id)}
+                    disabled={disabled}
+                    aria-label={t('followUp.removeQueuedMessage', 'Remove')}
+                  >
+                    <Trash2 className="h-3 w-3" />
+                  </Button>
+                </div>
+              </>
+            )}
+          </li>
+        ))}
+      </ol>
+    </div>
+  );
+}
@@ -118,6 +118,7 @@ export const useConversationHistory = ({
       (ep) =>
         ep.run_reason === 'setupscript' ||
         ep.run_reason === 'cleanupscript' ||
+        ep.run_reason === 'qualitygate' ||
         ep.run_reason === 'codingagent'
     );
   }, [executionProcessesRaw]);
@@ -327,6 +328,9 @@ export const useConversationHistory = ({
               case 'ToolInstallScript':
                 toolName = 'Tool Install Script';
                 break;
+              case 'QualityGate':
+                toolName = 'Quality Gate';
+                break;
               default:
                 return [];
             }
@@ -23,7 +23,8 @@ export function useGitOperations(
         const data = err?.error;
         const isConflict =
           data?.type === 'merge_conflicts' ||
-          data?.type === 'rebase_in_progress';
+          data?.type === 'rebase_in_progress' ||
+          data?.type === 'conflict_resolution_started';
         if (!isConflict) {
           setError(err.message || 'Failed to rebase');
         }
@@ -5,15 +5,25 @@ import type { QueueStatus, QueuedMessage } from 'shared/types';
 interface UseQueueStatusResult {
   /** Current queue status */
   queueStatus: QueueStatus;
-  /** Whether a message is currently queued */
+  /** Whether any message is currently queued */
   isQueued: boolean;
-  /** The queued message if any */
-  queuedMessage: QueuedMessage | null;
+  /** Queued messages in the order they will be sent */
+  queuedMessages: QueuedMessage[];
   /** Whether an operation is in progress */
   isLoading: boolean;
-  /** Queue a new message */
+  /** Append a message to the queue */
   queueMessage: (message: string, variant: string | null) => Promise<void>;
-  /** Cancel the queued message */
+  /** Edit a queued message in place */
+  updateQueuedMessage: (
+    messageId: string,
+    message: string,
+    variant: string | null
+  ) => Promise<void>;
+  /** Remove a single queued message */
+  removeQueuedMessage: (messageId: string) => Promise<void>;
+  /** Move a queued message up (-1) or down (+1) */
+  moveQueuedMessage: (messageId: string, offset: -1 | 1) => Promise<void>;
+  /** Cancel all queued messages */
   cancelQueue: () => Promise<void>;
   /** Refresh the queue status from the server */
   refresh: () => Promise<void>;
@@ -35,13 +45,13 @@ export function useQueueStatus(sessionId?: string): UseQueueStatusResult {
     }
   }, [sessionId]);
 
-  const queueMessage = useCallback(
-    async (message: string, variant: string | null) => {
+  // Run a queue mutation and adopt the status it returns
+  const mutate = useCallback(
+    async (op: (sessionId: string) => Promise<QueueStatus>) => {
       if (!sessionId) return;
       setIsLoading(true);
       try {
-        const status = await queueApi.queue(sessionId, { message, variant });
-        setQueueStatus(status);
+        setQueueStatus(await op(sessionId));
       } finally {
         setIsLoading(false);
       }
@@ -49,16 +59,27 @@ export function useQueueStatus(sessionId?: string): UseQueueStatusResult {
     [sessionId]
   );
 
-  const cancelQueue = useCallback(async () => {
-    if (!sessionId) return;
-    setIsLoading(true);
-    try {
-      const status = await queueApi.cancel(sessionId);
-      setQueueStatus(status);
-    } finally {
-      setIsLoading(false);
-    }
-  }, [sessionId]);
+  const queueMessage = useCallback(
+    (message: string, variant: string | null) =>
+      mutate((id) => queueApi.queue(id, { message, variant })),
+    [mutate]
+  );
+
+  const updateQueuedMessage = useCallback(
+    (messageId: string, message: string, variant: string | null) =>
+      mutate((id) => queueApi.update(id, messageId, { message, variant })),
+    [mutate]
+  );
+
+  const removeQueuedMessage = useCallback(
+    (messageId: string) => mutate((id) => queueApi.remove(id, messageId)),
+    [mutate]
+  );
+
+  const cancelQueue = useCallback(
+    () => mutate((id) => queueApi.cancel(id)),
+    [mutate]
+  );
 
   // Fetch initial status when sessionId changes
   useEffect(() => {
@@ -69,17 +90,31 @@ export function useQueueStatus(sessionId?: string): UseQueueStatusResult {
     }
   }, [sessionId, refresh]);
 
-  const isQueued = queueStatus.status === 'queued';
-  const queuedMessage = isQueued
-    ? (queueStatus as Extract<QueueStatus, { status: 'queued' }>).message
-    : null;
+  const queuedMessages =
+    queueStatus.status === 'queued' ? queueStatus.messages : [];
+  const isQueued = queuedMessages.length > 0;
+
+  const moveQueuedMessage = useCallback(
+    async (messageId: string, offset: -1 | 1) => {
+      const ids = queuedMessages.map((m) => m.id);
+      const from = ids.indexOf(messageId);
+      const to = from + offset;
+      if (from < 0 || to < 0 || to >= ids.length) return;
+      [ids[from], ids[to]] = [ids[to], ids[from]];
+      await mutate((id) => queueApi.reorder(id, ids));
+    },
+    [queuedMessages, mutate]
+  );
 
   return {
     queueStatus,
     isQueued,
-    queuedMessage,
+    queuedMessages,
     isLoading,
     queueMessage,
+    updateQueuedMessage,
+    removeQueuedMessage,
+    moveQueuedMessage,
     cancelQueue,
     refresh,
   };
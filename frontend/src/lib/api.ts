@@ -8,6 +8,7 @@ import {
   EditorType,
   CreateGitHubPrRequest,
   CreateTask,
+  CreateTaskDependency,
   CreateAndStartTaskRequest,
   CreateTaskAttemptBody,
   CreateTag,
@@ -52,6 +53,13 @@ import {
   RunAgentSetupResponse,
   GhCliSetupError,
   RunScriptError,
+  RunQualityGatesRequest,
+  CreateStackedWorkspaceRequest,
+  WorkspaceStack,
+  RestackResult,
+  CreateStackedPrsRequest,
+  StackedPr,
+  RepoQualityGate,
   StatusResponse,
   ListOrganizationsResponse,
   OrganizationMemberWithProfile,
@@ -89,6 +97,29 @@ import {
   AbortConflictsRequest,
   Session,
   Workspace,
+  TaskAutoStart,
+  TaskDependencies,
+  TaskDependency,
+  UpsertTaskAutoStart,
+  TaskSchedule,
+  CreateTaskSchedule,
+  UpdateTaskSchedule,
+  TaskScheduleRun,
+  AttemptGroup,
+  AttemptGroupWithMembers,
+  AttemptComparison,
+  CreateAttemptGroupBody,
+  FullTextSearchQuery,
+  SearchHit,
+  Webhook,
+  CreateWebhook,
+  UpdateWebhook,
+  CreatedWebhook,
+  WebhookDelivery,
+  Approval,
+  ApprovalRule,
+  CreateApprovalRule,
+  UpdateApprovalRule,
 } from 'shared/types';
 import type { WorkspaceWithSession } from '@/types/attempt';
 import { createWorkspaceWithSession } from '@/types/attempt';
@@ -432,6 +463,58 @@ export const tasksApi = {
     return handleApiResponse<ShareTaskResponse>(response);
   },
 
+  getDependencies: async (taskId: string): Promise<TaskDependencies> => {
+    const response = await makeRequest(`/api/tasks/${taskId}/dependencies`);
+    return handleApiResponse<TaskDependencies>(response);
+  },
+
+  getApprovals: async (taskId: string): Promise<Approval[]> => {
+    const response = await makeRequest(`/api/tasks/${taskId}/approvals`);
+    return handleApiResponse<Approval[]>(response);
+  },
+
+  addDependency: async (
+    taskId: string,
+    data: CreateTaskDependency
+  ): Promise<TaskDependency> => {
+    const response = await makeRequest(`/api/tasks/${taskId}/dependencies`, {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<TaskDependency>(response);
+  },
+
+  removeDependency: async (
+    taskId: string,
+    dependsOnTaskId: string
+  ): Promise<void> => {
+    const response = await makeRequest(
+      `/api/tasks/${taskId}/dependencies/${dependsOnTaskId}`,
+      {
+        method: 'DELETE',
+      }
+    );
+    return handleApiResponse<void>(response);
+  },
+
+  setAutoStart: async (
+    taskId: string,
+    data: UpsertTaskAutoStart
+  ): Promise<TaskAutoStart> => {
+    const response = await makeRequest(`/api/tasks/${taskId}/auto-start`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<TaskAutoStart>(response);
+  },
+
+  clearAutoStart: async (taskId: string): Promise<void> => {
+    const response = await makeRequest(`/api/tasks/${taskId}/auto-start`, {
+      method: 'DELETE',
+    });
+    return handleApiResponse<void>(response);
+  },
+
   reassign: async (
     sharedTaskId: string,
     data: { new_assignee_user_id: string | null }
@@ -744,6 +827,29 @@ export const attemptsApi = {
     );
   },
 
+  getQualityGates: async (attemptId: string): Promise<RepoQualityGate[]> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/quality-gates`
+    );
+    return handleApiResponse<RepoQualityGate[]>(response);
+  },
+
+  runQualityGates: async (
+    attemptId: string,
+    data: RunQualityGatesRequest
+  ): Promise<Result<ExecutionProcess[], RunScriptError>> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/quality-gates/run`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponseAsResult<ExecutionProcess[], RunScriptError>(
+      response
+    );
+  },
+
   getPrComments: async (
     attemptId: string,
     repoId: string
@@ -753,6 +859,152 @@ export const attemptsApi = {
     );
     return handleApiResponse<PrCommentsResponse>(response);
   },
+
+  getStack: async (attemptId: string): Promise<WorkspaceStack> => {
+    const response = await makeRequest(`/api/task-attempts/${attemptId}/stack`);
+    return handleApiResponse<WorkspaceStack>(response);
+  },
+
+  createStacked: async (
+    attemptId: string,
+    data: CreateStackedWorkspaceRequest
+  ): Promise<Workspace> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/stack`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<Workspace>(response);
+  },
+
+  restack: async (attemptId: string): Promise<RestackResult> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/stack/restack`,
+      {
+        method: 'POST',
+      }
+    );
+    return handleApiResponse<RestackResult>(response);
+  },
+
+  createStackedPrs: async (
+    attemptId: string,
+    data: CreateStackedPrsRequest
+  ): Promise<StackedPr[]> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/stack/pr`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<StackedPr[]>(response);
+  },
+};
+
+// Best-of-N Attempt Group APIs
+export const attemptGroupsApi = {
+  list: async (taskId: string): Promise<AttemptGroup[]> => {
+    const response = await makeRequest(
+      `/api/attempt-groups?task_id=${encodeURIComponent(taskId)}`
+    );
+    return handleApiResponse<AttemptGroup[]>(response);
+  },
+
+  get: async (groupId: string): Promise<AttemptGroupWithMembers> => {
+    const response = await makeRequest(`/api/attempt-groups/${groupId}`);
+    return handleApiResponse<AttemptGroupWithMembers>(response);
+  },
+
+  create: async (
+    data: CreateAttemptGroupBody
+  ): Promise<AttemptGroupWithMembers> => {
+    const response = await makeRequest('/api/attempt-groups', {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<AttemptGroupWithMembers>(response);
+  },
+
+  getComparison: async (groupId: string): Promise<AttemptComparison> => {
+    const response = await makeRequest(
+      `/api/attempt-groups/${groupId}/comparison`
+    );
+    return handleApiResponse<AttemptComparison>(response);
+  },
+
+  selectWinner: async (
+    groupId: string,
+    workspaceId: string
+  ): Promise<AttemptGroupWithMembers> => {
+    const response = await makeRequest(
+      `/api/attempt-groups/${groupId}/winner`,
+      {
+        method: 'POST',
+        body: JSON.stringify({ workspace_id: workspaceId }),
+      }
+    );
+    return handleApiResponse<AttemptGroupWithMembers>(response);
+  },
+};
+
+// Full-text Search APIs
+export const searchApi = {
+  search: async (query: FullTextSearchQuery): Promise<SearchHit[]> => {
+    const params = new URLSearchParams({ q: query.q });
+    if (query.project_id) params.set('project_id', query.project_id);
+    if (query.since) params.set('since', query.since);
+    if (query.limit !== null) params.set('limit', String(query.limit));
+    const response = await makeRequest(`/api/search?${params.toString()}`);
+    return handleApiResponse<SearchHit[]>(response);
+  },
+};
+
+// Outbound Webhook APIs
+export const webhooksApi = {
+  list: async (): Promise<Webhook[]> => {
+    const response = await makeRequest('/api/webhooks');
+    return handleApiResponse<Webhook[]>(response);
+  },
+
+  create: async (data: CreateWebhook): Promise<CreatedWebhook> => {
+    const response = await makeRequest('/api/webhooks', {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<CreatedWebhook>(response);
+  },
+
+  update: async (webhookId: string, data: UpdateWebhook): Promise<Webhook> => {
+    const response = await makeRequest(`/api/webhooks/${webhookId}`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<Webhook>(response);
+  },
+
+  delete: async (webhookId: string): Promise<void> => {
+    const response = await makeRequest(`/api/webhooks/${webhookId}`, {
+      method: 'DELETE',
+    });
+    return handleApiResponse<void>(response);
+  },
+
+  getDeliveries: async (webhookId: string): Promise<WebhookDelivery[]> => {
+    const response = await makeRequest(
+      `/api/webhooks/${webhookId}/deliveries`
+    );
+    return handleApiResponse<WebhookDelivery[]>(response);
+  },
+
+  test: async (webhookId: string): Promise<WebhookDelivery> => {
+    const response = await makeRequest(`/api/webhooks/${webhookId}/test`, {
+      method: 'POST',
+    });
+    return handleApiResponse<WebhookDelivery>(response);
+  },
 };
 
 // Execution Process APIs
@@ -896,6 +1148,49 @@ export const tagsApi = {
   },
 };
 
+// Task schedule APIs
+export const taskSchedulesApi = {
+  list: async (projectId: string): Promise<TaskSchedule[]> => {
+    const response = await makeRequest(
+      `/api/task-schedules?project_id=${encodeURIComponent(projectId)}`
+    );
+    return handleApiResponse<TaskSchedule[]>(response);
+  },
+
+  create: async (data: CreateTaskSchedule): Promise<TaskSchedule> => {
+    const response = await makeRequest('/api/task-schedules', {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<TaskSchedule>(response);
+  },
+
+  update: async (
+    scheduleId: string,
+    data: UpdateTaskSchedule
+  ): Promise<TaskSchedule> => {
+    const response = await makeRequest(`/api/task-schedules/${scheduleId}`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<TaskSchedule>(response);
+  },
+
+  delete: async (scheduleId: string): Promise<void> => {
+    const response = await makeRequest(`/api/task-schedules/${scheduleId}`, {
+      method: 'DELETE',
+    });
+    return handleApiResponse<void>(response);
+  },
+
+  getRuns: async (scheduleId: string): Promise<TaskScheduleRun[]> => {
+    const response = await makeRequest(
+      `/api/task-schedules/${scheduleId}/runs`
+    );
+    return handleApiResponse<TaskScheduleRun[]>(response);
+  },
+};
+
 // MCP Servers APIs
 export const mcpServersApi = {
   load: async (query: McpServerQuery): Promise<GetMcpServerResponse> => {
@@ -1043,6 +1338,43 @@ export const imagesApi = {
   },
 };
 
+// Approval rule APIs
+export const approvalRulesApi = {
+  list: async (projectId?: string): Promise<ApprovalRule[]> => {
+    const query = projectId
+      ? `?project_id=${encodeURIComponent(projectId)}`
+      : '';
+    const response = await makeRequest(`/api/approval-rules${query}`);
+    return handleApiResponse<ApprovalRule[]>(response);
+  },
+
+  create: async (data: CreateApprovalRule): Promise<ApprovalRule> => {
+    const response = await makeRequest('/api/approval-rules', {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<ApprovalRule>(response);
+  },
+
+  update: async (
+    ruleId: string,
+    data: UpdateApprovalRule
+  ): Promise<ApprovalRule> => {
+    const response = await makeRequest(`/api/approval-rules/${ruleId}`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<ApprovalRule>(response);
+  },
+
+  delete: async (ruleId: string): Promise<void> => {
+    const response = await makeRequest(`/api/approval-rules/${ruleId}`, {
+      method: 'DELETE',
+    });
+    return handleApiResponse<void>(response);
+  },
+};
+
 // Approval API
 export const approvalsApi = {
   respond: async (
@@ -1259,7 +1591,7 @@ export const scratchApi = {
 // Queue API for session follow-up messages
 export const queueApi = {
   /**
-   * Queue a follow-up message to be executed when current execution finishes
+   * Append a follow-up message to the queue; queued messages run one per completed execution
    */
   queue: async (
     sessionId: string,
@@ -1273,7 +1605,58 @@ export const queueApi = {
   },
 
   /**
-   * Cancel a queued follow-up message
+   * Edit a queued follow-up message in place
+   */
+  update: async (
+    sessionId: string,
+    messageId: string,
+    data: { message: string; variant: string | null }
+  ): Promise<QueueStatus> => {
+    const response = await makeRequest(
+      `/api/sessions/${sessionId}/queue/${messageId}`,
+      {
+        method: 'PUT',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<QueueStatus>(response);
+  },
+
+  /**
+   * Remove a single queued follow-up message
+   */
+  remove: async (
+    sessionId: string,
+    messageId: string
+  ): Promise<QueueStatus> => {
+    const response = await makeRequest(
+      `/api/sessions/${sessionId}/queue/${messageId}`,
+      {
+        method: 'DELETE',
+      }
+    );
+    return handleApiResponse<QueueStatus>(response);
+  },
+
+  /**
+   * Reorder the queue; messageIds must list every queued message once
+   */
+  reorder: async (
+    sessionId: string,
+    messageIds: string[]
+  ): Promise<QueueStatus> => {
+    const response = await makeRequest(
+      `/api/sessions/${sessionId}/queue/order`,
+      {
+        method: 'PUT',
+        body: JSON.stringify({ message_ids: messageIds }),
+      }
+    );
+    return handleApiResponse<QueueStatus>(response);
+  },
+
+  /**
+   * Cancel all queued follow-up messages
    */
   cancel: async (sessionId: string): Promise<QueueStatus> => {
     const response = await makeRequest(`/api/sessions/${sessionId}/queue`, {
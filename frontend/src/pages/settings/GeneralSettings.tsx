@@ -533,6 +533,66 @@ export function GeneralSettings() {
         </CardContent>
       </Card>
 
+      <Card>
+        <CardHeader>
+          <CardTitle>{t('settings.general.logStorage.title')}</CardTitle>
+          <CardDescription>
+            {t('settings.general.logStorage.description')}
+          </CardDescription>
+        </CardHeader>
+        <CardContent className="space-y-4">
+          <div className="flex items-center space-x-2">
+            <Checkbox
+              id="log-spill-to-files"
+              checked={draft?.log_storage.spill_to_files ?? false}
+              onCheckedChange={(checked: boolean) =>
+                updateDraft({
+                  log_storage: {
+                    ...draft!.log_storage,
+                    spill_to_files: checked,
+                  },
+                })
+              }
+            />
+            <div className="space-y-0.5">
+              <Label htmlFor="log-spill-to-files" className="cursor-pointer">
+                {t('settings.general.logStorage.spillToFiles.label')}
+              </Label>
+              <p className="text-sm text-muted-foreground">
+                {t('settings.general.logStorage.spillToFiles.helper')}
+              </p>
+            </div>
+          </div>
+          <div className="space-y-2">
+            <Label htmlFor="log-retention-days">
+              {t('settings.general.logStorage.retentionDays.label')}
+            </Label>
+            <Input
+              id="log-retention-days"
+              type="number"
+              min={1}
+              placeholder={t(
+                'settings.general.logStorage.retentionDays.placeholder'
+              )}
+              value={draft?.log_storage.retention_days ?? ''}
+              onChange={(e) => {
+                const days = parseInt(e.target.value, 10);
+                updateDraft({
+                  log_storage: {
+                    ...draft!.log_storage,
+                    retention_days:
+                      Number.isNaN(days) || days < 1 ? null : days,
+                  },
+                });
+              }}
+            />
+            <p className="text-sm text-muted-foreground">
+              {t('settings.general.logStorage.retentionDays.helper')}
+            </p>
+          </div>
+        </CardContent>
+      </Card>
+
       <Card>
         <CardHeader>
           <CardTitle>{t('settings.general.notifications.title')}</CardTitle>
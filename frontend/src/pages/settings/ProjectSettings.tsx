@@ -45,6 +45,8 @@ interface RepoScriptsFormState {
   parallel_setup_script: boolean;
   cleanup_script: string;
   copy_files: string;
+  gate_script: string;
+  gate_follow_up_on_failure: boolean;
 }
 
 function projectToFormState(project: Project): ProjectFormState {
@@ -64,6 +66,8 @@ function projectRepoToScriptsFormState(
     parallel_setup_script: projectRepo?.parallel_setup_script ?? false,
     cleanup_script: projectRepo?.cleanup_script ?? '',
     copy_files: projectRepo?.copy_files ?? '',
+    gate_script: projectRepo?.gate_script ?? '',
+    gate_follow_up_on_failure: projectRepo?.gate_follow_up_on_failure ?? false,
   };
 }
 
@@ -426,6 +430,8 @@ export function ProjectSettings() {
           cleanup_script: scriptsDraft.cleanup_script.trim() || null,
           copy_files: scriptsDraft.copy_files.trim() || null,
           parallel_setup_script: scriptsDraft.parallel_setup_script,
+          gate_script: scriptsDraft.gate_script.trim() || null,
+          gate_follow_up_on_failure: scriptsDraft.gate_follow_up_on_failure,
         }
       );
       setSelectedProjectRepo(updatedRepo);
@@ -875,6 +881,47 @@ export function ProjectSettings() {
                         </p>
                       </div>
 
+                      <div className="space-y-2">
+                        <Label htmlFor="gate-script">
+                          {t('settings.projects.scripts.gate.label')}
+                        </Label>
+                        <AutoExpandingTextarea
+                          id="gate-script"
+                          value={scriptsDraft.gate_script}
+                          onChange={(e) =>
+                            updateScriptsDraft({ gate_script: e.target.value })
+                          }
+                          placeholder={'#!/bin/bash\nnpm run build && npm test'}
+                          maxRows={12}
+                          className="w-full px-3 py-2 border border-input bg-background text-foreground rounded-md focus:outline-none focus:ring-2 focus:ring-ring font-mono"
+                        />
+                        <p className="text-sm text-muted-foreground">
+                          {t('settings.projects.scripts.gate.helper')}
+                        </p>
+
+                        <div className="flex items-center space-x-2 pt-2">
+                          <Checkbox
+                            id="gate-follow-up-on-failure"
+                            checked={scriptsDraft.gate_follow_up_on_failure}
+                            onCheckedChange={(checked) =>
+                              updateScriptsDraft({
+                                gate_follow_up_on_failure: checked === true,
+                              })
+                            }
+                            disabled={!scriptsDraft.gate_script.trim()}
+                          />
+                          <Label
+                            htmlFor="gate-follow-up-on-failure"
+                            className="text-sm font-normal cursor-pointer"
+                          >
+                            {t('settings.projects.scripts.gate.followUpLabel')}
+                          </Label>
+                        </div>
+                        <p className="text-sm text-muted-foreground pl-6">
+                          {t('settings.projects.scripts.gate.followUpHelper')}
+                        </p>
+                      </div>
+
                       <div className="space-y-2">
                         <Label>
                           {t('settings.projects.scripts.copyFiles.label')}
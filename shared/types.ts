@@ -24,11 +24,19 @@ export type SearchMatchType = "FileName" | "DirectoryName" | "FullPath";
 
 export type Repo = { id: string, path: string, name: string, display_name: string, created_at: Date, updated_at: Date, };
 
-export type ProjectRepo = { id: string, project_id: string, repo_id: string, setup_script: string | null, cleanup_script: string | null, copy_files: string | null, parallel_setup_script: boolean, };
+export type ProjectRepo = { id: string, project_id: string, repo_id: string, setup_script: string | null, cleanup_script: string | null, copy_files: string | null, parallel_setup_script: boolean, 
+/**
+ * Runs in the worktree before merge or PR creation; a non-zero exit blocks both
+ */
+gate_script: string | null, 
+/**
+ * Send a failed gate's output back to the coding agent as a follow-up
+ */
+gate_follow_up_on_failure: boolean, };
 
 export type CreateProjectRepo = { display_name: string, git_repo_path: string, };
 
-export type UpdateProjectRepo = { setup_script: string | null, cleanup_script: string | null, copy_files: string | null, parallel_setup_script: boolean | null, };
+export type UpdateProjectRepo = { setup_script: string | null, cleanup_script: string | null, copy_files: string | null, parallel_setup_script: boolean | null, gate_script: string | null, gate_follow_up_on_failure: boolean | null, };
 
 export type WorkspaceRepo = { id: string, workspace_id: string, repo_id: string, target_branch: string, created_at: Date, updated_at: Date, };
 
@@ -46,7 +54,7 @@ export type TaskStatus = "todo" | "inprogress" | "inreview" | "done" | "cancelle
 
 export type Task = { id: string, project_id: string, title: string, description: string | null, status: TaskStatus, parent_workspace_id: string | null, shared_task_id: string | null, created_at: string, updated_at: string, };
 
-export type TaskWithAttemptStatus = { has_in_progress_attempt: boolean, last_attempt_failed: boolean, executor: string, id: string, project_id: string, title: string, description: string | null, status: TaskStatus, parent_workspace_id: string | null, shared_task_id: string | null, created_at: string, updated_at: string, };
+export type TaskWithAttemptStatus = { has_in_progress_attempt: boolean, last_attempt_failed: boolean, is_blocked: boolean, executor: string, id: string, project_id: string, title: string, description: string | null, status: TaskStatus, parent_workspace_id: string | null, shared_task_id: string | null, created_at: string, updated_at: string, };
 
 export type TaskRelationships = { parent_task: Task | null, current_workspace: Workspace, children: Array<Task>, };
 
@@ -54,6 +62,222 @@ export type CreateTask = { project_id: string, title: string, description: strin
 
 export type UpdateTask = { title: string | null, description: string | null, status: TaskStatus | null, parent_workspace_id: string | null, image_ids: Array<string> | null, };
 
+export type TaskDependency = { task_id: string, depends_on_task_id: string, created_at: string, };
+
+export type CreateTaskDependency = { depends_on_task_id: string, };
+
+export type TaskDependencies = { blocked_by: Array<Task>, blocking: Array<Task>, auto_start: TaskAutoStart | null, };
+
+export type TaskAutoStart = { task_id: string, executor_profile_id: ExecutorProfileId, repos: Array<CreateWorkspaceRepo>, created_at: string, updated_at: string, };
+
+export type UpsertTaskAutoStart = { executor_profile_id: ExecutorProfileId, repos: Array<CreateWorkspaceRepo>, };
+
+export type TaskSchedule = { id: string, project_id: string, title: string, description: string | null, 
+/**
+ * Five-field cron expression, evaluated in UTC
+ */
+cron_expression: string, executor_profile_id: ExecutorProfileId, repos: Array<CreateWorkspaceRepo>, enabled: boolean, 
+/**
+ * `None` while the schedule is disabled
+ */
+next_run_at: string | null, last_run_at: string | null, created_at: string, updated_at: string, };
+
+export type CreateTaskSchedule = { project_id: string, title: string, description: string | null, cron_expression: string, executor_profile_id: ExecutorProfileId, repos: Array<CreateWorkspaceRepo>, enabled: boolean | null, };
+
+export type UpdateTaskSchedule = { title: string | null, 
+/**
+ * An empty string removes the description
+ */
+description: string | null, cron_expression: string | null, executor_profile_id: ExecutorProfileId | null, repos: Array<CreateWorkspaceRepo> | null, enabled: boolean | null, };
+
+export type TaskScheduleRun = { id: string, schedule_id: string, task_id: string | null, workspace_id: string | null, error: string | null, scheduled_for: string, created_at: string, };
+
+/**
+ * What changed a task's status
+ */
+export type TaskEventActor = "user" | "agent" | "pr_monitor" | "shared_task_sync" | "scheduler";
+
+/**
+ * One status change of a task
+ */
+export type TaskEvent = { id: string, task_id: string, 
+/**
+ * `None` for the event recorded when the task was created
+ */
+old_status: TaskStatus | null, new_status: TaskStatus, actor: TaskEventActor, created_at: string, };
+
+export type AttemptGroup = { id: string, task_id: string, winner_workspace_id: string | null, created_at: string, updated_at: string, };
+
+export type AttemptGroupMember = { group_id: string, workspace_id: string, executor_profile_id: ExecutorProfileId, 
+/**
+ * Set once another member was picked as the winner and this workspace was cleaned up
+ */
+archived_at: string | null, created_at: string, };
+
+export type SearchDocumentKind = "task" | "agent_turn" | "conversation";
+
+export type FullTextSearchQuery = { q: string, project_id: string | null, 
+/**
+ * Only return items created or updated at or after this time
+ */
+since: string | null, limit: number | null, };
+
+export type SearchHit = { kind: SearchDocumentKind, project_id: string, task_id: string, task_title: string, workspace_id: string | null, execution_process_id: string | null, 
+/**
+ * Matching excerpt with matched terms wrapped in `<mark>` tags
+ */
+snippet: string, 
+/**
+ * Relevance, higher is better
+ */
+score: number, updated_at: string, };
+
+export type AnalyticsFilter = { project_id: string | null, 
+/**
+ * Only include tasks created at or after this time
+ */
+since: string | null, 
+/**
+ * Only include tasks created before this time
+ */
+until: string | null, };
+
+export type WebhookEventType = "task.status_changed" | "execution.completed" | "execution.failed" | "approval.requested" | "pr.merged" | "ping";
+
+export type WebhookDeliveryStatus = "pending" | "delivered" | "failed";
+
+export type Webhook = { id: string, name: string, url: string, 
+/**
+ * Subscribed event types; empty subscribes to every event
+ */
+events: Array<WebhookEventType>, enabled: boolean, created_at: string, updated_at: string, };
+
+export type CreateWebhook = { name: string, url: string, 
+/**
+ * Generated when omitted
+ */
+secret: string | null, events: Array<WebhookEventType>, enabled: boolean | null, };
+
+export type UpdateWebhook = { name: string | null, url: string | null, secret: string | null, events: Array<WebhookEventType> | null, enabled: boolean | null, };
+
+/**
+ * A newly created webhook. `secret` is only ever returned here.
+ */
+export type CreatedWebhook = { secret: string, id: string, name: string, url: string, 
+/**
+ * Subscribed event types; empty subscribes to every event
+ */
+events: Array<WebhookEventType>, enabled: boolean, created_at: string, updated_at: string, };
+
+export type WebhookDelivery = { id: string, webhook_id: string, event_type: WebhookEventType, 
+/**
+ * Request body, sent unchanged on every attempt
+ */
+payload: string, status: WebhookDeliveryStatus, attempts: bigint, 
+/**
+ * When the next attempt is due while the delivery is pending
+ */
+next_attempt_at: string | null, 
+/**
+ * HTTP status of the most recent attempt, if a response was received
+ */
+response_status: bigint | null, last_error: string | null, delivered_at: string | null, created_at: string, updated_at: string, };
+
+/**
+ * What a token can authenticate
+ */
+export type ApiTokenScope = "api" | "mcp";
+
+/**
+ * A token for the authenticated headless server mode. The token itself is only shown
+ * once, when it is created.
+ */
+export type ApiToken = { id: string, name: string, 
+/**
+ * First characters of the token, to tell tokens apart
+ */
+token_prefix: string, 
+/**
+ * Admin tokens can create and revoke tokens
+ */
+is_admin: boolean, scope: ApiTokenScope, 
+/**
+ * The coding agent run the token was handed to; it is revoked when the run ends
+ */
+execution_process_id: string | null, last_used_at: string | null, revoked_at: string | null, created_at: string, };
+
+export type CreateApiToken = { name: string, is_admin: boolean, scope: ApiTokenScope, };
+
+/**
+ * One authenticated request made with a token
+ */
+export type ApiTokenAuditEntry = { id: bigint, token_id: string, method: string, path: string, status: bigint, created_at: string, };
+
+/**
+ * A newly created token. `token` is only ever returned here.
+ */
+export type CreatedApiToken = { token: string, id: string, name: string, 
+/**
+ * First characters of the token, to tell tokens apart
+ */
+token_prefix: string, 
+/**
+ * Admin tokens can create and revoke tokens
+ */
+is_admin: boolean, scope: ApiTokenScope, 
+/**
+ * The coding agent run the token was handed to; it is revoked when the run ends
+ */
+execution_process_id: string | null, last_used_at: string | null, revoked_at: string | null, created_at: string, };
+
+export type ApprovalDecision = "pending" | "approved" | "denied" | "timed_out";
+
+export type ApprovalActor = "user" | "rule" | "timeout";
+
+export type Approval = { id: string, execution_process_id: string, tool_name: string, tool_input: JsonValue, tool_call_id: string, status: ApprovalDecision, denial_reason: string | null, 
+/**
+ * The approval rule that decided the request instead of the user
+ */
+rule_id: string | null, 
+/**
+ * `None` while the request is pending
+ */
+actor: ApprovalActor | null, created_at: string, timeout_at: string, responded_at: string | null, };
+
+export type ApprovalRuleAction = "allow" | "deny";
+
+export type ApprovalRule = { id: string, 
+/**
+ * `None` applies the rule to every project
+ */
+project_id: string | null, 
+/**
+ * `None` applies the rule to every executor
+ */
+executor: BaseCodingAgent | null, 
+/**
+ * `None` applies the rule to every variant of the executor
+ */
+variant: string | null, action: ApprovalRuleAction, 
+/**
+ * Glob over the tool name, e.g. `Bash` or `mcp__*`
+ */
+tool_pattern: string, 
+/**
+ * Glob over the command or path the tool acts on, e.g. `cargo test*` for a command or
+ * `*.env*` for a path. `None` matches any input. Allow rules never match commands that
+ * chain, substitute or redirect, while deny rules also match each chained command.
+ */
+input_pattern: string | null, description: string | null, enabled: boolean, created_at: string, updated_at: string, };
+
+export type CreateApprovalRule = { project_id: string | null, executor: BaseCodingAgent | null, variant: string | null, action: ApprovalRuleAction, tool_pattern: string, input_pattern: string | null, description: string | null, enabled: boolean | null, };
+
+export type UpdateApprovalRule = { action: ApprovalRuleAction | null, tool_pattern: string | null, 
+/**
+ * An empty string removes the input pattern
+ */
+input_pattern: string | null, description: string | null, enabled: boolean | null, };
+
 export type DraftFollowUpData = { message: string, variant: string | null, };
 
 export type ScratchPayload = { "type": "DRAFT_TASK", "data": string } | { "type": "DRAFT_FOLLOW_UP", "data": DraftFollowUpData };
@@ -70,11 +294,15 @@ export type Image = { id: string, file_path: string, original_name: string, mime
 
 export type CreateImage = { file_path: string, original_name: string, mime_type: string | null, size_bytes: bigint, hash: string, };
 
-export type Workspace = { id: string, task_id: string, container_ref: string | null, branch: string, agent_working_dir: string | null, setup_completed_at: string | null, created_at: string, updated_at: string, };
+export type Workspace = { id: string, task_id: string, container_ref: string | null, branch: string, agent_working_dir: string | null, 
+/**
+ * Workspace whose branch this one is stacked on, set when it was created on top of it
+ */
+stack_parent_id: string | null, setup_completed_at: string | null, created_at: string, updated_at: string, };
 
 export type Session = { id: string, workspace_id: string, executor: string | null, created_at: string, updated_at: string, };
 
-export type ExecutionProcess = { id: string, session_id: string, run_reason: ExecutionProcessRunReason, executor_action: ExecutorAction, status: ExecutionProcessStatus, exit_code: bigint | null, 
+export type ExecutionProcess = { id: string, session_id: string, run_reason: ExecutionProcessRunReason, executor_action: ExecutorAction, status: ExecutionProcessStatus, exit_code: bigint | null, failure_reason: ExecutionFailureReason | null, 
 /**
  * dropped: true if this process is excluded from the current
  * history view (due to restore/trimming). Hidden from logs/timeline;
@@ -84,7 +312,9 @@ dropped: boolean, started_at: string, completed_at: string | null, created_at: s
 
 export enum ExecutionProcessStatus { running = "running", completed = "completed", failed = "failed", killed = "killed" }
 
-export type ExecutionProcessRunReason = "setupscript" | "cleanupscript" | "codingagent" | "devserver";
+export type ExecutionProcessRunReason = "setupscript" | "cleanupscript" | "codingagent" | "devserver" | "qualitygate";
+
+export type ExecutionFailureReason = "timeout" | "toolcalllimit" | "loglimit";
 
 export type ExecutionProcessRepoState = { id: string, execution_process_id: string, repo_id: string, before_head_commit: string | null, after_head_commit: string | null, merge_commit: string | null, created_at: Date, updated_at: Date, };
 
@@ -104,6 +334,12 @@ export type CreateApprovalRequest = { tool_name: string, tool_input: JsonValue,
 
 export type ApprovalResponse = { execution_process_id: string, status: ApprovalStatus, };
 
+export type MatchedApprovalRule = { rule_id: string, 
+/**
+ * The rule's description, or its patterns when it has none
+ */
+summary: string, };
+
 export type Diff = { change: DiffChangeKind, oldPath: string | null, newPath: string | null, oldContent: string | null, newContent: string | null, 
 /**
  * True when file contents are intentionally omitted (e.g., too large)
@@ -246,17 +482,50 @@ export type RunAgentSetupRequest = { executor_profile_id: ExecutorProfileId, };
 
 export type RunAgentSetupResponse = Record<string, never>;
 
+export type CreateAttemptGroupBody = { task_id: string, 
+/**
+ * One workspace is started per profile; the same profile may appear more than once
+ */
+executor_profile_ids: Array<ExecutorProfileId>, repos: Array<WorkspaceRepoInput>, };
+
+export type SelectAttemptWinnerRequest = { workspace_id: string, };
+
+export type AttemptGroupWithMembers = { members: Array<AttemptGroupMember>, id: string, task_id: string, winner_workspace_id: string | null, created_at: string, updated_at: string, };
+
+export type AttemptDiffStats = { files_changed: number, additions: number, deletions: number, };
+
+export type AttemptProcessOutcome = { status: ExecutionProcessStatus, exit_code: bigint | null, failure_reason: ExecutionFailureReason | null, };
+
+export type AttemptSummary = { workspace: Workspace, executor_profile_id: ExecutorProfileId, is_winner: boolean, archived_at: string | null, 
+/**
+ * Changes against each repo's target branch, summed over repos. `None` once the
+ * worktree has been archived or if it has not been created yet.
+ */
+diff_stats: AttemptDiffStats | null, setup_script: AttemptProcessOutcome | null, coding_agent: AttemptProcessOutcome | null, cleanup_script: AttemptProcessOutcome | null, 
+/**
+ * From the first process starting to the last one finishing, excluding dev servers.
+ * `None` while a process is still running.
+ */
+duration_secs: bigint | null, };
+
+export type AttemptComparison = { group: AttemptGroup, attempts: Array<AttemptSummary>, };
+
 export type GhCliSetupError = "BREW_MISSING" | "SETUP_HELPER_NOT_SUPPORTED" | { "OTHER": { message: string, } };
 
-export type RebaseTaskAttemptRequest = { repo_id: string, old_base_branch: string | null, new_base_branch: string | null, };
+export type RebaseTaskAttemptRequest = { repo_id: string, old_base_branch: string | null, new_base_branch: string | null, 
+/**
+ * On conflicts, ask the coding agent to resolve them and continue the rebase, aborting it
+ * if the agent fails
+ */
+auto_resolve_conflicts?: boolean, };
 
 export type AbortConflictsRequest = { repo_id: string, };
 
-export type GitOperationError = { "type": "merge_conflicts", message: string, op: ConflictOp, } | { "type": "rebase_in_progress" };
+export type GitOperationError = { "type": "merge_conflicts", message: string, op: ConflictOp, } | { "type": "rebase_in_progress" } | { "type": "conflict_resolution_started", message: string, op: ConflictOp, execution_process_id: string, };
 
 export type PushError = { "type": "force_push_required" };
 
-export type CreatePrError = { "type": "github_cli_not_installed" } | { "type": "github_cli_not_logged_in" } | { "type": "git_cli_not_logged_in" } | { "type": "git_cli_not_installed" } | { "type": "target_branch_not_found", branch: string, };
+export type CreatePrError = { "type": "github_cli_not_installed" } | { "type": "github_cli_not_logged_in" } | { "type": "git_cli_not_logged_in" } | { "type": "git_cli_not_installed" } | { "type": "target_branch_not_found", branch: string, } | { "type": "forge_not_authenticated", host: string, } | { "type": "unsupported_remote", remote_url: string, } | { "type": "quality_gate_running", repo_name: string, execution_process_id: string, } | { "type": "quality_gate_failed", repo_name: string, execution_process_id: string, } | { "type": "quality_gate_uncommitted_changes", repo_name: string, };
 
 export type BranchStatus = { commits_behind: number | null, commits_ahead: number | null, has_uncommitted_changes: boolean | null, head_oid: string | null, uncommitted_count: number | null, untracked_count: number | null, target_branch_name: string, remote_commits_behind: number | null, remote_commits_ahead: number | null, merges: Array<Merge>, 
 /**
@@ -272,7 +541,40 @@ conflict_op: ConflictOp | null,
  */
 conflicted_files: Array<string>, };
 
-export type RunScriptError = { "type": "no_script_configured" } | { "type": "process_already_running" };
+export type RunScriptError = { "type": "no_script_configured" } | { "type": "process_already_running" } | { "type": "uncommitted_changes" };
+
+export type RunQualityGatesRequest = { 
+/**
+ * Only run this repository's gate; runs every gate that is not already running if unset
+ */
+repo_id: string | null, };
+
+export type CreateStackedWorkspaceRequest = { 
+/**
+ * Task the new workspace works on; must belong to the same project
+ */
+task_id: string, executor_profile_id: ExecutorProfileId, };
+
+export type RestackOutcome = { "type": "rebased" } | { "type": "merge_conflicts", message: string, } | { "type": "rebase_in_progress" } | { "type": "failed", message: string, };
+
+export type RestackStep = { workspace_id: string, repo_id: string, repo_name: string, 
+/**
+ * Parent branch the workspace was rebased onto
+ */
+onto_branch: string, outcome: RestackOutcome, };
+
+export type RestackResult = { steps: Array<RestackStep>, 
+/**
+ * False if the restack stopped at a step that did not rebase cleanly; the workspaces
+ * after it were left untouched
+ */
+completed: boolean, };
+
+export type CreateStackedPrsRequest = { repo_id: string, draft: boolean | null, };
+
+export type StackedPrOutcome = { "type": "created", url: string, } | { "type": "existing", url: string, } | { "type": "failed", error: CreatePrError | null, message: string | null, };
+
+export type StackedPr = { workspace_id: string, target_branch: string, outcome: StackedPrOutcome, };
 
 export type AttachPrResponse = { pr_attached: boolean, pr_url: string | null, pr_number: bigint | null, pr_status: MergeStatus | null, };
 
@@ -280,7 +582,7 @@ export type AttachExistingPrRequest = { repo_id: string, };
 
 export type PrCommentsResponse = { comments: Array<UnifiedPrComment>, };
 
-export type GetPrCommentsError = { "type": "no_pr_attached" } | { "type": "github_cli_not_installed" } | { "type": "github_cli_not_logged_in" };
+export type GetPrCommentsError = { "type": "no_pr_attached" } | { "type": "github_cli_not_installed" } | { "type": "github_cli_not_logged_in" } | { "type": "forge_not_authenticated", host: string, } | { "type": "unsupported_remote", remote_url: string, };
 
 export type GetPrCommentsQuery = { repo_id: string, };
 
@@ -304,7 +606,11 @@ export type DirectoryEntry = { name: string, path: string, is_directory: boolean
 
 export type DirectoryListResponse = { entries: Array<DirectoryEntry>, current_path: string, };
 
-export type Config = { config_version: string, theme: ThemeMode, executor_profile: ExecutorProfileId, disclaimer_acknowledged: boolean, onboarding_acknowledged: boolean, notifications: NotificationConfig, editor: EditorConfig, github: GitHubConfig, analytics_enabled: boolean, workspace_dir: string | null, last_app_version: string | null, show_release_notes: boolean, language: UiLanguage, git_branch_prefix: string, showcases: ShowcaseState, pr_auto_description_enabled: boolean, pr_auto_description_prompt: string | null, };
+export type Config = { config_version: string, theme: ThemeMode, executor_profile: ExecutorProfileId, disclaimer_acknowledged: boolean, onboarding_acknowledged: boolean, notifications: NotificationConfig, editor: EditorConfig, github: GitHubConfig, analytics_enabled: boolean, workspace_dir: string | null, last_app_version: string | null, show_release_notes: boolean, language: UiLanguage, git_branch_prefix: string, showcases: ShowcaseState, pr_auto_description_enabled: boolean, pr_auto_description_prompt: string | null, log_storage: LogStorageConfig, forges: Array<ForgeHostConfig>, 
+/**
+ * Prices used to estimate what coding agent usage cost
+ */
+token_prices: Array<ModelTokenPrice>, };
 
 export type NotificationConfig = { sound_enabled: boolean, push_enabled: boolean, sound_file: SoundFile, };
 
@@ -324,25 +630,193 @@ export type UiLanguage = "BROWSER" | "EN" | "JA" | "ES" | "KO" | "ZH_HANS";
 
 export type ShowcaseState = { seen_features: Array<string>, };
 
-export type GitBranch = { name: string, is_current: boolean, is_remote: boolean, last_commit_date: Date, };
+export type LogStorageConfig = { 
+/**
+ * Write compacted logs of at least 64 KiB to files under the asset directory instead of
+ * the database
+ */
+spill_to_files: boolean, 
+/**
+ * Delete logs this many days after their process completed. Coding agent prompts
+ * and summaries are kept. `None` keeps logs forever.
+ */
+retention_days: number | null, };
 
-export type SharedTaskDetails = { id: string, project_id: string, title: string, description: string | null, status: TaskStatus, };
+export type ForgeKind = "github" | "gitlab" | "gitea";
 
-export type QueuedMessage = { 
+export type ForgeHostConfig = { 
 /**
- * The session this message is queued for
+ * Host as it appears in remote URLs, with the port if there is one, e.g.
+ * `gitlab.example.com` or `localhost:3000`
  */
-session_id: string, 
+host: string, kind: ForgeKind, 
 /**
- * The follow-up data (message + variant)
+ * Web URL of the instance when it is not `https://{host}`
+ */
+base_url: string | null, token: string | null, };
+
+export type ModelTokenPrice = { 
+/**
+ * Model name, or a prefix of it, e.g. `claude-sonnet-4-5` also matches
+ * `claude-sonnet-4-5-20250929`. The longest matching entry wins.
  */
-data: DraftFollowUpData, 
+model: string, input: number, output: number, cache_read: number | null, cache_write: number | null, };
+
+export type UsageGroupBy = "task" | "workspace" | "project" | "day";
+
+export type UsageQuery = { group_by: UsageGroupBy, project_id: string | null, task_id: string | null, workspace_id: string | null, since: string | null, until: string | null, };
+
+export type UsageTotals = { turns: bigint, input_tokens: bigint, output_tokens: bigint, cache_read_tokens: bigint, cache_write_tokens: bigint, 
+/**
+ * Estimated cost of the turns whose model has a configured price
+ */
+cost_usd: number | null, 
 /**
- * Timestamp when the message was queued
+ * Turns left out of `cost_usd` because no price matches their model
  */
-queued_at: string, };
+unpriced_turns: bigint, };
 
-export type QueueStatus = { "status": "empty" } | { "status": "queued", message: QueuedMessage, };
+export type ModelUsage = { model: string | null, usage: UsageTotals, };
+
+export type UsageGroup = { 
+/**
+ * Task, workspace or project id, or the day as `YYYY-MM-DD`
+ */
+key: string, 
+/**
+ * Task title, workspace branch, project name or day
+ */
+label: string, usage: UsageTotals, models: Array<ModelUsage>, };
+
+export type UsageReport = { group_by: UsageGroupBy, total: UsageTotals, 
+/**
+ * In order of their first turn
+ */
+groups: Array<UsageGroup>, };
+
+export type DurationStats = { count: bigint, median_seconds: bigint | null, p90_seconds: bigint | null, };
+
+/**
+ * Time tasks spent in each non-terminal status, summed over every stay. Taken from the
+ * status history; for tasks created before history was recorded it is derived from attempt
+ * and merge timestamps instead: a task is in `todo` until its first attempt starts,
+ * `inprogress` until its last coding agent run finishes, and `inreview` until it is merged
+ * or marked done.
+ */
+export type StatusDurations = { todo: DurationStats, inprogress: DurationStats, inreview: DurationStats, };
+
+export type AnalyticsMetrics = { tasks: bigint, tasks_done: bigint, attempts: bigint, 
+/**
+ * Attempts whose last coding agent run completed successfully
+ */
+successful_attempts: bigint, attempt_success_rate: number | null, 
+/**
+ * Extra attempts per task, over tasks that were attempted at all
+ */
+retries_per_task: number | null, prs_opened: bigint, prs_merged: bigint, pr_merge_rate: number | null, direct_merges: bigint, setup_runs: bigint, setup_failures: bigint, setup_failure_rate: number | null, dev_server_runs: bigint, dev_server_failures: bigint, dev_server_failure_rate: number | null, 
+/**
+ * From task creation to done
+ */
+cycle_time: DurationStats, time_in_status: StatusDurations, };
+
+export type ProjectMetrics = { project_id: string, project_name: string, metrics: AnalyticsMetrics, };
+
+/**
+ * Attempts are grouped by the profile they were started with; tasks by the profile of
+ * their latest attempt
+ */
+export type ExecutorProfileMetrics = { executor_profile_id: ExecutorProfileId, metrics: AnalyticsMetrics, };
+
+export type AnalyticsReport = { overall: AnalyticsMetrics, projects: Array<ProjectMetrics>, executor_profiles: Array<ExecutorProfileMetrics>, };
+
+export type ExportProjectOptions = { 
+/**
+ * Include execution process logs, so history can be replayed after import
+ */
+include_logs: boolean, 
+/**
+ * Include images attached to tasks
+ */
+include_images: boolean, };
+
+export type ImportRepoPath = { 
+/**
+ * Repository id from the bundle
+ */
+repo_id: string, 
+/**
+ * Git repository on this machine to use instead of the original path
+ */
+path: string, };
+
+export type ImportProjectOptions = { 
+/**
+ * Name of the imported project; defaults to the name in the bundle
+ */
+name: string | null, 
+/**
+ * Repositories whose original path does not exist on this machine
+ */
+repo_paths: Array<ImportRepoPath>, 
+/**
+ * Recreate workspaces and execution logs from the bundle as read-only history
+ */
+import_history: boolean, };
+
+/**
+ * What a bundle contains, shown before importing it
+ */
+export type ProjectBundleSummary = { version: number, exported_at: string, project_name: string, repos: Array<BundledRepoSummary>, task_count: number, tag_count: number, image_count: number, has_history: boolean, };
+
+export type BundledRepoSummary = { repo_id: string, display_name: string, original_path: string, 
+/**
+ * Whether the original path is a directory on this machine
+ */
+path_exists: boolean, };
+
+export type StackedWorkspace = { task_title: string, 
+/**
+ * 0 for the root of the stack
+ */
+depth: number, id: string, task_id: string, container_ref: string | null, branch: string, agent_working_dir: string | null, 
+/**
+ * Workspace whose branch this one is stacked on, set when it was created on top of it
+ */
+stack_parent_id: string | null, setup_completed_at: string | null, created_at: string, updated_at: string, };
+
+export type WorkspaceStack = { root_workspace_id: string, 
+/**
+ * Every workspace in the stack, each parent before its children
+ */
+workspaces: Array<StackedWorkspace>, };
+
+export type QualityGateState = "not_run" | "running" | "passed" | "failed" | "outdated" | "uncommitted_changes";
+
+/**
+ * Gate status of one repository in a workspace
+ */
+export type RepoQualityGate = { repo_id: string, repo_name: string, state: QualityGateState, 
+/**
+ * The latest gate run for this repository
+ */
+execution_process_id: string | null, };
+
+/**
+ * Why a merge or pull request is blocked by a repository's quality gate
+ */
+export type QualityGateError = { "type": "quality_gate_running", repo_name: string, execution_process_id: string, } | { "type": "quality_gate_failed", repo_name: string, execution_process_id: string, } | { "type": "quality_gate_uncommitted_changes", repo_name: string, };
+
+export type GitBranch = { name: string, is_current: boolean, is_remote: boolean, last_commit_date: Date, };
+
+export type SharedTaskDetails = { id: string, project_id: string, title: string, description: string | null, status: TaskStatus, };
+
+export type QueuedMessage = { id: string, session_id: string, position: bigint, 
+/**
+ * The follow-up data (message + variant)
+ */
+data: DraftFollowUpData, queued_at: string, updated_at: string, };
+
+export type QueueStatus = { "status": "empty" } | { "status": "queued", messages: Array<QueuedMessage>, };
 
 export type ConflictOp = "rebase" | "merge" | "cherry_pick" | "revert";
 
@@ -352,7 +826,7 @@ export type McpConfig = { servers: { [key in string]?: JsonValue }, servers_path
 
 export type ExecutorActionType = { "type": "CodingAgentInitialRequest" } & CodingAgentInitialRequest | { "type": "CodingAgentFollowUpRequest" } & CodingAgentFollowUpRequest | { "type": "ScriptRequest" } & ScriptRequest;
 
-export type ScriptContext = "SetupScript" | "CleanupScript" | "DevServer" | "ToolInstallScript";
+export type ScriptContext = "SetupScript" | "CleanupScript" | "DevServer" | "ToolInstallScript" | "QualityGate";
 
 export type ScriptRequest = { script: string, language: ScriptRequestLanguage, context: ScriptContext, 
 /**
@@ -363,9 +837,9 @@ working_dir: string | null, };
 
 export type ScriptRequestLanguage = "Bash";
 
-export enum BaseCodingAgent { CLAUDE_CODE = "CLAUDE_CODE", AMP = "AMP", GEMINI = "GEMINI", CODEX = "CODEX", OPENCODE = "OPENCODE", CURSOR_AGENT = "CURSOR_AGENT", QWEN_CODE = "QWEN_CODE", COPILOT = "COPILOT", DROID = "DROID" }
+export enum BaseCodingAgent { CLAUDE_CODE = "CLAUDE_CODE", AMP = "AMP", GEMINI = "GEMINI", CODEX = "CODEX", OPENCODE = "OPENCODE", CURSOR_AGENT = "CURSOR_AGENT", QWEN_CODE = "QWEN_CODE", COPILOT = "COPILOT", DROID = "DROID", CUSTOM_AGENT = "CUSTOM_AGENT" }
 
-export type CodingAgent = { "CLAUDE_CODE": ClaudeCode } | { "AMP": Amp } | { "GEMINI": Gemini } | { "CODEX": Codex } | { "OPENCODE": Opencode } | { "CURSOR_AGENT": CursorAgent } | { "QWEN_CODE": QwenCode } | { "COPILOT": Copilot } | { "DROID": Droid };
+export type CodingAgent = { "CLAUDE_CODE": ClaudeCode } | { "AMP": Amp } | { "GEMINI": Gemini } | { "CODEX": Codex } | { "OPENCODE": Opencode } | { "CURSOR_AGENT": CursorAgent } | { "QWEN_CODE": QwenCode } | { "COPILOT": Copilot } | { "DROID": Droid } | { "CUSTOM_AGENT": CustomAgent };
 
 export type AvailabilityInfo = { "type": "LOGIN_DETECTED", last_auth_timestamp: bigint, } | { "type": "INSTALLATION_FOUND" } | { "type": "NOT_FOUND" };
 
@@ -389,19 +863,19 @@ executor: BaseCodingAgent,
  */
 variant: string | null, };
 
-export type ExecutorConfig = { [key in string]?: { "CLAUDE_CODE": ClaudeCode } | { "AMP": Amp } | { "GEMINI": Gemini } | { "CODEX": Codex } | { "OPENCODE": Opencode } | { "CURSOR_AGENT": CursorAgent } | { "QWEN_CODE": QwenCode } | { "COPILOT": Copilot } | { "DROID": Droid } };
+export type ExecutorConfig = { [key in string]?: { "CLAUDE_CODE": ClaudeCode } | { "AMP": Amp } | { "GEMINI": Gemini } | { "CODEX": Codex } | { "OPENCODE": Opencode } | { "CURSOR_AGENT": CursorAgent } | { "QWEN_CODE": QwenCode } | { "COPILOT": Copilot } | { "DROID": Droid } | { "CUSTOM_AGENT": CustomAgent } };
 
 export type ExecutorConfigs = { executors: { [key in BaseCodingAgent]?: ExecutorConfig }, };
 
 export enum BaseAgentCapability { SESSION_FORK = "SESSION_FORK", SETUP_HELPER = "SETUP_HELPER" }
 
-export type ClaudeCode = { append_prompt: AppendPrompt, claude_code_router?: boolean | null, plan?: boolean | null, approvals?: boolean | null, model?: string | null, dangerously_skip_permissions?: boolean | null, disable_api_key?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+export type ClaudeCode = { append_prompt: AppendPrompt, claude_code_router?: boolean | null, plan?: boolean | null, approvals?: boolean | null, model?: string | null, dangerously_skip_permissions?: boolean | null, disable_api_key?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, max_runtime_secs?: bigint | null, max_tool_calls?: bigint | null, max_log_bytes?: bigint | null, };
 
-export type Gemini = { append_prompt: AppendPrompt, model?: string | null, yolo?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+export type Gemini = { append_prompt: AppendPrompt, model?: string | null, yolo?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, max_runtime_secs?: bigint | null, max_tool_calls?: bigint | null, max_log_bytes?: bigint | null, };
 
-export type Amp = { append_prompt: AppendPrompt, dangerously_allow_all?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+export type Amp = { append_prompt: AppendPrompt, dangerously_allow_all?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, max_runtime_secs?: bigint | null, max_tool_calls?: bigint | null, max_log_bytes?: bigint | null, };
 
-export type Codex = { append_prompt: AppendPrompt, sandbox?: SandboxMode | null, ask_for_approval?: AskForApproval | null, oss?: boolean | null, model?: string | null, model_reasoning_effort?: ReasoningEffort | null, model_reasoning_summary?: ReasoningSummary | null, model_reasoning_summary_format?: ReasoningSummaryFormat | null, profile?: string | null, base_instructions?: string | null, include_apply_patch_tool?: boolean | null, model_provider?: string | null, compact_prompt?: string | null, developer_instructions?: string | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+export type Codex = { append_prompt: AppendPrompt, sandbox?: SandboxMode | null, ask_for_approval?: AskForApproval | null, oss?: boolean | null, model?: string | null, model_reasoning_effort?: ReasoningEffort | null, model_reasoning_summary?: ReasoningSummary | null, model_reasoning_summary_format?: ReasoningSummaryFormat | null, profile?: string | null, base_instructions?: string | null, include_apply_patch_tool?: boolean | null, model_provider?: string | null, compact_prompt?: string | null, developer_instructions?: string | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, max_runtime_secs?: bigint | null, max_tool_calls?: bigint | null, max_log_bytes?: bigint | null, };
 
 export type SandboxMode = "auto" | "read-only" | "workspace-write" | "danger-full-access";
 
@@ -413,26 +887,34 @@ export type ReasoningSummary = "auto" | "concise" | "detailed" | "none";
 
 export type ReasoningSummaryFormat = "none" | "experimental";
 
-export type CursorAgent = { append_prompt: AppendPrompt, force?: boolean | null, model?: string | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+export type CursorAgent = { append_prompt: AppendPrompt, force?: boolean | null, model?: string | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, max_runtime_secs?: bigint | null, max_tool_calls?: bigint | null, max_log_bytes?: bigint | null, };
 
-export type Copilot = { append_prompt: AppendPrompt, model?: string | null, allow_all_tools?: boolean | null, allow_tool?: string | null, deny_tool?: string | null, add_dir?: Array<string> | null, disable_mcp_server?: Array<string> | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+export type Copilot = { append_prompt: AppendPrompt, model?: string | null, allow_all_tools?: boolean | null, allow_tool?: string | null, deny_tool?: string | null, add_dir?: Array<string> | null, disable_mcp_server?: Array<string> | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, max_runtime_secs?: bigint | null, max_tool_calls?: bigint | null, max_log_bytes?: bigint | null, };
 
 export type Opencode = { append_prompt: AppendPrompt, model?: string | null, mode?: string | null, 
 /**
  * Auto-approve agent actions
  */
-auto_approve: boolean, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+auto_approve: boolean, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, max_runtime_secs?: bigint | null, max_tool_calls?: bigint | null, max_log_bytes?: bigint | null, };
 
-export type QwenCode = { append_prompt: AppendPrompt, yolo?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+export type QwenCode = { append_prompt: AppendPrompt, yolo?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, max_runtime_secs?: bigint | null, max_tool_calls?: bigint | null, max_log_bytes?: bigint | null, };
 
-export type Droid = { append_prompt: AppendPrompt, autonomy: Autonomy, model?: string | null, reasoning_effort?: DroidReasoningEffort | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+export type Droid = { append_prompt: AppendPrompt, autonomy: Autonomy, model?: string | null, reasoning_effort?: DroidReasoningEffort | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, max_runtime_secs?: bigint | null, max_tool_calls?: bigint | null, max_log_bytes?: bigint | null, };
 
 export type Autonomy = "normal" | "low" | "medium" | "high" | "skip-permissions-unsafe";
 
 export type DroidReasoningEffort = "none" | "dynamic" | "off" | "low" | "medium" | "high";
 
+export type CustomAgent = { append_prompt: AppendPrompt, command: string, follow_up_command?: string | null, output_format: CustomOutputFormat, jsonl?: JsonlFieldMappings | null, session_id_regex?: string | null, session_id_pointer?: string | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, max_runtime_secs?: bigint | null, max_tool_calls?: bigint | null, max_log_bytes?: bigint | null, };
+
+export type CustomOutputFormat = "plain_text" | "jsonl" | "acp";
+
+export type JsonlFieldMappings = { kind_pointer: string, content_pointer: string, tool_name_pointer?: string | null, tool_arguments_pointer?: string | null, assistant_kinds: Array<string>, thinking_kinds: Array<string>, tool_kinds: Array<string>, error_kinds: Array<string>, };
+
 export type AppendPrompt = string | null;
 
+export type ExecutionLimits = { max_runtime_secs?: bigint | null, max_tool_calls?: bigint | null, max_log_bytes?: bigint | null, };
+
 export type CodingAgentInitialRequest = { prompt: string, 
 /**
  * Executor profile specification
@@ -487,7 +969,11 @@ value: JsonValue, };
 
 export type ToolResultValueType = { "type": "markdown" } | { "type": "json" };
 
-export type ToolStatus = { "status": "created" } | { "status": "success" } | { "status": "failed" } | { "status": "denied", reason: string | null, } | { "status": "pending_approval", approval_id: string, requested_at: string, timeout_at: string, } | { "status": "timed_out" };
+export type ToolStatus = { "status": "created" } | { "status": "success" } | { "status": "failed" } | { "status": "denied", reason: string | null, 
+/**
+ * Set when an approval rule denied the call instead of the user
+ */
+rule?: MatchedApprovalRule, } | { "status": "auto_approved", rule: MatchedApprovalRule, } | { "status": "pending_approval", approval_id: string, requested_at: string, timeout_at: string, } | { "status": "timed_out" };
 
 export type PatchType = { "type": "NORMALIZED_ENTRY", "content": NormalizedEntry } | { "type": "STDOUT", "content": string } | { "type": "STDERR", "content": string } | { "type": "DIFF", "content": Diff };
 